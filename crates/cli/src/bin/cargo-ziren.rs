@@ -1,7 +1,17 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use zkm_cli::{
-    commands::{build::BuildCmd, new::NewCmd, vkey::VkeyCmd},
+    commands::{
+        build::BuildCmd,
+        estimate::EstimateCmd,
+        execute::ExecuteCmd,
+        export::{ContractsCmd, ExportCmd},
+        new::NewCmd,
+        prove::ProveCmd,
+        trace_diff::TraceDiffCmd,
+        verify::VerifyCmd,
+        vkey::VkeyCmd,
+    },
     ZKM_VERSION_MESSAGE,
 };
 
@@ -23,6 +33,13 @@ pub enum ProveCliCommands {
     New(NewCmd),
     Build(BuildCmd),
     Vkey(VkeyCmd),
+    Execute(ExecuteCmd),
+    Estimate(EstimateCmd),
+    Prove(ProveCmd),
+    Verify(VerifyCmd),
+    Export(ExportCmd),
+    Contracts(ContractsCmd),
+    TraceDiff(TraceDiffCmd),
 }
 
 fn main() -> Result<()> {
@@ -32,5 +49,12 @@ fn main() -> Result<()> {
         ProveCliCommands::New(cmd) => cmd.run(),
         ProveCliCommands::Build(cmd) => cmd.run(),
         ProveCliCommands::Vkey(cmd) => cmd.run(),
+        ProveCliCommands::Execute(cmd) => cmd.run(),
+        ProveCliCommands::Estimate(cmd) => cmd.run(),
+        ProveCliCommands::Prove(cmd) => cmd.run(),
+        ProveCliCommands::Verify(cmd) => cmd.run(),
+        ProveCliCommands::Export(cmd) => cmd.run(),
+        ProveCliCommands::Contracts(cmd) => cmd.run(),
+        ProveCliCommands::TraceDiff(cmd) => cmd.run(),
     }
 }
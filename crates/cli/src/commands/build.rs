@@ -1,17 +1,35 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Parser;
 use zkm_build::{execute_build_program, BuildArgs};
+use zkm_core_executor::Program;
 
 #[derive(Parser)]
 #[command(name = "build", about = "Compile a Ziren program")]
 pub struct BuildCmd {
     #[command(flatten)]
     build_args: BuildArgs,
+
+    /// Validate the built ELFs for instructions the zkVM cannot execute.
+    #[arg(long)]
+    check: bool,
 }
 
 impl BuildCmd {
     pub fn run(&self) -> Result<()> {
-        execute_build_program(&self.build_args, None)?;
+        let elfs = execute_build_program(&self.build_args, None)?;
+
+        if self.check {
+            let mut failed = false;
+            for (name, path) in elfs {
+                let elf = std::fs::read(&path)?;
+                let report = Program::validate(&elf)?;
+                println!("{name} ({path}):\n{report}");
+                failed |= report.has_errors();
+            }
+            if failed {
+                bail!("the built ELFs contain instructions the zkVM cannot execute");
+            }
+        }
 
         Ok(())
     }
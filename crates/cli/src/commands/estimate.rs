@@ -0,0 +1,81 @@
+use std::fs;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use zkm_sdk::{ProverClient, ShardKind};
+
+use crate::commands::execute::StdinArgs;
+
+#[derive(Parser)]
+#[command(
+    name = "estimate",
+    about = "Estimate the shards, proof size and proving time of a program without proving it."
+)]
+pub struct EstimateCmd {
+    /// The path to the ELF file.
+    #[arg(long)]
+    elf: String,
+
+    /// The input to the program.
+    #[command(flatten)]
+    stdin: StdinArgs,
+
+    /// The shard size the proof would be made with.
+    #[arg(long)]
+    shard_size: Option<usize>,
+
+    /// The maximum number of cpu cycles to use for execution.
+    #[arg(long)]
+    max_cycles: Option<u64>,
+
+    /// Print the estimate as JSON instead of a human-readable report.
+    #[arg(long)]
+    json: bool,
+}
+
+impl EstimateCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf =
+            fs::read(&self.elf).with_context(|| format!("failed to read ELF {}", self.elf))?;
+        let stdin = self.stdin.load()?;
+
+        let client = ProverClient::cpu();
+        let mut estimate = client.estimate(&elf, stdin);
+        if let Some(shard_size) = self.shard_size {
+            estimate = estimate.shard_size(shard_size);
+        }
+        if let Some(max_cycles) = self.max_cycles {
+            estimate = estimate.max_cycles(max_cycles);
+        }
+        let estimate = estimate.run()?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&estimate)?);
+            return Ok(());
+        }
+
+        let count = |kind| estimate.shards.iter().filter(|shard| shard.kind == kind).count();
+        println!("cycles: {}", estimate.cycles);
+        println!(
+            "shards: {} ({} core, {} precompile, {} memory)",
+            estimate.shards.len(),
+            count(ShardKind::Core),
+            count(ShardKind::Precompile),
+            count(ShardKind::Memory),
+        );
+        println!("max log2 trace heights:");
+        for (chip, log2_height) in estimate.max_log2_heights() {
+            println!("  {chip}: {log2_height}");
+        }
+        println!("core LDE cells: {}", estimate.core_lde_cells);
+        println!("recursion nodes: {}", estimate.recursion_nodes);
+        println!("recursion LDE cells: {}", estimate.recursion_lde_cells);
+        println!("core proof size: ~{} bytes", estimate.core_proof_size);
+        println!("compressed proof size: ~{} bytes", estimate.compressed_proof_size);
+        println!("core proving time: ~{:.1}s", estimate.core_time.as_secs_f64());
+        println!("compress time: ~{:.1}s", estimate.compress_time.as_secs_f64());
+        println!("total time: ~{:.1}s", estimate.total_time().as_secs_f64());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    net::TcpListener,
+};
+
+use anyhow::{Context, Result};
+use clap::{Args, Parser};
+use serde::Serialize;
+use zkm_core_executor::{
+    profiler::Profiler, trace::TraceWriter, ExecutionError, ExecutionReport, Executor, Program,
+    ZKMContext,
+};
+use zkm_sdk::{ZKMPublicValues, ZKMStdin};
+use zkm_stark::ZKMCoreOpts;
+
+#[derive(Parser)]
+#[command(name = "execute", about = "Execute a program and print its execution report.")]
+pub struct ExecuteCmd {
+    /// The path to the ELF file.
+    #[arg(long)]
+    elf: String,
+
+    /// The input to the program.
+    #[command(flatten)]
+    stdin: StdinArgs,
+
+    /// The maximum number of cpu cycles to use for execution.
+    #[arg(long)]
+    max_cycles: Option<u64>,
+
+    /// Print the result as JSON instead of a human-readable report.
+    #[arg(long)]
+    json: bool,
+
+    /// Wait for a GDB remote debugger on this address (e.g. `127.0.0.1:9001`) before executing.
+    #[arg(long)]
+    gdb: Option<String>,
+
+    /// Profile the execution and write `<PREFIX>.folded` (flamegraph) and `<PREFIX>.pb` (pprof).
+    #[arg(long, value_name = "PREFIX")]
+    profile: Option<String>,
+
+    /// Sample every N-th instruction when profiling instead of every instruction.
+    #[arg(long, default_value_t = 1, requires = "profile")]
+    profile_interval: u64,
+
+    /// Write an execution trace to this path, see `zkm_core_executor::trace`.
+    #[arg(long)]
+    trace: Option<String>,
+
+    /// Emulate floating-point (COP1) instructions in the executor, for programs without the FPU
+    /// trap handler of zkm-zkvm. Such executions cannot be proven.
+    #[arg(long)]
+    fpu_emulation: bool,
+}
+
+/// The sources a [`ZKMStdin`] can be loaded from.
+#[derive(Debug, Clone, Default, Args)]
+#[group(required = false, multiple = false)]
+pub struct StdinArgs {
+    /// Path to a file whose raw bytes are written to stdin as a single input.
+    #[arg(long)]
+    stdin: Option<String>,
+
+    /// Hex-encoded bytes written to stdin as a single input.
+    #[arg(long)]
+    stdin_hex: Option<String>,
+
+    /// Path to a bincode-serialized `ZKMStdin`.
+    #[arg(long)]
+    stdin_bincode: Option<String>,
+}
+
+impl StdinArgs {
+    /// Load the [`ZKMStdin`] described by the arguments, or an empty one if none were given.
+    pub fn load(&self) -> Result<ZKMStdin> {
+        if let Some(path) = &self.stdin {
+            let bytes = fs::read(path).with_context(|| format!("failed to read stdin {path}"))?;
+            Ok(ZKMStdin::from(&bytes))
+        } else if let Some(encoded) = &self.stdin_hex {
+            let bytes = hex::decode(encoded.trim_start_matches("0x"))
+                .context("failed to decode hex stdin")?;
+            Ok(ZKMStdin::from(&bytes))
+        } else if let Some(path) = &self.stdin_bincode {
+            let bytes = fs::read(path).with_context(|| format!("failed to read stdin {path}"))?;
+            bincode::deserialize(&bytes).context("failed to deserialize ZKMStdin")
+        } else {
+            Ok(ZKMStdin::new())
+        }
+    }
+}
+
+/// The result of executing a program, as printed by `--json`.
+#[derive(Serialize)]
+struct ExecutionOutput {
+    exit_code: u32,
+    cycles: u64,
+    public_values: String,
+    report: ExecutionReport,
+}
+
+impl ExecuteCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf =
+            fs::read(&self.elf).with_context(|| format!("failed to read ELF {}", self.elf))?;
+        let stdin = self.stdin.load()?;
+
+        let program = Program::from(&elf)?;
+        let mut context_builder = ZKMContext::builder();
+        if let Some(max_cycles) = self.max_cycles {
+            context_builder.max_cycles(max_cycles);
+        }
+        let mut runtime =
+            Executor::with_context(program, ZKMCoreOpts::default(), context_builder.build());
+        runtime.write_vecs(&stdin.buffer);
+        runtime.write_files(&stdin.files);
+        for (proof, vkey) in stdin.proofs.iter() {
+            runtime.write_proof(proof.clone(), vkey.clone());
+        }
+        if let Some(path) = &self.trace {
+            let file = File::create(path).with_context(|| format!("failed to create {path}"))?;
+            runtime.trace_writer = Some(TraceWriter::new(BufWriter::new(file), &runtime.program)?);
+        }
+        runtime.fpu_emulation = self.fpu_emulation;
+        if self.profile.is_some() {
+            runtime.profiler = Some(Profiler::new(&elf, self.profile_interval)?);
+        }
+
+        // A non-zero exit code is a result of the program, not a failure of the executor.
+        let exit_code = if let Some(addr) = &self.gdb {
+            let listener = TcpListener::bind(addr)?;
+            eprintln!("Waiting for gdb on {}", listener.local_addr()?);
+            runtime.run_with_gdb(&listener).context("gdb session failed")?.unwrap_or(0)
+        } else {
+            match runtime.run_fast() {
+                Ok(()) => 0,
+                Err(ExecutionError::HaltWithNonZeroExitCode(code)) => code,
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        if let (Some(prefix), Some(profiler)) = (&self.profile, &runtime.profiler) {
+            profiler.write_folded_stacks(format!("{prefix}.folded"))?;
+            profiler.write_pprof(format!("{prefix}.pb"))?;
+            eprintln!("Wrote profile to {prefix}.folded and {prefix}.pb");
+            for site in profiler.syscall_sites() {
+                eprintln!(
+                    "  {:?} x{} at 0x{:08x} in {}",
+                    site.syscall, site.count, site.pc, site.stack
+                );
+            }
+        }
+
+        let public_values = ZKMPublicValues::from(&runtime.state.public_values_stream);
+        let output = ExecutionOutput {
+            exit_code,
+            cycles: runtime.state.global_clk,
+            public_values: format!("0x{}", hex::encode(public_values.as_slice())),
+            report: std::mem::take(&mut runtime.report),
+        };
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&output)?);
+            return Ok(());
+        }
+
+        print!("{}", output.report);
+        if !output.report.cycle_tracker.is_empty() {
+            println!("cycle tracker:");
+            let mut spans = output.report.cycle_tracker.iter().collect::<Vec<_>>();
+            spans.sort_by(|a, b| b.1.cmp(a.1));
+            for (name, cycles) in spans {
+                println!("  {name}: {cycles}");
+            }
+        }
+        println!("touched memory addresses: {}", output.report.touched_memory_addresses);
+        println!("cycles: {}", output.cycles);
+        println!("public values: {}", output.public_values);
+        println!("exit code: {}", output.exit_code);
+
+        Ok(())
+    }
+}
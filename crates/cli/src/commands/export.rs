@@ -0,0 +1,91 @@
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, ValueEnum};
+use zkm_sdk::{export_verifier_contracts, ProverClient, ZKMProofKind, ZKMProofWithPublicValues};
+
+use crate::commands::verify::VerifyingKeySource;
+
+#[derive(Parser)]
+#[command(
+    name = "export",
+    about = "Export a Plonk or Groth16 proof as a JSON fixture and `verifyProof` calldata."
+)]
+pub struct ExportCmd {
+    /// The path to the proof saved by `cargo ziren prove`.
+    #[arg(long)]
+    proof: String,
+
+    /// The program the proof is checked against.
+    #[command(flatten)]
+    key: VerifyingKeySource,
+
+    /// Where to write the JSON fixture.
+    #[arg(long)]
+    fixture: Option<String>,
+
+    /// Where to write the hex-encoded calldata of `IZKMVerifier.verifyProof`.
+    #[arg(long)]
+    calldata: Option<String>,
+}
+
+impl ExportCmd {
+    pub fn run(&self) -> Result<()> {
+        if self.fixture.is_none() && self.calldata.is_none() {
+            bail!("nothing to export; pass --fixture and/or --calldata");
+        }
+
+        let proof = ZKMProofWithPublicValues::load(&self.proof)
+            .with_context(|| format!("failed to load proof {}", self.proof))?;
+        let client = ProverClient::new();
+        let vk = self.key.load(&client)?;
+
+        if let Some(path) = &self.fixture {
+            let fixture = proof.fixture(&vk)?;
+            fs::write(path, serde_json::to_string_pretty(&fixture)?)
+                .with_context(|| format!("failed to write fixture {path}"))?;
+            println!("Wrote the fixture to {path}.");
+        }
+        if let Some(path) = &self.calldata {
+            let calldata = proof.calldata(&vk)?;
+            fs::write(path, format!("0x{}", hex::encode(calldata)))
+                .with_context(|| format!("failed to write calldata {path}"))?;
+            println!("Wrote the calldata to {path}.");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ProofSystem {
+    Groth16,
+    Plonk,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "contracts",
+    about = "Generate the Solidity verifier contracts for the current circuits."
+)]
+pub struct ContractsCmd {
+    /// The proof system to generate the verifier for.
+    #[arg(long, value_enum, default_value = "groth16")]
+    system: ProofSystem,
+
+    /// The directory to write the contracts to.
+    #[arg(long)]
+    out: String,
+}
+
+impl ContractsCmd {
+    pub fn run(&self) -> Result<()> {
+        let kind = match self.system {
+            ProofSystem::Groth16 => ZKMProofKind::Groth16,
+            ProofSystem::Plonk => ZKMProofKind::Plonk,
+        };
+        export_verifier_contracts(kind, &self.out)
+            .with_context(|| format!("failed to generate contracts in {}", self.out))?;
+        println!("Wrote the verifier contracts to {}.", self.out);
+        Ok(())
+    }
+}
@@ -1,3 +1,9 @@
 pub mod build;
+pub mod estimate;
+pub mod execute;
+pub mod export;
 pub mod new;
+pub mod prove;
+pub mod trace_diff;
+pub mod verify;
 pub mod vkey;
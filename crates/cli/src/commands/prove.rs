@@ -0,0 +1,85 @@
+use std::fs;
+
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use zkm_sdk::{HashableKey, ProverClient};
+
+use crate::commands::execute::StdinArgs;
+
+/// The kind of proof to generate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ProofMode {
+    /// A proof whose size scales linearly with the number of cycles.
+    #[default]
+    Core,
+    /// A constant-size STARK proof.
+    Compressed,
+    /// A Plonk proof over BN254.
+    Plonk,
+    /// A Groth16 proof over BN254.
+    Groth16,
+}
+
+#[derive(Parser)]
+#[command(name = "prove", about = "Generate a proof for a Ziren program and save it to disk.")]
+pub struct ProveCmd {
+    /// The path to the ELF file.
+    #[arg(long)]
+    elf: String,
+
+    /// The input to the program.
+    #[command(flatten)]
+    stdin: StdinArgs,
+
+    /// The kind of proof to generate.
+    #[arg(long, value_enum, default_value_t = ProofMode::Core)]
+    mode: ProofMode,
+
+    /// The path to write the proof to.
+    #[arg(long, default_value = "proof.bin")]
+    output: String,
+
+    /// An optional path to write the bincode-serialized verifying key to.
+    #[arg(long)]
+    vk_output: Option<String>,
+
+    /// The maximum number of cpu cycles to use for execution.
+    #[arg(long)]
+    max_cycles: Option<u64>,
+}
+
+impl ProveCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf =
+            fs::read(&self.elf).with_context(|| format!("failed to read ELF {}", self.elf))?;
+        let stdin = self.stdin.load()?;
+
+        let client = ProverClient::new();
+        let (pk, vk) = client.setup(&elf);
+
+        let mut prove = client.prove(&pk, stdin);
+        prove = match self.mode {
+            ProofMode::Core => prove.core(),
+            ProofMode::Compressed => prove.compressed(),
+            ProofMode::Plonk => prove.plonk(),
+            ProofMode::Groth16 => prove.groth16(),
+        };
+        if let Some(max_cycles) = self.max_cycles {
+            prove = prove.cycle_limit(max_cycles);
+        }
+        let proof = prove.run()?;
+
+        proof.save(&self.output)?;
+        println!("Proof written to {}", self.output);
+
+        if let Some(vk_output) = &self.vk_output {
+            let bytes = bincode::serialize(&vk)?;
+            fs::write(vk_output, bytes)
+                .with_context(|| format!("failed to write verifying key {vk_output}"))?;
+            println!("Verifying key written to {vk_output}");
+        }
+        println!("Verification Key Hash:\n{}", vk.vk.bytes32());
+
+        Ok(())
+    }
+}
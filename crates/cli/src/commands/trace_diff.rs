@@ -0,0 +1,61 @@
+use std::{fs::File, io::BufReader};
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use zkm_core_executor::trace::{first_divergence, TraceReader, TraceRecord};
+
+#[derive(Parser)]
+#[command(
+    name = "trace-diff",
+    about = "Find the first cycle at which two execution traces differ."
+)]
+pub struct TraceDiffCmd {
+    /// The path to the first trace.
+    left: String,
+
+    /// The path to the second trace.
+    right: String,
+}
+
+fn open(path: &str) -> Result<TraceReader<BufReader<File>>> {
+    let file = File::open(path).with_context(|| format!("failed to open trace {path}"))?;
+    TraceReader::new(BufReader::new(file)).with_context(|| format!("failed to read trace {path}"))
+}
+
+fn describe(record: Option<&TraceRecord>) -> String {
+    let Some(record) = record else {
+        return "<end of trace>".to_string();
+    };
+    let mut description =
+        format!("clk {} pc 0x{:08x} {:?}", record.clk, record.pc, record.instruction);
+    for access in &record.accesses {
+        let kind = if access.is_write { "write" } else { "read" };
+        description.push_str(&format!(
+            "\n    {kind} {:?} [0x{:08x}] 0x{:08x} -> 0x{:08x}",
+            access.position, access.addr, access.prev_value, access.value
+        ));
+    }
+    description
+}
+
+impl TraceDiffCmd {
+    pub fn run(&self) -> Result<()> {
+        let mut left = open(&self.left)?;
+        let mut right = open(&self.right)?;
+        if left.header().program_hash != right.header().program_hash {
+            println!("note: the traces were produced by different programs");
+        }
+
+        match first_divergence(&mut left, &mut right)? {
+            None => {
+                println!("traces are identical");
+                Ok(())
+            }
+            Some(divergence) => {
+                println!("{}: {}", self.left, describe(divergence.left.as_ref()));
+                println!("{}: {}", self.right, describe(divergence.right.as_ref()));
+                bail!("traces diverge at record {}", divergence.index)
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::fs;
+
+use anyhow::{Context, Result};
+use clap::{Args, Parser};
+use zkm_sdk::{ProverClient, ZKMProofWithPublicValues, ZKMVerificationError, ZKMVerifyingKey};
+
+#[derive(Parser)]
+#[command(name = "verify", about = "Verify a proof saved by `cargo ziren prove`.")]
+pub struct VerifyCmd {
+    /// The path to the proof.
+    #[arg(long)]
+    proof: String,
+
+    /// The program the proof is checked against.
+    #[command(flatten)]
+    key: VerifyingKeySource,
+}
+
+#[derive(Debug, Clone, Args)]
+#[group(required = true, multiple = false)]
+pub struct VerifyingKeySource {
+    /// The path to the ELF file the verifying key is derived from.
+    #[arg(long)]
+    elf: Option<String>,
+    /// The path to a bincode-serialized verifying key.
+    #[arg(long)]
+    vk: Option<String>,
+}
+
+impl VerifyingKeySource {
+    /// Load the verifying key, setting up the program if it is given as an ELF.
+    pub fn load(&self, client: &ProverClient) -> Result<ZKMVerifyingKey> {
+        if let Some(path) = &self.elf {
+            let elf = fs::read(path).with_context(|| format!("failed to read ELF {path}"))?;
+            Ok(client.setup(&elf).1)
+        } else if let Some(path) = &self.vk {
+            let bytes =
+                fs::read(path).with_context(|| format!("failed to read verifying key {path}"))?;
+            bincode::deserialize(&bytes).context("failed to deserialize verifying key")
+        } else {
+            unreachable!()
+        }
+    }
+}
+
+/// Map a verification failure to the process exit code reported by `cargo ziren verify`.
+///
+/// Exit code 1 is left for errors which happen before verification, e.g. unreadable files.
+pub fn verification_exit_code(err: &ZKMVerificationError) -> i32 {
+    match err {
+        ZKMVerificationError::InvalidPublicValues => 2,
+        ZKMVerificationError::VersionMismatch(_) => 3,
+        ZKMVerificationError::Core(_) => 4,
+        ZKMVerificationError::Recursion(_) => 5,
+        ZKMVerificationError::Plonk(_) => 6,
+        ZKMVerificationError::Groth16(_) => 7,
+        ZKMVerificationError::UnsupportedProofKind => 8,
+    }
+}
+
+impl VerifyCmd {
+    pub fn run(&self) -> Result<()> {
+        let proof = ZKMProofWithPublicValues::load(&self.proof)
+            .with_context(|| format!("failed to load proof {}", self.proof))?;
+
+        let client = ProverClient::new();
+        let vk = self.key.load(&client)?;
+
+        match client.verify(&proof, &vk) {
+            Ok(()) => {
+                println!("Proof verified successfully.");
+                Ok(())
+            }
+            Err(err) => {
+                eprintln!("Proof verification failed: {err}");
+                std::process::exit(verification_exit_code(&err));
+            }
+        }
+    }
+}
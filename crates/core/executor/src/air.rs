@@ -43,6 +43,10 @@ pub enum MipsAirId {
     Poseidon2Permute = 46,
     /// The Keccak sponge chip.
     KeccakSponge = 48,
+    /// The BLAKE3 compress chip.
+    Blake3Compress = 49,
+    /// The BLAKE2b compress chip.
+    Blake2bCompress = 50,
     /// The bn254 add assign chip.
     Bn254AddAssign = 13,
     /// The bn254 double assign chip.
@@ -111,6 +115,24 @@ pub enum MipsAirId {
     Byte = 45,
     /// The SysLinux chip.
     SysLinux = 47,
+    /// The SysRead chip.
+    SysRead = 59,
+    /// The extension chip of the `CUSTOM_0` precompile.
+    Custom0 = 51,
+    /// The extension chip of the `CUSTOM_1` precompile.
+    Custom1 = 52,
+    /// The extension chip of the `CUSTOM_2` precompile.
+    Custom2 = 53,
+    /// The extension chip of the `CUSTOM_3` precompile.
+    Custom3 = 54,
+    /// The extension chip of the `CUSTOM_4` precompile.
+    Custom4 = 55,
+    /// The extension chip of the `CUSTOM_5` precompile.
+    Custom5 = 56,
+    /// The extension chip of the `CUSTOM_6` precompile.
+    Custom6 = 57,
+    /// The extension chip of the `CUSTOM_7` precompile.
+    Custom7 = 58,
 }
 
 impl MipsAirId {
@@ -136,6 +158,22 @@ impl MipsAirId {
         ]
     }
 
+    /// Whether the AIR is an extension chip from outside of Ziren.
+    #[must_use]
+    pub fn is_custom(&self) -> bool {
+        matches!(
+            self,
+            Self::Custom0
+                | Self::Custom1
+                | Self::Custom2
+                | Self::Custom3
+                | Self::Custom4
+                | Self::Custom5
+                | Self::Custom6
+                | Self::Custom7
+        )
+    }
+
     /// Returns the string representation of the AIR.
     #[must_use]
     pub fn as_str(&self) -> &str {
@@ -154,6 +192,8 @@ impl MipsAirId {
             Self::Secp256r1DoubleAssign => "Secp256r1DoubleAssign",
             Self::Poseidon2Permute => "Poseidon2Permute",
             Self::KeccakSponge => "KeccakSponge",
+            Self::Blake3Compress => "Blake3Compress",
+            Self::Blake2bCompress => "Blake2bCompress",
             Self::Bn254AddAssign => "Bn254AddAssign",
             Self::Bn254DoubleAssign => "Bn254DoubleAssign",
             Self::Bls12381AddAssign => "Bls12381AddAssign",
@@ -188,6 +228,15 @@ impl MipsAirId {
             Self::Global => "Global",
             Self::Byte => "Byte",
             Self::SysLinux => "SysLinux",
+            Self::SysRead => "SysRead",
+            Self::Custom0 => "Custom0",
+            Self::Custom1 => "Custom1",
+            Self::Custom2 => "Custom2",
+            Self::Custom3 => "Custom3",
+            Self::Custom4 => "Custom4",
+            Self::Custom5 => "Custom5",
+            Self::Custom6 => "Custom6",
+            Self::Custom7 => "Custom7",
         }
     }
 }
@@ -1,10 +1,12 @@
 use core::mem::take;
+use std::sync::Arc;
 
 use hashbrown::HashMap;
 
 use crate::{
     hook::{hookify, BoxedHook, HookEnv, HookRegistry},
     subproof::SubproofVerifier,
+    syscalls::{Syscall, SyscallCode},
 };
 
 /// Context to run a program inside Ziren.
@@ -23,6 +25,18 @@ pub struct ZKMContext<'a> {
 
     /// Skip deferred proof verification.
     pub skip_deferred_proof_verification: bool,
+
+    /// Precompiles registered from outside of Ziren, see [`ZKMContextBuilder::precompile`].
+    pub precompiles: Vec<(SyscallCode, Arc<dyn Syscall>)>,
+
+    /// The wall-clock time at the start of execution, see [`ZKMContextBuilder::epoch`].
+    pub epoch: u32,
+
+    /// The seed of the random stream, see [`ZKMContextBuilder::random_seed`].
+    pub random_seed: u64,
+
+    /// Fail on unrecognized Linux syscalls, see [`ZKMContextBuilder::strict_syscalls`].
+    pub strict_syscalls: bool,
 }
 
 /// A builder for [`ZKMContext`].
@@ -33,6 +47,10 @@ pub struct ZKMContextBuilder<'a> {
     subproof_verifier: Option<&'a dyn SubproofVerifier>,
     max_cycles: Option<u64>,
     skip_deferred_proof_verification: bool,
+    precompiles: Vec<(SyscallCode, Arc<dyn Syscall>)>,
+    epoch: u32,
+    random_seed: u64,
+    strict_syscalls: bool,
 }
 
 impl<'a> ZKMContext<'a> {
@@ -72,11 +90,19 @@ impl<'a> ZKMContextBuilder<'a> {
         let subproof_verifier = take(&mut self.subproof_verifier);
         let cycle_limit = take(&mut self.max_cycles);
         let skip_deferred_proof_verification = take(&mut self.skip_deferred_proof_verification);
+        let precompiles = take(&mut self.precompiles);
+        let epoch = take(&mut self.epoch);
+        let random_seed = take(&mut self.random_seed);
+        let strict_syscalls = take(&mut self.strict_syscalls);
         ZKMContext {
             hook_registry,
             subproof_verifier,
             max_cycles: cycle_limit,
             skip_deferred_proof_verification,
+            precompiles,
+            epoch,
+            random_seed,
+            strict_syscalls,
         }
     }
 
@@ -122,6 +148,52 @@ impl<'a> ZKMContextBuilder<'a> {
         self.skip_deferred_proof_verification = skip;
         self
     }
+
+    /// Register a precompile implemented outside of Ziren.
+    ///
+    /// The guest invokes it with the `syscall` instruction and `code` in register V0. To be
+    /// provable, the machine must include a chip receiving its syscalls, see
+    /// `MipsAir::machine_with_extensions` in `zkm-core-machine`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `code` is not one of the `SyscallCode::CUSTOM_*` codes.
+    pub fn precompile(&mut self, code: SyscallCode, syscall: Arc<dyn Syscall>) -> &mut Self {
+        assert!(code.is_custom(), "{code} is not reserved for custom precompiles");
+        self.precompiles.push((code, syscall));
+        self
+    }
+
+    /// Set the wall-clock time at the start of execution, in seconds since the Unix epoch.
+    ///
+    /// `clock_gettime` returns it for `CLOCK_REALTIME`, advanced by a tick of 2^-16 seconds on
+    /// every call so that the time is deterministic. Defaults to zero.
+    ///
+    /// The epoch is committed as a public value of the proof. Wrapped and aggregated proofs do
+    /// not expose it, so only executions with a zero epoch and seed can be wrapped or aggregated.
+    pub fn epoch(&mut self, epoch: u32) -> &mut Self {
+        self.epoch = epoch;
+        self
+    }
+
+    /// Set the seed of the deterministic word stream returned by `getrandom`. Defaults to zero.
+    ///
+    /// The seed is committed as a public value of the proof, like the [epoch](Self::epoch).
+    /// Anyone who knows it can compute the stream, so guests must not rely on the bytes being
+    /// unpredictable.
+    pub fn random_seed(&mut self, seed: u64) -> &mut Self {
+        self.random_seed = seed;
+        self
+    }
+
+    /// Fail with [`ExecutionError::UnsupportedSyscall`](crate::ExecutionError::UnsupportedSyscall)
+    /// on Linux syscalls the executor does not recognize, instead of treating them as no-ops.
+    ///
+    /// Strict mode is also enabled by `ZKMCoreOpts::strict_syscalls`.
+    pub fn strict_syscalls(&mut self, strict: bool) -> &mut Self {
+        self.strict_syscalls = strict;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -144,7 +144,8 @@ pub fn estimate_mips_event_counts(
     // Compute the number of events in the jump chip.
     events_counts[MipsAirId::Jump] = opcode_counts[Opcode::Jump]
         + opcode_counts[Opcode::Jumpi]
-        + opcode_counts[Opcode::JumpDirect];
+        + opcode_counts[Opcode::JumpDirect]
+        + opcode_counts[Opcode::TRAP];
 
     // Compute the number of events in the MemoryInstrs chip.
     events_counts[MipsAirId::MemoryInstrs] = opcode_counts[Opcode::LB]
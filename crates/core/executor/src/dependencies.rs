@@ -242,7 +242,7 @@ pub fn emit_jump_dependencies(executor: &mut Executor, event: JumpEvent) {
             };
             executor.record.add_events.push(add_event);
         }
-        Opcode::Jump | Opcode::Jumpi => {}
+        Opcode::Jump | Opcode::Jumpi | Opcode::TRAP => {}
         _ => unreachable!(),
     }
 }
@@ -0,0 +1,372 @@
+//! Differential testing of the executor against an independent reference interpreter.
+//!
+//! Each case is a straight-line stream of MIPS instruction words, forward branches and jumps
+//! included, together with an initial register and memory state. The stream is decoded with
+//! [`Instruction::decode_from`] and stepped through the [`Executor`] one cycle at a time, while the
+//! [`Reference`] interpreter executes the raw words. Registers, HI/LO, the program counters and the
+//! data memory are compared after every step.
+//!
+//! `test_differential_corpus` replays the regressions in `corpus.txt`. `test_differential_random`
+//! runs freshly generated cases; set `ZKM_DIFFERENTIAL_ITERATIONS` and `ZKM_DIFFERENTIAL_SEED` to
+//! fuzz for longer. A failing case is printed as a line that can be appended to the corpus.
+
+mod reference;
+
+use std::{
+    fmt::Write,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use zkm_stark::ZKMCoreOpts;
+
+use crate::{Executor, Instruction, Program, Register};
+use reference::{Reference, StepError};
+
+/// The address of the first instruction of a case.
+const CODE_BASE: u32 = 0x1000;
+
+/// The address of the data memory of a case.
+const DATA_BASE: u32 = 0x0010_0000;
+
+/// The number of words of data memory.
+const DATA_WORDS: usize = 16;
+
+/// The register holding [`DATA_BASE`]. Generated instructions never write to it, so every memory
+/// access stays inside the data memory.
+const BASE_REG: u32 = Register::GP as u32;
+
+/// The number of instructions in a generated case.
+const CASE_LEN: usize = 24;
+
+/// A differential test case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Case {
+    code: Vec<u32>,
+    regs: [u32; 32],
+    hi: u32,
+    lo: u32,
+    memory: [u32; DATA_WORDS],
+}
+
+impl Case {
+    /// Parse a case from a corpus line of `;`-separated `key=values` fields, where values are
+    /// comma-separated hex words. Only `code` is required.
+    fn parse(line: &str) -> Self {
+        let mut case =
+            Self { code: Vec::new(), regs: [0; 32], hi: 0, lo: 0, memory: [0; DATA_WORDS] };
+        case.regs[BASE_REG as usize] = DATA_BASE;
+        for field in line.split(';').map(str::trim).filter(|field| !field.is_empty()) {
+            let (key, values) = field.split_once('=').expect("expected key=values");
+            let values = values
+                .split(',')
+                .map(|value| u32::from_str_radix(value.trim(), 16).expect("expected a hex word"))
+                .collect::<Vec<_>>();
+            match key.trim() {
+                "code" => case.code = values,
+                "regs" => case.regs[..values.len()].copy_from_slice(&values),
+                "hi" => case.hi = values[0],
+                "lo" => case.lo = values[0],
+                "mem" => case.memory[..values.len()].copy_from_slice(&values),
+                key => panic!("unknown corpus field {key}"),
+            }
+        }
+        case
+    }
+
+    /// Format the case as a corpus line.
+    fn to_line(&self) -> String {
+        let hex = |values: &[u32]| {
+            values.iter().map(|value| format!("{value:x}")).collect::<Vec<_>>().join(",")
+        };
+        format!(
+            "code={}; regs={}; hi={:x}; lo={:x}; mem={}",
+            hex(&self.code),
+            hex(&self.regs),
+            self.hi,
+            self.lo,
+            hex(&self.memory)
+        )
+    }
+
+    fn program(&self) -> Program {
+        let instructions = self
+            .code
+            .iter()
+            .map(|&insn| Instruction::decode_from(insn).expect("decoding never fails"))
+            .collect();
+        let mut program = Program::new(instructions, CODE_BASE, CODE_BASE);
+        for (reg, &value) in self.regs.iter().enumerate().skip(1) {
+            program.image.insert(reg as u32, value);
+        }
+        program.image.insert(Register::LO as u32, self.lo);
+        program.image.insert(Register::HI as u32, self.hi);
+        for (i, &value) in self.memory.iter().enumerate() {
+            program.image.insert(DATA_BASE + 4 * i as u32, value);
+        }
+        program
+    }
+
+    fn reference(&self) -> Reference {
+        let mut reference = Reference::new(CODE_BASE, self.code.clone());
+        reference.regs = self.regs;
+        reference.regs[0] = 0;
+        reference.hi = self.hi;
+        reference.lo = self.lo;
+        for (i, &value) in self.memory.iter().enumerate() {
+            reference.memory.insert(DATA_BASE + 4 * i as u32, value);
+        }
+        reference
+    }
+}
+
+/// Returns a description of every difference between the executor and the reference.
+fn compare(runtime: &Executor, reference: &Reference) -> String {
+    let value = |addr: u32| runtime.state.memory.get(addr).map_or(0, |record| record.value);
+    let mut diff = String::new();
+    for reg in 0..32 {
+        if value(reg as u32) != reference.regs[reg] {
+            let (actual, expected) = (value(reg as u32), reference.regs[reg]);
+            writeln!(diff, "  ${reg}: executor {actual:#x}, reference {expected:#x}").unwrap();
+        }
+    }
+    let special = [
+        ("hi", value(Register::HI as u32), reference.hi),
+        ("lo", value(Register::LO as u32), reference.lo),
+        ("pc", runtime.state.pc, reference.pc),
+        ("next_pc", runtime.state.next_pc, reference.next_pc),
+    ];
+    for (name, actual, expected) in special {
+        if actual != expected {
+            writeln!(diff, "  {name}: executor {actual:#x}, reference {expected:#x}").unwrap();
+        }
+    }
+    for i in 0..DATA_WORDS {
+        let addr = DATA_BASE + 4 * i as u32;
+        let expected = reference.memory.get(&addr).copied().unwrap_or(0);
+        if value(addr) != expected {
+            let actual = value(addr);
+            writeln!(diff, "  [{addr:#x}]: executor {actual:#x}, reference {expected:#x}").unwrap();
+        }
+    }
+    diff
+}
+
+/// Run a case through the executor and the reference, panicking with a report on the first
+/// divergence. Execution stops early at the first instruction the reference considers
+/// unpredictable.
+fn check(case: &Case) {
+    let mut reference = case.reference();
+    let mut runtime = Executor::new(case.program(), ZKMCoreOpts::default());
+    runtime.initialize();
+
+    let mut step = 0;
+    while !reference.is_done() {
+        let pc = reference.pc;
+        match reference.step() {
+            Ok(()) => {}
+            Err(StepError::Unpredictable(_)) => return,
+            Err(StepError::Unsupported(insn)) => {
+                panic!("the reference does not support {insn:#010x}\ncase: {}", case.to_line())
+            }
+        }
+
+        let result = catch_unwind(AssertUnwindSafe(|| runtime.execute_cycle()));
+        let diff = match result {
+            Ok(Ok(_)) => compare(&runtime, &reference),
+            Ok(Err(err)) => format!("  executor error: {err}\n"),
+            Err(_) => "  executor panicked\n".to_string(),
+        };
+        assert!(
+            diff.is_empty(),
+            "divergence at step {step}, pc {pc:#x} ({:#010x}):\n{diff}case: {}",
+            case.code[((pc - CODE_BASE) / 4) as usize],
+            case.to_line()
+        );
+        step += 1;
+    }
+}
+
+fn r_type(rs: u32, rt: u32, rd: u32, sa: u32, funct: u32) -> u32 {
+    (rs << 21) | (rt << 16) | (rd << 11) | (sa << 6) | funct
+}
+
+fn i_type(op: u32, rs: u32, rt: u32, imm: u32) -> u32 {
+    (op << 26) | (rs << 21) | (rt << 16) | (imm & 0xffff)
+}
+
+/// A register which is not [`BASE_REG`].
+fn dest(rng: &mut StdRng) -> u32 {
+    let reg = rng.gen_range(0..31);
+    if reg >= BASE_REG {
+        reg + 1
+    } else {
+        reg
+    }
+}
+
+/// A random word, biased towards edge cases.
+fn word(rng: &mut StdRng) -> u32 {
+    const EDGES: [u32; 8] = [0, 1, 2, 31, 32, 0x7fff_ffff, 0x8000_0000, 0xffff_ffff];
+    if rng.gen_bool(0.3) {
+        EDGES[rng.gen_range(0..EDGES.len())]
+    } else {
+        rng.gen()
+    }
+}
+
+/// A random instruction at `index`, which is not a branch or jump if `in_delay_slot`.
+fn instruction(rng: &mut StdRng, index: usize, in_delay_slot: bool) -> u32 {
+    let (rs, rt, rd, sa) =
+        (rng.gen_range(0..32), rng.gen_range(0..32), dest(rng), rng.gen_range(0..32));
+    let imm = rng.gen::<u32>() & 0xffff;
+    let offset =
+        |rng: &mut StdRng, align: u32| rng.gen_range(0..DATA_WORDS as u32 * 4) & !(align - 1);
+
+    // Branches and jumps only target instructions after their delay slot, or the end of the case.
+    let can_branch = !in_delay_slot && index + 1 < CASE_LEN;
+    match rng.gen_range(0..if can_branch { 12 } else { 10 }) {
+        // Register-register ALU.
+        0 => {
+            const FUNCTS: [u32; 15] = [
+                0x04, 0x06, 0x07, 0x0a, 0x0b, 0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x2a,
+                0x2b,
+            ];
+            r_type(rs, rt, rd, 0, FUNCTS[rng.gen_range(0..FUNCTS.len())])
+        }
+        // Shifts and rotates by an immediate or a register.
+        1 => match rng.gen_range(0..5) {
+            0 => r_type(0, rt, rd, sa, 0x00),
+            1 => r_type(0, rt, rd, sa, 0x02),
+            2 => r_type(1, rt, rd, sa, 0x02),
+            3 => r_type(0, rt, rd, sa, 0x03),
+            _ => r_type(rs, rt, rd, 1, 0x06),
+        },
+        // Immediate ALU.
+        2 => i_type(rng.gen_range(0x08..0x10), rs, dest(rng), imm),
+        // Multiply, divide and HI/LO moves.
+        3 => match rng.gen_range(0..8) {
+            0 => r_type(rs, rt, 0, 0, rng.gen_range(0x18..0x1c)),
+            1 => r_type(rs, rt, rd, 3, rng.gen_range(0x1a..0x1c)),
+            2 => r_type(0, 0, rd, 0, [0x10, 0x12][rng.gen_range(0..2)]),
+            3 => r_type(rs, 0, 0, 0, [0x11, 0x13][rng.gen_range(0..2)]),
+            _ => (0x1c << 26) | r_type(rs, rt, 0, 0, [0x00, 0x01, 0x04, 0x05][rng.gen_range(0..4)]),
+        },
+        // SPECIAL2: MUL, CLZ, CLO.
+        4 => (0x1c << 26) | r_type(rs, rt, rd, 0, [0x02, 0x20, 0x21][rng.gen_range(0..3)]),
+        // SPECIAL3: EXT, INS, WSBH, SEB, SEH.
+        5 => {
+            let lsb = rng.gen_range(0..32);
+            match rng.gen_range(0..3) {
+                0 => {
+                    let size = rng.gen_range(1..=32 - lsb);
+                    (0x1f << 26) | r_type(rs, dest(rng), size - 1, lsb, 0x00)
+                }
+                1 => {
+                    let msb = rng.gen_range(lsb..32);
+                    (0x1f << 26) | r_type(rs, dest(rng), msb, lsb, 0x04)
+                }
+                _ => {
+                    (0x1f << 26) | r_type(0, rt, rd, [0x02, 0x10, 0x18][rng.gen_range(0..3)], 0x20)
+                }
+            }
+        }
+        // Aligned loads and stores, and LL/SC.
+        6 | 7 => {
+            let (op, align) = [
+                (0x20, 1),
+                (0x21, 2),
+                (0x23, 4),
+                (0x24, 1),
+                (0x25, 2),
+                (0x28, 1),
+                (0x29, 2),
+                (0x2b, 4),
+                (0x30, 4),
+                (0x38, 4),
+            ][rng.gen_range(0..10)];
+            let rt = if op >= 0x28 && op != 0x30 && op != 0x38 { rt } else { dest(rng) };
+            i_type(op, BASE_REG, rt, offset(rng, align))
+        }
+        // Unaligned loads and stores.
+        8 => {
+            let op = [0x22, 0x26, 0x2a, 0x2e][rng.gen_range(0..4)];
+            let rt = if op < 0x28 { dest(rng) } else { rt };
+            i_type(op, BASE_REG, rt, offset(rng, 1))
+        }
+        // No-ops.
+        9 => [0, r_type(0, 0, 0, 0, 0x0f), i_type(0x33, BASE_REG, 0, 0)][rng.gen_range(0..3)],
+        // Branches.
+        10 => {
+            let target = rng.gen_range(index + 2..=CASE_LEN);
+            let imm = (target - index - 1) as u32;
+            match rng.gen_range(0..7) {
+                0 => i_type(0x04, rs, rt, imm),
+                1 => i_type(0x05, rs, rt, imm),
+                2 => i_type(0x06, rs, 0, imm),
+                3 => i_type(0x07, rs, 0, imm),
+                4 => i_type(0x01, rs, 0x00, imm),
+                5 => i_type(0x01, rs, 0x01, imm),
+                _ => i_type(0x01, 0, 0x11, imm),
+            }
+        }
+        // Jumps.
+        _ => {
+            let target = rng.gen_range(index + 2..=CASE_LEN);
+            let op = rng.gen_range(0x02..0x04);
+            (op << 26) | ((CODE_BASE + 4 * target as u32) >> 2)
+        }
+    }
+}
+
+fn random_case(rng: &mut StdRng) -> Case {
+    let mut code = Vec::with_capacity(CASE_LEN);
+    let mut in_delay_slot = false;
+    for index in 0..CASE_LEN {
+        let insn = instruction(rng, index, in_delay_slot);
+        let op = insn >> 26;
+        in_delay_slot = matches!(op, 0x01..=0x07) && !(op == 0x01 && (insn >> 16) & 0x1f == 0x1f);
+        code.push(insn);
+    }
+
+    let mut regs = [0; 32];
+    for reg in regs.iter_mut().skip(1) {
+        *reg = word(rng);
+    }
+    regs[BASE_REG as usize] = DATA_BASE;
+    let mut memory = [0; DATA_WORDS];
+    for value in &mut memory {
+        *value = word(rng);
+    }
+    Case { code, regs, hi: word(rng), lo: word(rng), memory }
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+#[test]
+fn test_differential_corpus() {
+    let corpus = include_str!("corpus.txt");
+    let cases =
+        corpus.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+    for line in cases {
+        check(&Case::parse(line));
+    }
+}
+
+#[test]
+fn test_differential_random() {
+    let seed = env_or("ZKM_DIFFERENTIAL_SEED", 0x5eed_u64);
+    let iterations = env_or("ZKM_DIFFERENTIAL_ITERATIONS", 200_usize);
+    let mut rng = StdRng::seed_from_u64(seed);
+    for _ in 0..iterations {
+        check(&random_case(&mut rng));
+    }
+}
+
+#[test]
+fn test_case_roundtrip() {
+    let case = random_case(&mut StdRng::seed_from_u64(0));
+    assert_eq!(Case::parse(&case.to_line()), case);
+}
@@ -0,0 +1,310 @@
+//! A minimal little-endian MIPS32r2 interpreter used as the reference for differential testing.
+//!
+//! The interpreter decodes raw instruction words itself and works on bytes, so it shares no code
+//! with [`crate::Instruction::decode_from`] or the executor. It deliberately follows the zkVM where
+//! the zkVM has no traps: `ADD`/`ADDI`/`SUB` do not trap on overflow, and `SC` always succeeds.
+//! Everything the architecture leaves unpredictable is reported instead of executed.
+
+use std::collections::BTreeMap;
+
+/// Why the reference interpreter did not execute an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepError {
+    /// The instruction is outside of the subset modelled by the reference.
+    Unsupported(u32),
+    /// The result of the instruction is unpredictable on MIPS32 for the current state.
+    Unpredictable(&'static str),
+}
+
+/// The architectural state of the reference interpreter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub regs: [u32; 32],
+    pub hi: u32,
+    pub lo: u32,
+    pub pc: u32,
+    pub next_pc: u32,
+    /// Memory, as aligned words.
+    pub memory: BTreeMap<u32, u32>,
+    code_base: u32,
+    code: Vec<u32>,
+}
+
+impl Reference {
+    pub fn new(code_base: u32, code: Vec<u32>) -> Self {
+        Self {
+            regs: [0; 32],
+            hi: 0,
+            lo: 0,
+            pc: code_base,
+            next_pc: code_base + 4,
+            memory: BTreeMap::new(),
+            code_base,
+            code,
+        }
+    }
+
+    /// Whether the program counter has left the code.
+    pub fn is_done(&self) -> bool {
+        self.pc.wrapping_sub(self.code_base) >= (self.code.len() * 4) as u32
+    }
+
+    fn load_byte(&self, addr: u32) -> u8 {
+        let word = self.memory.get(&(addr & !3)).copied().unwrap_or(0);
+        word.to_le_bytes()[(addr & 3) as usize]
+    }
+
+    fn store_byte(&mut self, addr: u32, value: u8) {
+        let word = self.memory.entry(addr & !3).or_insert(0);
+        let mut bytes = word.to_le_bytes();
+        bytes[(addr & 3) as usize] = value;
+        *word = u32::from_le_bytes(bytes);
+    }
+
+    fn load(&self, addr: u32, size: u32) -> Result<u32, StepError> {
+        if addr % size != 0 {
+            return Err(StepError::Unpredictable("unaligned load"));
+        }
+        Ok((0..size).fold(0, |acc, i| acc | (u32::from(self.load_byte(addr + i)) << (8 * i))))
+    }
+
+    fn store(&mut self, addr: u32, size: u32, value: u32) -> Result<(), StepError> {
+        if addr % size != 0 {
+            return Err(StepError::Unpredictable("unaligned store"));
+        }
+        for i in 0..size {
+            self.store_byte(addr + i, value.to_le_bytes()[i as usize]);
+        }
+        Ok(())
+    }
+
+    fn set(&mut self, reg: usize, value: u32) {
+        if reg != 0 {
+            self.regs[reg] = value;
+        }
+    }
+
+    /// Execute the instruction at the program counter.
+    ///
+    /// On error, the state is left unchanged.
+    #[allow(clippy::too_many_lines)]
+    pub fn step(&mut self) -> Result<(), StepError> {
+        let insn = self.code[((self.pc - self.code_base) / 4) as usize];
+        let unsupported = Err(StepError::Unsupported(insn));
+
+        let op = insn >> 26;
+        let rs = ((insn >> 21) & 0x1f) as usize;
+        let rt = ((insn >> 16) & 0x1f) as usize;
+        let rd = ((insn >> 11) & 0x1f) as usize;
+        let sa = (insn >> 6) & 0x1f;
+        let funct = insn & 0x3f;
+        let imm = insn & 0xffff;
+        let simm = imm as u16 as i16 as i32 as u32;
+        let (s, t) = (self.regs[rs], self.regs[rt]);
+        let link = self.pc.wrapping_add(8);
+        let branch_target = self.pc.wrapping_add(4).wrapping_add(simm << 2);
+
+        let mut target = None;
+        match op {
+            0x00 => match funct {
+                0x00 if rs == 0 => self.set(rd, t << sa),
+                0x02 if rs == 0 => self.set(rd, t >> sa),
+                0x02 if rs == 1 => self.set(rd, t.rotate_right(sa)),
+                0x03 if rs == 0 => self.set(rd, ((t as i32) >> sa) as u32),
+                0x04 if sa == 0 => self.set(rd, t << (s & 0x1f)),
+                0x06 if sa == 0 => self.set(rd, t >> (s & 0x1f)),
+                0x06 if sa == 1 => self.set(rd, t.rotate_right(s & 0x1f)),
+                0x07 if sa == 0 => self.set(rd, ((t as i32) >> (s & 0x1f)) as u32),
+                0x08 => target = Some(s),
+                0x09 => {
+                    self.set(rd, link);
+                    target = Some(s);
+                }
+                0x0a => {
+                    if t == 0 {
+                        self.set(rd, s);
+                    }
+                }
+                0x0b => {
+                    if t != 0 {
+                        self.set(rd, s);
+                    }
+                }
+                0x0f => {}
+                0x10 => self.set(rd, self.hi),
+                0x11 => self.hi = s,
+                0x12 => self.set(rd, self.lo),
+                0x13 => self.lo = s,
+                0x18 => {
+                    let product = i64::from(s as i32) * i64::from(t as i32);
+                    (self.hi, self.lo) = ((product >> 32) as u32, product as u32);
+                }
+                0x19 => {
+                    let product = u64::from(s) * u64::from(t);
+                    (self.hi, self.lo) = ((product >> 32) as u32, product as u32);
+                }
+                0x1a | 0x1b if sa == 0 || sa == 3 => {
+                    if t == 0 {
+                        return Err(StepError::Unpredictable("division by zero"));
+                    }
+                    let (quotient, remainder) = if funct == 0x1a {
+                        let (s, t) = (s as i32, t as i32);
+                        if s == i32::MIN && t == -1 {
+                            return Err(StepError::Unpredictable("division overflow"));
+                        }
+                        ((s / t) as u32, (s % t) as u32)
+                    } else {
+                        (s / t, s % t)
+                    };
+                    if sa == 0 {
+                        (self.hi, self.lo) = (remainder, quotient);
+                    } else {
+                        self.set(rd, remainder);
+                    }
+                }
+                0x20 | 0x21 => self.set(rd, s.wrapping_add(t)),
+                0x22 | 0x23 => self.set(rd, s.wrapping_sub(t)),
+                0x24 => self.set(rd, s & t),
+                0x25 => self.set(rd, s | t),
+                0x26 => self.set(rd, s ^ t),
+                0x27 => self.set(rd, !(s | t)),
+                0x2a => self.set(rd, u32::from((s as i32) < (t as i32))),
+                0x2b => self.set(rd, u32::from(s < t)),
+                _ => return unsupported,
+            },
+            0x01 => match rt {
+                0x00 => target = ((s as i32) < 0).then_some(branch_target),
+                0x01 => target = ((s as i32) >= 0).then_some(branch_target),
+                0x11 if rs == 0 => {
+                    self.set(31, link);
+                    target = Some(branch_target);
+                }
+                0x1f => {}
+                _ => return unsupported,
+            },
+            0x02 | 0x03 => {
+                if op == 0x03 {
+                    self.set(31, link);
+                }
+                target =
+                    Some((self.pc.wrapping_add(4) & 0xf000_0000) | ((insn & 0x03ff_ffff) << 2));
+            }
+            0x04 => target = (s == t).then_some(branch_target),
+            0x05 => target = (s != t).then_some(branch_target),
+            0x06 => target = ((s as i32) <= 0).then_some(branch_target),
+            0x07 => target = ((s as i32) > 0).then_some(branch_target),
+            0x08 | 0x09 => self.set(rt, s.wrapping_add(simm)),
+            0x0a => self.set(rt, u32::from((s as i32) < (simm as i32))),
+            0x0b => self.set(rt, u32::from(s < simm)),
+            0x0c => self.set(rt, s & imm),
+            0x0d => self.set(rt, s | imm),
+            0x0e => self.set(rt, s ^ imm),
+            0x0f => self.set(rt, imm << 16),
+            0x1c => match funct {
+                0x00 | 0x01 | 0x04 | 0x05 => {
+                    let acc = (u64::from(self.hi) << 32) | u64::from(self.lo);
+                    let product = if funct & 1 == 0 {
+                        (i64::from(s as i32) * i64::from(t as i32)) as u64
+                    } else {
+                        u64::from(s) * u64::from(t)
+                    };
+                    let acc = if funct < 0x04 {
+                        acc.wrapping_add(product)
+                    } else {
+                        acc.wrapping_sub(product)
+                    };
+                    (self.hi, self.lo) = ((acc >> 32) as u32, acc as u32);
+                }
+                0x02 => self.set(rd, s.wrapping_mul(t)),
+                0x20 => self.set(rd, s.leading_zeros()),
+                0x21 => self.set(rd, s.leading_ones()),
+                _ => return unsupported,
+            },
+            0x1f => match funct {
+                // EXT: extract `msbd + 1` bits at `lsb`.
+                0x00 => {
+                    let (pos, size) = (sa, rd as u32 + 1);
+                    if pos + size > 32 {
+                        return Err(StepError::Unpredictable("EXT beyond bit 31"));
+                    }
+                    let field = if size == 32 { s } else { (s >> pos) & ((1 << size) - 1) };
+                    self.set(rt, field);
+                }
+                // INS: insert the low `msb - lsb + 1` bits of rs at `lsb`.
+                0x04 => {
+                    let (lsb, msb) = (sa, rd as u32);
+                    if msb < lsb {
+                        return Err(StepError::Unpredictable("INS with msb < lsb"));
+                    }
+                    let mask = ((u64::from(u32::MAX) >> (31 - (msb - lsb))) << lsb) as u32;
+                    self.set(rt, (t & !mask) | ((s << lsb) & mask));
+                }
+                0x20 => match sa {
+                    0x02 => {
+                        let [b0, b1, b2, b3] = t.to_le_bytes();
+                        self.set(rd, u32::from_le_bytes([b1, b0, b3, b2]));
+                    }
+                    0x10 => self.set(rd, t as u8 as i8 as i32 as u32),
+                    0x18 => self.set(rd, t as u16 as i16 as i32 as u32),
+                    _ => return unsupported,
+                },
+                _ => return unsupported,
+            },
+            0x20 | 0x21 | 0x23 | 0x24 | 0x25 | 0x30 => {
+                let addr = s.wrapping_add(simm);
+                let value = match op {
+                    0x20 => self.load(addr, 1)? as u8 as i8 as i32 as u32,
+                    0x21 => self.load(addr, 2)? as u16 as i16 as i32 as u32,
+                    0x24 => self.load(addr, 1)?,
+                    0x25 => self.load(addr, 2)?,
+                    _ => self.load(addr, 4)?,
+                };
+                self.set(rt, value);
+            }
+            // LWL/LWR load the bytes up to/from `addr` into the high/low end of rt.
+            0x22 | 0x26 => {
+                let addr = s.wrapping_add(simm);
+                let (base, offset) = (addr & !3, addr & 3);
+                let mut bytes = t.to_le_bytes();
+                if op == 0x22 {
+                    for i in 0..=offset {
+                        bytes[(3 - offset + i) as usize] = self.load_byte(base + i);
+                    }
+                } else {
+                    for i in offset..4 {
+                        bytes[(i - offset) as usize] = self.load_byte(base + i);
+                    }
+                }
+                self.set(rt, u32::from_le_bytes(bytes));
+            }
+            0x28 => self.store(s.wrapping_add(simm), 1, t)?,
+            0x29 => self.store(s.wrapping_add(simm), 2, t)?,
+            0x2b => self.store(s.wrapping_add(simm), 4, t)?,
+            0x38 => {
+                self.store(s.wrapping_add(simm), 4, t)?;
+                self.set(rt, 1);
+            }
+            // SWL/SWR store the high/low end of rt into the bytes up to/from `addr`.
+            0x2a | 0x2e => {
+                let addr = s.wrapping_add(simm);
+                let (base, offset) = (addr & !3, addr & 3);
+                let bytes = t.to_le_bytes();
+                if op == 0x2a {
+                    for i in 0..=offset {
+                        self.store_byte(base + i, bytes[(3 - offset + i) as usize]);
+                    }
+                } else {
+                    for i in offset..4 {
+                        self.store_byte(base + i, bytes[(i - offset) as usize]);
+                    }
+                }
+            }
+            0x33 => {}
+            _ => return unsupported,
+        }
+
+        self.pc = self.next_pc;
+        self.next_pc = target.unwrap_or(self.next_pc.wrapping_add(4));
+        Ok(())
+    }
+}
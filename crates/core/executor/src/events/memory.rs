@@ -24,7 +24,7 @@ pub struct MemoryRecord {
 ///
 /// Note: The register positions require that they be read and written in the following order:
 /// C, B, A.
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum MemoryAccessPosition {
     /// Memory access position.
     Memory = 0,
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use crate::events::{
+    memory::{MemoryReadRecord, MemoryWriteRecord},
+    MemoryLocalEvent,
+};
+
+/// BLAKE2b Compress Event.
+///
+/// This event is emitted when a BLAKE2b compress operation is performed.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Blake2bCompressEvent {
+    /// The shard number.
+    pub shard: u32,
+    /// The clock cycle.
+    pub clk: u32,
+    /// The pointer to the state.
+    pub state_ptr: u32,
+    /// The pointer to the message block.
+    pub msg_ptr: u32,
+    /// The initialized working vector, whose first half is the chaining value.
+    pub state: [u64; 16],
+    /// The message block as a list of 64-bit words.
+    pub msg: [u64; 16],
+    /// The memory records for the working vector, two per 64-bit word.
+    pub state_read_records: Vec<MemoryReadRecord>,
+    /// The memory records for the message block, two per 64-bit word.
+    pub msg_read_records: Vec<MemoryReadRecord>,
+    /// The memory records for the new chaining value, two per 64-bit word.
+    pub state_write_records: Vec<MemoryWriteRecord>,
+    /// The local memory accesses.
+    pub local_mem_access: Vec<MemoryLocalEvent>,
+}
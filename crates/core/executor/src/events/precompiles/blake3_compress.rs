@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use crate::events::{
+    memory::{MemoryReadRecord, MemoryWriteRecord},
+    MemoryLocalEvent,
+};
+
+/// BLAKE3 Compress Event.
+///
+/// This event is emitted when a BLAKE3 compress operation is performed.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Blake3CompressEvent {
+    /// The shard number.
+    pub shard: u32,
+    /// The clock cycle.
+    pub clk: u32,
+    /// The pointer to the state.
+    pub state_ptr: u32,
+    /// The pointer to the message block.
+    pub msg_ptr: u32,
+    /// The initial state, the chaining value followed by the IV, counter, block length and flags.
+    pub state: [u32; 16],
+    /// The message block as a list of words.
+    pub msg: [u32; 16],
+    /// The memory records for the initial state.
+    pub state_read_records: Vec<MemoryReadRecord>,
+    /// The memory records for the message block.
+    pub msg_read_records: Vec<MemoryReadRecord>,
+    /// The memory records for the output.
+    pub state_write_records: Vec<MemoryWriteRecord>,
+    /// The local memory accesses.
+    pub local_mem_access: Vec<MemoryLocalEvent>,
+}
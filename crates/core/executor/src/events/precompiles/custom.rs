@@ -0,0 +1,45 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::events::MemoryLocalEvent;
+
+/// Custom Precompile Event.
+///
+/// This event is emitted by precompiles registered from outside of Ziren. The precompile's own
+/// event is stored serialized, and decoded again by its chip when generating the trace.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct CustomPrecompileEvent {
+    /// The shard number.
+    pub shard: u32,
+    /// The clock cycle.
+    pub clk: u32,
+    /// The serialized event of the precompile.
+    pub data: Vec<u8>,
+    /// The local memory access events.
+    pub local_mem_access: Vec<MemoryLocalEvent>,
+}
+
+impl CustomPrecompileEvent {
+    /// Create a new [`CustomPrecompileEvent`] by serializing `event`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `event` cannot be serialized.
+    pub fn new<T: Serialize>(
+        shard: u32,
+        clk: u32,
+        event: &T,
+        local_mem_access: Vec<MemoryLocalEvent>,
+    ) -> Self {
+        let data = bincode::serialize(event).expect("failed to serialize custom event");
+        Self { shard, clk, data, local_mem_access }
+    }
+
+    /// Deserialize the event of the precompile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a serialized `T`.
+    pub fn decode<T: DeserializeOwned>(&self) -> bincode::Result<T> {
+        bincode::deserialize(&self.data)
+    }
+}
@@ -1,10 +1,49 @@
 use serde::{Deserialize, Serialize};
 
-use crate::events::{
-    memory::{MemoryReadRecord, MemoryWriteRecord},
-    MemoryLocalEvent,
+use crate::{
+    events::{
+        memory::{MemoryReadRecord, MemoryWriteRecord},
+        MemoryLocalEvent,
+    },
+    syscalls::SyscallCode,
 };
 
+/// The maximum number of memory words written by a single `SYS_READ` or `SYS_GETRANDOM`.
+///
+/// These calls return at most as many bytes as fit in these words, which is allowed since
+/// `read(2)` and `getrandom(2)` may return fewer bytes than requested. Each word is a row of the
+/// `SysRead` chip, and all the rows of a call are proven in the same shard.
+pub const SYS_READ_MAX_WORDS: usize = 1024;
+
+/// The word mixed with the seed and the counter into every word of the `SYS_GETRANDOM` stream,
+/// the first word of the BLAKE3 IV.
+pub const RANDOM_WORD_KEY: u32 = 0x6a09_e667;
+
+/// The nanoseconds that the clocks of `SYS_CLOCK_GETTIME` advance on every call, which is about
+/// 2^-16 seconds so that 2^16 ticks make up one second.
+pub const CLOCK_TICK_NANOS: u32 = 15_258;
+
+/// Returns the word `counter` of the `SYS_GETRANDOM` stream of `seed`.
+///
+/// The word is the `d` output of two rounds of the BLAKE3 `G` function without message words,
+/// applied to `(seed_lo, seed_hi, RANDOM_WORD_KEY, counter)`. It is cheap to prove, but it is
+/// not a cryptographic generator: anyone who knows the seed knows the stream.
+#[must_use]
+pub fn random_word(seed: u64, counter: u32) -> u32 {
+    let (mut a, mut b, mut c, mut d) = (seed as u32, (seed >> 32) as u32, RANDOM_WORD_KEY, counter);
+    for _ in 0..2 {
+        a = a.wrapping_add(b);
+        d = (d ^ a).rotate_right(16);
+        c = c.wrapping_add(d);
+        b = (b ^ c).rotate_right(12);
+        a = a.wrapping_add(b);
+        d = (d ^ a).rotate_right(8);
+        c = c.wrapping_add(d);
+        b = (b ^ c).rotate_right(7);
+    }
+    d
+}
+
 /// Linux Syscall Event.
 ///
 /// This event is emitted when a Linux Syscall operation is performed.
@@ -25,8 +64,28 @@ pub struct LinuxEvent {
     /// The memory records for the word.
     pub read_records: Vec<MemoryReadRecord>,
     /// The memory records for the word.
-    /// The memory records for the word.
     pub write_records: Vec<MemoryWriteRecord>,
     /// The local memory accesses.
     pub local_mem_access: Vec<MemoryLocalEvent>,
 }
+
+impl LinuxEvent {
+    /// The number of `SysRead` rows of the event: one per written buffer word, and at least one.
+    #[must_use]
+    pub fn num_rows(&self) -> usize {
+        self.write_records.len().saturating_sub(self.buffer_start()).max(1)
+    }
+
+    /// The index of the first buffer word in `write_records`.
+    ///
+    /// The buffer words come after the record of a3 and, for `SYS_GETRANDOM` and
+    /// `SYS_CLOCK_GETTIME`, after the record of their counter register.
+    #[must_use]
+    pub fn buffer_start(&self) -> usize {
+        if self.syscall_code == SyscallCode::SYS_READ.syscall_id() {
+            1
+        } else {
+            2
+        }
+    }
+}
@@ -1,3 +1,6 @@
+mod blake2b_compress;
+mod blake3_compress;
+mod custom;
 mod ec;
 mod edwards;
 mod fptower;
@@ -11,6 +14,9 @@ mod uint256;
 
 use super::{MemoryLocalEvent, SyscallEvent};
 use crate::syscalls::SyscallCode;
+pub use blake2b_compress::*;
+pub use blake3_compress::*;
+pub use custom::*;
 pub use ec::*;
 pub use edwards::*;
 pub use fptower::*;
@@ -34,6 +40,10 @@ pub enum PrecompileEvent {
     ShaCompress(ShaCompressEvent),
     /// Keccak sponge precompile event.
     KeccakSponge(KeccakSpongeEvent),
+    /// BLAKE3 compress precompile event.
+    Blake3Compress(Blake3CompressEvent),
+    /// BLAKE2b compress precompile event.
+    Blake2bCompress(Blake2bCompressEvent),
     /// Edwards curve add precompile event.
     EdAdd(EllipticCurveAddEvent),
     /// Edwards curve decompress precompile event.
@@ -82,6 +92,8 @@ pub enum PrecompileEvent {
     Poseidon2Permute(Poseidon2PermuteEvent),
     /// linux precompile event.
     Linux(LinuxEvent),
+    /// Precompile event of a precompile registered from outside of Ziren.
+    Custom(CustomPrecompileEvent),
 }
 
 /// Trait to retrieve all the local memory events from a vec of precompile events.
@@ -105,6 +117,12 @@ impl PrecompileLocalMemory for Vec<(SyscallEvent, PrecompileEvent)> {
                 PrecompileEvent::KeccakSponge(e) => {
                     iterators.push(e.local_mem_access.iter());
                 }
+                PrecompileEvent::Blake3Compress(e) => {
+                    iterators.push(e.local_mem_access.iter());
+                }
+                PrecompileEvent::Blake2bCompress(e) => {
+                    iterators.push(e.local_mem_access.iter());
+                }
                 PrecompileEvent::EdDecompress(e) => {
                     iterators.push(e.local_mem_access.iter());
                 }
@@ -148,6 +166,9 @@ impl PrecompileLocalMemory for Vec<(SyscallEvent, PrecompileEvent)> {
                 PrecompileEvent::Linux(e) => {
                     iterators.push(e.local_mem_access.iter());
                 }
+                PrecompileEvent::Custom(e) => {
+                    iterators.push(e.local_mem_access.iter());
+                }
             }
         }
 
@@ -1,9 +1,4 @@
-use std::{
-    fs::File,
-    io::{BufWriter, Write},
-    str::FromStr,
-    sync::Arc,
-};
+use std::{fs::File, io::BufWriter, str::FromStr, sync::Arc};
 
 use enum_map::EnumMap;
 use hashbrown::HashMap;
@@ -26,11 +21,14 @@ use crate::{
     hook::{HookEnv, HookRegistry},
     memory::{Entry, PagedMemory},
     pad_mips_event_counts,
+    profiler::Profiler,
     record::{ExecutionRecord, MemoryAccessRecord},
     sign_extend,
-    state::{ExecutionState, ForkState},
+    state::{ExecutionState, ForkState, LinuxEnv},
     subproof::SubproofVerifier,
     syscalls::{default_syscall_map, Syscall, SyscallCode, SyscallContext},
+    trace::{TraceAccess, TraceWriter},
+    validate::symbol_at,
     ExecutionReport, Instruction, MipsAirId, Opcode, Program, Register, NUM_REGISTERS,
 };
 
@@ -133,8 +131,8 @@ pub struct Executor<'a> {
     /// A buffer for stdout and stderr IO.
     pub io_buf: HashMap<u32, String>,
 
-    /// A buffer for writing trace events to a file.
-    pub trace_buf: Option<BufWriter<File>>,
+    /// A writer for the execution trace, see [`crate::trace`].
+    pub trace_writer: Option<TraceWriter<BufWriter<File>>>,
 
     /// The state of the runtime when in unconstrained mode.
     pub unconstrained_state: ForkState,
@@ -145,6 +143,17 @@ pub struct Executor<'a> {
     /// Statistics for event counts.
     pub local_counts: LocalCounts,
 
+    /// The cycle profiler, if profiling is enabled.
+    pub profiler: Option<Profiler>,
+
+    /// Whether to emulate floating-point instructions in [`ExecutorMode::Simple`] for programs
+    /// without the FPU trap handler, see [`crate::fpu`].
+    pub fpu_emulation: bool,
+
+    /// Whether to fail on Linux syscalls that are not recognized instead of treating them as
+    /// no-ops.
+    pub strict_syscalls: bool,
+
     /// Verifier used to sanity check `verify_zkm_proof` during runtime.
     pub subproof_verifier: Option<&'a dyn SubproofVerifier>,
 
@@ -165,6 +174,9 @@ pub struct Executor<'a> {
 
     /// The maximum LDE size to allow.
     pub lde_size_threshold: u64,
+
+    /// If set, the estimated number of events of each AIR in every finished shard.
+    pub shard_event_counts: Option<Vec<EnumMap<MipsAirId, u64>>>,
 }
 
 /// The different modes the executor can run in.
@@ -201,8 +213,32 @@ pub enum ExecutionError {
     InvalidMemoryAccess(Opcode, u32),
 
     /// The execution failed with an unimplemented syscall.
-    #[error("unimplemented syscall {0}")]
-    UnsupportedSyscall(u32),
+    #[error(
+        "unimplemented syscall {code} at pc 0x{pc:08x} in {}",
+        .symbol.as_deref().unwrap_or("an unknown function")
+    )]
+    UnsupportedSyscall {
+        /// The syscall number.
+        code: u32,
+        /// The address of the `syscall` instruction.
+        pc: u32,
+        /// The function containing the `syscall` instruction, if the ELF has a symbol table.
+        symbol: Option<String>,
+    },
+
+    /// A floating-point instruction that traps into the FPU handler was executed in a branch
+    /// delay slot, see [`crate::fpu`].
+    #[error("floating-point instruction 0x{raw:08x} at pc 0x{pc:08x} is in a branch delay slot")]
+    FpuTrapInDelaySlot {
+        /// The raw instruction.
+        raw: u32,
+        /// The address of the instruction.
+        pc: u32,
+    },
+
+    /// The execution trace could not be written.
+    #[error("failed to write the execution trace: {0}")]
+    TraceWrite(String),
 
     /// The execution failed with an unimplemented instruction.
     #[error("unimplemented instruction {0}")]
@@ -216,6 +252,15 @@ pub enum ExecutionError {
     #[error("exceeded cycle limit of {0}")]
     ExceededCycleLimit(u64),
 
+    /// A syscall was called with invalid arguments, see [`Syscall::check_args`].
+    #[error("invalid arguments for syscall {code}: {reason}")]
+    InvalidSyscallArgs {
+        /// The syscall number.
+        code: u32,
+        /// Why the arguments are invalid.
+        reason: String,
+    },
+
     /// The execution failed because the syscall was called in unconstrained mode.
     #[error("syscall called in unconstrained mode")]
     InvalidSyscallUsage(u64),
@@ -257,14 +302,15 @@ impl<'a> Executor<'a> {
         let record = ExecutionRecord::new(program.clone());
 
         // Determine the maximum number of cycles for any syscall.
-        let syscall_map = default_syscall_map();
+        let mut syscall_map = default_syscall_map();
+        syscall_map.extend(context.precompiles);
         let max_syscall_cycles =
             syscall_map.values().map(|syscall| syscall.num_extra_cycles()).max().unwrap_or(0);
 
-        // If `TRACE_FILE`` is set, initialize the trace buffer.
-        let trace_buf = if let Ok(trace_file) = std::env::var("TRACE_FILE") {
+        // If `TRACE_FILE` is set, initialize the trace writer.
+        let trace_writer = if let Ok(trace_file) = std::env::var("TRACE_FILE") {
             let file = File::create(trace_file).unwrap();
-            Some(BufWriter::new(file))
+            Some(TraceWriter::new(BufWriter::new(file), &program).unwrap())
         } else {
             None
         };
@@ -275,17 +321,21 @@ impl<'a> Executor<'a> {
         let costs: HashMap<MipsAirId, usize> =
             costs.into_iter().map(|(k, v)| (MipsAirId::from_str(&k).unwrap(), v)).collect();
 
+        let mut state = ExecutionState::new(program.pc_start, program.next_pc);
+        state.linux_env.epoch = context.epoch;
+        state.linux_env.random_seed = context.random_seed;
+
         Self {
             record,
             records: vec![],
-            state: ExecutionState::new(program.pc_start, program.next_pc),
+            state,
             program,
             memory_accesses: MemoryAccessRecord::default(),
             shard_size: (opts.shard_size as u32) * 4,
             shard_batch_size: opts.shard_batch_size as u32,
             cycle_tracker: HashMap::new(),
             io_buf: HashMap::new(),
-            trace_buf,
+            trace_writer,
             unconstrained: false,
             unconstrained_state: ForkState::default(),
             syscall_map,
@@ -294,6 +344,9 @@ impl<'a> Executor<'a> {
             max_syscall_cycles,
             report: ExecutionReport::default(),
             local_counts: LocalCounts::default(),
+            profiler: None,
+            fpu_emulation: false,
+            strict_syscalls: opts.strict_syscalls || context.strict_syscalls,
             print_report: false,
             subproof_verifier: context.subproof_verifier,
             hook_registry,
@@ -311,6 +364,7 @@ impl<'a> Executor<'a> {
             costs: costs.into_iter().map(|(k, v)| (k, v as u64)).collect(),
             shape_check_frequency: 16,
             lde_size_check: false,
+            shard_event_counts: None,
             lde_size_threshold: 0,
         }
     }
@@ -346,6 +400,26 @@ impl<'a> Executor<'a> {
         runtime
     }
 
+    /// Resume a program that ended a step of an incremental computation with
+    /// [`SyscallCode::END_STEP`] in `state`.
+    ///
+    /// The next step starts in a new shard, so that its shards can be proven on their own and
+    /// chained to the shards of the steps before it.
+    pub fn resume_step(&mut self, mut state: ExecutionState) {
+        state.paused = false;
+        state.current_shard += 1;
+        state.clk = 0;
+        self.state = state;
+    }
+
+    /// Register a precompile implemented outside of Ziren, see [`ZKMContextBuilder::precompile`].
+    ///
+    /// [`ZKMContextBuilder::precompile`]: crate::ZKMContextBuilder::precompile
+    pub fn register_precompile(&mut self, code: SyscallCode, syscall: Arc<dyn Syscall>) {
+        self.max_syscall_cycles = self.max_syscall_cycles.max(syscall.num_extra_cycles());
+        self.syscall_map.insert(code, syscall);
+    }
+
     /// Get the current value of a register, but doesn't use a memory record.
     /// Careful call it directly.
     #[must_use]
@@ -596,6 +670,10 @@ impl<'a> Executor<'a> {
         // Read the address from memory and create a memory read record.
         let record = self.mr(addr, self.shard(), self.timestamp(&position), None);
 
+        if let Some(writer) = self.trace_writer.as_mut().filter(|_| !self.unconstrained) {
+            writer.access(TraceAccess::read(addr, position, &record));
+        }
+
         if position != MemoryAccessPosition::Memory {
             // If the position is not Memory, we are reading from a register.
             log::debug!("pc: {:X} read register {}, {:X}", self.state.pc, addr, record.value);
@@ -627,6 +705,10 @@ impl<'a> Executor<'a> {
         // Read the address from memory and create a memory read record.
         let record = self.mw(addr, value, self.shard(), self.timestamp(&position), None);
 
+        if let Some(writer) = self.trace_writer.as_mut().filter(|_| !self.unconstrained) {
+            writer.access(TraceAccess::write(addr, position, &record));
+        }
+
         // If we're not in unconstrained mode, record the access for the current cycle.
         if !self.unconstrained && self.executor_mode == ExecutorMode::Trace {
             match position {
@@ -698,7 +780,7 @@ impl<'a> Executor<'a> {
             self.emit_mem_instr_event(instruction.opcode, a, b, c);
         } else if instruction.is_branch_instruction() {
             self.emit_branch_event(instruction.opcode, a, b, c, next_pc, next_next_pc);
-        } else if instruction.is_jump_instruction() {
+        } else if instruction.is_jump_instruction() || instruction.opcode == Opcode::TRAP {
             self.emit_jump_event(instruction.opcode, a, b, c, next_pc, next_next_pc);
         } else if instruction.is_syscall_instruction() {
             self.emit_syscall_event(clk, record.a, syscall_code, b, c, next_pc);
@@ -922,9 +1004,11 @@ impl<'a> Executor<'a> {
         emit_misc_dependencies(self, event);
     }
 
+    /// Create a syscall event for the syscall executed at the current program counter.
     #[inline]
     #[allow(clippy::too_many_arguments)]
-    pub(crate) fn syscall_event(
+    #[must_use]
+    pub fn syscall_event(
         &self,
         clk: u32,
         a_record: Option<MemoryRecordEnum>,
@@ -1043,6 +1127,7 @@ impl<'a> Executor<'a> {
         let mut hi_or_prev_a = None;
         let mut syscall_code = 0u32;
 
+        let is_delayslot = self.state.next_is_delayslot;
         self.state.next_is_delayslot = false;
 
         if self.executor_mode == ExecutorMode::Trace {
@@ -1122,6 +1207,15 @@ impl<'a> Executor<'a> {
                     c
                 );
 
+                if syscall == SyscallCode::SYS_NOP {
+                    if self.strict_syscalls {
+                        return Err(self.unsupported_syscall(syscall_id));
+                    }
+                    if self.print_report && !self.unconstrained {
+                        *self.report.nop_syscall_counts.entry(syscall_id).or_insert(0) += 1;
+                    }
+                }
+
                 if self.print_report && !self.unconstrained {
                     self.report.syscall_counts[syscall] += 1;
                 }
@@ -1144,6 +1238,11 @@ impl<'a> Executor<'a> {
                 *syscall_count += 1;
 
                 let syscall_impl = self.get_syscall(syscall).cloned();
+                if let Some(syscall_impl) = &syscall_impl {
+                    syscall_impl.check_args(b, c).map_err(|reason| {
+                        ExecutionError::InvalidSyscallArgs { code: syscall_id, reason }
+                    })?;
+                }
                 syscall_code = syscall.syscall_id();
                 let mut precompile_rt = SyscallContext::new(self);
                 let (precompile_next_pc, precompile_cycles, returned_exit_code) =
@@ -1171,7 +1270,7 @@ impl<'a> Executor<'a> {
                             precompile_rt.exit_code,
                         )
                     } else {
-                        return Err(ExecutionError::UnsupportedSyscall(syscall_id));
+                        return Err(self.unsupported_syscall(syscall_id));
                     };
 
                 if syscall == SyscallCode::HALT && returned_exit_code == 0 {
@@ -1265,6 +1364,15 @@ impl<'a> Executor<'a> {
                 (a, b, c, next_next_pc) = self.execute_jump_direct(instruction);
                 self.state.next_is_delayslot = true;
             }
+            // A trap has no delay slot, so the handler runs next.
+            Opcode::TRAP => {
+                if is_delayslot {
+                    let raw = instruction.raw.unwrap_or_default();
+                    return Err(ExecutionError::FpuTrapInDelaySlot { raw, pc });
+                }
+                (a, b, c, next_pc) = self.execute_trap(instruction);
+                next_next_pc = next_pc.wrapping_add(4);
+            }
 
             // Misc instructions.
             Opcode::MEQ | Opcode::MNE => {
@@ -1299,8 +1407,14 @@ impl<'a> Executor<'a> {
             }
 
             Opcode::UNIMPL => {
-                log::error!("{:X}: {:X}", self.state.pc, instruction.op_c);
-                return Err(ExecutionError::UnsupportedInstruction(instruction.op_c));
+                let target = self.execute_fpu(instruction.op_c, next_pc).inspect_err(|_| {
+                    log::error!("{:X}: {:X}", self.state.pc, instruction.op_c);
+                })?;
+                if let Some(target) = target {
+                    next_next_pc = target;
+                    self.state.next_is_delayslot = true;
+                }
+                (a, b, c) = (0, 0, 0);
             }
         }
 
@@ -1343,7 +1457,7 @@ impl<'a> Executor<'a> {
         let lo_val = self.register(32.into());
         let hi_val = self.register(33.into());
         let addend = ((hi_val as u64) << 32) + lo_val as u64;
-        let out = multiply + addend;
+        let out = multiply.wrapping_add(addend);
         let out_lo = out as u32;
         let out_hi = (out >> 32) as u32;
         self.rw(lo, out_lo, MemoryAccessPosition::A);
@@ -1363,7 +1477,7 @@ impl<'a> Executor<'a> {
         let lo_val = self.register(32.into());
         let hi_val = self.register(33.into());
         let addend = ((hi_val as u64) << 32) + lo_val as u64;
-        let out = addend - multiply;
+        let out = addend.wrapping_sub(multiply);
         let out_lo = out as u32;
         let out_hi = (out >> 32) as u32;
         self.rw(lo, out_lo, MemoryAccessPosition::A);
@@ -1383,7 +1497,7 @@ impl<'a> Executor<'a> {
         let lo_val = self.register(32.into());
         let hi_val = self.register(33.into());
         let addend = ((hi_val as u64) << 32) + lo_val as u64;
-        let out = (multiply + (addend as i64)) as u64;
+        let out = multiply.wrapping_add(addend as i64) as u64;
         let out_lo = out as u32;
         let out_hi = (out >> 32) as u32;
         self.rw(lo, out_lo, MemoryAccessPosition::A);
@@ -1403,7 +1517,7 @@ impl<'a> Executor<'a> {
         let lo_val = self.register(32.into());
         let hi_val = self.register(33.into());
         let addend = ((hi_val as u64) << 32) + lo_val as u64;
-        let out = ((addend as i64) - multiply) as u64;
+        let out = (addend as i64).wrapping_sub(multiply) as u64;
         let out_lo = out as u32;
         let out_hi = (out >> 32) as u32;
         self.rw(lo, out_lo, MemoryAccessPosition::A);
@@ -1438,7 +1552,8 @@ impl<'a> Executor<'a> {
         let b = self.rr(rt, MemoryAccessPosition::B);
         let msbd = c >> 5;
         let lsb = c & 0x1f;
-        let mask_msb = (1 << (msbd + lsb + 1)) - 1;
+        // The field may extend up to bit 31, so compute the mask in 64 bits.
+        let mask_msb = ((1u64 << (msbd + lsb + 1)) - 1) as u32;
         let a = (b & mask_msb) >> lsb;
         self.rw(rd, a, MemoryAccessPosition::A);
         (a, b, c)
@@ -1452,7 +1567,8 @@ impl<'a> Executor<'a> {
         let prev_a = a;
         let msb = c >> 5;
         let lsb = c & 0x1f;
-        let mask = (1 << (msb - lsb + 1)) - 1;
+        // The field may span all 32 bits, so compute the mask in 64 bits.
+        let mask = ((1u64 << (msb - lsb + 1)) - 1) as u32;
         let mask_field = mask << lsb;
         let a = (a & !mask_field) | ((b << lsb) & mask_field);
         self.rw(rd, a, MemoryAccessPosition::A);
@@ -1744,10 +1860,20 @@ impl<'a> Executor<'a> {
         (return_pc, offset, 0, target_pc)
     }
 
+    /// Trap into the FPU handler, linking the address of the next instruction, see [`crate::fpu`].
+    fn execute_trap(&mut self, instruction: &Instruction) -> (u32, u32, u32, u32) {
+        let (link, handler) = (instruction.op_a.into(), instruction.op_b);
+
+        let return_pc = self.state.pc.wrapping_add(4);
+        self.rw(link, return_pc, MemoryAccessPosition::A);
+
+        (return_pc, handler, 0, handler)
+    }
+
     /// Executes one cycle of the program, returning whether the program has finished.
     #[inline]
     #[allow(clippy::too_many_lines)]
-    fn execute_cycle(&mut self) -> Result<bool, ExecutionError> {
+    pub(crate) fn execute_cycle(&mut self) -> Result<bool, ExecutionError> {
         // Fetch the instruction at the current program counter.
         let instruction = self.fetch();
 
@@ -1755,8 +1881,27 @@ impl<'a> Executor<'a> {
         #[cfg(debug_assertions)]
         self.log(&instruction);
 
-        // Execute the instruction.
+        // Attribute the instruction to the current call stack.
+        if let Some(profiler) = self.profiler.as_mut() {
+            let syscall = (instruction.opcode == Opcode::SYSCALL).then(|| {
+                let syscall_id = self.state.memory.get(Register::V0 as u32).map_or(0, |r| r.value);
+                SyscallCode::from_u32(syscall_id)
+            });
+            profiler.record(self.state.pc, &instruction, syscall);
+        }
+
+        // Execute the instruction, and record it in the trace unless it is unconstrained.
+        let (pc, unconstrained) = (self.state.pc, self.unconstrained);
         self.execute_operation(&instruction)?;
+        if let Some(writer) = self.trace_writer.as_mut() {
+            if unconstrained {
+                writer.discard();
+            } else {
+                writer
+                    .record(self.state.global_clk, pc, &instruction)
+                    .map_err(|err| ExecutionError::TraceWrite(err.to_string()))?;
+            }
+        }
 
         // Increment the clock.
         self.state.global_clk += 1;
@@ -1854,6 +1999,7 @@ impl<'a> Executor<'a> {
             }
 
             if cpu_exit || !shape_match_found {
+                self.record_shard_event_counts();
                 self.state.current_shard += 1;
                 self.state.clk = 0;
                 self.bump_record();
@@ -1869,6 +2015,7 @@ impl<'a> Executor<'a> {
 
         let done = self.state.pc == 0
             || self.state.exited
+            || self.state.paused
             || self.state.pc.wrapping_sub(self.program.pc_base)
                 >= (self.program.instructions.len() * 4) as u32;
         if done && self.unconstrained {
@@ -1879,6 +2026,24 @@ impl<'a> Executor<'a> {
         Ok(done)
     }
 
+    /// Appends the estimated event counts of the current shard to `self.shard_event_counts`.
+    fn record_shard_event_counts(&mut self) {
+        let Some(shard_event_counts) = self.shard_event_counts.as_mut() else {
+            return;
+        };
+
+        // Sent syscalls aren't tracked per shard, so bound them by the syscall instructions.
+        let num_syscalls = self.local_counts.event_counts[Opcode::SYSCALL];
+        let mut event_counts = estimate_mips_event_counts(
+            (self.state.clk / 5) as u64,
+            self.local_counts.local_mem as u64,
+            num_syscalls,
+            *self.local_counts.event_counts,
+        );
+        event_counts[MipsAirId::SyscallInstrs] = num_syscalls;
+        shard_event_counts.push(event_counts);
+    }
+
     /// Bump the record.
     pub fn bump_record(&mut self) {
         self.local_counts = LocalCounts::default();
@@ -1943,7 +2108,7 @@ impl<'a> Executor<'a> {
             let memory_checkpoint = std::mem::take(&mut self.memory_checkpoint);
             let uninitialized_memory_checkpoint =
                 std::mem::take(&mut self.uninitialized_memory_checkpoint);
-            if done && !self.emit_global_memory_events {
+            if done && !self.state.paused && !self.emit_global_memory_events {
                 // If it's the last shard, and we're not emitting memory events, we need to include
                 // all memory so that memory events can be emitted from the checkpoint. But we need
                 // to first reset any modified memory to as it was before the execution.
@@ -1980,7 +2145,7 @@ impl<'a> Executor<'a> {
         Ok((checkpoint, done))
     }
 
-    fn initialize(&mut self) {
+    pub(crate) fn initialize(&mut self) {
         self.state.clk = 0;
 
         tracing::debug!("loading memory image");
@@ -2058,7 +2223,12 @@ impl<'a> Executor<'a> {
         let public_values = self.record.public_values;
 
         if done {
-            self.postprocess();
+            self.record_shard_event_counts();
+        }
+
+        // A program that ended a step is resumed later, so its memory isn't finalized yet.
+        if done && !self.state.paused {
+            self.postprocess()?;
 
             // Push the remaining execution record with memory initialize & finalize events.
             self.bump_record();
@@ -2071,6 +2241,7 @@ impl<'a> Executor<'a> {
         }
 
         // Set the global public values for all shards.
+        let LinuxEnv { epoch, random_seed } = self.state.linux_env;
         let mut last_next_pc = 0;
         let mut last_exit_code = 0;
         for (i, record) in self.records.iter_mut().enumerate() {
@@ -2078,6 +2249,8 @@ impl<'a> Executor<'a> {
             record.public_values = public_values;
             record.public_values.committed_value_digest = public_values.committed_value_digest;
             record.public_values.deferred_proofs_digest = public_values.deferred_proofs_digest;
+            record.public_values.random_seed = [random_seed as u32, (random_seed >> 32) as u32];
+            record.public_values.epoch = epoch;
             record.public_values.execution_shard = start_shard + i as u32;
             if record.cpu_events.is_empty() {
                 record.public_values.start_pc = last_next_pc;
@@ -2095,7 +2268,7 @@ impl<'a> Executor<'a> {
         Ok(done)
     }
 
-    fn postprocess(&mut self) {
+    pub(crate) fn postprocess(&mut self) -> Result<(), ExecutionError> {
         // Flush remaining stdout/stderr
         for (fd, buf) in &self.io_buf {
             if !buf.is_empty() {
@@ -2111,9 +2284,9 @@ impl<'a> Executor<'a> {
             }
         }
 
-        // Flush trace buf
-        if let Some(ref mut buf) = self.trace_buf {
-            buf.flush().unwrap();
+        // Flush the trace writer.
+        if let Some(ref mut writer) = self.trace_writer {
+            writer.flush().map_err(|err| ExecutionError::TraceWrite(err.to_string()))?;
         }
 
         // Ensure that all proofs and input bytes were read, otherwise warn the user.
@@ -2127,6 +2300,10 @@ impl<'a> Executor<'a> {
             tracing::warn!("Not all input bytes were read.");
         }
 
+        // Count the number of touched memory addresses manually, since `PagedMemory` doesn't
+        // already know its length.
+        self.report.touched_memory_addresses = self.state.memory.keys().count() as u64;
+
         if self.emit_global_memory_events
             && (self.executor_mode == ExecutorMode::Trace
                 || self.executor_mode == ExecutorMode::Checkpoint)
@@ -2150,11 +2327,7 @@ impl<'a> Executor<'a> {
                 MemoryInitializeFinalizeEvent::initialize(0, 0, addr_0_record.is_some());
             memory_initialize_events.push(addr_0_initialize_event);
 
-            // Count the number of touched memory addresses manually, since `PagedMemory` doesn't
-            // already know its length.
-            self.report.touched_memory_addresses = 0;
             for addr in self.state.memory.keys() {
-                self.report.touched_memory_addresses += 1;
                 if addr == 0 {
                     // Handled above.
                     continue;
@@ -2176,22 +2349,23 @@ impl<'a> Executor<'a> {
                     .push(MemoryInitializeFinalizeEvent::finalize_from_record(addr, &record));
             }
         }
+        Ok(())
     }
 
     fn get_syscall(&mut self, code: SyscallCode) -> Option<&Arc<dyn Syscall>> {
         self.syscall_map.get(&code)
     }
 
+    /// The error for a syscall the executor cannot run, naming the function making it.
+    fn unsupported_syscall(&self, code: u32) -> ExecutionError {
+        let pc = self.state.pc;
+        let symbol = symbol_at(&self.program.symbols, pc).map(|(name, _)| name);
+        ExecutionError::UnsupportedSyscall { code, pc, symbol }
+    }
+
     #[inline]
     #[cfg(debug_assertions)]
     fn log(&mut self, _: &Instruction) {
-        // Write the current program counter to the trace buffer for the cycle tracer.
-        if let Some(ref mut buf) = self.trace_buf {
-            if !self.unconstrained {
-                buf.write_all(&u32::to_be_bytes(self.state.pc)).unwrap();
-            }
-        }
-
         if !self.unconstrained && self.state.global_clk.is_multiple_of(10_000_000) {
             log::info!("clk = {} pc = 0x{:x?}", self.state.global_clk, self.state.pc);
         }
@@ -2225,11 +2399,16 @@ mod tests {
         secp256r1_double_program, simple_memory_program, simple_program, ssz_withdrawals_program,
         u256xu2048_mul_program,
     };
+    use std::sync::Arc;
+
     use zkm_stark::ZKMCoreOpts;
 
-    use crate::{Instruction, Opcode, Register};
+    use crate::{
+        syscalls::{Syscall, SyscallCode, SyscallContext},
+        Instruction, Opcode, Register, ZKMContext,
+    };
 
-    use super::{Executor, Program};
+    use super::{ExecutionError, Executor, Program};
 
     fn _assert_send<T: Send>() {}
 
@@ -2505,6 +2684,142 @@ mod tests {
         assert_eq!(runtime.register(Register::RA), 0);
     }
 
+    #[test]
+    fn test_custom_precompile() {
+        struct AddSyscall;
+
+        impl Syscall for AddSyscall {
+            fn execute(
+                &self,
+                _: &mut SyscallContext,
+                _: SyscallCode,
+                arg1: u32,
+                arg2: u32,
+            ) -> Option<u32> {
+                Some(arg1 + arg2)
+            }
+        }
+
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::CUSTOM_0 as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 0, 5, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, 37, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let context =
+            ZKMContext::builder().precompile(SyscallCode::CUSTOM_0, Arc::new(AddSyscall)).build();
+        let mut runtime = Executor::with_context(program, ZKMCoreOpts::default(), context);
+        runtime.run_fast().unwrap();
+        assert_eq!(runtime.register(Register::V0), 42);
+    }
+
+    #[test]
+    fn test_strict_syscalls() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 2, 0, 4999, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            Instruction::new(Opcode::ADD, 2, 0, 4999, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+
+        let mut runtime = Executor::new(program.clone(), ZKMCoreOpts::default());
+        runtime.run_fast().unwrap();
+        assert_eq!(runtime.report.nop_syscall_counts.get(&4999), Some(&2));
+
+        let context = ZKMContext::builder().strict_syscalls(true).build();
+        let mut runtime = Executor::with_context(program, ZKMCoreOpts::default(), context);
+        match runtime.run_fast() {
+            Err(ExecutionError::UnsupportedSyscall { code, pc, symbol }) => {
+                assert_eq!((code, pc, symbol), (4999, 4, None));
+            }
+            result => panic!("expected an unsupported syscall, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_blake3_compress_overlap() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 4, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, 0x1020, false, true),
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::BLAKE3_COMPRESS as u32, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+
+        let mut runtime = Executor::new(program, ZKMCoreOpts::default());
+        match runtime.run_fast() {
+            Err(ExecutionError::InvalidSyscallArgs { code, .. }) => {
+                assert_eq!(code, SyscallCode::BLAKE3_COMPRESS as u32);
+            }
+            result => panic!("expected invalid syscall arguments, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_end_step() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::END_STEP as u32, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            Instruction::new(Opcode::ADD, 16, 0, 5, false, true),
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::HALT as u32, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+
+        // The first step stops after `END_STEP` without finalizing the memory.
+        let mut runtime = Executor::new(program.clone(), ZKMCoreOpts::default());
+        runtime.run().unwrap();
+        assert!(runtime.state.paused);
+        assert!(!runtime.state.exited);
+        assert_eq!(runtime.state.pc, 8);
+        assert_eq!(runtime.register(Register::S0), 0);
+        assert!(runtime
+            .records
+            .iter()
+            .all(|record| record.global_memory_finalize_events.is_empty()));
+
+        // The second step resumes in a new shard and runs until the program halts.
+        let state = runtime.state.clone();
+        let mut runtime = Executor::new(program, ZKMCoreOpts::default());
+        runtime.resume_step(state);
+        runtime.run().unwrap();
+        assert!(runtime.state.exited);
+        assert_eq!(runtime.state.current_shard, 2);
+        assert_eq!(runtime.register(Register::S0), 5);
+        assert_eq!(runtime.records[0].public_values.start_pc, 8);
+        assert!(!runtime.records.last().unwrap().global_memory_finalize_events.is_empty());
+    }
+
+    #[test]
+    fn test_hint_after_sys_read() {
+        let instructions = vec![
+            // Read 2 bytes of stdin to 0x100.
+            Instruction::new(Opcode::ADD, 5, 0, 0x100, false, true),
+            Instruction::new(Opcode::ADD, 6, 0, 2, false, true),
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_READ as u32, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            // Hint the rest of the input to 0x200.
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYSHINTLEN as u32, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            Instruction::new(Opcode::ADD, 16, 2, 0, false, true),
+            Instruction::new(Opcode::ADD, 4, 0, 0x200, false, true),
+            Instruction::new(Opcode::ADD, 5, 16, 0, false, true),
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYSHINTREAD as u32, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+
+        let mut runtime = Executor::new(program, ZKMCoreOpts::default());
+        runtime.state.input_stream = vec![vec![1, 2, 3, 4, 5], vec![6]];
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::S0), 3);
+        assert_eq!(runtime.state.uninitialized_memory.get(0x200), Some(&0x0005_0403));
+        assert_eq!(runtime.state.input_stream_ptr, 1);
+        assert_eq!(runtime.state.input_stream_offset, 0);
+    }
+
     #[test]
     fn test_addi() {
         //     addi x29, x0, 5
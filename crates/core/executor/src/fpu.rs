@@ -0,0 +1,214 @@
+//! Floating-point (COP1) instructions.
+//!
+//! Ziren has no AIR for floating-point instructions. Instead, a guest that defines the trap
+//! handler [`FPU_TRAP_SYMBOL`] (the `zkm-zkvm` entrypoint does) runs them in software:
+//! [`Program::from`] decodes each FPU instruction to [`Opcode::TRAP`], which jumps to the handler
+//! without a delay slot and links the address of the next instruction in `$k0`. The handler
+//! emulates the instruction with [`zkm_primitives::fpu`], so it is proven like any other guest
+//! code, and keeps `$k1` equal to the condition code 0. BC1F and BC1T on that condition code are
+//! decoded to BEQ and BNE on `$k1`; the other FPU branches are not supported.
+//!
+//! A trap in a branch delay slot would return past the branch, so executing one fails with
+//! [`ExecutionError::FpuTrapInDelaySlot`], and [`Program::validate`] reports it. Compile
+//! hard-float code with `-fno-delayed-branch` to keep FPU instructions out of delay slots.
+//!
+//! Programs without the handler can still be executed with [`Executor::fpu_emulation`], which
+//! emulates COP1 instructions in the executor. This is only available in
+//! [`ExecutorMode::Simple`], i.e. for `execute`: when generating events for proving, COP1
+//! instructions are still reported as [`ExecutionError::UnsupportedInstruction`].
+
+use zkm_primitives::fpu::{fcc0_branch, FpuBus, FpuError};
+pub use zkm_primitives::fpu::{is_fpu_instruction, FpuState};
+
+use crate::{
+    events::MemoryAccessPosition, ExecutionError, Executor, ExecutorMode, Instruction, Opcode,
+    Program, Register,
+};
+
+/// The symbol of the guest's FPU trap handler.
+pub const FPU_TRAP_SYMBOL: &str = "__zkm_fpu_trap";
+
+/// Decode the FPU instruction `word` for a program whose trap handler is at `handler`.
+///
+/// Returns `None` if `word` is not an FPU instruction, or is an FPU branch that cannot be
+/// trapped.
+#[must_use]
+pub fn decode_fpu_trap(word: u32, handler: u32) -> Option<Instruction> {
+    if let Some(on_true) = fcc0_branch(word) {
+        // BNE or BEQ $k1, $zero, offset.
+        let opcode = if on_true { 0x05 } else { 0x04 };
+        let branch = (opcode << 26) | ((Register::K1 as u32) << 21) | (word & 0xffff);
+        return Instruction::decode_from(branch).ok();
+    }
+    // The remaining branches, BC1F and BC1T on other condition codes and their likely variants.
+    if word >> 21 == (0x11 << 5) | 0x08 || !is_fpu_instruction(word) {
+        return None;
+    }
+    Some(Instruction::new_with_raw(Opcode::TRAP, Register::K0 as u8, handler, 0, true, true, word))
+}
+
+impl Program {
+    /// Decode the FPU instructions of the program to traps into the handler at `handler`.
+    pub(crate) fn trap_fpu_instructions(&mut self, handler: u32) {
+        for instruction in &mut self.instructions {
+            if instruction.opcode != Opcode::UNIMPL {
+                continue;
+            }
+            if let Some(trap) = instruction.raw.and_then(|word| decode_fpu_trap(word, handler)) {
+                *instruction = trap;
+            }
+        }
+    }
+}
+
+/// The registers and memory of the executor, as accessed by an emulated FPU instruction.
+struct ExecutorBus<'a, 'b> {
+    rt: &'a mut Executor<'b>,
+    reads: usize,
+}
+
+impl FpuBus for ExecutorBus<'_, '_> {
+    fn read_register(&mut self, reg: u32) -> u32 {
+        let position =
+            if self.reads == 0 { MemoryAccessPosition::B } else { MemoryAccessPosition::C };
+        self.reads += 1;
+        self.rt.rr((reg as u8).into(), position)
+    }
+
+    fn write_register(&mut self, reg: u32, value: u32) {
+        self.rt.rw((reg as u8).into(), value, MemoryAccessPosition::A);
+    }
+
+    fn load(&mut self, addr: u32) -> u32 {
+        self.rt.mr_cpu(addr, MemoryAccessPosition::Memory)
+    }
+
+    fn store(&mut self, addr: u32, value: u32) {
+        self.rt.mw_cpu(addr, value, MemoryAccessPosition::Memory);
+    }
+}
+
+impl Executor<'_> {
+    /// Emulate the FPU instruction `word`, returning the branch target if it is a taken branch.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ExecutionError::UnsupportedInstruction`] if emulation is disabled, the executor
+    /// is not in [`ExecutorMode::Simple`], or the instruction is not supported.
+    pub(crate) fn execute_fpu(
+        &mut self,
+        word: u32,
+        next_pc: u32,
+    ) -> Result<Option<u32>, ExecutionError> {
+        if !self.fpu_emulation || self.executor_mode != ExecutorMode::Simple {
+            return Err(ExecutionError::UnsupportedInstruction(word));
+        }
+        let mut fpu = std::mem::take(&mut self.state.fpu);
+        let result = fpu.execute(word, next_pc, &mut ExecutorBus { rt: self, reads: 0 });
+        self.state.fpu = fpu;
+        result.map_err(|err| match err {
+            FpuError::Unsupported(word) => ExecutionError::UnsupportedInstruction(word),
+            FpuError::Misaligned(addr) => ExecutionError::InvalidMemoryAccess(Opcode::UNIMPL, addr),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zkm_stark::ZKMCoreOpts;
+
+    use super::decode_fpu_trap;
+    use crate::{ExecutionError, Executor, Instruction, Opcode, Program, Register};
+
+    /// Run `words` with FPU emulation enabled and return the executor.
+    fn run(words: &[u32], fpu_emulation: bool) -> Result<Executor<'static>, ExecutionError> {
+        let instructions =
+            words.iter().map(|&word| Instruction::decode_from(word).unwrap()).collect();
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Executor::new(program, ZKMCoreOpts::default());
+        runtime.fpu_emulation = fpu_emulation;
+        runtime.run_fast()?;
+        Ok(runtime)
+    }
+
+    #[test]
+    fn test_fpu_arithmetic() {
+        let runtime = run(
+            &[
+                0x3c04_3fc0, // lui $a0, 0x3fc0         (1.5)
+                0x3c05_4020, // lui $a1, 0x4020         (2.5)
+                0x4484_0000, // mtc1 $a0, $f0
+                0x4485_0800, // mtc1 $a1, $f1
+                0x4601_0082, // mul.s $f2, $f0, $f1
+                0x4600_10a1, // cvt.d.s $f2, $f2
+                0x4620_1124, // cvt.w.d $f4, $f2
+                0x4406_2000, // mfc1 $a2, $f4
+                0x4601_003c, // c.lt.s $f0, $f1
+                0x0000_3801, // movf $a3, $zero, $fcc0
+                0x0081_3801, // movt $a3, $a0, $fcc0
+            ],
+            true,
+        )
+        .unwrap();
+        // 1.5 * 2.5 = 3.75, which rounds to 4.
+        assert_eq!(runtime.register(Register::A2), 4);
+        assert_eq!(runtime.register(Register::A3), 0x3fc0_0000);
+        assert_eq!(runtime.state.fpu.fpr[3], 0x400e_0000);
+    }
+
+    #[test]
+    fn test_fpu_emulation_disabled() {
+        let err = run(&[0x4484_0000], false).err().unwrap();
+        assert!(matches!(err, ExecutionError::UnsupportedInstruction(0x4484_0000)));
+        assert_eq!(Instruction::decode_from(0x4484_0000).unwrap().opcode, Opcode::UNIMPL);
+    }
+
+    #[test]
+    fn test_decode_fpu_trap() {
+        // mtc1 $a0, $f0
+        let trap = decode_fpu_trap(0x4484_0000, 0x100).unwrap();
+        assert_eq!(trap.opcode, Opcode::TRAP);
+        assert_eq!(
+            (trap.op_a, trap.op_b, trap.raw),
+            (Register::K0 as u8, 0x100, Some(0x4484_0000))
+        );
+        // bc1t $fcc0, 3 is bne $k1, $zero, 3.
+        let branch = decode_fpu_trap(0x4501_0003, 0x100).unwrap();
+        assert_eq!(branch.opcode, Opcode::BNE);
+        assert_eq!(branch.op_a, Register::K1 as u8);
+        // bc1f $fcc1, 3 and addiu $a0, $a0, 1 are not trapped.
+        assert!(decode_fpu_trap(0x4504_0003, 0x100).is_none());
+        assert!(decode_fpu_trap(0x2484_0001, 0x100).is_none());
+    }
+
+    #[test]
+    fn test_fpu_trap() {
+        let instructions = vec![
+            Instruction::new(Opcode::TRAP, 26, 16, 0, true, true),
+            Instruction::new(Opcode::ADD, 3, 0, 1, false, true),
+            Instruction::new(Opcode::Jumpi, 0, 24, 0, true, true),
+            Instruction::new(Opcode::ADD, 0, 0, 0, false, true),
+            // The handler.
+            Instruction::new(Opcode::ADD, 2, 0, 5, false, true),
+            Instruction::new(Opcode::Jump, 0, 26, 0, false, true),
+            Instruction::new(Opcode::ADD, 0, 0, 0, false, true),
+        ];
+        let mut runtime = Executor::new(Program::new(instructions, 0, 0), ZKMCoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::K0), 4);
+        assert_eq!(runtime.register(Register::V0), 5);
+        assert_eq!(runtime.register(Register::V1), 1);
+    }
+
+    #[test]
+    fn test_fpu_trap_in_delay_slot() {
+        let instructions = vec![
+            Instruction::new(Opcode::Jumpi, 0, 8, 0, true, true),
+            Instruction::new_with_raw(Opcode::TRAP, 26, 8, 0, true, true, 0x4484_0000),
+            Instruction::new(Opcode::ADD, 0, 0, 0, false, true),
+        ];
+        let mut runtime = Executor::new(Program::new(instructions, 0, 0), ZKMCoreOpts::default());
+        let err = runtime.run().unwrap_err();
+        assert!(matches!(err, ExecutionError::FpuTrapInDelaySlot { raw: 0x4484_0000, pc: 4 }));
+    }
+}
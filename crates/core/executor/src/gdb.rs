@@ -0,0 +1,631 @@
+//! A GDB remote serial protocol (RSP) stub for the executor.
+//!
+//! [`Executor::run_with_gdb`] serves a single debugger connection on a TCP socket, so that
+//! `gdb-multiarch` can attach to a running guest:
+//!
+//! ```text
+//! (gdb) set architecture mips:isa32r2
+//! (gdb) set endian little
+//! (gdb) file path/to/guest.elf
+//! (gdb) target remote 127.0.0.1:9001
+//! ```
+//!
+//! The stub exposes the 32 general purpose registers, `lo`, `hi` and `pc`, memory reads and
+//! writes, software breakpoints, watchpoints, single-stepping and continuing. The executor runs in
+//! [`ExecutorMode::Simple`], so no events are traced while debugging.
+
+use std::{
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+};
+
+use hashbrown::HashSet;
+
+use crate::{
+    events::MemoryRecord, ExecutionError, Executor, ExecutorMode, Register, MAX_MEMORY,
+    NUM_REGISTERS,
+};
+
+/// The number of registers in GDB's `mips` register file: 32 GPRs, `sr`, `lo`, `hi`, `bad`,
+/// `cause`, `pc`, 32 FPRs, `fsr` and `fir`.
+const NUM_GDB_REGISTERS: usize = 72;
+
+/// The index of `lo` in GDB's `mips` register file.
+const GDB_LO: usize = 33;
+
+/// The index of `hi` in GDB's `mips` register file.
+const GDB_HI: usize = 34;
+
+/// The index of `pc` in GDB's `mips` register file.
+const GDB_PC: usize = 37;
+
+/// The number of cycles executed between polls for an interrupt (`Ctrl-C`) from the debugger.
+const INTERRUPT_POLL_CYCLES: u64 = 1 << 16;
+
+const SIGINT: u8 = 2;
+const SIGILL: u8 = 4;
+const SIGTRAP: u8 = 5;
+const SIGABRT: u8 = 6;
+
+/// The kind of memory access a watchpoint triggers on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WatchKind {
+    Write,
+    Read,
+    Access,
+}
+
+/// A watched memory range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Watchpoint {
+    kind: WatchKind,
+    addr: u32,
+    len: u32,
+}
+
+/// A packet received from the debugger.
+enum Packet {
+    /// A `$...#xx` command packet.
+    Command(String),
+    /// A `0x03` interrupt request.
+    Interrupt,
+}
+
+/// Why the guest stopped after being resumed.
+enum Stop {
+    Signal(u8),
+    Watch(WatchKind, u32),
+    Exited(u32),
+}
+
+impl Stop {
+    /// The stop reply packet sent to the debugger.
+    fn reply(&self) -> String {
+        match self {
+            Stop::Signal(signal) => format!("S{signal:02x}"),
+            Stop::Watch(kind, addr) => {
+                let name = match kind {
+                    WatchKind::Write => "watch",
+                    WatchKind::Read => "rwatch",
+                    WatchKind::Access => "awatch",
+                };
+                format!("T{SIGTRAP:02x}{name}:{addr:x};")
+            }
+            Stop::Exited(code) => format!("W{:02x}", code & 0xff),
+        }
+    }
+}
+
+impl Executor<'_> {
+    /// Executes the program under the control of a debugger speaking the GDB remote serial
+    /// protocol.
+    ///
+    /// Blocks until a debugger connects to `listener`, then serves its requests until it detaches
+    /// or kills the session. On detach, the program runs to completion without the debugger.
+    /// Returns the exit code of the program, or `None` if the session was killed before the
+    /// program finished.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the connection fails or if the program execution
+    /// fails.
+    pub fn run_with_gdb(&mut self, listener: &TcpListener) -> anyhow::Result<Option<u32>> {
+        let (stream, peer) = listener.accept()?;
+        stream.set_nodelay(true)?;
+        tracing::info!("gdb connected from {peer}");
+
+        self.executor_mode = ExecutorMode::Simple;
+        self.print_report = true;
+        if self.state.global_clk == 0 {
+            self.initialize();
+        }
+
+        let mut stub = GdbStub {
+            rt: self,
+            stream,
+            pending: Vec::new(),
+            breakpoints: HashSet::new(),
+            watchpoints: Vec::new(),
+            exit_code: None,
+            error: None,
+        };
+        stub.serve()
+    }
+}
+
+/// The state of a debugging session.
+struct GdbStub<'r, 'a> {
+    rt: &'r mut Executor<'a>,
+    stream: TcpStream,
+    /// Bytes received from the debugger which have not been parsed yet.
+    pending: Vec<u8>,
+    breakpoints: HashSet<u32>,
+    watchpoints: Vec<Watchpoint>,
+    /// The exit code of the guest, once it has finished.
+    exit_code: Option<u32>,
+    /// The error that stopped the guest, returned once the debugger goes away.
+    error: Option<ExecutionError>,
+}
+
+impl GdbStub<'_, '_> {
+    /// Serve debugger requests until the session ends.
+    fn serve(&mut self) -> anyhow::Result<Option<u32>> {
+        loop {
+            let command = match self.read_packet()? {
+                Some(Packet::Command(command)) => command,
+                // Interrupts are only meaningful while the guest is running.
+                Some(Packet::Interrupt) => continue,
+                None => break,
+            };
+
+            match command.as_bytes().first() {
+                Some(b'c') => {
+                    let stop = self.resume(false)?;
+                    self.send(&stop.reply())?;
+                }
+                Some(b's') => {
+                    let stop = self.resume(true)?;
+                    self.send(&stop.reply())?;
+                }
+                Some(b'D') => {
+                    self.send("OK")?;
+                    if self.error.is_none() {
+                        self.breakpoints.clear();
+                        self.watchpoints.clear();
+                        self.resume(false)?;
+                    }
+                    break;
+                }
+                Some(b'k') => break,
+                _ => {
+                    let reply = self.handle(&command);
+                    self.send(&reply)?;
+                }
+            }
+        }
+
+        match self.error.take() {
+            Some(err) => Err(err.into()),
+            None => Ok(self.exit_code),
+        }
+    }
+
+    /// Handle a request which does not resume the guest, returning the reply.
+    fn handle(&mut self, command: &str) -> String {
+        let Some(kind) = command.get(..1) else {
+            return String::new();
+        };
+        let args = &command[1..];
+        match kind {
+            "?" => format!("S{SIGTRAP:02x}"),
+            "g" => (0..NUM_GDB_REGISTERS).map(|i| self.read_register(i)).collect(),
+            "G" => {
+                for (i, chunk) in args.as_bytes().chunks(8).enumerate() {
+                    if let Some(value) = std::str::from_utf8(chunk).ok().and_then(decode_word) {
+                        self.write_register(i, value);
+                    }
+                }
+                "OK".to_string()
+            }
+            "p" => match usize::from_str_radix(args, 16) {
+                Ok(i) if i < NUM_GDB_REGISTERS => self.read_register(i),
+                _ => "E01".to_string(),
+            },
+            "P" => {
+                let parsed = args.split_once('=').and_then(|(i, value)| {
+                    Some((usize::from_str_radix(i, 16).ok()?, decode_word(value)?))
+                });
+                match parsed {
+                    Some((i, value)) if i < NUM_GDB_REGISTERS => {
+                        self.write_register(i, value);
+                        "OK".to_string()
+                    }
+                    _ => "E01".to_string(),
+                }
+            }
+            "m" => match parse_addr_len(args) {
+                Some((addr, len)) => {
+                    let bytes = (0..len)
+                        .map(|i| self.read_byte(addr.wrapping_add(i)))
+                        .collect::<Option<Vec<_>>>();
+                    bytes.map_or_else(|| "E14".to_string(), hex::encode)
+                }
+                None => "E01".to_string(),
+            },
+            "M" => {
+                let parsed = args.split_once(':').and_then(|(range, data)| {
+                    Some((parse_addr_len(range)?, hex::decode(data).ok()?))
+                });
+                match parsed {
+                    Some(((addr, len), data)) if data.len() == len as usize => {
+                        let written = data
+                            .iter()
+                            .enumerate()
+                            .all(|(i, byte)| self.write_byte(addr.wrapping_add(i as u32), *byte));
+                        let reply = if written { "OK" } else { "E14" };
+                        reply.to_string()
+                    }
+                    _ => "E01".to_string(),
+                }
+            }
+            "Z" | "z" => self.update_breakpoint(kind == "Z", args),
+            "H" => "OK".to_string(),
+            "q" => {
+                if args.starts_with("Supported") {
+                    "PacketSize=4000".to_string()
+                } else if args == "Attached" {
+                    "1".to_string()
+                } else if args == "C" {
+                    "QC1".to_string()
+                } else if args == "fThreadInfo" {
+                    "m1".to_string()
+                } else if args == "sThreadInfo" {
+                    "l".to_string()
+                } else if args.starts_with("Symbol") {
+                    "OK".to_string()
+                } else {
+                    String::new()
+                }
+            }
+            // An empty reply tells the debugger the request is not supported.
+            _ => String::new(),
+        }
+    }
+
+    /// Insert (`Z`) or remove (`z`) a breakpoint or watchpoint.
+    fn update_breakpoint(&mut self, insert: bool, args: &str) -> String {
+        let mut parts = args.split(',');
+        let (Some(kind), Some(addr), Some(len)) = (parts.next(), parts.next(), parts.next()) else {
+            return "E01".to_string();
+        };
+        let (Ok(addr), Ok(len)) = (u32::from_str_radix(addr, 16), u32::from_str_radix(len, 16))
+        else {
+            return "E01".to_string();
+        };
+
+        let kind = match kind {
+            // Software and hardware breakpoints behave the same way in the executor.
+            "0" | "1" => {
+                if insert {
+                    self.breakpoints.insert(addr);
+                } else {
+                    self.breakpoints.remove(&addr);
+                }
+                return "OK".to_string();
+            }
+            "2" => WatchKind::Write,
+            "3" => WatchKind::Read,
+            "4" => WatchKind::Access,
+            _ => return String::new(),
+        };
+        let watchpoint = Watchpoint { kind, addr, len: len.max(1) };
+        if insert {
+            self.watchpoints.push(watchpoint);
+        } else {
+            self.watchpoints.retain(|w| *w != watchpoint);
+        }
+        "OK".to_string()
+    }
+
+    /// Resume the guest until it stops, exits, or the debugger interrupts it.
+    ///
+    /// Single-stepping never stops inside a branch delay slot: stepping a branch or jump also
+    /// executes its delay slot, so the guest stops at the branch target as on hardware.
+    fn resume(&mut self, step: bool) -> anyhow::Result<Stop> {
+        if let Some(code) = self.exit_code {
+            return Ok(Stop::Exited(code));
+        }
+        if self.error.is_some() {
+            return Ok(Stop::Signal(SIGABRT));
+        }
+
+        let mut cycles = 0u64;
+        loop {
+            let watch = self.triggered_watchpoint();
+            let done = match self.rt.execute_cycle() {
+                Ok(done) => done,
+                Err(ExecutionError::HaltWithNonZeroExitCode(code)) => {
+                    self.rt.postprocess()?;
+                    self.exit_code = Some(code);
+                    return Ok(Stop::Exited(code));
+                }
+                Err(err) => {
+                    tracing::error!("execution stopped at pc 0x{:x}: {err}", self.rt.state.pc);
+                    let signal = match err {
+                        ExecutionError::UnsupportedInstruction(_) => SIGILL,
+                        _ => SIGABRT,
+                    };
+                    self.error = Some(err);
+                    return Ok(Stop::Signal(signal));
+                }
+            };
+
+            if done {
+                self.rt.postprocess()?;
+                self.exit_code = Some(0);
+                return Ok(Stop::Exited(0));
+            }
+            if let Some((kind, addr)) = watch {
+                return Ok(Stop::Watch(kind, addr));
+            }
+            if step && !self.rt.state.next_is_delayslot {
+                return Ok(Stop::Signal(SIGTRAP));
+            }
+            if self.breakpoints.contains(&self.rt.state.pc) {
+                return Ok(Stop::Signal(SIGTRAP));
+            }
+
+            cycles += 1;
+            if !step && cycles.is_multiple_of(INTERRUPT_POLL_CYCLES) && self.interrupted()? {
+                return Ok(Stop::Signal(SIGINT));
+            }
+        }
+    }
+
+    /// Returns the watchpoint hit by the instruction at the current pc, if any.
+    fn triggered_watchpoint(&self) -> Option<(WatchKind, u32)> {
+        if self.watchpoints.is_empty() {
+            return None;
+        }
+
+        let instruction = self.rt.program.fetch(self.rt.state.pc);
+        let is_load = instruction.is_memory_load_instruction();
+        let is_store = instruction.is_memory_store_instruction();
+        if !is_load && !is_store {
+            return None;
+        }
+
+        // Loads and stores address `rs + offset`, and always access the aligned word.
+        let base = self.peek(instruction.op_b);
+        let addr = base.wrapping_add(instruction.op_c) & !3;
+        self.watchpoints
+            .iter()
+            .find(|w| {
+                let kind_matches = match w.kind {
+                    WatchKind::Write => is_store,
+                    WatchKind::Read => is_load,
+                    WatchKind::Access => true,
+                };
+                kind_matches
+                    && w.addr.checked_add(w.len).is_none_or(|end| addr < end)
+                    && addr.checked_add(4).is_none_or(|end| w.addr < end)
+            })
+            .map(|w| (w.kind, addr.max(w.addr)))
+    }
+
+    /// Read register `i` in GDB's numbering, hex-encoded in target byte order.
+    fn read_register(&self, i: usize) -> String {
+        let value = match i {
+            0..=31 => self.peek(i as u32),
+            GDB_LO => self.peek(Register::LO as u32),
+            GDB_HI => self.peek(Register::HI as u32),
+            GDB_PC => self.rt.state.pc,
+            // The executor has no coprocessor or FPU state.
+            _ => return "xxxxxxxx".to_string(),
+        };
+        hex::encode(value.to_le_bytes())
+    }
+
+    /// Write register `i` in GDB's numbering. Writes to unknown registers are ignored.
+    fn write_register(&mut self, i: usize, value: u32) {
+        match i {
+            // Register 0 is hardwired to zero.
+            0 => {}
+            1..=31 => self.poke(i as u32, value),
+            GDB_LO => self.poke(Register::LO as u32, value),
+            GDB_HI => self.poke(Register::HI as u32, value),
+            GDB_PC => {
+                self.rt.state.pc = value;
+                self.rt.state.next_pc = value.wrapping_add(4);
+            }
+            _ => {}
+        }
+    }
+
+    /// Read a byte of guest memory, or `None` if the address is outside of guest memory.
+    fn read_byte(&self, addr: u32) -> Option<u8> {
+        let word = align_guest_addr(addr)?;
+        Some((self.peek(word) >> ((addr % 4) * 8)) as u8)
+    }
+
+    /// Write a byte of guest memory, returning whether the address is inside guest memory.
+    fn write_byte(&mut self, addr: u32, byte: u8) -> bool {
+        let Some(word) = align_guest_addr(addr) else {
+            return false;
+        };
+        let shift = (addr % 4) * 8;
+        let value = (self.peek(word) & !(0xff << shift)) | (u32::from(byte) << shift);
+        self.poke(word, value);
+        true
+    }
+
+    /// Read a word of memory without recording an access.
+    fn peek(&self, addr: u32) -> u32 {
+        match self.rt.state.memory.get(addr) {
+            Some(record) => record.value,
+            None => self.rt.state.uninitialized_memory.get(addr).copied().unwrap_or(0),
+        }
+    }
+
+    /// Write a word of memory without recording an access.
+    fn poke(&mut self, addr: u32, value: u32) {
+        match self.rt.state.memory.get_mut(addr) {
+            Some(record) => record.value = value,
+            None => {
+                self.rt.state.memory.insert(addr, MemoryRecord { value, shard: 0, timestamp: 0 });
+            }
+        }
+    }
+
+    /// Checks, without blocking, whether the debugger has sent an interrupt.
+    fn interrupted(&mut self) -> io::Result<bool> {
+        self.stream.set_nonblocking(true)?;
+        let mut buf = [0u8; 64];
+        let read = self.stream.read(&mut buf);
+        self.stream.set_nonblocking(false)?;
+        match read {
+            Ok(n) => {
+                self.pending.extend_from_slice(&buf[..n]);
+                Ok(self.pending.contains(&0x03))
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Read the next packet, acknowledging it. Returns `None` once the connection is closed.
+    fn read_packet(&mut self) -> io::Result<Option<Packet>> {
+        loop {
+            // Skip acknowledgements and any noise before the start of a packet.
+            if let Some(pos) = self.pending.iter().position(|&b| b == b'$' || b == 0x03) {
+                if self.pending[pos] == 0x03 {
+                    self.pending.drain(..=pos);
+                    return Ok(Some(Packet::Interrupt));
+                }
+                if let Some(end) = self.pending[pos..].iter().position(|&b| b == b'#') {
+                    let end = pos + end;
+                    if self.pending.len() >= end + 3 {
+                        let packet = self.pending.drain(..end + 3).collect::<Vec<_>>();
+                        let body = &packet[pos + 1..end];
+                        let checksum = std::str::from_utf8(&packet[end + 1..])
+                            .ok()
+                            .and_then(|s| u8::from_str_radix(s, 16).ok());
+                        if checksum != Some(checksum_of(body)) {
+                            self.stream.write_all(b"-")?;
+                            continue;
+                        }
+                        self.stream.write_all(b"+")?;
+                        return Ok(Some(Packet::Command(
+                            String::from_utf8_lossy(&unescape(body)).into_owned(),
+                        )));
+                    }
+                }
+            } else {
+                self.pending.clear();
+            }
+
+            let mut buf = [0u8; 4096];
+            let n = self.stream.read(&mut buf)?;
+            if n == 0 {
+                return Ok(None);
+            }
+            self.pending.extend_from_slice(&buf[..n]);
+        }
+    }
+
+    /// Send a packet to the debugger.
+    fn send(&mut self, body: &str) -> io::Result<()> {
+        let packet = format!("${body}#{:02x}", checksum_of(body.as_bytes()));
+        self.stream.write_all(packet.as_bytes())?;
+        self.stream.flush()
+    }
+}
+
+/// The word-aligned address containing `addr`, or `None` if it is outside of guest memory.
+///
+/// The lowest words alias the registers in `PagedMemory`, so they are not exposed as memory.
+fn align_guest_addr(addr: u32) -> Option<u32> {
+    let word = addr & !3;
+    (word >= NUM_REGISTERS as u32 && word < MAX_MEMORY as u32).then_some(word)
+}
+
+/// The modulo-256 sum of the packet body.
+fn checksum_of(body: &[u8]) -> u8 {
+    body.iter().fold(0u8, |sum, b| sum.wrapping_add(*b))
+}
+
+/// Undo the `}`-escaping of binary data in a packet body.
+fn unescape(body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len());
+    let mut iter = body.iter();
+    while let Some(&b) = iter.next() {
+        if b == b'}' {
+            if let Some(&next) = iter.next() {
+                out.push(next ^ 0x20);
+            }
+        } else {
+            out.push(b);
+        }
+    }
+    out
+}
+
+/// Decode a register value sent in target (little-endian) byte order.
+fn decode_word(s: &str) -> Option<u32> {
+    let bytes: [u8; 4] = hex::decode(s).ok()?.try_into().ok()?;
+    Some(u32::from_le_bytes(bytes))
+}
+
+/// Parse an `addr,length` pair.
+fn parse_addr_len(s: &str) -> Option<(u32, u32)> {
+    let (addr, len) = s.split_once(',')?;
+    Some((u32::from_str_radix(addr, 16).ok()?, u32::from_str_radix(len, 16).ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+    };
+
+    use zkm_stark::ZKMCoreOpts;
+
+    use super::checksum_of;
+    use crate::{Executor, Instruction, Opcode, Program, Register};
+
+    /// Send a packet and return the reply, skipping acknowledgements.
+    fn request(stream: &mut TcpStream, body: &str) -> String {
+        let packet = format!("${body}#{:02x}", checksum_of(body.as_bytes()));
+        stream.write_all(packet.as_bytes()).unwrap();
+
+        let mut reply = Vec::new();
+        let mut byte = [0u8; 1];
+        loop {
+            stream.read_exact(&mut byte).unwrap();
+            match byte[0] {
+                b'+' if reply.is_empty() => {}
+                b'#' => {
+                    let mut checksum = [0u8; 2];
+                    stream.read_exact(&mut checksum).unwrap();
+                    stream.write_all(b"+").unwrap();
+                    return String::from_utf8(reply[1..].to_vec()).unwrap();
+                }
+                b => reply.push(b),
+            }
+        }
+    }
+
+    #[test]
+    fn test_gdb_step_and_continue() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 5, false, true),
+            Instruction::new(Opcode::ADD, 30, 0, 37, false, true),
+            Instruction::new(Opcode::ADD, 31, 30, 29, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Executor::new(program, ZKMCoreOpts::default());
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            assert_eq!(request(&mut stream, "?"), "S05");
+            assert_eq!(request(&mut stream, "s"), "S05");
+            // pc is register 37 and sp is register 29, both in target byte order.
+            assert_eq!(request(&mut stream, "p25"), "04000000");
+            assert_eq!(request(&mut stream, "p1d"), "05000000");
+            assert_eq!(request(&mut stream, "Z0,8,4"), "OK");
+            assert_eq!(request(&mut stream, "c"), "S05");
+            assert_eq!(request(&mut stream, "p25"), "08000000");
+            assert_eq!(request(&mut stream, "z0,8,4"), "OK");
+            assert_eq!(request(&mut stream, "c"), "W00");
+            // The stub closes the connection instead of replying to a kill request.
+            stream.write_all(format!("$k#{:02x}", checksum_of(b"k")).as_bytes()).unwrap();
+        });
+
+        assert_eq!(runtime.run_with_gdb(&listener).unwrap(), Some(0));
+        client.join().unwrap();
+        assert_eq!(runtime.register(Register::RA), 42);
+    }
+}
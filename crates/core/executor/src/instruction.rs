@@ -449,6 +449,10 @@ impl Instruction {
             (0b011111, 0b000000) => {
                 Ok(Self::new(Opcode::EXT, rt as u8, rs, (rd as u32) << 5 | sa, false, true))
             }
+            // INS of all 32 bits is a move, which the INS chip cannot shift by 32.
+            (0b011111, 0b000100) if rd == 31 && sa == 0 => {
+                Ok(Self::new(Opcode::ADD, rt as u8, rs, 0, false, true))
+            }
             // INS
             (0b011111, 0b000100) => {
                 Ok(Self::new(Opcode::INS, rt as u8, rs, (rd as u32) << 5 | sa, false, true))
@@ -1,4 +1,4 @@
-use std::io::Read;
+use std::{collections::BTreeMap, io::Read};
 
 use serde::{de::DeserializeOwned, Serialize};
 use zkm_stark::{koala_bear_poseidon2::KoalaBearPoseidon2, StarkVerifyingKey};
@@ -33,6 +33,15 @@ impl Executor<'_> {
         }
     }
 
+    /// Add files to the virtual filesystem, keyed by the path the program opens them with.
+    ///
+    /// The files are a hint: they are not committed, and the file syscalls are not proven.
+    pub fn write_files(&mut self, files: &BTreeMap<String, Vec<u8>>) {
+        for (path, contents) in files {
+            self.state.files.insert(path.clone(), contents.clone());
+        }
+    }
+
     /// Write a proof and verifying key to the proof stream.
     pub fn write_proof(
         &mut self,
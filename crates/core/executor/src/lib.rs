@@ -2,13 +2,18 @@ mod air;
 mod context;
 mod cost;
 mod dependencies;
+#[cfg(test)]
+mod differential;
 pub mod events;
 mod executor;
+pub mod fpu;
+mod gdb;
 pub mod hook;
 mod instruction;
 mod io;
 pub mod memory;
 mod opcode;
+pub mod profiler;
 mod program;
 #[cfg(test)]
 pub mod programs;
@@ -19,7 +24,9 @@ pub mod report;
 mod state;
 pub mod subproof;
 pub mod syscalls;
+pub mod trace;
 mod utils;
+mod validate;
 
 pub use air::*;
 pub use context::*;
@@ -36,3 +43,4 @@ pub use report::*;
 pub use state::*;
 pub use subproof::*;
 pub use utils::*;
+pub use validate::*;
@@ -73,6 +73,8 @@ pub enum Opcode {
     MODU = 53,  // DIVREM
     MADD = 54,  // MADDSUB
     MSUB = 55,  // MADDSUB
+    // Control Flow
+    TRAP = 56, // JUMP
     UNIMPL = 0xff,
 }
 
@@ -137,6 +139,7 @@ impl Opcode {
             Opcode::MODU => "modu",
             Opcode::MADD => "madd",
             Opcode::MSUB => "msub",
+            Opcode::TRAP => "trap",
             Opcode::UNIMPL => "unimpl",
         }
     }
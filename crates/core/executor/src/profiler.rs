@@ -0,0 +1,444 @@
+//! A symbol-aware cycle profiler for guest programs.
+//!
+//! The [`Profiler`] attributes every executed instruction to a call stack reconstructed from the
+//! ELF symbol table and the guest's control flow: linking jumps (`jal`, `bal`, `jalr`) push a
+//! frame once their delay slot has executed, `jr $ra` pops one, and any other transfer into a
+//! different function is treated as a tail call or an unwind. Syscalls are recorded as a leaf frame
+//! below their call site, so precompile usage shows up in the flamegraph.
+//!
+//! Results can be written as folded stacks for `inferno`/`flamegraph.pl`, or as a pprof protobuf
+//! for `go tool pprof`.
+
+use std::{fs::File, io::Write, path::Path};
+
+use anyhow::{anyhow, Result};
+use elf::{endian::LittleEndian, ElfBytes};
+use hashbrown::HashMap;
+use prost::Message;
+
+use crate::{program::function_symbols, syscalls::SyscallCode, Instruction, Opcode, Register};
+
+/// The index of the root node of the call tree.
+const ROOT: usize = 0;
+
+/// The frame used for instructions outside of any known function.
+const UNKNOWN: usize = 0;
+
+/// A function from the ELF symbol table.
+#[derive(Debug, Clone)]
+struct Function {
+    start: u32,
+    end: u32,
+    frame: usize,
+}
+
+/// A node of the call tree, identified by the path of frames from the root.
+#[derive(Debug, Clone)]
+struct Node {
+    parent: usize,
+    frame: usize,
+    children: HashMap<usize, usize>,
+    cycles: u64,
+}
+
+/// A control transfer whose effect is applied once the delay slot has executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transfer {
+    Call,
+    Return,
+}
+
+/// The number of times a syscall was invoked from a given call site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyscallSite {
+    /// The call stack of the call site, from the outermost frame, separated by `;`.
+    pub stack: String,
+    /// The program counter of the `syscall` instruction.
+    pub pc: u32,
+    /// The syscall that was invoked.
+    pub syscall: SyscallCode,
+    /// The number of invocations.
+    pub count: u64,
+}
+
+/// A profiler attributing executed instructions to guest functions.
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    /// The names of the frames. Frame [`UNKNOWN`] is used for addresses outside of any function.
+    frames: Vec<String>,
+    /// The functions of the program, sorted by start address.
+    functions: Vec<Function>,
+    /// The frames used for syscalls.
+    syscall_frames: HashMap<SyscallCode, usize>,
+    /// The call tree.
+    nodes: Vec<Node>,
+    /// The node of the instruction being executed.
+    current: usize,
+    /// A pending call or return and the number of instructions until it takes effect.
+    pending: Option<(Transfer, u8)>,
+    /// Only every `sample_interval`-th instruction is sampled.
+    sample_interval: u64,
+    /// The number of instructions seen so far.
+    instructions: u64,
+    /// Syscall invocations, keyed by call tree node, pc and syscall.
+    syscall_sites: HashMap<(usize, u32, SyscallCode), u64>,
+}
+
+impl Profiler {
+    /// Create a profiler for the given ELF which samples every `sample_interval`-th instruction.
+    ///
+    /// A `sample_interval` of 1 attributes every instruction exactly.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the ELF or its symbol table cannot be parsed.
+    pub fn new(elf_code: &[u8], sample_interval: u64) -> Result<Self> {
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(elf_code)
+            .map_err(|err| anyhow!("Elf parse error: {err}"))?;
+
+        let mut frames = vec!["[unknown]".to_string()];
+        let mut frame_ids: HashMap<String, usize> = HashMap::new();
+        let mut functions = Vec::new();
+        for symbol in function_symbols(&elf)? {
+            let frame = *frame_ids.entry(symbol.name.clone()).or_insert_with(|| {
+                frames.push(symbol.name);
+                frames.len() - 1
+            });
+            functions.push(Function { start: symbol.start, end: symbol.end, frame });
+        }
+
+        let root = Node { parent: ROOT, frame: UNKNOWN, children: HashMap::new(), cycles: 0 };
+        Ok(Self {
+            frames,
+            functions,
+            syscall_frames: HashMap::new(),
+            nodes: vec![root],
+            current: ROOT,
+            pending: None,
+            sample_interval: sample_interval.max(1),
+            instructions: 0,
+            syscall_sites: HashMap::new(),
+        })
+    }
+
+    /// Record the execution of `instruction` at `pc`.
+    ///
+    /// `syscall` is the syscall invoked by the instruction, if it is a `syscall` instruction.
+    pub fn record(&mut self, pc: u32, instruction: &Instruction, syscall: Option<SyscallCode>) {
+        let frame = self.frame_at(pc);
+
+        match self.pending {
+            Some((transfer, 1)) => {
+                self.pending = None;
+                match transfer {
+                    Transfer::Call => self.current = self.child(self.current, frame),
+                    Transfer::Return => {
+                        self.current = self.nodes[self.current].parent;
+                        self.enter(frame);
+                    }
+                }
+            }
+            Some((transfer, n)) => {
+                self.pending = Some((transfer, n - 1));
+                self.enter(frame);
+            }
+            None => self.enter(frame),
+        }
+
+        // A jump takes effect after its delay slot, i.e. two instructions from now. An FPU trap
+        // has no delay slot, and its handler returns with `jr $k0`.
+        if instruction.opcode == Opcode::TRAP {
+            self.pending = Some((Transfer::Call, 1));
+        } else if instruction.is_jump_instruction() {
+            let link = instruction.op_a != Register::ZERO as u8;
+            let returns = instruction.opcode == Opcode::Jump
+                && !link
+                && (instruction.op_b == Register::RA as u32
+                    || instruction.op_b == Register::K0 as u32);
+            if link {
+                self.pending = Some((Transfer::Call, 2));
+            } else if returns {
+                self.pending = Some((Transfer::Return, 2));
+            }
+        }
+
+        let mut node = self.current;
+        if let Some(syscall) = syscall {
+            *self.syscall_sites.entry((self.current, pc, syscall)).or_insert(0) += 1;
+            let frame = *self.syscall_frames.entry(syscall).or_insert_with(|| {
+                self.frames.push(format!("[syscall {syscall:?}]"));
+                self.frames.len() - 1
+            });
+            node = self.child(self.current, frame);
+        }
+
+        self.instructions += 1;
+        if self.instructions.is_multiple_of(self.sample_interval) {
+            self.nodes[node].cycles += self.sample_interval;
+        }
+    }
+
+    /// Move to `frame` without a call: either stay in the current function, return to a caller
+    /// further up the stack, or replace the current frame (a tail call).
+    fn enter(&mut self, frame: usize) {
+        if self.current != ROOT && self.nodes[self.current].frame == frame {
+            return;
+        }
+
+        let mut node = self.current;
+        while node != ROOT {
+            if self.nodes[node].frame == frame {
+                self.current = node;
+                return;
+            }
+            node = self.nodes[node].parent;
+        }
+
+        let parent = self.nodes[self.current].parent;
+        self.current = self.child(parent, frame);
+    }
+
+    /// Returns the child of `parent` for `frame`, creating it if needed.
+    fn child(&mut self, parent: usize, frame: usize) -> usize {
+        if let Some(&child) = self.nodes[parent].children.get(&frame) {
+            return child;
+        }
+        let child = self.nodes.len();
+        self.nodes.push(Node { parent, frame, children: HashMap::new(), cycles: 0 });
+        self.nodes[parent].children.insert(frame, child);
+        child
+    }
+
+    /// Returns the frame of the function containing `pc`.
+    fn frame_at(&self, pc: u32) -> usize {
+        let idx = self.functions.partition_point(|f| f.start <= pc);
+        match idx.checked_sub(1).map(|i| &self.functions[i]) {
+            Some(function) if pc < function.end => function.frame,
+            _ => UNKNOWN,
+        }
+    }
+
+    /// Returns the frames from the root to `node`, excluding the root.
+    fn path(&self, mut node: usize) -> Vec<usize> {
+        let mut path = Vec::new();
+        while node != ROOT {
+            path.push(self.nodes[node].frame);
+            node = self.nodes[node].parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Returns the `;`-separated names of the frames from the root to `node`.
+    fn stack(&self, node: usize) -> String {
+        self.path(node).iter().map(|&f| self.frames[f].as_str()).collect::<Vec<_>>().join(";")
+    }
+
+    /// The number of instructions recorded.
+    #[must_use]
+    pub fn total_instructions(&self) -> u64 {
+        self.instructions
+    }
+
+    /// The profile in the folded-stack format, one `stack cycles` line per call path.
+    #[must_use]
+    pub fn folded_stacks(&self) -> String {
+        let mut lines = (1..self.nodes.len())
+            .filter(|&node| self.nodes[node].cycles > 0)
+            .map(|node| format!("{} {}", self.stack(node), self.nodes[node].cycles))
+            .collect::<Vec<_>>();
+        lines.sort();
+        lines.join("\n")
+    }
+
+    /// The syscalls invoked by the program, by call site, most frequent first.
+    #[must_use]
+    pub fn syscall_sites(&self) -> Vec<SyscallSite> {
+        let mut sites = self
+            .syscall_sites
+            .iter()
+            .map(|(&(node, pc, syscall), &count)| SyscallSite {
+                stack: self.stack(node),
+                pc,
+                syscall,
+                count,
+            })
+            .collect::<Vec<_>>();
+        sites.sort_by(|a, b| b.count.cmp(&a.count).then(a.pc.cmp(&b.pc)));
+        sites
+    }
+
+    /// The profile encoded as a pprof `Profile` protobuf message.
+    #[must_use]
+    pub fn pprof(&self) -> Vec<u8> {
+        // String 0 must be empty.
+        let mut string_table = vec![String::new(), "cycles".to_string(), "count".to_string()];
+        let string_base = string_table.len() as i64;
+        string_table.extend(self.frames.iter().cloned());
+
+        // One function and one location per frame, with ids starting at 1.
+        let function = (0..self.frames.len())
+            .map(|f| pprof::Function {
+                id: f as u64 + 1,
+                name: string_base + f as i64,
+                system_name: string_base + f as i64,
+                ..Default::default()
+            })
+            .collect();
+        let location = (0..self.frames.len())
+            .map(|f| pprof::Location {
+                id: f as u64 + 1,
+                line: vec![pprof::Line { function_id: f as u64 + 1, line: 0 }],
+                ..Default::default()
+            })
+            .collect();
+
+        // Samples list their locations from the leaf to the root.
+        let sample = (1..self.nodes.len())
+            .filter(|&node| self.nodes[node].cycles > 0)
+            .map(|node| pprof::Sample {
+                location_id: self.path(node).iter().rev().map(|&f| f as u64 + 1).collect(),
+                value: vec![self.nodes[node].cycles as i64],
+            })
+            .collect();
+
+        let cycles = pprof::ValueType { r#type: 1, unit: 2 };
+        pprof::Profile {
+            sample_type: vec![cycles.clone()],
+            sample,
+            location,
+            function,
+            string_table,
+            period_type: Some(cycles),
+            period: self.sample_interval as i64,
+        }
+        .encode_to_vec()
+    }
+
+    /// Write the folded stacks to `path`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be written.
+    pub fn write_folded_stacks(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let mut file = File::create(path)?;
+        writeln!(file, "{}", self.folded_stacks())
+    }
+
+    /// Write the pprof protobuf to `path`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be written.
+    pub fn write_pprof(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.pprof())
+    }
+}
+
+/// The subset of the pprof `profile.proto` messages written by the profiler.
+mod pprof {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Profile {
+        #[prost(message, repeated, tag = "1")]
+        pub sample_type: Vec<ValueType>,
+        #[prost(message, repeated, tag = "2")]
+        pub sample: Vec<Sample>,
+        #[prost(message, repeated, tag = "4")]
+        pub location: Vec<Location>,
+        #[prost(message, repeated, tag = "5")]
+        pub function: Vec<Function>,
+        #[prost(string, repeated, tag = "6")]
+        pub string_table: Vec<String>,
+        #[prost(message, optional, tag = "11")]
+        pub period_type: Option<ValueType>,
+        #[prost(int64, tag = "12")]
+        pub period: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ValueType {
+        #[prost(int64, tag = "1")]
+        pub r#type: i64,
+        #[prost(int64, tag = "2")]
+        pub unit: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Sample {
+        #[prost(uint64, repeated, tag = "1")]
+        pub location_id: Vec<u64>,
+        #[prost(int64, repeated, tag = "2")]
+        pub value: Vec<i64>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Location {
+        #[prost(uint64, tag = "1")]
+        pub id: u64,
+        #[prost(uint64, tag = "2")]
+        pub mapping_id: u64,
+        #[prost(uint64, tag = "3")]
+        pub address: u64,
+        #[prost(message, repeated, tag = "4")]
+        pub line: Vec<Line>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Line {
+        #[prost(uint64, tag = "1")]
+        pub function_id: u64,
+        #[prost(int64, tag = "2")]
+        pub line: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Function {
+        #[prost(uint64, tag = "1")]
+        pub id: u64,
+        #[prost(int64, tag = "2")]
+        pub name: i64,
+        #[prost(int64, tag = "3")]
+        pub system_name: i64,
+        #[prost(int64, tag = "4")]
+        pub filename: i64,
+        #[prost(int64, tag = "5")]
+        pub start_line: i64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use test_artifacts::KECCAK_SPONGE_ELF;
+    use zkm_stark::ZKMCoreOpts;
+
+    use super::{pprof, Profiler};
+    use crate::{syscalls::SyscallCode, Executor, Program};
+
+    #[test]
+    fn test_profile_keccak_sponge() {
+        let program = Program::from(KECCAK_SPONGE_ELF).unwrap();
+        let mut runtime = Executor::new(program, ZKMCoreOpts::default());
+        runtime.profiler = Some(Profiler::new(KECCAK_SPONGE_ELF, 1).unwrap());
+        runtime.run_fast().unwrap();
+
+        let profiler = runtime.profiler.take().unwrap();
+        assert_eq!(profiler.total_instructions(), runtime.state.global_clk);
+
+        // Every instruction is attributed to exactly one stack.
+        let folded = profiler.folded_stacks();
+        let total: u64 = folded
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap().1.parse::<u64>().unwrap())
+            .sum();
+        assert_eq!(total, runtime.state.global_clk);
+        assert!(folded.contains("main"));
+
+        let sites = profiler.syscall_sites();
+        assert!(sites.iter().any(|site| site.syscall == SyscallCode::KECCAK_SPONGE));
+
+        let profile = pprof::Profile::decode(profiler.pprof().as_slice()).unwrap();
+        let sampled: i64 = profile.sample.iter().map(|sample| sample.value[0]).sum();
+        assert_eq!(sampled as u64, total);
+    }
+}
@@ -21,7 +21,7 @@ use zkm_stark::septic_extension::SepticExtension;
 use zkm_stark::shape::Shape;
 use zkm_stark::LookupKind;
 
-use crate::{Instruction, MipsAirId, Register};
+use crate::{fpu::FPU_TRAP_SYMBOL, Instruction, MipsAirId, Register};
 
 pub const MAX_MEMORY: usize = 0x7F000000;
 pub const MAX_CODE_MEMORY: usize = 0x3F000000;
@@ -29,7 +29,7 @@ pub const INIT_SP: u32 = MAX_MEMORY as u32 - 0x4000;
 pub const WORD_SIZE: usize = core::mem::size_of::<u32>();
 
 /// A program that can be executed by the ZKM.
-#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Program {
     pub instructions: Vec<Instruction>,
     /// The entrypoint of the program, PC
@@ -40,6 +40,22 @@ pub struct Program {
     pub image: BTreeMap<u32, u32>,
     /// The shape for the preprocessed tables.
     pub preprocessed_shape: Option<Shape<MipsAirId>>,
+    /// The function symbols of the ELF, used to name the caller of an unsupported syscall.
+    ///
+    /// They are debug information, which is neither serialized nor compared.
+    #[serde(skip)]
+    pub(crate) symbols: Vec<FunctionSymbol>,
+}
+
+impl PartialEq for Program {
+    fn eq(&self, other: &Self) -> bool {
+        self.instructions == other.instructions
+            && self.pc_start == other.pc_start
+            && self.pc_base == other.pc_base
+            && self.next_pc == other.next_pc
+            && self.image == other.image
+            && self.preprocessed_shape == other.preprocessed_shape
+    }
 }
 
 impl Program {
@@ -150,6 +166,8 @@ impl Program {
 
         image.insert(Register::BRK as u32, hiaddr); // $brk
         image.insert(Register::HEAP as u32, 0x20000000); // $heap
+        image.insert(Register::RNG as u32, 0); // getrandom counter
+        image.insert(Register::CLOCK as u32, 0); // clock_gettime counter
 
         patch_stack(&mut image);
 
@@ -157,14 +175,19 @@ impl Program {
         let instructions: Vec<_> =
             instructions.par_iter().map(|inst| Instruction::decode_from(*inst).unwrap()).collect();
 
-        Ok(Program {
+        let mut program = Program {
             instructions,
             pc_start: entry,
             pc_base: base_address,
             next_pc: entry + 4,
             image,
             preprocessed_shape: None,
-        })
+            symbols: function_symbols(&elf).unwrap_or_default(),
+        };
+        if let Some(handler) = program.symbol_address(FPU_TRAP_SYMBOL) {
+            program.trap_fpu_instructions(handler);
+        }
+        Ok(program)
     }
 
     /// Custom logic for padding the trace to a power of two according to the proof shape.
@@ -177,6 +200,11 @@ impl Program {
         })
     }
 
+    /// Returns the address of the function `name`, if the ELF has a symbol table.
+    pub(crate) fn symbol_address(&self, name: &str) -> Option<u32> {
+        self.symbols.iter().find(|symbol| symbol.name == name).map(|symbol| symbol.start)
+    }
+
     #[must_use]
     /// Fetch the instruction at the given program counter.
     pub fn fetch(&self, pc: u32) -> Instruction {
@@ -185,6 +213,36 @@ impl Program {
     }
 }
 
+/// A function from the ELF symbol table.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FunctionSymbol {
+    pub(crate) start: u32,
+    pub(crate) end: u32,
+    pub(crate) name: String,
+}
+
+/// Returns the function symbols of the ELF with demangled names, sorted by start address.
+pub(crate) fn function_symbols(f: &ElfBytes<LittleEndian>) -> Result<Vec<FunctionSymbol>> {
+    let (symbols, strings) = f
+        .symbol_table()
+        .map_err(|err| anyhow!("failed to read symbol table: {err}"))?
+        .ok_or_else(|| anyhow!("missing symbol table"))?;
+
+    let mut functions = symbols
+        .iter()
+        .filter(|symbol| symbol.st_symtype() == elf::abi::STT_FUNC && symbol.st_size != 0)
+        .filter_map(|symbol| {
+            let name = strings.get(symbol.st_name as usize).ok()?;
+            // Symbols that do not fit in the 32-bit address space are malformed and skipped.
+            let start = u32::try_from(symbol.st_value).ok()?;
+            let end = start.checked_add(u32::try_from(symbol.st_size).ok()?)?;
+            Some(FunctionSymbol { start, end, name: rustc_demangle::demangle(name).to_string() })
+        })
+        .collect::<Vec<_>>();
+    functions.sort_by_key(|function| function.start);
+    Ok(functions)
+}
+
 pub fn patch_elf(f: &elf::ElfBytes<LittleEndian>, patch_list: &mut BTreeMap<u32, u32>) {
     let symbols = f
         .symbol_table()
@@ -112,6 +112,20 @@ impl ExecutionRecord {
         execution_record
     }
 
+    /// The number of events of a precompile that [`Self::split`] puts in a single shard. Keccak
+    /// events are counted in absorbed blocks and `SYS_READ` events in written words.
+    #[must_use]
+    pub fn precompile_split_threshold(syscall_code: SyscallCode, opts: SplitOpts) -> usize {
+        match syscall_code {
+            SyscallCode::KECCAK_SPONGE => opts.keccak,
+            SyscallCode::SHA_EXTEND => opts.sha_extend,
+            SyscallCode::SHA_COMPRESS => opts.sha_compress,
+            SyscallCode::BLAKE3_COMPRESS => opts.blake3_compress,
+            SyscallCode::BLAKE2B_COMPRESS => opts.blake2b_compress,
+            _ => opts.deferred,
+        }
+    }
+
     /// Splits the deferred [`ExecutionRecord`] into multiple [`ExecutionRecord`]s, each which
     /// contain a "reasonable" number of deferred events.
     pub fn split(&mut self, last: bool, opts: SplitOpts) -> Vec<ExecutionRecord> {
@@ -120,32 +134,33 @@ impl ExecutionRecord {
         let precompile_events = take(&mut self.precompile_events);
 
         for (syscall_code, events) in precompile_events.into_iter() {
-            let threshold = match syscall_code {
-                SyscallCode::KECCAK_SPONGE => opts.keccak,
-                SyscallCode::SHA_EXTEND => opts.sha_extend,
-                SyscallCode::SHA_COMPRESS => opts.sha_compress,
-                _ => opts.deferred,
-            };
+            let threshold = Self::precompile_split_threshold(syscall_code, opts);
 
             let mut shards_input = Vec::new();
-            let remainder = if syscall_code == SyscallCode::KECCAK_SPONGE {
+            let remainder = if syscall_code == SyscallCode::KECCAK_SPONGE
+                || syscall_code == SyscallCode::SYS_READ
+            {
                 let mut current_shard = Vec::new();
                 let mut current_len = 0;
 
                 for (syscall_event, event) in events {
-                    if let PrecompileEvent::KeccakSponge(event) = &event {
+                    let input_len = match &event {
                         // Here, input_len_u32s must be a multiple of GENERAL_BLOCK_SIZE_U32S.
-                        let input_len = event.input_len_u32s as usize / GENERAL_BLOCK_SIZE_U32S;
-
-                        if current_len + input_len > threshold && !current_shard.is_empty() {
-                            let mut record = ExecutionRecord::new(self.program.clone());
-                            record.precompile_events.insert(syscall_code, current_shard);
-                            shards_input.push(record);
-                            current_shard = Vec::new();
-                            current_len = 0;
+                        PrecompileEvent::KeccakSponge(event) => {
+                            event.input_len_u32s as usize / GENERAL_BLOCK_SIZE_U32S
                         }
-                        current_len += input_len;
+                        PrecompileEvent::Linux(event) => event.num_rows(),
+                        _ => 1,
+                    };
+
+                    if current_len + input_len > threshold && !current_shard.is_empty() {
+                        let mut record = ExecutionRecord::new(self.program.clone());
+                        record.precompile_events.insert(syscall_code, current_shard);
+                        shards_input.push(record);
+                        current_shard = Vec::new();
+                        current_len = 0;
                     }
+                    current_len += input_len;
                     current_shard.push((syscall_event, event));
                 }
 
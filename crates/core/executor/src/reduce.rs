@@ -1,5 +1,8 @@
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use zkm_stark::{Dom, ShardProof, StarkGenericConfig, StarkVerifyingKey};
+use zkm_stark::{
+    Dom, LegacyShardProof, LegacyStarkVerifyingKey, SecurityConfig, ShardProof, StarkGenericConfig,
+    StarkVerifyingKey,
+};
 /// An intermediate proof which proves the execution.
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(bound(serialize = "ShardProof<SC>: Serialize, Dom<SC>: Serialize"))]
@@ -19,3 +22,34 @@ impl<SC: StarkGenericConfig> std::fmt::Debug for ZKMReduceProof<SC> {
         debug_struct.finish()
     }
 }
+
+/// A [`ZKMReduceProof`] encoded before shard proofs and verifying keys recorded their security
+/// parameters.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(bound(serialize = "LegacyShardProof<SC>: Serialize, Dom<SC>: Serialize"))]
+#[serde(bound(deserialize = "LegacyShardProof<SC>: Deserialize<'de>, Dom<SC>: DeserializeOwned"))]
+pub struct LegacyReduceProof<SC: StarkGenericConfig> {
+    /// The compress verifying key associated with the proof.
+    pub vk: LegacyStarkVerifyingKey<SC>,
+    /// The shard proof representing the compressed proof.
+    pub proof: LegacyShardProof<SC>,
+}
+
+impl<SC: StarkGenericConfig> LegacyReduceProof<SC> {
+    /// The proof, assuming it was proven with `security`.
+    pub fn with_security(self, security: SecurityConfig) -> ZKMReduceProof<SC> {
+        ZKMReduceProof {
+            vk: self.vk.with_security(security),
+            proof: self.proof.with_security(security),
+        }
+    }
+}
+
+impl<SC: StarkGenericConfig> std::fmt::Debug for LegacyReduceProof<SC> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug_struct = f.debug_struct("LegacyReduceProof");
+        debug_struct.field("vk", &self.vk);
+        debug_struct.field("proof", &self.proof);
+        debug_struct.finish()
+    }
+}
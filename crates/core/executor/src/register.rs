@@ -1,6 +1,6 @@
 //! Registers for the Ziren zkVM.
 
-pub const NUM_REGISTERS: usize = 36;
+pub const NUM_REGISTERS: usize = 38;
 /// A register stores a 32-bit value used by operations.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Register {
@@ -40,6 +40,8 @@ pub enum Register {
     HI = 33,
     BRK = 34,
     HEAP = 35,
+    RNG = 36,
+    CLOCK = 37,
 }
 
 impl From<u8> for Register {
@@ -81,6 +83,8 @@ impl From<u8> for Register {
             33 => Register::HI,
             34 => Register::BRK,
             35 => Register::HEAP,
+            36 => Register::RNG,
+            37 => Register::CLOCK,
             _ => panic!("invalid register {value}"),
         }
     }
@@ -5,16 +5,19 @@ use std::{
 
 use enum_map::{EnumArray, EnumMap};
 use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
 
 use crate::{events::generate_execution_report, syscalls::SyscallCode, Opcode};
 
 /// An execution report.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExecutionReport {
     /// The opcode counts.
     pub opcode_counts: Box<EnumMap<Opcode, u64>>,
     /// The syscall counts.
     pub syscall_counts: Box<EnumMap<SyscallCode, u64>>,
+    /// The counts of the unrecognized Linux syscalls executed as no-ops, by syscall number.
+    pub nop_syscall_counts: HashMap<u32, u64>,
     /// The cycle tracker counts.
     pub cycle_tracker: HashMap<String, u64>,
     /// The unique memory address counts.
@@ -50,6 +53,9 @@ impl AddAssign for ExecutionReport {
     fn add_assign(&mut self, rhs: Self) {
         counts_add_assign(&mut self.opcode_counts, *rhs.opcode_counts);
         counts_add_assign(&mut self.syscall_counts, *rhs.syscall_counts);
+        for (code, count) in rhs.nop_syscall_counts {
+            *self.nop_syscall_counts.entry(code).or_insert(0) += count;
+        }
         self.touched_memory_addresses += rhs.touched_memory_addresses;
     }
 }
@@ -74,6 +80,15 @@ impl Display for ExecutionReport {
         for line in generate_execution_report(self.syscall_counts.as_ref()) {
             writeln!(f, "  {line}")?;
         }
+
+        if !self.nop_syscall_counts.is_empty() {
+            writeln!(f, "unrecognized syscalls executed as no-ops:")?;
+            let mut counts = self.nop_syscall_counts.iter().collect::<Vec<_>>();
+            counts.sort_unstable();
+            for (code, count) in counts {
+                writeln!(f, "  {code}: {count}")?;
+            }
+        }
         Ok(())
     }
 }
@@ -9,6 +9,7 @@ use zkm_stark::{koala_bear_poseidon2::KoalaBearPoseidon2, StarkVerifyingKey};
 
 use crate::{
     events::MemoryRecord,
+    fpu::FpuState,
     memory::PagedMemory,
     record::{ExecutionRecord, MemoryAccessRecord},
     syscalls::SyscallCode,
@@ -34,6 +35,11 @@ pub struct ExecutionState {
     /// if the next instruction is in delay slot for branch and jump.
     pub next_is_delayslot: bool,
 
+    /// Whether the program ended a step of an incremental computation with `END_STEP` and waits
+    /// to be resumed, see [`crate::Executor::resume_step`].
+    #[serde(default)]
+    pub paused: bool,
+
     /// The memory which instructions operate over. Values contain the memory value and last shard
     /// + timestamp that each memory address was accessed.
     pub memory: PagedMemory<MemoryRecord>,
@@ -55,6 +61,23 @@ pub struct ExecutionState {
     /// A ptr to the current position in the input stream incremented by `HINT_READ` opcode.
     pub input_stream_ptr: usize,
 
+    /// The number of bytes of the current input already consumed by `SYS_READ` on stdin. The
+    /// hint syscalls read the rest of the input.
+    #[serde(default)]
+    pub input_stream_offset: usize,
+
+    /// The files of the virtual filesystem, keyed by path, that the program can open.
+    #[serde(default)]
+    pub files: HashMap<String, Vec<u8>>,
+
+    /// The files opened by the program, keyed by file descriptor.
+    #[serde(default)]
+    pub open_files: HashMap<u32, OpenFile>,
+
+    /// The deterministic environment seen by the emulated Linux syscalls.
+    #[serde(default)]
+    pub linux_env: LinuxEnv,
+
     /// A stream of proofs (reduce vk, proof, verifying key) inputted to the program.
     pub proof_stream:
         Vec<(ZKMReduceProof<KoalaBearPoseidon2>, StarkVerifyingKey<KoalaBearPoseidon2>)>,
@@ -70,6 +93,10 @@ pub struct ExecutionState {
     pub public_values_stream_ptr: usize,
     // /// Keeps track of how many times a certain syscall has been called.
     pub syscall_counts: HashMap<SyscallCode, u64>,
+
+    /// The state of the emulated FPU, see [`crate::fpu`].
+    #[serde(default)]
+    pub fpu: FpuState,
 }
 
 impl ExecutionState {
@@ -85,19 +112,47 @@ impl ExecutionState {
             next_pc,
             exited: false,
             next_is_delayslot: false,
+            paused: false,
             memory: PagedMemory::new_preallocated(),
             uninitialized_memory: PagedMemory::default(),
             input_stream: Vec::new(),
             input_stream_ptr: 0,
+            input_stream_offset: 0,
+            files: HashMap::new(),
+            open_files: HashMap::new(),
+            linux_env: LinuxEnv::default(),
             public_values_stream: Vec::new(),
             public_values_stream_ptr: 0,
             proof_stream: Vec::new(),
             proof_stream_ptr: 0,
             syscall_counts: HashMap::new(),
+            fpu: FpuState::default(),
         }
     }
 }
 
+/// A file of the virtual filesystem opened by `SYS_OPEN`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpenFile {
+    /// The path of the file in [`ExecutionState::files`].
+    pub path: String,
+    /// The position of the next read, which may be past the end of the file.
+    pub offset: usize,
+}
+
+/// The values that the Linux syscalls derive what a real kernel would take from its environment.
+///
+/// Both are committed as public values of every shard. The positions in the random stream and on
+/// the clock are kept in the [`Register::RNG`](crate::Register::RNG) and
+/// [`Register::CLOCK`](crate::Register::CLOCK) pseudo-registers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LinuxEnv {
+    /// The wall-clock time at the start of execution, in seconds since the Unix epoch.
+    pub epoch: u32,
+    /// The seed of the word stream returned by `SYS_GETRANDOM`.
+    pub random_seed: u64,
+}
+
 /// Holds data to track changes made to the runtime since a fork point.
 #[derive(Debug, Clone, Default)]
 #[allow(dead_code)]
@@ -114,6 +169,8 @@ pub struct ForkState {
     pub op_record: MemoryAccessRecord,
     /// The original execution record at the fork point.
     pub record: ExecutionRecord,
+    /// The original FPU state at the fork point.
+    pub fpu: FpuState,
     // /// Whether `emit_events` was enabled at the fork point.
     pub executor_mode: ExecutorMode,
 }
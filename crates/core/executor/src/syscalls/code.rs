@@ -2,6 +2,8 @@ use enum_map::Enum;
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
+use crate::MipsAirId;
+
 /// System Calls.
 ///
 /// A system call is invoked by the `syscall` instruction with a specific value in register V0.
@@ -74,6 +76,9 @@ pub enum SyscallCode {
     /// Executes the `COMMIT` precompile.
     COMMIT = 0x00_00_00_10,
 
+    /// Ends the current step of an incremental computation, see [`crate::Executor::resume_step`].
+    END_STEP = 0x00_00_00_11,
+
     /// Executes the `COMMIT_DEFERRED_PROOFS` precompile.
     COMMIT_DEFERRED_PROOFS = 0x00_00_00_1A,
 
@@ -156,6 +161,30 @@ pub enum SyscallCode {
     /// Read
     SYS_READ = 4003,
 
+    /// Open
+    SYS_OPEN = 4005,
+
+    /// Openat
+    SYS_OPENAT = 4288,
+
+    /// Close
+    SYS_CLOSE = 4006,
+
+    /// Lseek
+    SYS_LSEEK = 4019,
+
+    /// Getpid
+    SYS_GETPID = 4020,
+
+    /// Sched yield
+    SYS_SCHED_YIELD = 4162,
+
+    /// Clock gettime
+    SYS_CLOCK_GETTIME = 4263,
+
+    /// Getrandom
+    SYS_GETRANDOM = 4353,
+
     /// Write
     SYS_WRITE = 4004,
 
@@ -167,6 +196,36 @@ pub enum SyscallCode {
 
     /// Executes the `POSEIDON2_PERMUTE` precompile.
     POSEIDON2_PERMUTE = 0x00_01_00_30,
+
+    /// Executes the `BLAKE3_COMPRESS` precompile.
+    BLAKE3_COMPRESS = 0x01_01_00_31,
+
+    /// Executes the `BLAKE2B_COMPRESS` precompile.
+    BLAKE2B_COMPRESS = 0x01_01_00_32,
+
+    /// A precompile registered from outside of Ziren, see [`crate::ZKMContextBuilder::precompile`].
+    CUSTOM_0 = 0x01_01_00_40,
+
+    /// A precompile registered from outside of Ziren.
+    CUSTOM_1 = 0x01_01_00_41,
+
+    /// A precompile registered from outside of Ziren.
+    CUSTOM_2 = 0x01_01_00_42,
+
+    /// A precompile registered from outside of Ziren.
+    CUSTOM_3 = 0x01_01_00_43,
+
+    /// A precompile registered from outside of Ziren.
+    CUSTOM_4 = 0x01_01_00_44,
+
+    /// A precompile registered from outside of Ziren.
+    CUSTOM_5 = 0x01_01_00_45,
+
+    /// A precompile registered from outside of Ziren.
+    CUSTOM_6 = 0x01_01_00_46,
+
+    /// A precompile registered from outside of Ziren.
+    CUSTOM_7 = 0x01_01_00_47,
     SYS_LINUX = 5000,
 
     UNIMPLEMENTED = 0xFF_FF_FF_FF,
@@ -196,9 +255,12 @@ impl SyscallCode {
             0x01_01_00_0E => SyscallCode::BN254_ADD,
             0x00_01_00_0F => SyscallCode::BN254_DOUBLE,
             0x00_00_00_10 => SyscallCode::COMMIT,
+            0x00_00_00_11 => SyscallCode::END_STEP,
             0x00_00_00_1A => SyscallCode::COMMIT_DEFERRED_PROOFS,
             0x00_00_00_1B => SyscallCode::VERIFY_ZKM_PROOF,
             0x00_01_00_30 => SyscallCode::POSEIDON2_PERMUTE,
+            0x01_01_00_31 => SyscallCode::BLAKE3_COMPRESS,
+            0x01_01_00_32 => SyscallCode::BLAKE2B_COMPRESS,
             0x00_01_00_1C => SyscallCode::BLS12381_DECOMPRESS,
             0x01_01_00_1D => SyscallCode::UINT256_MUL,
             0x01_01_00_1E => SyscallCode::BLS12381_ADD,
@@ -219,6 +281,14 @@ impl SyscallCode {
             0x00_01_00_2D => SyscallCode::SECP256R1_DOUBLE,
             0x00_01_00_2E => SyscallCode::SECP256R1_DECOMPRESS,
             0x01_01_00_2F => SyscallCode::U256XU2048_MUL,
+            0x01_01_00_40 => SyscallCode::CUSTOM_0,
+            0x01_01_00_41 => SyscallCode::CUSTOM_1,
+            0x01_01_00_42 => SyscallCode::CUSTOM_2,
+            0x01_01_00_43 => SyscallCode::CUSTOM_3,
+            0x01_01_00_44 => SyscallCode::CUSTOM_4,
+            0x01_01_00_45 => SyscallCode::CUSTOM_5,
+            0x01_01_00_46 => SyscallCode::CUSTOM_6,
+            0x01_01_00_47 => SyscallCode::CUSTOM_7,
             _ => {
                 if (0x100..=0x0ffff).contains(&value) {
                     // These are the syscall numbers for the Linux syscalls.
@@ -226,11 +296,19 @@ impl SyscallCode {
                     match value {
                         4003 => SyscallCode::SYS_READ,
                         4004 => SyscallCode::SYS_WRITE,
+                        4005 => SyscallCode::SYS_OPEN,
+                        4006 => SyscallCode::SYS_CLOSE,
+                        4019 => SyscallCode::SYS_LSEEK,
+                        4020 => SyscallCode::SYS_GETPID,
                         4055 => SyscallCode::SYS_FCNTL,
                         4045 => SyscallCode::SYS_BRK,
                         4090 => SyscallCode::SYS_MMAP2,
                         4120 => SyscallCode::SYS_CLONE,
+                        4162 => SyscallCode::SYS_SCHED_YIELD,
                         4246 => SyscallCode::SYS_EXT_GROUP,
+                        4263 => SyscallCode::SYS_CLOCK_GETTIME,
+                        4288 => SyscallCode::SYS_OPENAT,
+                        4353 => SyscallCode::SYS_GETRANDOM,
                         4210 => SyscallCode::SYS_MMAP,
                         5000 => SyscallCode::SYS_LINUX,
                         _ => SyscallCode::SYS_NOP,
@@ -243,6 +321,39 @@ impl SyscallCode {
         }
     }
 
+    /// Whether the syscall is reserved for precompiles registered from outside of Ziren.
+    #[must_use]
+    pub fn is_custom(self) -> bool {
+        matches!(
+            self,
+            SyscallCode::CUSTOM_0
+                | SyscallCode::CUSTOM_1
+                | SyscallCode::CUSTOM_2
+                | SyscallCode::CUSTOM_3
+                | SyscallCode::CUSTOM_4
+                | SyscallCode::CUSTOM_5
+                | SyscallCode::CUSTOM_6
+                | SyscallCode::CUSTOM_7
+        )
+    }
+
+    /// The identifier of the extension chip proving a precompile registered from outside of
+    /// Ziren, or `None` if the syscall is not reserved for such precompiles.
+    #[must_use]
+    pub fn custom_air_id(self) -> Option<MipsAirId> {
+        match self {
+            SyscallCode::CUSTOM_0 => Some(MipsAirId::Custom0),
+            SyscallCode::CUSTOM_1 => Some(MipsAirId::Custom1),
+            SyscallCode::CUSTOM_2 => Some(MipsAirId::Custom2),
+            SyscallCode::CUSTOM_3 => Some(MipsAirId::Custom3),
+            SyscallCode::CUSTOM_4 => Some(MipsAirId::Custom4),
+            SyscallCode::CUSTOM_5 => Some(MipsAirId::Custom5),
+            SyscallCode::CUSTOM_6 => Some(MipsAirId::Custom6),
+            SyscallCode::CUSTOM_7 => Some(MipsAirId::Custom7),
+            _ => None,
+        }
+    }
+
     /// Get the system call identifier.
     #[must_use]
     pub fn syscall_id(self) -> u32 {
@@ -279,6 +390,7 @@ impl SyscallCode {
             SyscallCode::BLS12381_FP_MUL => SyscallCode::BLS12381_FP_ADD,
             SyscallCode::BLS12381_FP2_SUB => SyscallCode::BLS12381_FP2_ADD,
             SyscallCode::SYS_MMAP2 => SyscallCode::SYS_MMAP,
+            SyscallCode::SYS_OPENAT => SyscallCode::SYS_OPEN,
             _ => *self,
         }
     }
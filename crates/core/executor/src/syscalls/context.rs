@@ -2,9 +2,11 @@ use hashbrown::HashMap;
 
 use crate::{
     events::{
-        MemoryLocalEvent, MemoryReadRecord, MemoryWriteRecord, PrecompileEvent, SyscallEvent,
+        MemoryAccessPosition, MemoryLocalEvent, MemoryReadRecord, MemoryWriteRecord,
+        PrecompileEvent, SyscallEvent,
     },
     record::ExecutionRecord,
+    trace::TraceAccess,
     Executor, ExecutorMode, Register,
 };
 
@@ -71,6 +73,9 @@ impl<'a, 'b> SyscallContext<'a, 'b> {
     pub fn mr(&mut self, addr: u32) -> (MemoryReadRecord, u32) {
         let record =
             self.rt.mr(addr, self.current_shard, self.clk, Some(&mut self.local_memory_access));
+        if let Some(writer) = self.rt.trace_writer.as_mut() {
+            writer.access(TraceAccess::read(addr, MemoryAccessPosition::Memory, &record));
+        }
         (record, record.value)
     }
 
@@ -88,7 +93,17 @@ impl<'a, 'b> SyscallContext<'a, 'b> {
 
     /// Write a word to memory.
     pub fn mw(&mut self, addr: u32, value: u32) -> MemoryWriteRecord {
-        self.rt.mw(addr, value, self.current_shard, self.clk, Some(&mut self.local_memory_access))
+        let record = self.rt.mw(
+            addr,
+            value,
+            self.current_shard,
+            self.clk,
+            Some(&mut self.local_memory_access),
+        );
+        if let Some(writer) = self.rt.trace_writer.as_mut() {
+            writer.access(TraceAccess::write(addr, MemoryAccessPosition::Memory, &record));
+        }
+        record
     }
 
     /// Write a slice of words to memory.
@@ -17,7 +17,9 @@ impl Syscall for HintLenSyscall {
                 ctx.rt.state.input_stream.len()
             );
         }
-        Some(ctx.rt.state.input_stream[ctx.rt.state.input_stream_ptr].len() as u32)
+        // `SYS_READ` on stdin may have consumed the start of the input already.
+        let input = &ctx.rt.state.input_stream[ctx.rt.state.input_stream_ptr];
+        Some((input.len() - ctx.rt.state.input_stream_offset) as u32)
     }
 }
 
@@ -32,7 +34,9 @@ impl Syscall for HintReadSyscall {
                 ctx.rt.state.input_stream.len()
             );
         }
-        let vec = &ctx.rt.state.input_stream[ctx.rt.state.input_stream_ptr];
+        // Read the rest of the input that `SYS_READ` on stdin didn't consume.
+        let offset = std::mem::take(&mut ctx.rt.state.input_stream_offset);
+        let vec = &ctx.rt.state.input_stream[ctx.rt.state.input_stream_ptr][offset..];
         ctx.rt.state.input_stream_ptr += 1;
         assert!(!ctx.rt.unconstrained, "hint read should not be used in a unconstrained block");
         assert_eq!(vec.len() as u32, len, "hint input stream read length mismatch");
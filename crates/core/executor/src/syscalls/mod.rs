@@ -7,6 +7,7 @@ mod deferred;
 mod halt;
 mod hint;
 pub(crate) mod precompiles;
+mod step;
 mod unconstrained;
 mod verify;
 mod write;
@@ -22,15 +23,19 @@ pub use code::*;
 pub use context::*;
 use hint::{HintLenSyscall, HintReadSyscall};
 use precompiles::{
+    blake2b::compress::Blake2bCompressSyscall,
+    blake3::compress::Blake3CompressSyscall,
     edwards::{add::EdwardsAddAssignSyscall, decompress::EdwardsDecompressSyscall},
     fptower::{Fp2AddSubSyscall, Fp2MulSyscall, FpOpSyscall},
     keccak::sponge::KeccakSpongeSyscall,
     poseidon2::permute::Poseidon2PermuteSyscall,
     sha256::{compress::Sha256CompressSyscall, extend::Sha256ExtendSyscall},
     sys_linux::{
-        sysbrk::SysBrkSyscall, sysclone::SysCloneSyscall, sysexitgroup::SysExitGroupSyscall,
-        sysfcntl::SysFcntlSyscall, sysmmap::SysMmapSyscall, sysnop::SysNopSyscall,
-        sysread::SysReadSyscall, syswrite::SysWriteSyscall,
+        sysbrk::SysBrkSyscall, sysclockgettime::SysClockGettimeSyscall, sysclone::SysCloneSyscall,
+        sysclose::SysCloseSyscall, sysexitgroup::SysExitGroupSyscall, sysfcntl::SysFcntlSyscall,
+        sysgetpid::SysGetpidSyscall, sysgetrandom::SysGetrandomSyscall, syslseek::SysLseekSyscall,
+        sysmmap::SysMmapSyscall, sysnop::SysNopSyscall, sysopen::SysOpenSyscall,
+        sysread::SysReadSyscall, sysschedyield::SysSchedYieldSyscall, syswrite::SysWriteSyscall,
     },
     u256x2048_mul::U256xU2048MulSyscall,
     uint256::Uint256MulSyscall,
@@ -40,6 +45,7 @@ use precompiles::{
     },
 };
 
+use step::EndStepSyscall;
 use unconstrained::{EnterUnconstrainedSyscall, ExitUnconstrainedSyscall};
 use verify::VerifySyscall;
 use write::WriteSyscall;
@@ -79,6 +85,14 @@ pub trait Syscall: Send + Sync {
     fn num_extra_cycles(&self) -> u32 {
         0
     }
+
+    /// Checks the arguments of the syscall before it executes.
+    ///
+    /// Returns the reason why the arguments are invalid, which fails the execution with
+    /// [`crate::ExecutionError::InvalidSyscallArgs`].
+    fn check_args(&self, _arg1: u32, _arg2: u32) -> Result<(), String> {
+        Ok(())
+    }
 }
 
 /// Creates the default syscall map.
@@ -104,6 +118,10 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Arc<dyn Syscall>> {
 
     syscall_map.insert(SyscallCode::KECCAK_SPONGE, Arc::new(KeccakSpongeSyscall));
 
+    syscall_map.insert(SyscallCode::BLAKE3_COMPRESS, Arc::new(Blake3CompressSyscall));
+
+    syscall_map.insert(SyscallCode::BLAKE2B_COMPRESS, Arc::new(Blake2bCompressSyscall));
+
     syscall_map.insert(
         SyscallCode::SECP256K1_ADD,
         Arc::new(WeierstrassAddAssignSyscall::<Secp256k1>::new()),
@@ -220,6 +238,8 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Arc<dyn Syscall>> {
 
     syscall_map.insert(SyscallCode::COMMIT, Arc::new(CommitSyscall));
 
+    syscall_map.insert(SyscallCode::END_STEP, Arc::new(EndStepSyscall));
+
     syscall_map.insert(SyscallCode::COMMIT_DEFERRED_PROOFS, Arc::new(CommitDeferredSyscall));
 
     // todo: choose one
@@ -237,6 +257,14 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Arc<dyn Syscall>> {
 
     syscall_map.insert(SyscallCode::SYS_BRK, Arc::new(SysBrkSyscall));
     syscall_map.insert(SyscallCode::SYS_READ, Arc::new(SysReadSyscall));
+    syscall_map.insert(SyscallCode::SYS_OPEN, Arc::new(SysOpenSyscall));
+    syscall_map.insert(SyscallCode::SYS_OPENAT, Arc::new(SysOpenSyscall));
+    syscall_map.insert(SyscallCode::SYS_CLOSE, Arc::new(SysCloseSyscall));
+    syscall_map.insert(SyscallCode::SYS_LSEEK, Arc::new(SysLseekSyscall));
+    syscall_map.insert(SyscallCode::SYS_GETPID, Arc::new(SysGetpidSyscall));
+    syscall_map.insert(SyscallCode::SYS_SCHED_YIELD, Arc::new(SysSchedYieldSyscall));
+    syscall_map.insert(SyscallCode::SYS_CLOCK_GETTIME, Arc::new(SysClockGettimeSyscall));
+    syscall_map.insert(SyscallCode::SYS_GETRANDOM, Arc::new(SysGetrandomSyscall));
     syscall_map.insert(SyscallCode::SYS_WRITE, Arc::new(SysWriteSyscall));
     syscall_map.insert(SyscallCode::SYS_EXT_GROUP, Arc::new(SysExitGroupSyscall));
     syscall_map.insert(SyscallCode::SYS_MMAP, Arc::new(SysMmapSyscall));
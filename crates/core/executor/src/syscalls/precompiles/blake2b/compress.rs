@@ -0,0 +1,119 @@
+use crate::{
+    events::{Blake2bCompressEvent, PrecompileEvent},
+    syscalls::{precompiles::check_disjoint_words, Syscall, SyscallCode, SyscallContext},
+};
+
+/// The message word indices used by each of the 12 BLAKE2b rounds.
+pub const BLAKE2B_SIGMA: [[usize; 16]; 12] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+];
+
+/// The state words mixed by each of the 8 `G` functions of a round, columns first.
+pub const BLAKE2B_G_INDEX: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+pub(crate) struct Blake2bCompressSyscall;
+
+impl Syscall for Blake2bCompressSyscall {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn check_args(&self, arg1: u32, arg2: u32) -> Result<(), String> {
+        // The state and the message are read at the same clk, so they can't overlap.
+        check_disjoint_words(arg1, arg2, 32)
+    }
+
+    fn execute(
+        &self,
+        rt: &mut SyscallContext,
+        syscall_code: SyscallCode,
+        arg1: u32,
+        arg2: u32,
+    ) -> Option<u32> {
+        let state_ptr = arg1;
+        let msg_ptr = arg2;
+
+        let start_clk = rt.clk;
+        let (state_read_records, state) = rt.mr_slice(state_ptr, 32);
+        let (msg_read_records, msg) = rt.mr_slice(msg_ptr, 32);
+        let state: [u64; 16] =
+            core::array::from_fn(|i| u64_from_words(state[2 * i], state[2 * i + 1]));
+        let msg: [u64; 16] = core::array::from_fn(|i| u64_from_words(msg[2 * i], msg[2 * i + 1]));
+
+        let h = blake2b_compress(&state, &msg);
+
+        // Increment the clk by 1 before writing the output, since we've already read the state at
+        // the start_clk.
+        rt.clk += 1;
+        let output =
+            h.iter().flat_map(|lane| [*lane as u32, (lane >> 32) as u32]).collect::<Vec<_>>();
+        let state_write_records = rt.mw_slice(state_ptr, &output);
+
+        // Push the BLAKE2b compress event.
+        let shard = rt.current_shard();
+        let event = PrecompileEvent::Blake2bCompress(Blake2bCompressEvent {
+            shard,
+            clk: start_clk,
+            state_ptr,
+            msg_ptr,
+            state,
+            msg,
+            state_read_records,
+            msg_read_records,
+            state_write_records,
+            local_mem_access: rt.postprocess(),
+        });
+        let syscall_event =
+            rt.rt.syscall_event(start_clk, None, rt.next_pc, syscall_code.syscall_id(), arg1, arg2);
+        rt.add_precompile_event(syscall_code, syscall_event, event);
+
+        None
+    }
+}
+
+fn u64_from_words(lo: u32, hi: u32) -> u64 {
+    u64::from(lo) | (u64::from(hi) << 32)
+}
+
+/// Runs the 12 rounds of BLAKE2b on the initialized working vector and returns the new chaining
+/// value.
+///
+/// The first 8 words of the working vector are the input chaining value.
+#[must_use]
+pub fn blake2b_compress(state: &[u64; 16], msg: &[u64; 16]) -> [u64; 8] {
+    let mut v = *state;
+    for sigma in BLAKE2B_SIGMA {
+        for (g, [a, b, c, d]) in BLAKE2B_G_INDEX.into_iter().enumerate() {
+            let (mx, my) = (msg[sigma[2 * g]], msg[sigma[2 * g + 1]]);
+            v[a] = v[a].wrapping_add(v[b]).wrapping_add(mx);
+            v[d] = (v[d] ^ v[a]).rotate_right(32);
+            v[c] = v[c].wrapping_add(v[d]);
+            v[b] = (v[b] ^ v[c]).rotate_right(24);
+            v[a] = v[a].wrapping_add(v[b]).wrapping_add(my);
+            v[d] = (v[d] ^ v[a]).rotate_right(16);
+            v[c] = v[c].wrapping_add(v[d]);
+            v[b] = (v[b] ^ v[c]).rotate_right(63);
+        }
+    }
+    core::array::from_fn(|i| state[i] ^ v[i] ^ v[i + 8])
+}
@@ -0,0 +1,108 @@
+use crate::{
+    events::{Blake3CompressEvent, PrecompileEvent},
+    syscalls::{precompiles::check_disjoint_words, Syscall, SyscallCode, SyscallContext},
+};
+
+/// The message word indices used by each of the 7 BLAKE3 rounds.
+pub const BLAKE3_MSG_SCHEDULE: [[usize; 16]; 7] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8],
+    [3, 4, 10, 12, 13, 2, 7, 14, 6, 5, 9, 0, 11, 15, 8, 1],
+    [10, 7, 12, 9, 14, 3, 13, 15, 4, 0, 11, 2, 5, 8, 1, 6],
+    [12, 13, 9, 11, 15, 10, 14, 8, 7, 2, 5, 3, 0, 1, 6, 4],
+    [9, 14, 11, 5, 8, 12, 15, 1, 13, 3, 0, 10, 2, 6, 4, 7],
+    [11, 15, 5, 0, 1, 9, 8, 6, 14, 10, 2, 12, 3, 4, 7, 13],
+];
+
+/// The state words mixed by each of the 8 `G` functions of a round, columns first.
+pub const BLAKE3_G_INDEX: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+pub(crate) struct Blake3CompressSyscall;
+
+impl Syscall for Blake3CompressSyscall {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn check_args(&self, arg1: u32, arg2: u32) -> Result<(), String> {
+        // The state and the message are read at the same clk, so they can't overlap.
+        check_disjoint_words(arg1, arg2, 16)
+    }
+
+    fn execute(
+        &self,
+        rt: &mut SyscallContext,
+        syscall_code: SyscallCode,
+        arg1: u32,
+        arg2: u32,
+    ) -> Option<u32> {
+        let state_ptr = arg1;
+        let msg_ptr = arg2;
+
+        let start_clk = rt.clk;
+        let (state_read_records, state) = rt.mr_slice(state_ptr, 16);
+        let (msg_read_records, msg) = rt.mr_slice(msg_ptr, 16);
+        let state: [u32; 16] = state.try_into().unwrap();
+        let msg: [u32; 16] = msg.try_into().unwrap();
+
+        let output = blake3_compress(&state, &msg);
+
+        // Increment the clk by 1 before writing the output, since we've already read the state at
+        // the start_clk.
+        rt.clk += 1;
+        let state_write_records = rt.mw_slice(state_ptr, &output);
+
+        // Push the BLAKE3 compress event.
+        let shard = rt.current_shard();
+        let event = PrecompileEvent::Blake3Compress(Blake3CompressEvent {
+            shard,
+            clk: start_clk,
+            state_ptr,
+            msg_ptr,
+            state,
+            msg,
+            state_read_records,
+            msg_read_records,
+            state_write_records,
+            local_mem_access: rt.postprocess(),
+        });
+        let syscall_event =
+            rt.rt.syscall_event(start_clk, None, rt.next_pc, syscall_code.syscall_id(), arg1, arg2);
+        rt.add_precompile_event(syscall_code, syscall_event, event);
+
+        None
+    }
+}
+
+/// Runs the 7 rounds of BLAKE3 on the initial state and returns the 16 output words.
+///
+/// The first 8 words of the initial state are the input chaining value.
+#[must_use]
+pub fn blake3_compress(state: &[u32; 16], msg: &[u32; 16]) -> [u32; 16] {
+    let mut v = *state;
+    for schedule in BLAKE3_MSG_SCHEDULE {
+        for (g, [a, b, c, d]) in BLAKE3_G_INDEX.into_iter().enumerate() {
+            let (mx, my) = (msg[schedule[2 * g]], msg[schedule[2 * g + 1]]);
+            v[a] = v[a].wrapping_add(v[b]).wrapping_add(mx);
+            v[d] = (v[d] ^ v[a]).rotate_right(16);
+            v[c] = v[c].wrapping_add(v[d]);
+            v[b] = (v[b] ^ v[c]).rotate_right(12);
+            v[a] = v[a].wrapping_add(v[b]).wrapping_add(my);
+            v[d] = (v[d] ^ v[a]).rotate_right(8);
+            v[c] = v[c].wrapping_add(v[d]);
+            v[b] = (v[b] ^ v[c]).rotate_right(7);
+        }
+    }
+
+    // The first half is the new chaining value, the second half extends the output.
+    core::array::from_fn(|i| if i < 8 { v[i] ^ v[i + 8] } else { v[i] ^ state[i - 8] })
+}
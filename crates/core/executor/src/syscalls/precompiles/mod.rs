@@ -1,3 +1,5 @@
+pub mod blake2b;
+pub mod blake3;
 pub mod edwards;
 pub mod fptower;
 pub mod keccak;
@@ -7,3 +9,19 @@ pub mod sys_linux;
 pub mod u256x2048_mul;
 pub mod uint256;
 pub mod weierstrass;
+
+/// Checks that the `len` words at `state_ptr` and at `msg_ptr` are aligned, within the address
+/// space and disjoint, as a precompile reading both at the same clk requires.
+pub(crate) fn check_disjoint_words(state_ptr: u32, msg_ptr: u32, len: u32) -> Result<(), String> {
+    if !state_ptr.is_multiple_of(4) || !msg_ptr.is_multiple_of(4) {
+        return Err(format!("state 0x{state_ptr:08x} and message 0x{msg_ptr:08x} must be aligned"));
+    }
+    let size = 4 * len;
+    if state_ptr.checked_add(size).is_none() || msg_ptr.checked_add(size).is_none() {
+        return Err(format!("state 0x{state_ptr:08x} or message 0x{msg_ptr:08x} is out of bounds"));
+    }
+    if state_ptr.abs_diff(msg_ptr) < size {
+        return Err(format!("state 0x{state_ptr:08x} and message 0x{msg_ptr:08x} overlap"));
+    }
+    Ok(())
+}
@@ -1,8 +1,15 @@
 pub mod sysbrk;
+pub mod sysclockgettime;
 pub mod sysclone;
+pub mod sysclose;
 pub mod sysexitgroup;
 pub mod sysfcntl;
+pub mod sysgetpid;
+pub mod sysgetrandom;
+pub mod syslseek;
 pub mod sysmmap;
 pub mod sysnop;
+pub mod sysopen;
 pub mod sysread;
+pub mod sysschedyield;
 pub mod syswrite;
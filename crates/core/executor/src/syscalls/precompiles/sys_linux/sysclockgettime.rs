@@ -0,0 +1,98 @@
+use crate::{
+    events::{LinuxEvent, PrecompileEvent, CLOCK_TICK_NANOS},
+    syscalls::{Syscall, SyscallCode, SyscallContext},
+    LinuxEnv, Register,
+};
+use zkm_primitives::consts::errno::MIPS_EFAULT;
+
+const CLOCK_REALTIME: u32 = 0;
+const CLOCK_REALTIME_COARSE: u32 = 5;
+
+/// Writes a deterministic time to the `timespec` at `a1`.
+///
+/// Every call, even a failing one, advances the clocks by one tick of [`CLOCK_TICK_NANOS`]. The
+/// number of ticks is kept in [`Register::CLOCK`], which starts at zero in the program image. The
+/// real-time clocks start at the epoch configured with [`crate::ZKMContextBuilder::epoch`], which
+/// is committed as a public value, and all others at zero.
+pub(crate) struct SysClockGettimeSyscall;
+
+impl Syscall for SysClockGettimeSyscall {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(
+        &self,
+        rt: &mut SyscallContext,
+        syscall_code: SyscallCode,
+        a0: u32,
+        a1: u32,
+    ) -> Option<u32> {
+        let start_clk = rt.clk;
+        let ticks = rt.rt.register(Register::CLOCK);
+        let mut write_records = vec![];
+        let v0 = if a1 % 4 != 0 {
+            write_records.push(rt.mw(Register::A3 as u32, MIPS_EFAULT));
+            write_records.push(rt.mw(Register::CLOCK as u32, ticks.wrapping_add(1)));
+            0xffffffff
+        } else {
+            write_records.push(rt.mw(Register::A3 as u32, 0));
+            write_records.push(rt.mw(Register::CLOCK as u32, ticks.wrapping_add(1)));
+            let (sec, nsec) = clock_time(&rt.rt.state.linux_env, a0, ticks);
+            write_records.extend(rt.mw_slice(a1, &[sec, nsec]));
+            0
+        };
+
+        let shard = rt.current_shard();
+        let event = PrecompileEvent::Linux(LinuxEvent {
+            shard,
+            clk: start_clk,
+            a0,
+            a1,
+            v0,
+            syscall_code: syscall_code.syscall_id(),
+            read_records: vec![],
+            write_records,
+            local_mem_access: rt.postprocess(),
+        });
+        let syscall_event =
+            rt.rt.syscall_event(start_clk, None, rt.next_pc, syscall_code.syscall_id(), a0, a1);
+        rt.add_precompile_event(SyscallCode::SYS_READ, syscall_event, event);
+        Some(v0)
+    }
+}
+
+/// The time of the clock `clock_id` after `ticks` calls, in seconds and nanoseconds.
+///
+/// A second is 2^16 ticks. The seconds of the o32 `timespec` are 32 bits wide, so they wrap
+/// around like the counter.
+fn clock_time(env: &LinuxEnv, clock_id: u32, ticks: u32) -> (u32, u32) {
+    let start = match clock_id {
+        CLOCK_REALTIME | CLOCK_REALTIME_COARSE => env.epoch,
+        _ => 0,
+    };
+    let sec = start.wrapping_add(ticks >> 16);
+    let nsec = (ticks & 0xffff) * CLOCK_TICK_NANOS;
+    (sec, nsec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_time() {
+        let env = LinuxEnv { epoch: 1_700_000_000, ..Default::default() };
+        assert_eq!(clock_time(&env, CLOCK_REALTIME, 5), (1_700_000_000, 5 * CLOCK_TICK_NANOS));
+        assert_eq!(clock_time(&env, CLOCK_REALTIME, (3 << 16) + 5), (1_700_000_003, 76_290));
+        // CLOCK_MONOTONIC
+        assert_eq!(clock_time(&env, 1, (1 << 16) + 5), (1, 76_290));
+
+        // The last tick of a second is still less than a second.
+        assert_eq!(clock_time(&env, 1, 0xffff), (0, 999_933_030));
+
+        // Times past 2106 don't fit in the `timespec` and wrap around.
+        let env = LinuxEnv { epoch: u32::MAX, ..Default::default() };
+        assert_eq!(clock_time(&env, CLOCK_REALTIME, 1 << 16), (0, 0));
+    }
+}
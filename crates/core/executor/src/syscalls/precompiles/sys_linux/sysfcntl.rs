@@ -3,11 +3,10 @@ use crate::{
     syscalls::{Syscall, SyscallCode, SyscallContext},
     Register,
 };
+use zkm_primitives::consts::errno::MIPS_EBADF;
 
 pub use zkm_primitives::consts::fd::*;
 
-pub const MIPS_EBADF: u32 = 9;
-
 pub(crate) struct SysFcntlSyscall;
 
 impl Syscall for SysFcntlSyscall {
@@ -0,0 +1,92 @@
+use super::sysread::write_buffer;
+use crate::{
+    events::{random_word, LinuxEvent, PrecompileEvent, SYS_READ_MAX_WORDS},
+    syscalls::{Syscall, SyscallCode, SyscallContext},
+    Register,
+};
+
+/// Fills the buffer at `a0` with up to `a1` bytes of a deterministic stream.
+///
+/// Like `SYS_READ`, a call writes at most `SYS_READ_MAX_WORDS` words, so callers must loop
+/// until they have as many bytes as they need. Each written word of the buffer takes its bytes
+/// from the next word [`random_word`] of the stream of the seed set with
+/// [`crate::ZKMContextBuilder::random_seed`], and a call that writes nothing skips a word.
+///
+/// The position in the stream is kept in [`Register::RNG`], which starts at zero in the program
+/// image, and the seed is committed as a public value, so the proof binds the bytes to the seed.
+pub(crate) struct SysGetrandomSyscall;
+
+impl Syscall for SysGetrandomSyscall {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(
+        &self,
+        rt: &mut SyscallContext,
+        syscall_code: SyscallCode,
+        a0: u32,
+        a1: u32,
+    ) -> Option<u32> {
+        let start_clk = rt.clk;
+        let buf = a0;
+        let count = a1;
+
+        let max_len = SYS_READ_MAX_WORDS as u32 * 4 - buf % 4;
+        let len = count.min(max_len);
+        let counter = rt.rt.register(Register::RNG);
+        let bytes = random_bytes(rt.rt.state.linux_env.random_seed, counter, buf % 4, len);
+        let num_words = (buf % 4 + len).div_ceil(4).max(1);
+
+        let mut write_records = vec![rt.mw(Register::A3 as u32, 0)];
+        write_records.push(rt.mw(Register::RNG as u32, counter.wrapping_add(num_words)));
+        write_records.extend(write_buffer(rt, buf, &bytes));
+        let v0 = len;
+
+        let shard = rt.current_shard();
+        let event = PrecompileEvent::Linux(LinuxEvent {
+            shard,
+            clk: start_clk,
+            a0,
+            a1,
+            v0,
+            syscall_code: syscall_code.syscall_id(),
+            read_records: vec![],
+            write_records,
+            local_mem_access: rt.postprocess(),
+        });
+        let syscall_event =
+            rt.rt.syscall_event(start_clk, None, rt.next_pc, syscall_code.syscall_id(), a0, a1);
+        rt.add_precompile_event(SyscallCode::SYS_READ, syscall_event, event);
+        Some(v0)
+    }
+}
+
+/// Returns the `len` bytes of the random stream written to a buffer at `offset` in its first
+/// word, where the first word takes the stream word `counter`.
+///
+/// Every byte keeps its position in its word, so the bytes of the first word before the offset
+/// are skipped.
+fn random_bytes(seed: u64, counter: u32, offset: u32, len: u32) -> Vec<u8> {
+    (offset..offset + len)
+        .map(|i| random_word(seed, counter.wrapping_add(i / 4)).to_le_bytes()[(i % 4) as usize])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_bytes() {
+        let bytes = random_bytes(7, 0, 0, 20);
+        assert_eq!(bytes[..4], random_word(7, 0).to_le_bytes());
+        assert_eq!(bytes[16..], random_word(7, 4).to_le_bytes());
+
+        // A misaligned buffer keeps the bytes at their positions in the words.
+        assert_eq!(random_bytes(7, 0, 2, 6), bytes[2..8]);
+        assert_eq!(random_bytes(7, 3, 0, 4), bytes[12..16]);
+
+        assert_ne!(random_bytes(8, 0, 0, 20), bytes);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    events::{LinuxEvent, PrecompileEvent},
+    syscalls::{Syscall, SyscallCode, SyscallContext},
+    Register,
+};
+use zkm_primitives::consts::errno::{MIPS_EBADF, MIPS_EINVAL};
+
+const SEEK_SET: u32 = 0;
+const SEEK_CUR: u32 = 1;
+const SEEK_END: u32 = 2;
+
+/// Moves the read position of a file opened by `SYS_OPEN`.
+pub(crate) struct SysLseekSyscall;
+
+impl Syscall for SysLseekSyscall {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(
+        &self,
+        rt: &mut SyscallContext,
+        syscall_code: SyscallCode,
+        a0: u32,
+        a1: u32,
+    ) -> Option<u32> {
+        let start_clk = rt.clk;
+        let fd = a0;
+        // The offset is signed, as `off_t` is 32 bits wide on o32.
+        let offset = a1 as i32 as i64;
+        let (whence_record, whence) = rt.mr(Register::A2 as u32);
+
+        let state = &mut rt.rt.state;
+        let (v0, errno) = match state.open_files.get_mut(&fd) {
+            None => (0xffffffff, MIPS_EBADF),
+            Some(file) => {
+                let base = match whence {
+                    SEEK_SET => Some(0),
+                    SEEK_CUR => Some(file.offset as i64),
+                    SEEK_END => Some(state.files[&file.path].len() as i64),
+                    _ => None,
+                };
+                match base.map(|base| base + offset) {
+                    Some(position) if (0..=i32::MAX as i64).contains(&position) => {
+                        file.offset = position as usize;
+                        (position as u32, 0)
+                    }
+                    _ => (0xffffffff, MIPS_EINVAL),
+                }
+            }
+        };
+        let a3_record = rt.mw(Register::A3 as u32, errno);
+
+        let shard = rt.current_shard();
+        let event = PrecompileEvent::Linux(LinuxEvent {
+            shard,
+            clk: start_clk,
+            a0,
+            a1,
+            v0,
+            syscall_code: syscall_code.syscall_id(),
+            read_records: vec![whence_record],
+            write_records: vec![a3_record],
+            local_mem_access: rt.postprocess(),
+        });
+        let syscall_event =
+            rt.rt.syscall_event(start_clk, None, rt.next_pc, syscall_code.syscall_id(), a0, a1);
+        rt.add_precompile_event(SyscallCode::SYS_LINUX, syscall_event, event);
+        Some(v0)
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{
+    events::{LinuxEvent, PrecompileEvent},
+    syscalls::{Syscall, SyscallCode, SyscallContext},
+    OpenFile, Register,
+};
+use zkm_primitives::consts::errno::{MIPS_ENAMETOOLONG, MIPS_ENOENT, MIPS_EROFS};
+
+/// The access mode bits of the `open` flags.
+const O_ACCMODE: u32 = 3;
+
+/// The maximum length of a path, including the terminating NUL byte.
+const PATH_MAX: u32 = 4096;
+
+/// The lowest file descriptor handed out by `SYS_OPEN`, after stdin, stdout and stderr.
+const FIRST_FILE_FD: u32 = 3;
+
+/// Opens a file of the virtual filesystem for reading, for both `open` and `openat`.
+///
+/// The directory fd of `openat` is ignored: paths are looked up exactly as the program passes
+/// them, so they must match the keys the host wrote the files with.
+pub(crate) struct SysOpenSyscall;
+
+impl Syscall for SysOpenSyscall {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(
+        &self,
+        rt: &mut SyscallContext,
+        syscall_code: SyscallCode,
+        a0: u32,
+        a1: u32,
+    ) -> Option<u32> {
+        let start_clk = rt.clk;
+        let (a2_record, a2) = rt.mr(Register::A2 as u32);
+        let (path_ptr, flags) = match syscall_code {
+            SyscallCode::SYS_OPENAT => (a1, a2),
+            _ => (a0, a1),
+        };
+
+        let (v0, errno) = match read_path(rt, path_ptr) {
+            None => (0xffffffff, MIPS_ENAMETOOLONG),
+            Some(_) if flags & O_ACCMODE != 0 => (0xffffffff, MIPS_EROFS),
+            Some(path) if !rt.rt.state.files.contains_key(&path) => (0xffffffff, MIPS_ENOENT),
+            Some(path) => {
+                let state = &mut rt.rt.state;
+                let fd = (FIRST_FILE_FD..)
+                    .find(|fd| !state.open_files.contains_key(fd))
+                    .expect("out of file descriptors");
+                state.open_files.insert(fd, OpenFile { path, offset: 0 });
+                (fd, 0)
+            }
+        };
+        let a3_record = rt.mw(Register::A3 as u32, errno);
+
+        let shard = rt.current_shard();
+        let event = PrecompileEvent::Linux(LinuxEvent {
+            shard,
+            clk: start_clk,
+            a0,
+            a1,
+            v0,
+            syscall_code: syscall_code.syscall_id(),
+            read_records: vec![a2_record],
+            write_records: vec![a3_record],
+            local_mem_access: rt.postprocess(),
+        });
+        let syscall_event =
+            rt.rt.syscall_event(start_clk, None, rt.next_pc, syscall_code.syscall_id(), a0, a1);
+        rt.add_precompile_event(SyscallCode::SYS_LINUX, syscall_event, event);
+        Some(v0)
+    }
+}
+
+/// Reads the NUL-terminated path at `ptr`, returning `None` if it is longer than `PATH_MAX`.
+fn read_path(rt: &mut SyscallContext, ptr: u32) -> Option<String> {
+    let mut bytes = Vec::new();
+    for i in 0..PATH_MAX {
+        match rt.byte_unsafe(ptr.wrapping_add(i)) {
+            0 => return Some(String::from_utf8_lossy(&bytes).into_owned()),
+            byte => bytes.push(byte),
+        }
+    }
+    None
+}
@@ -1,11 +1,11 @@
 use crate::{
-    events::{LinuxEvent, PrecompileEvent},
+    events::{LinuxEvent, MemoryWriteRecord, PrecompileEvent, SYS_READ_MAX_WORDS},
     syscalls::{Syscall, SyscallCode, SyscallContext},
-    Register,
+    ExecutionState, Register,
 };
+use zkm_primitives::consts::errno::MIPS_EBADF;
 pub use zkm_primitives::consts::fd::*;
 
-pub const MIPS_EBADF: u32 = 9;
 pub(crate) struct SysReadSyscall;
 
 impl Syscall for SysReadSyscall {
@@ -22,12 +22,30 @@ impl Syscall for SysReadSyscall {
     ) -> Option<u32> {
         let start_clk = rt.clk;
         let fd = a0;
-        let mut v0 = 0;
-        let a3_record = if fd != FD_STDIN {
-            v0 = 0xffffffff; // Return error for non-stdin reads.
-            rt.mw(Register::A3 as u32, MIPS_EBADF)
-        } else {
-            rt.mw(Register::A3 as u32, 0)
+        let buf = a1;
+        let (count_record, count) = rt.mr(Register::A2 as u32);
+        let mut write_records = vec![];
+
+        // Read no more than fits in `SYS_READ_MAX_WORDS` words from the aligned base of `buf`.
+        let offset = buf % 4;
+        let max_len = SYS_READ_MAX_WORDS as u32 * 4 - offset;
+        let len = count.min(max_len) as usize;
+        let bytes = match fd {
+            FD_STDIN => Some(read_stdin(&mut rt.rt.state, len)),
+            _ => read_file(&mut rt.rt.state, fd, len),
+        };
+
+        let v0 = match bytes {
+            None => {
+                // Return error for fds that are neither stdin nor an open file.
+                write_records.push(rt.mw(Register::A3 as u32, MIPS_EBADF));
+                0xffffffff
+            }
+            Some(bytes) => {
+                write_records.push(rt.mw(Register::A3 as u32, 0));
+                write_records.extend(write_buffer(rt, buf, &bytes));
+                bytes.len() as u32
+            }
         };
 
         let shard = rt.current_shard();
@@ -38,13 +56,98 @@ impl Syscall for SysReadSyscall {
             a1,
             v0,
             syscall_code: syscall_code.syscall_id(),
-            read_records: vec![],
-            write_records: vec![a3_record],
+            read_records: vec![count_record],
+            write_records,
             local_mem_access: rt.postprocess(),
         });
         let syscall_event =
             rt.rt.syscall_event(start_clk, None, rt.next_pc, syscall_code.syscall_id(), a0, a1);
-        rt.add_precompile_event(SyscallCode::SYS_LINUX, syscall_event, event);
+        rt.add_precompile_event(SyscallCode::SYS_READ, syscall_event, event);
         Some(v0)
     }
 }
+
+/// Writes `bytes` to memory starting at `buf`, keeping the other bytes of the touched words.
+pub(crate) fn write_buffer(
+    rt: &mut SyscallContext,
+    buf: u32,
+    bytes: &[u8],
+) -> Vec<MemoryWriteRecord> {
+    let offset = buf % 4;
+    let base = buf.wrapping_sub(offset);
+    let end = offset + bytes.len() as u32;
+    let mut records = Vec::new();
+    for word in 0..end.div_ceil(4) {
+        let addr = base.wrapping_add(word * 4);
+        let prev = match rt.rt.state.memory.get(addr) {
+            Some(record) => record.value,
+            None => rt.rt.state.uninitialized_memory.get(addr).copied().unwrap_or(0),
+        };
+        let mut value = prev.to_le_bytes();
+        for (i, byte) in value.iter_mut().enumerate() {
+            let position = word * 4 + i as u32;
+            if position >= offset && position < end {
+                *byte = bytes[(position - offset) as usize];
+            }
+        }
+        records.push(rt.mw(addr, u32::from_le_bytes(value)));
+    }
+    records
+}
+
+/// Consumes up to `len` bytes of the input stream, returning an empty vector at its end.
+///
+/// The inputs are read as one contiguous byte stream, so a read may span several of them.
+fn read_stdin(state: &mut ExecutionState, len: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(len);
+    while bytes.len() < len && state.input_stream_ptr < state.input_stream.len() {
+        let input = &state.input_stream[state.input_stream_ptr];
+        let available = &input[state.input_stream_offset..];
+        let n = available.len().min(len - bytes.len());
+        bytes.extend_from_slice(&available[..n]);
+        state.input_stream_offset += n;
+        if state.input_stream_offset == input.len() {
+            state.input_stream_ptr += 1;
+            state.input_stream_offset = 0;
+        }
+    }
+    bytes
+}
+
+/// Reads up to `len` bytes of the file opened as `fd`, or returns `None` if `fd` is not open.
+fn read_file(state: &mut ExecutionState, fd: u32, len: usize) -> Option<Vec<u8>> {
+    let file = state.open_files.get_mut(&fd)?;
+    let contents = &state.files[&file.path];
+    let start = file.offset.min(contents.len());
+    let end = (start + len).min(contents.len());
+    file.offset += end - start;
+    Some(contents[start..end].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OpenFile;
+
+    #[test]
+    fn test_read_stdin() {
+        let mut state = ExecutionState::new(0, 4);
+        state.input_stream = vec![vec![1, 2, 3], vec![], vec![4, 5]];
+
+        assert_eq!(read_stdin(&mut state, 2), vec![1, 2]);
+        assert_eq!(read_stdin(&mut state, 8), vec![3, 4, 5]);
+        assert_eq!(state.input_stream_ptr, 3);
+        assert_eq!(read_stdin(&mut state, 8), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_read_file() {
+        let mut state = ExecutionState::new(0, 4);
+        state.files.insert("data".to_string(), vec![1, 2, 3]);
+        state.open_files.insert(3, OpenFile { path: "data".to_string(), offset: 1 });
+
+        assert_eq!(read_file(&mut state, 3, 8), Some(vec![2, 3]));
+        assert_eq!(read_file(&mut state, 3, 8), Some(vec![]));
+        assert_eq!(read_file(&mut state, 4, 8), None);
+    }
+}
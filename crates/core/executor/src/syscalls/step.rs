@@ -0,0 +1,10 @@
+use super::{context::SyscallContext, Syscall, SyscallCode};
+
+pub(crate) struct EndStepSyscall;
+
+impl Syscall for EndStepSyscall {
+    fn execute(&self, ctx: &mut SyscallContext, _: SyscallCode, _: u32, _: u32) -> Option<u32> {
+        ctx.rt.state.paused = true;
+        None
+    }
+}
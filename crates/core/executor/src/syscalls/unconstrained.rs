@@ -19,6 +19,7 @@ impl Syscall for EnterUnconstrainedSyscall {
             memory_diff: HashMap::default(),
             record: std::mem::take(&mut ctx.rt.record),
             op_record: std::mem::take(&mut ctx.rt.memory_accesses),
+            fpu: ctx.rt.state.fpu.clone(),
             executor_mode: ctx.rt.executor_mode,
         };
         ctx.rt.executor_mode = ExecutorMode::Simple;
@@ -47,6 +48,7 @@ impl Syscall for ExitUnconstrainedSyscall {
                 }
             }
             ctx.rt.record = std::mem::take(&mut ctx.rt.unconstrained_state.record);
+            ctx.rt.state.fpu = std::mem::take(&mut ctx.rt.unconstrained_state.fpu);
             ctx.rt.memory_accesses = std::mem::take(&mut ctx.rt.unconstrained_state.op_record);
             ctx.rt.executor_mode = ctx.rt.unconstrained_state.executor_mode;
             ctx.rt.unconstrained = false;
@@ -0,0 +1,455 @@
+//! A versioned binary format for execution traces.
+//!
+//! When `TRACE_FILE` is set, the executor writes a trace of every constrained cycle to that file.
+//! All integers are little-endian. A trace starts with a header:
+//!
+//! | field          | size | description                                           |
+//! |----------------|------|-------------------------------------------------------|
+//! | magic          | 8    | `ZKMTRACE`                                            |
+//! | version        | 4    | [`TRACE_VERSION`]                                     |
+//! | program hash   | 32   | [`program_hash`] of the executed program              |
+//! | pc start       | 4    | the entrypoint of the program                         |
+//!
+//! followed by one record per cycle until the end of the file:
+//!
+//! | field          | size | description                                           |
+//! |----------------|------|-------------------------------------------------------|
+//! | clk            | 8    | the global clock of the cycle                         |
+//! | pc             | 4    | the program counter of the instruction                |
+//! | opcode         | 1    | the index of the [`Opcode`]                           |
+//! | op a           | 1    | the first operand                                     |
+//! | op b           | 4    | the second operand                                    |
+//! | op c           | 4    | the third operand                                     |
+//! | flags          | 1    | bit 0: `imm_b`, bit 1: `imm_c`, bit 2: `raw` is set   |
+//! | raw            | 4    | the raw instruction, or 0                             |
+//! | access count   | 4    | the number of memory accesses that follow             |
+//!
+//! Each memory access of the cycle, in the order they were performed and including the accesses of
+//! syscalls, is encoded as:
+//!
+//! | field          | size | description                                           |
+//! |----------------|------|-------------------------------------------------------|
+//! | position       | 1    | the [`MemoryAccessPosition`]                          |
+//! | kind           | 1    | 0 for a read, 1 for a write                           |
+//! | addr           | 4    | the accessed address (a register index for registers) |
+//! | value          | 4    | the value after the access                            |
+//! | prev value     | 4    | the value before the access                           |
+
+use std::io::{self, ErrorKind, Read, Write};
+
+use enum_map::Enum;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::{
+    events::{MemoryAccessPosition, MemoryReadRecord, MemoryWriteRecord},
+    Instruction, Opcode, Program,
+};
+
+/// The magic bytes at the start of every trace.
+pub const TRACE_MAGIC: [u8; 8] = *b"ZKMTRACE";
+
+/// The version of the trace format written by this executor.
+pub const TRACE_VERSION: u32 = 1;
+
+/// Errors that can occur while reading a trace.
+#[derive(Error, Debug)]
+pub enum TraceError {
+    /// The underlying reader failed, or the trace ended in the middle of a record.
+    #[error("failed to read trace: {0}")]
+    Io(#[from] io::Error),
+
+    /// The input does not start with [`TRACE_MAGIC`].
+    #[error("not an execution trace")]
+    InvalidMagic,
+
+    /// The trace was written with a version this reader does not understand.
+    #[error("unsupported trace version {0}, expected {TRACE_VERSION}")]
+    UnsupportedVersion(u32),
+
+    /// A record contains an unknown opcode.
+    #[error("invalid opcode {0}")]
+    InvalidOpcode(u8),
+
+    /// A record contains an unknown memory access position or kind.
+    #[error("invalid memory access position {0} or kind {1}")]
+    InvalidAccess(u8, u8),
+}
+
+/// The header of a trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceHeader {
+    /// The version of the trace format.
+    pub version: u32,
+    /// The [`program_hash`] of the executed program.
+    pub program_hash: [u8; 32],
+    /// The entrypoint of the program.
+    pub pc_start: u32,
+}
+
+/// A memory access performed by an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceAccess {
+    /// The position of the access in the instruction.
+    pub position: MemoryAccessPosition,
+    /// Whether the access is a write.
+    pub is_write: bool,
+    /// The accessed address. Registers are addressed by their index.
+    pub addr: u32,
+    /// The value after the access.
+    pub value: u32,
+    /// The value before the access. Equal to `value` for reads.
+    pub prev_value: u32,
+}
+
+impl TraceAccess {
+    /// Create a trace access from a memory read.
+    #[must_use]
+    pub fn read(addr: u32, position: MemoryAccessPosition, record: &MemoryReadRecord) -> Self {
+        Self { position, is_write: false, addr, value: record.value, prev_value: record.value }
+    }
+
+    /// Create a trace access from a memory write.
+    #[must_use]
+    pub fn write(addr: u32, position: MemoryAccessPosition, record: &MemoryWriteRecord) -> Self {
+        Self { position, is_write: true, addr, value: record.value, prev_value: record.prev_value }
+    }
+}
+
+/// The execution of a single cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceRecord {
+    /// The global clock of the cycle.
+    pub clk: u64,
+    /// The program counter of the instruction.
+    pub pc: u32,
+    /// The executed instruction.
+    pub instruction: Instruction,
+    /// The memory accesses of the instruction, in the order they were performed.
+    pub accesses: Vec<TraceAccess>,
+}
+
+impl TraceRecord {
+    /// The register writes of the instruction, as `(register, value)` pairs.
+    pub fn register_writes(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.accesses
+            .iter()
+            .filter(|access| access.is_write && access.position != MemoryAccessPosition::Memory)
+            .map(|access| (access.addr, access.value))
+    }
+}
+
+/// The first cycle at which two traces differ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceDivergence {
+    /// The index of the first differing record.
+    pub index: u64,
+    /// The record of the first trace, or `None` if it ended.
+    pub left: Option<TraceRecord>,
+    /// The record of the second trace, or `None` if it ended.
+    pub right: Option<TraceRecord>,
+}
+
+/// Returns a hash identifying the program a trace was produced by.
+#[must_use]
+pub fn program_hash(program: &Program) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(program.pc_start.to_le_bytes());
+    hasher.update(program.pc_base.to_le_bytes());
+    let mut buf = Vec::with_capacity(INSTRUCTION_SIZE);
+    for instruction in &program.instructions {
+        buf.clear();
+        encode_instruction(&mut buf, instruction);
+        hasher.update(&buf);
+    }
+    for (addr, value) in &program.image {
+        hasher.update(addr.to_le_bytes());
+        hasher.update(value.to_le_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// The encoded size of an instruction.
+const INSTRUCTION_SIZE: usize = 15;
+
+fn encode_instruction(buf: &mut Vec<u8>, instruction: &Instruction) {
+    let flags = u8::from(instruction.imm_b)
+        | (u8::from(instruction.imm_c) << 1)
+        | (u8::from(instruction.raw.is_some()) << 2);
+    buf.push(instruction.opcode.into_usize() as u8);
+    buf.push(instruction.op_a);
+    buf.extend_from_slice(&instruction.op_b.to_le_bytes());
+    buf.extend_from_slice(&instruction.op_c.to_le_bytes());
+    buf.push(flags);
+    buf.extend_from_slice(&instruction.raw.unwrap_or(0).to_le_bytes());
+}
+
+fn decode_instruction(buf: &[u8; INSTRUCTION_SIZE]) -> Result<Instruction, TraceError> {
+    if buf[0] as usize >= Opcode::LENGTH {
+        return Err(TraceError::InvalidOpcode(buf[0]));
+    }
+    let flags = buf[10];
+    let raw = u32::from_le_bytes(buf[11..15].try_into().unwrap());
+    Ok(Instruction {
+        opcode: Opcode::from_usize(buf[0] as usize),
+        op_a: buf[1],
+        op_b: u32::from_le_bytes(buf[2..6].try_into().unwrap()),
+        op_c: u32::from_le_bytes(buf[6..10].try_into().unwrap()),
+        imm_b: flags & 1 != 0,
+        imm_c: flags & 2 != 0,
+        raw: (flags & 4 != 0).then_some(raw),
+    })
+}
+
+const fn encode_position(position: MemoryAccessPosition) -> u8 {
+    position as u8
+}
+
+const fn decode_position(position: u8) -> Option<MemoryAccessPosition> {
+    match position {
+        0 => Some(MemoryAccessPosition::Memory),
+        1 => Some(MemoryAccessPosition::C),
+        2 => Some(MemoryAccessPosition::B),
+        3 => Some(MemoryAccessPosition::A),
+        4 => Some(MemoryAccessPosition::HI),
+        _ => None,
+    }
+}
+
+/// Writes an execution trace.
+#[derive(Debug)]
+pub struct TraceWriter<W: Write> {
+    inner: W,
+    accesses: Vec<TraceAccess>,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> TraceWriter<W> {
+    /// Create a writer for a trace of `program` and write the header.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the header cannot be written.
+    pub fn new(mut inner: W, program: &Program) -> io::Result<Self> {
+        inner.write_all(&TRACE_MAGIC)?;
+        inner.write_all(&TRACE_VERSION.to_le_bytes())?;
+        inner.write_all(&program_hash(program))?;
+        inner.write_all(&program.pc_start.to_le_bytes())?;
+        Ok(Self { inner, accesses: Vec::new(), buf: Vec::new() })
+    }
+
+    /// Add a memory access to the record of the current cycle.
+    pub fn access(&mut self, access: TraceAccess) {
+        self.accesses.push(access);
+    }
+
+    /// Discard the accesses of the current cycle without writing a record.
+    pub fn discard(&mut self) {
+        self.accesses.clear();
+    }
+
+    /// Write the record of the current cycle with the accesses added since the last record.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the record cannot be written.
+    pub fn record(&mut self, clk: u64, pc: u32, instruction: &Instruction) -> io::Result<()> {
+        self.buf.clear();
+        self.buf.extend_from_slice(&clk.to_le_bytes());
+        self.buf.extend_from_slice(&pc.to_le_bytes());
+        encode_instruction(&mut self.buf, instruction);
+        let count = u32::try_from(self.accesses.len())
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "too many memory accesses"))?;
+        self.buf.extend_from_slice(&count.to_le_bytes());
+        for access in self.accesses.drain(..) {
+            self.buf.push(encode_position(access.position));
+            self.buf.push(u8::from(access.is_write));
+            self.buf.extend_from_slice(&access.addr.to_le_bytes());
+            self.buf.extend_from_slice(&access.value.to_le_bytes());
+            self.buf.extend_from_slice(&access.prev_value.to_le_bytes());
+        }
+        self.inner.write_all(&self.buf)
+    }
+
+    /// Flush the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying writer cannot be flushed.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads an execution trace.
+#[derive(Debug)]
+pub struct TraceReader<R: Read> {
+    inner: R,
+    header: TraceHeader,
+}
+
+impl<R: Read> TraceReader<R> {
+    /// Create a reader and read the header of the trace.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the header is missing or has an unsupported version.
+    pub fn new(mut inner: R) -> Result<Self, TraceError> {
+        let mut magic = [0u8; 8];
+        inner.read_exact(&mut magic).map_err(|err| match err.kind() {
+            ErrorKind::UnexpectedEof => TraceError::InvalidMagic,
+            _ => err.into(),
+        })?;
+        if magic != TRACE_MAGIC {
+            return Err(TraceError::InvalidMagic);
+        }
+        let version = u32::from_le_bytes(read_array(&mut inner)?);
+        if version != TRACE_VERSION {
+            return Err(TraceError::UnsupportedVersion(version));
+        }
+        let program_hash = read_array(&mut inner)?;
+        let pc_start = u32::from_le_bytes(read_array(&mut inner)?);
+        Ok(Self { inner, header: TraceHeader { version, program_hash, pc_start } })
+    }
+
+    /// The header of the trace.
+    #[must_use]
+    pub fn header(&self) -> &TraceHeader {
+        &self.header
+    }
+
+    /// Read the next record, or `None` at the end of the trace.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the record is truncated or malformed.
+    pub fn next_record(&mut self) -> Result<Option<TraceRecord>, TraceError> {
+        let mut clk = [0u8; 8];
+        let mut filled = 0;
+        while filled < clk.len() {
+            match self.inner.read(&mut clk[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+                Ok(n) => filled += n,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let pc = u32::from_le_bytes(read_array(&mut self.inner)?);
+        let instruction = decode_instruction(&read_array(&mut self.inner)?)?;
+        let count = u32::from_le_bytes(read_array(&mut self.inner)?);
+        let accesses = (0..count)
+            .map(|_| {
+                let buf = read_array::<14>(&mut self.inner)?;
+                let position = decode_position(buf[0])
+                    .filter(|_| buf[1] <= 1)
+                    .ok_or(TraceError::InvalidAccess(buf[0], buf[1]))?;
+                Ok(TraceAccess {
+                    position,
+                    is_write: buf[1] == 1,
+                    addr: u32::from_le_bytes(buf[2..6].try_into().unwrap()),
+                    value: u32::from_le_bytes(buf[6..10].try_into().unwrap()),
+                    prev_value: u32::from_le_bytes(buf[10..14].try_into().unwrap()),
+                })
+            })
+            .collect::<Result<Vec<_>, TraceError>>()?;
+
+        Ok(Some(TraceRecord { clk: u64::from_le_bytes(clk), pc, instruction, accesses }))
+    }
+}
+
+impl<R: Read> Iterator for TraceReader<R> {
+    type Item = Result<TraceRecord, TraceError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_record().transpose()
+    }
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Compare two traces record by record and return the first point at which they differ, or `None`
+/// if they are identical.
+///
+/// The headers are not compared, so traces of different builds of the same program can be diffed.
+///
+/// # Errors
+///
+/// This function will return an error if either trace cannot be read.
+pub fn first_divergence<A: Read, B: Read>(
+    left: &mut TraceReader<A>,
+    right: &mut TraceReader<B>,
+) -> Result<Option<TraceDivergence>, TraceError> {
+    let mut index = 0;
+    loop {
+        let (l, r) = (left.next_record()?, right.next_record()?);
+        match (l, r) {
+            (None, None) => return Ok(None),
+            (l, r) if l != r => return Ok(Some(TraceDivergence { index, left: l, right: r })),
+            _ => index += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use zkm_stark::ZKMCoreOpts;
+
+    use super::{first_divergence, program_hash, TraceReader, TraceWriter, TRACE_VERSION};
+    use crate::{programs::tests::fibonacci_program, Executor};
+
+    fn trace() -> Vec<u8> {
+        let program = fibonacci_program();
+        let writer = TraceWriter::new(Vec::new(), &program).unwrap();
+        let mut runtime = Executor::new(program, ZKMCoreOpts::default());
+        runtime.trace_writer = Some(writer);
+        runtime.run_fast().unwrap();
+        runtime.trace_writer.take().unwrap().inner
+    }
+
+    #[test]
+    fn test_trace_roundtrip() {
+        let mut reader = TraceReader::new(Cursor::new(trace())).unwrap();
+        assert_eq!(reader.header().version, TRACE_VERSION);
+        assert_eq!(reader.header().program_hash, program_hash(&fibonacci_program()));
+
+        let records = reader.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+        assert!(!records.is_empty());
+        assert_eq!(records[0].pc, reader.header().pc_start);
+        assert!(records.iter().any(|record| record.register_writes().next().is_some()));
+    }
+
+    #[test]
+    fn test_trace_divergence() {
+        let bytes = trace();
+        let mut left = TraceReader::new(Cursor::new(bytes.clone())).unwrap();
+        let mut right = TraceReader::new(Cursor::new(bytes.clone())).unwrap();
+        assert_eq!(first_divergence(&mut left, &mut right).unwrap(), None);
+
+        // Rewrite the trace with a different register value in the fifth record.
+        let records =
+            TraceReader::new(Cursor::new(bytes.clone())).unwrap().collect::<Result<Vec<_>, _>>();
+        let mut writer = TraceWriter::new(Vec::new(), &fibonacci_program()).unwrap();
+        for (i, record) in records.unwrap().into_iter().enumerate() {
+            for mut access in record.accesses {
+                if i == 5 {
+                    access.value ^= 1;
+                }
+                writer.access(access);
+            }
+            writer.record(record.clk, record.pc, &record.instruction).unwrap();
+        }
+
+        let mut left = TraceReader::new(Cursor::new(bytes)).unwrap();
+        let mut right = TraceReader::new(Cursor::new(writer.inner)).unwrap();
+        let divergence = first_divergence(&mut left, &mut right).unwrap().unwrap();
+        assert_eq!(divergence.index, 5);
+    }
+}
@@ -0,0 +1,298 @@
+//! Static validation of guest ELFs.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
+
+use anyhow::{anyhow, Result};
+use elf::{endian::LittleEndian, ElfBytes};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fpu::{decode_fpu_trap, FPU_TRAP_SYMBOL},
+    program::{function_symbols, patch_elf, FunctionSymbol},
+    Instruction, Opcode, Program, WORD_SIZE,
+};
+
+/// The number of instructions after an `LL` in which the matching `SC` is expected.
+const LL_SC_WINDOW: usize = 16;
+
+/// The kind of problem found in an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FindingKind {
+    /// A floating-point (COP1/COP1X) instruction that cannot be executed: any of them in a program
+    /// without the FPU trap handler, see [`crate::fpu`], and FPU branches other than BC1F and BC1T
+    /// on condition code 0.
+    FloatingPoint,
+    /// A floating-point instruction in a branch delay slot, which cannot be trapped.
+    FloatingPointInDelaySlot,
+    /// A branch-likely instruction (`BEQL`, `BNEL`, ...), which the zkVM does not support.
+    BranchLikely,
+    /// An instruction that decodes to [`Opcode::UNIMPL`].
+    Unsupported,
+    /// An `SC` without a preceding `LL`.
+    UnpairedSc,
+    /// A load or store between an `LL` and its `SC`, which makes the `SC` unpredictable.
+    MemoryAccessInLlSc,
+}
+
+impl FindingKind {
+    /// Whether executing the instruction is guaranteed to fail.
+    #[must_use]
+    pub const fn is_error(&self) -> bool {
+        matches!(
+            self,
+            Self::FloatingPoint
+                | Self::FloatingPointInDelaySlot
+                | Self::BranchLikely
+                | Self::Unsupported
+        )
+    }
+}
+
+impl Display for FindingKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FloatingPoint => write!(f, "floating-point instruction"),
+            Self::FloatingPointInDelaySlot => {
+                write!(f, "floating-point instruction in a branch delay slot")
+            }
+            Self::BranchLikely => write!(f, "branch-likely instruction"),
+            Self::Unsupported => write!(f, "unsupported instruction"),
+            Self::UnpairedSc => write!(f, "sc without a preceding ll"),
+            Self::MemoryAccessInLlSc => write!(f, "memory access between ll and sc"),
+        }
+    }
+}
+
+/// A problem found in an instruction of a program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    /// The address of the instruction.
+    pub addr: u32,
+    /// The raw instruction.
+    pub word: u32,
+    /// The function containing the instruction and the offset into it, if known.
+    pub symbol: Option<(String, u32)>,
+    /// The kind of problem.
+    pub kind: FindingKind,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{:08x}", self.addr)?;
+        if let Some((name, offset)) = &self.symbol {
+            write!(f, " <{name}+0x{offset:x}>")?;
+        }
+        write!(f, ": {:08x} {}", self.word, self.kind)
+    }
+}
+
+/// The result of [`Program::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// The number of instructions scanned.
+    pub instructions: usize,
+    /// The problems found, ordered by address.
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    /// Whether any instruction is guaranteed to fail if executed.
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|finding| finding.kind.is_error())
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for finding in &self.findings {
+            let severity = if finding.kind.is_error() { "error" } else { "warning" };
+            writeln!(f, "{severity}: {finding}")?;
+        }
+        let errors = self.findings.iter().filter(|finding| finding.kind.is_error()).count();
+        writeln!(
+            f,
+            "scanned {} instructions: {} errors, {} warnings",
+            self.instructions,
+            errors,
+            self.findings.len() - errors
+        )
+    }
+}
+
+/// Classify a raw instruction, independently of whether [`Instruction::decode_from`] supports it.
+fn classify(word: u32) -> Option<FindingKind> {
+    let (op, rt) = (word >> 26, (word >> 16) & 0x1f);
+    match op {
+        // COP1, COP1X, LWC1, LDC1, SWC1, SDC1.
+        0x11 | 0x13 | 0x31 | 0x35 | 0x39 | 0x3d => Some(FindingKind::FloatingPoint),
+        // BEQL, BNEL, BLEZL, BGTZL.
+        0x14..=0x17 => Some(FindingKind::BranchLikely),
+        // BLTZL, BGEZL, BLTZALL, BGEZALL.
+        0x01 if matches!(rt, 0x02 | 0x03 | 0x12 | 0x13) => Some(FindingKind::BranchLikely),
+        _ => None,
+    }
+}
+
+/// Whether `word` is a `BREAK`, which the compiler emits for aborts and which is never reached in
+/// a program that runs to completion.
+const fn is_break(word: u32) -> bool {
+    word >> 26 == 0 && word & 0x3f == 0x0d
+}
+
+/// Find `SC`s without an `LL` and memory accesses between an `LL` and its `SC`.
+fn check_ll_sc(instructions: &[(u32, u32, Instruction)], findings: &mut Vec<(usize, FindingKind)>) {
+    let mut open_ll: Option<usize> = None;
+    for (i, (_, _, instruction)) in instructions.iter().enumerate() {
+        if open_ll.is_some_and(|ll| i - ll > LL_SC_WINDOW) {
+            open_ll = None;
+        }
+        match instruction.opcode {
+            Opcode::LL => open_ll = Some(i),
+            Opcode::SC if open_ll.is_none() => findings.push((i, FindingKind::UnpairedSc)),
+            Opcode::SC => open_ll = None,
+            _ if open_ll.is_some() && instruction.is_memory_instruction() => {
+                findings.push((i, FindingKind::MemoryAccessInLlSc));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the function containing `addr` and the offset into it.
+pub(crate) fn symbol_at(symbols: &[FunctionSymbol], addr: u32) -> Option<(String, u32)> {
+    let idx = symbols.partition_point(|symbol| symbol.start <= addr).checked_sub(1)?;
+    let symbol = &symbols[idx];
+    (addr < symbol.end).then(|| (symbol.name.clone(), addr - symbol.start))
+}
+
+impl Program {
+    /// Statically scan the executable segments of an ELF for instructions the zkVM cannot execute.
+    ///
+    /// Every word of every executable `PT_LOAD` segment is decoded, after applying the same patches
+    /// as [`Program::from`]. Words that are never executed are reported as well, since the scan
+    /// cannot know which words are reachable.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the ELF cannot be parsed or has no symbol table.
+    pub fn validate(elf_code: &[u8]) -> Result<ValidationReport> {
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(elf_code)
+            .map_err(|err| anyhow!("Elf parse error: {err}"))?;
+        // Like `Program::from`, reject stripped ELFs, which cannot be patched.
+        let symbols = function_symbols(&elf)?;
+        let mut patch_list = BTreeMap::new();
+        patch_elf(&elf, &mut patch_list);
+        let fpu_handler =
+            symbols.iter().find(|symbol| symbol.name == FPU_TRAP_SYMBOL).map(|symbol| symbol.start);
+
+        // The address, raw word and decoded instruction of every executable word.
+        let mut instructions = Vec::new();
+        let segments = elf.segments().ok_or_else(|| anyhow!("Missing segment table"))?;
+        let executable = segments.iter().filter(|segment| {
+            segment.p_type == elf::abi::PT_LOAD && segment.p_flags & elf::abi::PF_X != 0
+        });
+        for segment in executable {
+            let data = elf
+                .segment_data(&segment)
+                .map_err(|err| anyhow!("failed to read segment data: {err}"))?;
+            for (i, chunk) in data.chunks(WORD_SIZE).enumerate() {
+                let addr = segment.p_vaddr as u32 + (i * WORD_SIZE) as u32;
+                let word = match patch_list.get(&addr) {
+                    Some(&word) => word,
+                    None => chunk.iter().rev().fold(0, |word, &byte| (word << 8) | u32::from(byte)),
+                };
+                let mut instruction = Instruction::decode_from(word)?;
+                // Decode FPU instructions to traps like `Program::from`.
+                if let Some(trap) = fpu_handler
+                    .filter(|_| instruction.opcode == Opcode::UNIMPL)
+                    .and_then(|handler| decode_fpu_trap(word, handler))
+                {
+                    instruction = trap;
+                }
+                instructions.push((addr, word, instruction));
+            }
+        }
+
+        let mut findings = Vec::new();
+        for (i, (_, word, instruction)) in instructions.iter().enumerate() {
+            let trapped = instruction.opcode != Opcode::UNIMPL;
+            if instruction.opcode == Opcode::TRAP {
+                let previous = i.checked_sub(1).map(|i| &instructions[i].2);
+                if previous.is_some_and(|previous| {
+                    previous.is_branch_instruction() || previous.is_jump_instruction()
+                }) {
+                    findings.push((i, FindingKind::FloatingPointInDelaySlot));
+                }
+            } else if let Some(kind) =
+                classify(*word).filter(|kind| *kind != FindingKind::FloatingPoint || !trapped)
+            {
+                findings.push((i, kind));
+            } else if instruction.opcode == Opcode::UNIMPL && !is_break(*word) {
+                findings.push((i, FindingKind::Unsupported));
+            }
+        }
+        check_ll_sc(&instructions, &mut findings);
+
+        let mut findings = findings
+            .into_iter()
+            .map(|(i, kind)| {
+                let (addr, word, _) = instructions[i];
+                Finding { addr, word, symbol: symbol_at(&symbols, addr), kind }
+            })
+            .collect::<Vec<_>>();
+        findings.sort_by_key(|finding| finding.addr);
+
+        Ok(ValidationReport { instructions: instructions.len(), findings })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_artifacts::{FIBONACCI_ELF, KECCAK_SPONGE_ELF};
+
+    use super::{classify, is_break, FindingKind};
+    use crate::Program;
+
+    #[test]
+    fn test_validate_test_artifacts() {
+        for elf in [FIBONACCI_ELF, KECCAK_SPONGE_ELF] {
+            let report = Program::validate(elf).unwrap();
+            assert!(report.instructions > 0);
+            assert!(!report.has_errors(), "{report}");
+        }
+    }
+
+    #[test]
+    fn test_validate_stripped_elf() {
+        // An ELF header with neither program nor section headers, so no symbol table.
+        let mut elf = vec![0x7f, b'E', b'L', b'F', 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        for field in [2u16, 8] {
+            elf.extend_from_slice(&field.to_le_bytes());
+        }
+        for field in [1u32, 0, 0, 0, 0] {
+            elf.extend_from_slice(&field.to_le_bytes());
+        }
+        for field in [52u16, 32, 0, 40, 0, 0] {
+            elf.extend_from_slice(&field.to_le_bytes());
+        }
+        assert!(Program::validate(&elf).is_err());
+    }
+
+    #[test]
+    fn test_classify() {
+        // add.s $f0, $f1, $f2
+        assert_eq!(classify(0x4602_0800), Some(FindingKind::FloatingPoint));
+        // beql $a0, $a1, 4
+        assert_eq!(classify(0x5085_0001), Some(FindingKind::BranchLikely));
+        // bgezl $a0, 4
+        assert_eq!(classify(0x0483_0001), Some(FindingKind::BranchLikely));
+        // addiu $a0, $a0, 1
+        assert_eq!(classify(0x2484_0001), None);
+        // break
+        assert!(is_break(0x0000_000d));
+    }
+}
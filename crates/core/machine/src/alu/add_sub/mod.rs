@@ -205,6 +205,7 @@ where
             AB::Expr::ZERO,
             AB::Expr::ZERO,
             AB::Expr::ZERO,
+            AB::Expr::ZERO,
             AB::Expr::ONE,
             local.is_add,
         );
@@ -227,6 +228,7 @@ where
             AB::Expr::ZERO,
             AB::Expr::ZERO,
             AB::Expr::ZERO,
+            AB::Expr::ZERO,
             AB::Expr::ONE,
             local.is_sub,
         );
@@ -215,6 +215,7 @@ where
             AB::Expr::ZERO,
             AB::Expr::ZERO,
             AB::Expr::ZERO,
+            AB::Expr::ZERO,
             AB::Expr::ONE,
             local.is_xor + local.is_or + local.is_and + local.is_nor,
         );
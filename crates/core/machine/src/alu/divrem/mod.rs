@@ -708,6 +708,7 @@ where
                 AB::Expr::ZERO,
                 AB::Expr::ONE,
                 AB::Expr::ZERO,
+                AB::Expr::ZERO,
                 AB::Expr::ONE,
                 local.is_div + local.is_divu,
             );
@@ -729,6 +730,7 @@ where
                 AB::Expr::ZERO,
                 AB::Expr::ZERO,
                 AB::Expr::ZERO,
+                AB::Expr::ZERO,
                 AB::Expr::ONE,
                 local.is_mod + local.is_modu,
             );
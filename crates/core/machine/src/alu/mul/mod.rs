@@ -468,6 +468,7 @@ where
             AB::Expr::ZERO,
             local.hi_record_is_real,
             AB::Expr::ZERO,
+            AB::Expr::ZERO,
             AB::Expr::ONE,
             local.is_real,
         );
@@ -23,18 +23,21 @@ where
         let local = main.row_slice(0);
         let local: &JumpColumns<AB::Var> = (*local).borrow();
 
-        // SAFETY: All selectors `is_jump`, `is_jumpi`, `is_jumpdirect`  are checked to be boolean.
-        // Each "real" row has exactly one selector turned on, as `is_real = is_jump + is_jumpi + is_jumpdirect` is boolean.
+        // SAFETY: All selectors `is_jump`, `is_jumpi`, `is_jumpdirect`, `is_trap` are checked to be boolean.
+        // Each "real" row has exactly one selector turned on, as `is_real = is_jump + is_jumpi + is_jumpdirect + is_trap` is boolean.
         // Therefore, the `opcode` matches the corresponding opcode.
         builder.assert_bool(local.is_jump);
         builder.assert_bool(local.is_jumpi);
         builder.assert_bool(local.is_jumpdirect);
-        let is_real = local.is_jump + local.is_jumpi + local.is_jumpdirect;
+        builder.assert_bool(local.is_trap);
+        let is_delayed = local.is_jump + local.is_jumpi + local.is_jumpdirect;
+        let is_real = is_delayed.clone() + local.is_trap;
         builder.assert_bool(is_real.clone());
 
         let opcode = local.is_jump * Opcode::Jump.as_field::<AB::F>()
             + local.is_jumpi * Opcode::Jumpi.as_field::<AB::F>()
-            + local.is_jumpdirect * Opcode::JumpDirect.as_field::<AB::F>();
+            + local.is_jumpdirect * Opcode::JumpDirect.as_field::<AB::F>()
+            + local.is_trap * Opcode::TRAP.as_field::<AB::F>();
 
         // SAFETY: This checks the following.
         // - `num_extra_cycles = 0`
@@ -42,6 +45,8 @@ where
         // - `is_memory = 0`
         // - `is_syscall = 0`
         // - `is_halt = 0`
+        // - `is_trap` is the trap selector, which exempts the `next_pc` of a trap from following the
+        //   previous instruction in the CpuChip, so that the handler runs without a delay slot.
         // `next_pc` and `op_a_value` still has to be constrained, and this is done below.
         builder.receive_instruction(
             AB::Expr::ZERO,
@@ -60,16 +65,28 @@ where
             AB::Expr::ZERO,
             AB::Expr::ZERO,
             AB::Expr::ZERO,
+            local.is_trap,
             AB::Expr::ZERO,
             is_real.clone(),
         );
 
         // Verify that the local.next_pc + 4 is op_a_value for all jump instructions.
-        builder.when(is_real.clone()).assert_eq(
+        builder.when(is_delayed).assert_eq(
             local.op_a_value.reduce::<AB>(),
             local.next_pc.reduce::<AB>() + AB::F::from_canonical_u32(4),
         );
 
+        // A trap links `pc + 4` in op_a, and continues at the handler in op_b without a delay
+        // slot, so `next_next_pc = next_pc + 4`.
+        builder
+            .when(local.is_trap)
+            .assert_eq(local.op_a_value.reduce::<AB>(), local.pc + AB::F::from_canonical_u32(4));
+        builder.when(local.is_trap).assert_word_eq(local.next_pc, local.op_b_value);
+        builder.when(local.is_trap).assert_eq(
+            local.next_next_pc.reduce::<AB>(),
+            local.next_pc.reduce::<AB>() + AB::F::from_canonical_u32(4),
+        );
+
         // Range check op_a, pc, and next_pc.
         // SAFETY: `is_real` is already checked to be boolean.
         // `op_a_value` is checked to be a valid word, as it matches the one in the CpuChip.
@@ -31,6 +31,8 @@ pub struct JumpColumns<T> {
     pub is_jump: T,
     pub is_jumpi: T,
     pub is_jumpdirect: T,
+    /// Whether the instruction is an FPU trap, which has no delay slot.
+    pub is_trap: T,
 
     // A range checker for `op_a` which may contain `next_pc + 4`.
     pub op_a_range_checker: KoalaBearWordRangeChecker<T>,
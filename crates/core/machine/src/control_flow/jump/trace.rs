@@ -85,6 +85,7 @@ impl JumpChip {
         cols.is_jump = F::from_bool(matches!(event.opcode, Opcode::Jump));
         cols.is_jumpi = F::from_bool(matches!(event.opcode, Opcode::Jumpi));
         cols.is_jumpdirect = F::from_bool(matches!(event.opcode, Opcode::JumpDirect));
+        cols.is_trap = F::from_bool(matches!(event.opcode, Opcode::TRAP));
 
         cols.op_a_value = event.a.into();
         cols.op_b_value = event.b.into();
@@ -79,6 +79,7 @@ where
             local.is_rw_a,
             local.is_write_hi,
             local.is_halt,
+            local.is_trap,
             local.is_sequential,
             local.is_real,
         );
@@ -161,10 +162,11 @@ impl CpuChip {
         // Verify the public value's start pc.
         builder.when_first_row().assert_eq(public_values.start_pc, local.pc);
 
-        // Verify the relationship between initial start pc and initial next pc.
+        // Verify the relationship between initial start pc and initial next pc. A trap sets its own
+        // `next_pc` to the handler, which the JumpChip checks.
         builder
             .when_first_row()
-            .when_not(local.is_halt)
+            .when_not(local.is_halt + local.is_trap)
             .assert_eq(local.pc + AB::Expr::from_canonical_u32(4), local.next_pc);
 
         // Verify the pc, next_pc, and next_next_pc
@@ -172,9 +174,12 @@ impl CpuChip {
         builder
             .when_transition()
             .when(next.is_real)
-            .when_not(next.is_halt)
+            .when_not(next.is_halt + next.is_trap)
             .assert_eq(local.next_next_pc, next.next_pc);
 
+        // A trap cannot be in a delay slot, where it would skip the target of the branch or jump.
+        builder.when_transition().when(next.is_trap).assert_one(local.is_sequential);
+
         builder
             .when_transition()
             .when(local.is_real)
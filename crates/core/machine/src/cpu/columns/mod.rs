@@ -58,6 +58,9 @@ pub struct CpuCols<T: Copy> {
     /// Whether this is a halt instruction.
     pub is_halt: T,
 
+    /// Whether this is an FPU trap, which sets its own `next_pc` to the handler.
+    pub is_trap: T,
+
     /// Whether this is a sequential instruction (not branch or jump or halt).
     pub is_sequential: T,
 
@@ -5,7 +5,7 @@ use zkm_core_executor::{
     events::{ByteLookupEvent, ByteRecord, CpuEvent, MemoryRecordEnum},
     syscalls::SyscallCode,
     ByteOpcode::{self, U16Range},
-    ExecutionRecord, Instruction, Program,
+    ExecutionRecord, Instruction, Opcode, Program,
 };
 use zkm_stark::air::MachineAir;
 
@@ -172,22 +172,25 @@ impl CpuChip {
             cols.op_c_access.populate(record, blu_events);
         }
 
-        let mut is_halt = false;
         if instruction.is_syscall_instruction() {
             let syscall_id0 = cols.op_a_access.prev_value[0];
             let syscall_id1 = cols.op_a_access.prev_value[1];
             let num_extra_cycles = cols.op_a_access.prev_value[3];
             let sys_exit_group = SyscallCode::SYS_EXT_GROUP.syscall_id();
-            is_halt = (syscall_id0 == F::from_canonical_u32(SyscallCode::HALT.syscall_id())
+            let is_halt = (syscall_id0 == F::from_canonical_u32(SyscallCode::HALT.syscall_id())
                 && syscall_id1 == F::ZERO)
                 || (syscall_id0 == F::from_canonical_u8(sys_exit_group as u8)
                     && syscall_id1 == F::from_canonical_u8((sys_exit_group >> 8) as u8));
             cols.is_halt = F::from_bool(is_halt);
             cols.num_extra_cycles = num_extra_cycles;
         }
+        cols.is_trap = F::from_bool(instruction.opcode == Opcode::TRAP);
 
         cols.is_sequential = F::from_bool(
-            !is_halt && !instruction.is_branch_instruction() && !instruction.is_jump_instruction(),
+            cols.is_halt == F::ZERO
+                && cols.is_trap == F::ZERO
+                && !instruction.is_branch_instruction()
+                && !instruction.is_jump_instruction(),
         );
 
         // Populate range checks for a.
@@ -1,6 +1,11 @@
+use std::collections::BTreeMap;
+
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use zkm_core_executor::ZKMReduceProof;
-use zkm_stark::{koala_bear_poseidon2::KoalaBearPoseidon2, StarkVerifyingKey};
+use zkm_core_executor::{LegacyReduceProof, ZKMReduceProof};
+use zkm_stark::{
+    koala_bear_poseidon2::KoalaBearPoseidon2, LegacyStarkVerifyingKey, SecurityConfig,
+    StarkVerifyingKey,
+};
 
 /// Standard input for the prover.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -10,17 +15,41 @@ pub struct ZKMStdin {
     pub buffer: Vec<Vec<u8>>,
     pub ptr: usize,
     pub proofs: Vec<(ZKMReduceProof<KoalaBearPoseidon2>, StarkVerifyingKey<KoalaBearPoseidon2>)>,
+    /// The files of the virtual filesystem, keyed by the path the guest opens them with.
+    pub files: BTreeMap<String, Vec<u8>>,
+}
+
+/// A [`ZKMStdin`] encoded before the virtual filesystem was added and before proofs recorded
+/// their security parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyZKMStdin {
+    pub buffer: Vec<Vec<u8>>,
+    pub ptr: usize,
+    pub proofs:
+        Vec<(LegacyReduceProof<KoalaBearPoseidon2>, LegacyStarkVerifyingKey<KoalaBearPoseidon2>)>,
+}
+
+impl LegacyZKMStdin {
+    /// The stdin, with no files, assuming its proofs were proven with `security`.
+    pub fn with_security(self, security: SecurityConfig) -> ZKMStdin {
+        let proofs = self
+            .proofs
+            .into_iter()
+            .map(|(proof, vk)| (proof.with_security(security), vk.with_security(security)))
+            .collect();
+        ZKMStdin { buffer: self.buffer, ptr: self.ptr, proofs, files: BTreeMap::new() }
+    }
 }
 
 impl ZKMStdin {
     /// Create a new `ZKMStdin`.
     pub const fn new() -> Self {
-        Self { buffer: Vec::new(), ptr: 0, proofs: Vec::new() }
+        Self { buffer: Vec::new(), ptr: 0, proofs: Vec::new(), files: BTreeMap::new() }
     }
 
     /// Create a `ZKMStdin` from a slice of bytes.
     pub fn from(data: &[u8]) -> Self {
-        Self { buffer: vec![data.to_vec()], ptr: 0, proofs: Vec::new() }
+        Self { buffer: vec![data.to_vec()], ptr: 0, proofs: Vec::new(), files: BTreeMap::new() }
     }
 
     /// Read a value from the buffer.
@@ -60,6 +89,17 @@ impl ZKMStdin {
     ) {
         self.proofs.push((proof, vk));
     }
+
+    /// Add a file to the virtual filesystem, which the guest can read through the `open`, `read`,
+    /// `lseek` and `close` syscalls.
+    ///
+    /// The virtual filesystem is not provable. Like the rest of the input, the files are a hint:
+    /// they are not committed, and the proof doesn't check the results of the file syscalls
+    /// against them, so a malicious prover can serve any contents. A verifier only learns about
+    /// the files what the guest commits, e.g. with `zkm_zkvm::io::read_file_and_commit`.
+    pub fn write_file(&mut self, path: impl Into<String>, contents: Vec<u8>) {
+        self.files.insert(path.into(), contents);
+    }
 }
 
 pub mod proof_serde {
@@ -98,3 +138,24 @@ pub mod proof_serde {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_stdin() {
+        let legacy = LegacyZKMStdin { buffer: vec![vec![1, 2, 3]], ptr: 1, proofs: vec![] };
+        let bytes = bincode::serialize(&legacy).unwrap();
+        let legacy: LegacyZKMStdin = bincode::deserialize(&bytes).unwrap();
+        let stdin = legacy.with_security(SecurityConfig::default());
+        assert_eq!((stdin.buffer, stdin.ptr), (vec![vec![1, 2, 3]], 1));
+        assert!(stdin.files.is_empty());
+
+        let mut stdin = ZKMStdin::new();
+        stdin.write_file("data", vec![4, 5]);
+        let bytes = bincode::serialize(&stdin).unwrap();
+        let decoded: ZKMStdin = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.files, stdin.files);
+    }
+}
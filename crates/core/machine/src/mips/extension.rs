@@ -0,0 +1,68 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{Field, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::SymbolicAirBuilder;
+use zkm_core_executor::{syscalls::SyscallCode, ExecutionRecord, Program};
+use zkm_stark::{air::MachineAir, LookupBuilder};
+
+/// A chip from outside of Ziren, proving the syscalls of a custom precompile.
+///
+/// The chip must be named after the `MipsAirId` of its syscall code, see
+/// [`SyscallCode::custom_air_id`], so that it can be part of the core shapes.
+pub trait MipsExtension<F: PrimeField32>:
+    MachineAir<F, Record = ExecutionRecord, Program = Program>
+    + Air<LookupBuilder<F>>
+    + Air<SymbolicAirBuilder<F>>
+{
+    /// The syscall code of the precompile, one of the `SyscallCode::CUSTOM_*` codes.
+    fn syscall_code(&self) -> SyscallCode;
+
+    /// The number of rows of the chip for each event of the precompile.
+    fn rows_per_event(&self) -> usize {
+        1
+    }
+}
+
+/// The extension of a [`crate::MipsAir`] machine without any chips from outside of Ziren.
+///
+/// Downstream crates that add precompiles define their own extension, usually an enum of their
+/// chips deriving [`MachineAir`], and build the machine with
+/// [`crate::MipsAir::machine_with_extensions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoExtension {}
+
+impl<F: Field> BaseAir<F> for NoExtension {
+    fn width(&self) -> usize {
+        match *self {}
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for NoExtension {
+    type Record = ExecutionRecord;
+
+    type Program = Program;
+
+    fn name(&self) -> String {
+        match *self {}
+    }
+
+    fn generate_trace(&self, _: &ExecutionRecord, _: &mut ExecutionRecord) -> RowMajorMatrix<F> {
+        match *self {}
+    }
+
+    fn included(&self, _: &Self::Record) -> bool {
+        match *self {}
+    }
+}
+
+impl<F: PrimeField32> MipsExtension<F> for NoExtension {
+    fn syscall_code(&self) -> SyscallCode {
+        match *self {}
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for NoExtension {
+    fn eval(&self, _: &mut AB) {
+        match *self {}
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
     },
 };
 use core::fmt;
+pub use extension::*;
 use hashbrown::{HashMap, HashSet};
 use itertools::Itertools;
 pub use mips_chips::*;
@@ -22,6 +23,8 @@ use zkm_stark::{
     Chip, LookupKind, StarkGenericConfig, StarkMachine,
 };
 
+mod extension;
+
 /// A module for importing all the different MIPS chips.
 pub(crate) mod mips_chips {
     pub use crate::{
@@ -39,10 +42,13 @@ pub(crate) mod mips_chips {
             chip::SyscallChip,
             instructions::SyscallInstrsChip,
             precompiles::{
+                blake2b::Blake2bCompressChip,
+                blake3::Blake3CompressChip,
                 edwards::{EdAddAssignChip, EdDecompressChip},
                 keccak_sponge::KeccakSpongeChip,
                 sha256::{ShaCompressChip, ShaExtendChip},
                 sys_linux::SysLinuxChip,
+                sys_read::SysReadChip,
                 u256x2048_mul::U256x2048MulChip,
                 uint256::Uint256MulChip,
                 weierstrass::{
@@ -72,9 +78,16 @@ pub const MAX_NUMBER_OF_SHARDS: usize = 1 << MAX_LOG_NUMBER_OF_SHARDS;
 /// This enum contains all the different AIRs that are used in the Ziren IOP. Each variant is
 /// a different AIR that is used to encode a different part of the Ziren execution, and the
 /// different AIR variants have a joint lookup argument.
+///
+/// Chips from outside of Ziren are added through the extension `E`, see
+/// [`MipsAir::machine_with_extensions`].
 #[derive(zkm_derive::MachineAir, EnumDiscriminants)]
 #[strum_discriminants(derive(Hash, EnumIter))]
-pub enum MipsAir<F: PrimeField32> {
+#[eval_trait_bound = "E: p3_air::Air<AB>"]
+pub enum MipsAir<
+    F: PrimeField32,
+    E: MachineAir<F, Record = ExecutionRecord, Program = Program> = NoExtension,
+> {
     /// An AIR that contains a preprocessed program table and a lookup for the instructions.
     Program(ProgramChip),
     /// An AIR for the MIPS CPU. Each row represents a cpu cycle.
@@ -143,6 +156,10 @@ pub enum MipsAir<F: PrimeField32> {
     Poseidon2Permute(Poseidon2PermuteChip),
     /// A precompile for the Keccak Sponge
     KeccakSponge(KeccakSpongeChip),
+    /// A precompile for the BLAKE3 compression function.
+    Blake3Compress(Blake3CompressChip),
+    /// A precompile for the BLAKE2b compression function.
+    Blake2bCompress(Blake2bCompressChip),
     /// A precompile for addition on the Elliptic curve bn254.
     Bn254Add(WeierstrassAddAssignChip<SwCurve<Bn254Parameters>>),
     /// A precompile for doubling a point on the Elliptic curve bn254.
@@ -171,14 +188,38 @@ pub enum MipsAir<F: PrimeField32> {
     Bn254Fp2AddSub(Fp2AddSubAssignChip<Bn254BaseField>),
     /// A precompile for Linux Syscall.
     SysLinux(SysLinuxChip),
+    /// A precompile for the Linux syscalls writing a buffer.
+    SysRead(SysReadChip),
+    /// A chip from outside of Ziren.
+    Extension(E),
 }
 
-impl<F: PrimeField32> MipsAir<F> {
+impl<F: PrimeField32, E: MipsExtension<F>> MipsAir<F, E> {
     pub fn machine<SC: StarkGenericConfig<Val = F>>(config: SC) -> StarkMachine<SC, Self> {
         let chips = Self::chips();
         StarkMachine::new(config, chips, ZKM_PROOF_NUM_PV_ELTS)
     }
 
+    /// Build a machine with all the MIPS AIRs and the given extension chips.
+    ///
+    /// The extension chips receive the syscalls of the precompiles registered with
+    /// `ZKMContextBuilder::precompile`, and read their events from the
+    /// `PrecompileEvent::Custom` events of the record. To prove with a shape config, add the
+    /// extension chips to it with [`crate::shape::CoreShapeConfig::with_extension`].
+    pub fn machine_with_extensions<SC: StarkGenericConfig<Val = F>>(
+        config: SC,
+        extensions: impl IntoIterator<Item = E>,
+    ) -> StarkMachine<SC, Self> {
+        let mut chips = Self::chips();
+        for extension in extensions {
+            let code = extension.syscall_code();
+            let air_id = code.custom_air_id().expect("extension chips must prove a custom syscall");
+            assert_eq!(extension.name(), air_id.as_str(), "extension chip for {code} is misnamed");
+            chips.push(Chip::new(MipsAir::Extension(extension)));
+        }
+        StarkMachine::new(config, chips, ZKM_PROOF_NUM_PV_ELTS)
+    }
+
     /// Get all the different MIPS AIRs.
     pub fn chips() -> Vec<Chip<F, Self>> {
         let (chips, _) = Self::get_chips_and_costs();
@@ -276,6 +317,14 @@ impl<F: PrimeField32> MipsAir<F> {
         costs.insert(keccak_sponge.name(), 24 * keccak_sponge.cost());
         chips.push(keccak_sponge);
 
+        let blake3_compress = Chip::new(MipsAir::Blake3Compress(Blake3CompressChip::new()));
+        costs.insert(blake3_compress.name(), 88 * blake3_compress.cost());
+        chips.push(blake3_compress);
+
+        let blake2b_compress = Chip::new(MipsAir::Blake2bCompress(Blake2bCompressChip::new()));
+        costs.insert(blake2b_compress.name(), 120 * blake2b_compress.cost());
+        chips.push(blake2b_compress);
+
         let bn254_add_assign = Chip::new(MipsAir::Bn254Add(WeierstrassAddAssignChip::<
             SwCurve<Bn254Parameters>,
         >::new()));
@@ -430,6 +479,10 @@ impl<F: PrimeField32> MipsAir<F> {
         costs.insert(sys_linux.name(), sys_linux.cost());
         chips.push(sys_linux);
 
+        let sys_read = Chip::new(MipsAir::SysRead(SysReadChip::default()));
+        costs.insert(sys_read.name(), sys_read.cost());
+        chips.push(sys_read);
+
         (chips, costs)
     }
 
@@ -482,6 +535,7 @@ impl<F: PrimeField32> MipsAir<F> {
             .map(|events| {
                 let events_len = match self {
                     Self::KeccakSponge(_) => self.keccak_permutation_in_record(record),
+                    Self::SysRead(_) => self.sys_read_rows_in_record(record),
                     _ => events.len(),
                 };
                 let num_rows = events_len * self.rows_per_event();
@@ -552,21 +606,22 @@ impl<F: PrimeField32> MipsAir<F> {
         airs.remove(&Self::Program(ProgramChip::default()));
         airs.remove(&Self::ByteLookup(ByteChip::default()));
 
-        airs.into_iter()
-            .map(|air| {
-                let chip = Chip::new(air);
-                let local_mem_events: usize = chip
-                    .sends()
-                    .iter()
-                    .chain(chip.receives())
-                    .filter(|lookup| {
-                        lookup.kind == LookupKind::Memory && lookup.scope == LookupScope::Local
-                    })
-                    .count();
+        airs.into_iter().map(Self::with_memory_events_per_row).collect()
+    }
 
-                (chip.into_inner(), local_mem_events)
+    /// Get the AIR along with the number of local memory events of each of its rows.
+    pub(crate) fn with_memory_events_per_row(self) -> (Self, usize) {
+        let chip = Chip::new(self);
+        let local_mem_events: usize = chip
+            .sends()
+            .iter()
+            .chain(chip.receives())
+            .filter(|lookup| {
+                lookup.kind == LookupKind::Memory && lookup.scope == LookupScope::Local
             })
-            .collect()
+            .count();
+
+        (chip.into_inner(), local_mem_events)
     }
 
     pub(crate) fn rows_per_event(&self) -> usize {
@@ -574,6 +629,9 @@ impl<F: PrimeField32> MipsAir<F> {
             Self::Sha256Compress(_) => 80,
             Self::Sha256Extend(_) => 48,
             Self::KeccakSponge(_) => 24,
+            Self::Blake3Compress(_) => 88,
+            Self::Blake2bCompress(_) => 120,
+            Self::Extension(extension) => extension.rows_per_event(),
             _ => 1,
         }
     }
@@ -597,6 +655,25 @@ impl<F: PrimeField32> MipsAir<F> {
             .unwrap_or(0)
     }
 
+    fn sys_read_rows_in_record(&self, record: &ExecutionRecord) -> usize {
+        record
+            .precompile_events
+            .get_events(SyscallCode::SYS_READ)
+            .map(|events| {
+                events
+                    .iter()
+                    .map(|(_, pre_e)| {
+                        if let PrecompileEvent::Linux(event) = pre_e {
+                            event.num_rows()
+                        } else {
+                            unreachable!()
+                        }
+                    })
+                    .sum::<usize>()
+            })
+            .unwrap_or(0)
+    }
+
     pub(crate) fn syscall_code(&self) -> SyscallCode {
         match self {
             Self::Bls12381Add(_) => SyscallCode::BLS12381_ADD,
@@ -624,7 +701,10 @@ impl<F: PrimeField32> MipsAir<F> {
             Self::Bls12381Fp2AddSub(_) => SyscallCode::BLS12381_FP2_ADD,
             Self::Poseidon2Permute(_) => SyscallCode::POSEIDON2_PERMUTE,
             Self::KeccakSponge(_) => SyscallCode::KECCAK_SPONGE,
+            Self::Blake3Compress(_) => SyscallCode::BLAKE3_COMPRESS,
+            Self::Blake2bCompress(_) => SyscallCode::BLAKE2B_COMPRESS,
             Self::SysLinux(_) => SyscallCode::SYS_LINUX,
+            Self::SysRead(_) => SyscallCode::SYS_READ,
             Self::Add(_) => unreachable!("Invalid for core chip"),
             Self::Bitwise(_) => unreachable!("Invalid for core chip"),
             Self::DivRem(_) => unreachable!("Invalid for core chip"),
@@ -648,25 +728,35 @@ impl<F: PrimeField32> MipsAir<F> {
             Self::SyscallInstrs(_) => unreachable!("Invalid for core chip"),
             Self::MemoryInstrs(_) => unreachable!("Invalid for core chip"),
             Self::MiscInstrs(_) => unreachable!("Invalid for core chip"),
+            Self::Extension(extension) => extension.syscall_code(),
         }
     }
 }
 
-impl<F: PrimeField32> fmt::Debug for MipsAir<F> {
+impl<F: PrimeField32, E: MachineAir<F, Record = ExecutionRecord, Program = Program>> fmt::Debug
+    for MipsAir<F, E>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.name())
     }
 }
 
-impl<F: PrimeField32> PartialEq for MipsAir<F> {
+impl<F: PrimeField32, E: MachineAir<F, Record = ExecutionRecord, Program = Program>> PartialEq
+    for MipsAir<F, E>
+{
     fn eq(&self, other: &Self) -> bool {
         self.name() == other.name()
     }
 }
 
-impl<F: PrimeField32> Eq for MipsAir<F> {}
+impl<F: PrimeField32, E: MachineAir<F, Record = ExecutionRecord, Program = Program>> Eq
+    for MipsAir<F, E>
+{
+}
 
-impl<F: PrimeField32> core::hash::Hash for MipsAir<F> {
+impl<F: PrimeField32, E: MachineAir<F, Record = ExecutionRecord, Program = Program>>
+    core::hash::Hash for MipsAir<F, E>
+{
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.name().hash(state);
     }
@@ -692,7 +782,7 @@ pub mod tests {
     use p3_koala_bear::KoalaBear;
     use strum::IntoEnumIterator;
 
-    use zkm_core_executor::{Instruction, MipsAirId, Opcode, Program};
+    use zkm_core_executor::{Executor, Instruction, MipsAirId, Opcode, Program, Register};
     use zkm_stark::air::MachineAir;
     use zkm_stark::{
         koala_bear_poseidon2::KoalaBearPoseidon2, CpuProver, StarkProvingKey, StarkVerifyingKey,
@@ -702,7 +792,7 @@ pub mod tests {
     #[test]
     fn test_primitives_and_machine_air_names_match() {
         let chips = MipsAir::<KoalaBear>::chips();
-        for (a, b) in chips.iter().zip_eq(MipsAirId::iter()) {
+        for (a, b) in chips.iter().zip_eq(MipsAirId::iter().filter(|air| !air.is_custom())) {
             assert_eq!(a.name(), b.to_string());
         }
     }
@@ -1029,6 +1119,39 @@ pub mod tests {
         run_test::<CpuProver<_, _>>(program).unwrap();
     }
 
+    #[test]
+    fn test_trap_prove() {
+        //   trap 16        (links 4 in $k0)
+        //   addi v1, zero, 1
+        //   j 24
+        //   nop
+        //   addi v0, zero, 5
+        //   jr k0
+        //   nop
+        //
+        // A trap jumps to the handler without a delay slot, and the handler returns to the
+        // instruction after the trap.
+        setup_logger();
+        let instructions = vec![
+            Instruction::new(Opcode::TRAP, 26, 16, 0, true, true),
+            Instruction::new(Opcode::ADD, 3, 0, 1, false, true),
+            Instruction::new(Opcode::Jumpi, 0, 24, 0, true, true),
+            Instruction::new(Opcode::ADD, 0, 0, 0, false, true),
+            Instruction::new(Opcode::ADD, 2, 0, 5, false, true),
+            Instruction::new(Opcode::Jump, 0, 26, 0, false, true),
+            Instruction::new(Opcode::ADD, 0, 0, 0, false, true),
+        ];
+        let program = Program::new(instructions, 0, 0);
+
+        // The handler and the instructions after the trap run in the same shard as the trap.
+        let mut runtime = Executor::new(program.clone(), ZKMCoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::V0), 5);
+        assert_eq!(runtime.register(Register::V1), 1);
+
+        run_test::<CpuProver<_, _>>(program).unwrap();
+    }
+
     #[test]
     fn test_sc_prove() {
         let instructions = vec![
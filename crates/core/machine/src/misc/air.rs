@@ -90,6 +90,7 @@ where
             AB::Expr::ONE,
             AB::Expr::ZERO,
             AB::Expr::ZERO,
+            AB::Expr::ZERO,
             AB::Expr::ONE,
             is_rw_a,
         );
@@ -111,6 +112,7 @@ where
             AB::Expr::ZERO,
             AB::Expr::ZERO,
             AB::Expr::ZERO,
+            AB::Expr::ZERO,
             AB::Expr::ONE,
             local.is_wsbh + local.is_sext + local.is_teq + local.is_ext,
         );
@@ -12,6 +12,7 @@ mod and;
 mod cmp;
 pub mod field;
 mod fixed_rotate_right;
+mod fixed_rotate_right_double;
 mod fixed_shift_right;
 mod global_accumulation;
 mod global_lookup;
@@ -23,6 +24,7 @@ mod koala_bear_word;
 mod not;
 mod or;
 pub mod poseidon2;
+mod random_word;
 mod xor;
 
 pub use add::*;
@@ -32,6 +34,7 @@ pub use adddouble::*;
 pub use and::*;
 pub use cmp::*;
 pub use fixed_rotate_right::*;
+pub use fixed_rotate_right_double::*;
 pub use fixed_shift_right::*;
 pub use global_accumulation::*;
 pub use global_lookup::*;
@@ -42,4 +45,5 @@ pub use koala_bear_range::*;
 pub use koala_bear_word::*;
 pub use not::*;
 pub use or::*;
+pub use random_word::*;
 pub use xor::*;
@@ -0,0 +1,155 @@
+use p3_field::Field;
+use zkm_core_executor::events::{ByteRecord, RANDOM_WORD_KEY};
+use zkm_derive::AlignedBorrow;
+use zkm_stark::{air::ZKMAirBuilder, Word};
+
+use super::{AddOperation, FixedRotateRightOperation, XorOperation};
+
+/// A set of columns needed to compute a word of the `getrandom` stream, see
+/// [`zkm_core_executor::events::random_word`].
+///
+/// The word is the `d` output of two rounds of the BLAKE3 `G` function without message words, so
+/// the last updates of `c` and `b` in the second round are not computed.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RandomWordOperation<T> {
+    /// `a1 := a + b`.
+    pub a1: AddOperation<T>,
+    pub d_xor_a1: XorOperation<T>,
+    /// `d1 := (d xor a1) rightrotate 16`.
+    pub d1: FixedRotateRightOperation<T>,
+    /// `c1 := c + d1`.
+    pub c1: AddOperation<T>,
+    pub b_xor_c1: XorOperation<T>,
+    /// `b1 := (b xor c1) rightrotate 12`.
+    pub b1: FixedRotateRightOperation<T>,
+    /// `a2 := a1 + b1`.
+    pub a2: AddOperation<T>,
+    pub d1_xor_a2: XorOperation<T>,
+    /// `d2 := (d1 xor a2) rightrotate 8`.
+    pub d2: FixedRotateRightOperation<T>,
+    /// `c2 := c1 + d2`.
+    pub c2: AddOperation<T>,
+    pub b1_xor_c2: XorOperation<T>,
+    /// `b2 := (b1 xor c2) rightrotate 7`.
+    pub b2: FixedRotateRightOperation<T>,
+
+    /// `a3 := a2 + b2`.
+    pub a3: AddOperation<T>,
+    pub d2_xor_a3: XorOperation<T>,
+    /// `d3 := (d2 xor a3) rightrotate 16`.
+    pub d3: FixedRotateRightOperation<T>,
+    /// `c3 := c2 + d3`.
+    pub c3: AddOperation<T>,
+    pub b2_xor_c3: XorOperation<T>,
+    /// `b3 := (b2 xor c3) rightrotate 12`.
+    pub b3: FixedRotateRightOperation<T>,
+    /// `a4 := a3 + b3`.
+    pub a4: AddOperation<T>,
+    pub d3_xor_a4: XorOperation<T>,
+    /// `d4 := (d3 xor a4) rightrotate 8`, the output word.
+    pub d4: FixedRotateRightOperation<T>,
+}
+
+impl<F: Field> RandomWordOperation<F> {
+    pub fn populate(&mut self, record: &mut impl ByteRecord, seed: u64, counter: u32) -> u32 {
+        let (a, b, c, d) = (seed as u32, (seed >> 32) as u32, RANDOM_WORD_KEY, counter);
+
+        let a1 = self.a1.populate(record, a, b);
+        let d_xor_a1 = self.d_xor_a1.populate(record, d, a1);
+        let d1 = self.d1.populate(record, d_xor_a1, 16);
+        let c1 = self.c1.populate(record, c, d1);
+        let b_xor_c1 = self.b_xor_c1.populate(record, b, c1);
+        let b1 = self.b1.populate(record, b_xor_c1, 12);
+        let a2 = self.a2.populate(record, a1, b1);
+        let d1_xor_a2 = self.d1_xor_a2.populate(record, d1, a2);
+        let d2 = self.d2.populate(record, d1_xor_a2, 8);
+        let c2 = self.c2.populate(record, c1, d2);
+        let b1_xor_c2 = self.b1_xor_c2.populate(record, b1, c2);
+        let b2 = self.b2.populate(record, b1_xor_c2, 7);
+
+        let a3 = self.a3.populate(record, a2, b2);
+        let d2_xor_a3 = self.d2_xor_a3.populate(record, d2, a3);
+        let d3 = self.d3.populate(record, d2_xor_a3, 16);
+        let c3 = self.c3.populate(record, c2, d3);
+        let b2_xor_c3 = self.b2_xor_c3.populate(record, b2, c3);
+        let b3 = self.b3.populate(record, b2_xor_c3, 12);
+        let a4 = self.a4.populate(record, a3, b3);
+        let d3_xor_a4 = self.d3_xor_a4.populate(record, d3, a4);
+        self.d4.populate(record, d3_xor_a4, 8)
+    }
+
+    /// Evaluates the word for the inputs `a`, `b`, `c` and `d`, which are the low and high words
+    /// of the seed, the key and the counter.
+    pub fn eval<AB: ZKMAirBuilder>(
+        builder: &mut AB,
+        [a, b, c, d]: [Word<AB::Var>; 4],
+        cols: RandomWordOperation<AB::Var>,
+        is_real: AB::Var,
+    ) {
+        AddOperation::<AB::F>::eval(builder, a, b, cols.a1, is_real.into());
+        XorOperation::<AB::F>::eval(builder, d, cols.a1.value, cols.d_xor_a1, is_real);
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            cols.d_xor_a1.value,
+            16,
+            cols.d1,
+            is_real,
+        );
+        AddOperation::<AB::F>::eval(builder, c, cols.d1.value, cols.c1, is_real.into());
+        XorOperation::<AB::F>::eval(builder, b, cols.c1.value, cols.b_xor_c1, is_real);
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            cols.b_xor_c1.value,
+            12,
+            cols.b1,
+            is_real,
+        );
+        AddOperation::<AB::F>::eval(builder, cols.a1.value, cols.b1.value, cols.a2, is_real.into());
+        XorOperation::<AB::F>::eval(builder, cols.d1.value, cols.a2.value, cols.d1_xor_a2, is_real);
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            cols.d1_xor_a2.value,
+            8,
+            cols.d2,
+            is_real,
+        );
+        AddOperation::<AB::F>::eval(builder, cols.c1.value, cols.d2.value, cols.c2, is_real.into());
+        XorOperation::<AB::F>::eval(builder, cols.b1.value, cols.c2.value, cols.b1_xor_c2, is_real);
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            cols.b1_xor_c2.value,
+            7,
+            cols.b2,
+            is_real,
+        );
+
+        AddOperation::<AB::F>::eval(builder, cols.a2.value, cols.b2.value, cols.a3, is_real.into());
+        XorOperation::<AB::F>::eval(builder, cols.d2.value, cols.a3.value, cols.d2_xor_a3, is_real);
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            cols.d2_xor_a3.value,
+            16,
+            cols.d3,
+            is_real,
+        );
+        AddOperation::<AB::F>::eval(builder, cols.c2.value, cols.d3.value, cols.c3, is_real.into());
+        XorOperation::<AB::F>::eval(builder, cols.b2.value, cols.c3.value, cols.b2_xor_c3, is_real);
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            cols.b2_xor_c3.value,
+            12,
+            cols.b3,
+            is_real,
+        );
+        AddOperation::<AB::F>::eval(builder, cols.a3.value, cols.b3.value, cols.a4, is_real.into());
+        XorOperation::<AB::F>::eval(builder, cols.d3.value, cols.a4.value, cols.d3_xor_a4, is_real);
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            cols.d3_xor_a4.value,
+            8,
+            cols.d4,
+            is_real,
+        );
+    }
+}
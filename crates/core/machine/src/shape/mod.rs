@@ -9,18 +9,18 @@ use p3_koala_bear::KoalaBear;
 use p3_util::log2_ceil_usize;
 use thiserror::Error;
 
-use zkm_core_executor::{ExecutionRecord, MipsAirId, Program};
+use zkm_core_executor::{syscalls::SyscallCode, ExecutionRecord, MipsAirId, Program};
 use zkm_stark::{
     air::MachineAir,
     shape::{OrderedShape, Shape, ShapeCluster},
-    MachineRecord,
+    Chip, MachineRecord,
 };
 
 use super::mips::mips_chips::{ByteChip, ProgramChip, SyscallChip};
 use crate::{
     global::GlobalChip,
     memory::{MemoryLocalChip, NUM_LOCAL_MEMORY_ENTRIES_PER_ROW},
-    mips::MipsAir,
+    mips::{MipsAir, MipsExtension, NoExtension},
 };
 
 /// The set of maximal shapes.
@@ -35,18 +35,38 @@ const MAXIMAL_SHAPES: &[u8] = include_bytes!("maximal_shapes.json");
 /// These shapes are used to optimize performance for smaller programs.
 const SMALL_SHAPES: &[u8] = include_bytes!("small_shapes.json");
 
+/// The allowed log2 heights of the precompile chips.
+const PRECOMPILE_LOG2_HEIGHTS: core::ops::Range<usize> = 3..21;
+
 /// A configuration for what shapes are allowed to be used by the prover.
+///
+/// The precompile shards of the extension chips `E` are only allowed once the chips are added with
+/// [`CoreShapeConfig::with_extension`].
 #[derive(Debug)]
-pub struct CoreShapeConfig<F: PrimeField32> {
+pub struct CoreShapeConfig<F: PrimeField32, E: MipsExtension<F> = NoExtension> {
     partial_preprocessed_shapes: ShapeCluster<MipsAirId>,
     partial_core_shapes: BTreeMap<usize, Vec<ShapeCluster<MipsAirId>>>,
     partial_memory_shapes: ShapeCluster<MipsAirId>,
-    partial_precompile_shapes: HashMap<MipsAir<F>, (usize, Vec<usize>)>,
+    partial_precompile_shapes: HashMap<MipsAir<F, E>, (usize, Vec<usize>)>,
     partial_small_shapes: Vec<ShapeCluster<MipsAirId>>,
     costs: HashMap<MipsAirId, usize>,
 }
 
-impl<F: PrimeField32> CoreShapeConfig<F> {
+impl<F: PrimeField32, E: MipsExtension<F>> CoreShapeConfig<F, E> {
+    /// Allow the precompile shards of an extension chip, see
+    /// [`MipsAir::machine_with_extensions`].
+    #[must_use]
+    pub fn with_extension(mut self, extension: E) -> Self {
+        let code = extension.syscall_code();
+        let air_id = code.custom_air_id().expect("extension chips must prove a custom syscall");
+        let chip = Chip::new(MipsAir::<F, E>::Extension(extension));
+        self.costs.insert(air_id, chip.cost() as usize);
+        let (air, memory_events_per_row) = chip.into_inner().with_memory_events_per_row();
+        self.partial_precompile_shapes
+            .insert(air, (memory_events_per_row, PRECOMPILE_LOG2_HEIGHTS.collect()));
+        self
+    }
+
     /// Fix the preprocessed shape of the proof.
     pub fn fix_preprocessed_shape(&self, program: &mut Program) -> Result<(), CoreShapeError> {
         // If the preprocessed shape is already fixed, return an error.
@@ -140,25 +160,8 @@ impl<F: PrimeField32> CoreShapeConfig<F> {
             let heights = MipsAir::<F>::core_heights(record);
 
             // Try to find the smallest shape fitting within at least one of the candidate shapes.
-            let log2_shard_size = record.cpu_events.len().next_power_of_two().ilog2() as usize;
-            let mut minimal_shape = None;
-            let mut minimal_area = usize::MAX;
-            let mut minimal_cluster = None;
-            for (_, clusters) in self.partial_core_shapes.range(log2_shard_size..) {
-                for (i, cluster) in clusters.iter().enumerate() {
-                    if let Some(shape) = cluster.find_shape(&heights) {
-                        if self.estimate_lde_size(&shape) < minimal_area {
-                            minimal_area = self.estimate_lde_size(&shape);
-                            minimal_shape = Some(shape.clone());
-                            minimal_cluster = Some(i);
-                        }
-                    }
-                }
-            }
-
-            if let Some(shape) = minimal_shape {
+            if let Some((cluster, shape)) = self.find_core_shape(&heights) {
                 let shard = record.public_values.shard;
-                let cluster = minimal_cluster.unwrap();
                 tracing::info!("Shard Lifted: Index={}, Cluster={}", shard, cluster);
 
                 for (air, height) in heights.iter() {
@@ -186,8 +189,7 @@ impl<F: PrimeField32> CoreShapeConfig<F> {
         {
             let heights = MipsAir::<F>::memory_heights(record);
             let shape = self
-                .partial_memory_shapes
-                .find_shape(&heights)
+                .find_memory_shape(&heights)
                 .ok_or(CoreShapeError::ShapeError(record.stats()))?;
             record.shape.as_mut().unwrap().extend(shape);
             return Ok(());
@@ -200,27 +202,14 @@ impl<F: PrimeField32> CoreShapeConfig<F> {
             if let Some((height, num_memory_local_events, num_global_events)) =
                 air.precompile_heights(record)
             {
-                for allowed_log2_height in allowed_log2_heights {
-                    let allowed_height = 1 << allowed_log2_height;
-                    if height <= allowed_height {
-                        for shape in self.get_precompile_shapes(
-                            air,
-                            *memory_events_per_row,
-                            *allowed_log2_height,
-                        ) {
-                            let mem_events_height = shape[2].1;
-                            let global_events_height = shape[3].1;
-                            if num_memory_local_events.div_ceil(NUM_LOCAL_MEMORY_ENTRIES_PER_ROW)
-                                <= (1 << mem_events_height)
-                                && num_global_events <= (1 << global_events_height)
-                            {
-                                record.shape.as_mut().unwrap().extend(
-                                    shape.iter().map(|x| (MipsAirId::from_str(&x.0).unwrap(), x.1)),
-                                );
-                                return Ok(());
-                            }
-                        }
-                    }
+                if let Some(shape) = self.find_precompile_shape(
+                    air,
+                    *memory_events_per_row,
+                    allowed_log2_heights,
+                    (height, num_memory_local_events, num_global_events),
+                ) {
+                    record.shape.as_mut().unwrap().extend(shape);
+                    return Ok(());
                 }
                 tracing::error!(
                     "Cannot find shape for precompile {:?}, height {:?}, and mem events {:?}",
@@ -235,9 +224,93 @@ impl<F: PrimeField32> CoreShapeConfig<F> {
         Err(CoreShapeError::PrecompileNotIncluded(record.stats()))
     }
 
+    /// Finds the smallest core shape fitting `heights`, along with the index of its cluster.
+    pub fn find_core_shape(
+        &self,
+        heights: &[(MipsAirId, usize)],
+    ) -> Option<(usize, Shape<MipsAirId>)> {
+        let cpu_height =
+            heights.iter().find(|(air, _)| *air == MipsAirId::Cpu).map_or(0, |(_, height)| *height);
+        let log2_shard_size = cpu_height.next_power_of_two().ilog2() as usize;
+
+        let mut minimal_shape = None;
+        let mut minimal_area = usize::MAX;
+        for (_, clusters) in self.partial_core_shapes.range(log2_shard_size..) {
+            for (i, cluster) in clusters.iter().enumerate() {
+                if let Some(shape) = cluster.find_shape(heights) {
+                    if self.estimate_lde_size(&shape) < minimal_area {
+                        minimal_area = self.estimate_lde_size(&shape);
+                        minimal_shape = Some((i, shape));
+                    }
+                }
+            }
+        }
+        minimal_shape
+    }
+
+    /// Finds the shape of a shard holding only memory init and finalize events.
+    pub fn find_memory_shape(&self, heights: &[(MipsAirId, usize)]) -> Option<Shape<MipsAirId>> {
+        self.partial_memory_shapes.find_shape(heights)
+    }
+
+    /// Estimates the shape of a shard holding `num_events` events of the precompile handling
+    /// `syscall_code`, assuming each event accesses as much memory as a row of its chip.
+    pub fn estimate_precompile_shape(
+        &self,
+        syscall_code: SyscallCode,
+        num_events: usize,
+    ) -> Option<Shape<MipsAirId>> {
+        let (air, (memory_events_per_row, allowed_log2_heights)) = self
+            .partial_precompile_shapes
+            .iter()
+            .find(|(air, _)| air.syscall_code() == syscall_code)?;
+        let num_memory_local_events = num_events * memory_events_per_row;
+        let heights = (
+            num_events * air.rows_per_event(),
+            num_memory_local_events,
+            2 * num_memory_local_events + num_events,
+        );
+        self.find_precompile_shape(air, *memory_events_per_row, allowed_log2_heights, heights)
+    }
+
+    /// The cost of a shape, in cells of its committed traces before the low-degree extension.
+    pub fn estimate_lde_size(&self, shape: &Shape<MipsAirId>) -> usize {
+        shape.iter().map(|(air, height)| self.costs[air] * (1 << height)).sum()
+    }
+
+    fn find_precompile_shape(
+        &self,
+        air: &MipsAir<F, E>,
+        memory_events_per_row: usize,
+        allowed_log2_heights: &[usize],
+        (height, num_memory_local_events, num_global_events): (usize, usize, usize),
+    ) -> Option<Shape<MipsAirId>> {
+        for allowed_log2_height in allowed_log2_heights {
+            let allowed_height = 1 << allowed_log2_height;
+            if height > allowed_height {
+                continue;
+            }
+            for shape in
+                self.get_precompile_shapes(air, memory_events_per_row, *allowed_log2_height)
+            {
+                let mem_events_height = shape[2].1;
+                let global_events_height = shape[3].1;
+                if num_memory_local_events.div_ceil(NUM_LOCAL_MEMORY_ENTRIES_PER_ROW)
+                    <= (1 << mem_events_height)
+                    && num_global_events <= (1 << global_events_height)
+                {
+                    return Some(
+                        shape.iter().map(|x| (MipsAirId::from_str(&x.0).unwrap(), x.1)).collect(),
+                    );
+                }
+            }
+        }
+        None
+    }
+
     fn get_precompile_shapes(
         &self,
-        air: &MipsAir<F>,
+        air: &MipsAir<F, E>,
         memory_events_per_row: usize,
         allowed_log2_height: usize,
     ) -> Vec<[(String, usize); 4]> {
@@ -418,10 +491,6 @@ impl<F: PrimeField32> CoreShapeConfig<F> {
         self.maximal_core_shapes(max_log_shard_size).into_iter().chain(precompile_shapes).collect()
     }
 
-    fn estimate_lde_size(&self, shape: &Shape<MipsAirId>) -> usize {
-        shape.iter().map(|(air, height)| self.costs[air] * (1 << height)).sum()
-    }
-
     pub fn small_program_shapes(&self) -> Vec<OrderedShape> {
         self.partial_small_shapes
             .iter()
@@ -442,7 +511,7 @@ impl<F: PrimeField32> CoreShapeConfig<F> {
     }
 }
 
-impl<F: PrimeField32> Default for CoreShapeConfig<F> {
+impl<F: PrimeField32, E: MipsExtension<F>> Default for CoreShapeConfig<F, E> {
     fn default() -> Self {
         // Load the maximal shapes.
         let maximal_shapes = std::env::var("MAXIMAL_SHAPES_FILE")
@@ -495,9 +564,9 @@ impl<F: PrimeField32> Default for CoreShapeConfig<F> {
         );
 
         let mut precompile_allowed_log2_heights = HashMap::new();
-        let precompile_heights = (3..21).collect::<Vec<_>>();
+        let precompile_heights = PRECOMPILE_LOG2_HEIGHTS.collect::<Vec<_>>();
         for (air, memory_events_per_row) in
-            MipsAir::<F>::precompile_airs_with_memory_events_per_row()
+            MipsAir::<F, E>::precompile_airs_with_memory_events_per_row()
         {
             precompile_allowed_log2_heights
                 .insert(air, (memory_events_per_row, precompile_heights.clone()));
@@ -71,6 +71,7 @@ where
             AB::Expr::ONE,
             AB::Expr::ZERO,
             local.is_halt,
+            AB::Expr::ZERO,
             is_sequential,
             local.is_real,
         );
@@ -0,0 +1,471 @@
+use core::borrow::Borrow;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+use zkm_core_executor::syscalls::SyscallCode;
+use zkm_stark::{
+    air::{BaseAirBuilder, LookupScope, ZKMAirBuilder},
+    Word,
+};
+
+use super::{
+    columns::{Blake2bCompressCols, NUM_BLAKE2B_COMPRESS_COLS},
+    rotate_lane_bytes, Blake2bCompressChip, BLAKE2B_G_INDEX, BLAKE2B_SIGMA,
+};
+use crate::{
+    air::{MemoryAirBuilder, WordAirBuilder},
+    memory::MemoryCols,
+    operations::{AddDoubleOperation, FixedRotateRightDoubleOperation, XorOperation},
+};
+
+impl<F> BaseAir<F> for Blake2bCompressChip {
+    fn width(&self) -> usize {
+        NUM_BLAKE2B_COMPRESS_COLS
+    }
+}
+
+impl<AB> Air<AB> for Blake2bCompressChip
+where
+    AB: ZKMAirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &Blake2bCompressCols<AB::Var> = (*local).borrow();
+        let next: &Blake2bCompressCols<AB::Var> = (*next).borrow();
+
+        self.eval_control_flow_flags(builder, local, next);
+
+        self.eval_memory(builder, local, next);
+
+        self.eval_compression_ops(builder, local, next);
+
+        self.eval_finalize_ops(builder, local, next);
+
+        builder.assert_eq(local.start, local.is_real * local.octet[0] * local.octet_num[0]);
+        builder.receive_syscall(
+            local.shard,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::BLAKE2B_COMPRESS.syscall_id()),
+            local.state_ptr,
+            local.msg_ptr,
+            local.start,
+            LookupScope::Local,
+        );
+    }
+}
+
+impl Blake2bCompressChip {
+    fn eval_control_flow_flags<AB: ZKMAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Blake2bCompressCols<AB::Var>,
+        next: &Blake2bCompressCols<AB::Var>,
+    ) {
+        // Verify that all of the octet columns are bool.
+        for i in 0..8 {
+            builder.assert_bool(local.octet[i]);
+        }
+
+        // Verify that exactly one of the octet columns is true.
+        let mut octet_sum = AB::Expr::ZERO;
+        for i in 0..8 {
+            octet_sum = octet_sum.clone() + local.octet[i].into();
+        }
+        builder.assert_one(octet_sum);
+
+        // Verify that the first row's octet value is correct.
+        builder.when_first_row().assert_one(local.octet[0]);
+
+        // Verify correct transition for octet column.
+        for i in 0..8 {
+            builder.when_transition().when(local.octet[i]).assert_one(next.octet[(i + 1) % 8])
+        }
+
+        // Verify that all of the octet_num columns are bool.
+        for i in 0..15 {
+            builder.assert_bool(local.octet_num[i]);
+        }
+
+        // Verify that exactly one of the octet_num columns is true.
+        let mut octet_num_sum = AB::Expr::ZERO;
+        for i in 0..15 {
+            octet_num_sum = octet_num_sum.clone() + local.octet_num[i].into();
+        }
+        builder.assert_one(octet_num_sum);
+
+        // The first row should have octet_num[0] = 1 if it's real.
+        builder.when_first_row().assert_one(local.octet_num[0]);
+
+        // If current row is not last of an octet and next row is real, octet_num should be the
+        // same.
+        for i in 0..15 {
+            builder
+                .when_transition()
+                .when_not(local.octet[7])
+                .assert_eq(local.octet_num[i], next.octet_num[i]);
+        }
+
+        // If current row is last of an octet and next row is real, octet_num should rotate by 1.
+        for i in 0..15 {
+            builder
+                .when_transition()
+                .when(local.octet[7])
+                .assert_eq(local.octet_num[i], next.octet_num[(i + 1) % 15]);
+        }
+
+        // Assert that the is_initialize flag is correct.
+        builder.assert_eq(
+            local.is_initialize,
+            (local.octet_num[0] + local.octet_num[1]) * local.is_real,
+        );
+
+        // Assert that the is_compression flag is correct.
+        let mut compression_sum = AB::Expr::ZERO;
+        for i in 2..14 {
+            compression_sum = compression_sum.clone() + local.octet_num[i].into();
+        }
+        builder.assert_eq(local.is_compression, compression_sum * local.is_real);
+
+        // Assert that the is_finalize flag is correct.
+        builder.assert_eq(local.is_finalize, local.octet_num[14] * local.is_real);
+
+        builder.assert_eq(local.is_last_row.into(), local.octet[7] * local.octet_num[14]);
+
+        // If this row is real and not the last cycle, then next row should have same inputs.
+        builder
+            .when_transition()
+            .when(local.is_real)
+            .when_not(local.is_last_row)
+            .assert_eq(local.shard, next.shard);
+        builder
+            .when_transition()
+            .when(local.is_real)
+            .when_not(local.is_last_row)
+            .assert_eq(local.clk, next.clk);
+        builder
+            .when_transition()
+            .when(local.is_real)
+            .when_not(local.is_last_row)
+            .assert_eq(local.state_ptr, next.state_ptr);
+        builder
+            .when_transition()
+            .when(local.is_real)
+            .when_not(local.is_last_row)
+            .assert_eq(local.msg_ptr, next.msg_ptr);
+
+        // The message is also carried over to the next row.
+        for i in 0..16 {
+            for k in 0..2 {
+                builder
+                    .when_transition()
+                    .when(local.is_real)
+                    .when_not(local.is_last_row)
+                    .assert_word_eq(local.m[i][k], next.m[i][k]);
+            }
+        }
+
+        // Assert that is_real is a bool.
+        builder.assert_bool(local.is_real);
+
+        // If this row is real and not the last cycle, then next row should also be real.
+        builder
+            .when_transition()
+            .when(local.is_real)
+            .when_not(local.is_last_row)
+            .assert_one(next.is_real);
+
+        // Once the is_real flag is changed to false, it should not be changed back.
+        builder.when_transition().when_not(local.is_real).assert_zero(next.is_real);
+
+        // Assert that the table ends in nonreal columns. Since each compress syscall is 120 cycles
+        // and the table is padded to a power of 2, the last row of the table should always be
+        // padding.
+        builder.when_last_row().assert_zero(local.is_real);
+    }
+
+    /// Constrains that memory addresses are correct and that memory is correctly written/read.
+    fn eval_memory<AB: ZKMAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Blake2bCompressCols<AB::Var>,
+        next: &Blake2bCompressCols<AB::Var>,
+    ) {
+        // Calculate the index of the state and message lane accessed in this row. The second
+        // initialize octet accesses the upper half of the lanes.
+        let mut lane_idx = local.octet_num[1] * AB::Expr::from_canonical_u32(8);
+        for i in 0..8 {
+            lane_idx = lane_idx.clone() + local.octet[i] * AB::Expr::from_canonical_usize(i);
+        }
+
+        let lane_addr = |ptr: AB::Var, k: usize| {
+            ptr + lane_idx.clone() * AB::Expr::from_canonical_u32(8)
+                + AB::Expr::from_canonical_usize(4 * k)
+        };
+        for k in 0..2 {
+            builder.eval_memory_access(
+                local.shard,
+                local.clk + local.is_finalize,
+                lane_addr(local.state_ptr, k),
+                &local.mem[k],
+                local.is_initialize + local.is_finalize,
+            );
+            builder.eval_memory_access(
+                local.shard,
+                local.clk,
+                lane_addr(local.msg_ptr, k),
+                &local.msg_mem[k],
+                local.is_initialize,
+            );
+        }
+
+        for k in 0..2 {
+            // During initialize, verify that the state is read only.
+            builder
+                .when(local.is_initialize)
+                .assert_word_eq(*local.mem[k].prev_value(), *local.mem[k].value());
+
+            // In the initialize phase, verify that the working vector and message lanes are
+            // correctly read from memory.
+            for n in 0..2 {
+                for i in 0..8 {
+                    builder
+                        .when(local.octet_num[n] * local.octet[i])
+                        .assert_word_eq(local.v[8 * n + i][k], *local.mem[k].value());
+                    builder
+                        .when(local.octet_num[n] * local.octet[i])
+                        .assert_word_eq(local.m[8 * n + i][k], *local.msg_mem[k].value());
+                }
+            }
+
+            // The working vector does not change during initialize.
+            for i in 0..16 {
+                builder
+                    .when_transition()
+                    .when(local.is_initialize)
+                    .assert_word_eq(local.v[i][k], next.v[i][k]);
+            }
+
+            // In the finalize phase, verify that the correct value is written to memory.
+            builder
+                .when(local.is_finalize)
+                .assert_word_eq(*local.mem[k].value(), local.finalize_h_xor[k].value);
+        }
+    }
+
+    fn eval_compression_ops<AB: ZKMAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Blake2bCompressCols<AB::Var>,
+        next: &Blake2bCompressCols<AB::Var>,
+    ) {
+        // Select the inputs of the `G` function from the working vector and the message.
+        for (g, [a, b, c, d]) in BLAKE2B_G_INDEX.into_iter().enumerate() {
+            for k in 0..2 {
+                let mut builder_g = builder.when(local.is_compression * local.octet[g]);
+                builder_g.assert_word_eq(local.a[k], local.v[a][k]);
+                builder_g.assert_word_eq(local.b[k], local.v[b][k]);
+                builder_g.assert_word_eq(local.c[k], local.v[c][k]);
+                builder_g.assert_word_eq(local.d[k], local.v[d][k]);
+            }
+        }
+        for (round, sigma) in BLAKE2B_SIGMA.iter().enumerate() {
+            for g in 0..8 {
+                for k in 0..2 {
+                    builder
+                        .when(local.octet_num[round + 2] * local.octet[g])
+                        .assert_word_eq(local.mx[k], local.m[sigma[2 * g]][k]);
+                    builder
+                        .when(local.octet_num[round + 2] * local.octet[g])
+                        .assert_word_eq(local.my[k], local.m[sigma[2 * g + 1]][k]);
+                }
+            }
+        }
+
+        // Calculate a1 := a + b + mx.
+        AddDoubleOperation::<AB::F>::eval(
+            builder,
+            local.a[0],
+            local.a[1],
+            local.b[0],
+            local.b[1],
+            local.a_add_b,
+            local.is_compression.into(),
+        );
+        AddDoubleOperation::<AB::F>::eval(
+            builder,
+            local.a_add_b.value,
+            local.a_add_b.value_hi,
+            local.mx[0],
+            local.mx[1],
+            local.a1,
+            local.is_compression.into(),
+        );
+        let a1 = [local.a1.value, local.a1.value_hi];
+
+        // Calculate d1 := (d xor a1) rightrotate 32.
+        for k in 0..2 {
+            XorOperation::<AB::F>::eval(
+                builder,
+                local.d[k],
+                a1[k],
+                local.d_xor_a[k],
+                local.is_compression,
+            );
+        }
+        let d1 = rotate_lane_bytes([local.d_xor_a[0].value, local.d_xor_a[1].value], 4);
+
+        // Calculate c1 := c + d1.
+        AddDoubleOperation::<AB::F>::eval(
+            builder,
+            local.c[0],
+            local.c[1],
+            d1[0],
+            d1[1],
+            local.c1,
+            local.is_compression.into(),
+        );
+        let c1 = [local.c1.value, local.c1.value_hi];
+
+        // Calculate b1 := (b xor c1) rightrotate 24.
+        for k in 0..2 {
+            XorOperation::<AB::F>::eval(
+                builder,
+                local.b[k],
+                c1[k],
+                local.b_xor_c[k],
+                local.is_compression,
+            );
+        }
+        let b1 = rotate_lane_bytes([local.b_xor_c[0].value, local.b_xor_c[1].value], 3);
+
+        // Calculate a2 := a1 + b1 + my.
+        AddDoubleOperation::<AB::F>::eval(
+            builder,
+            a1[0],
+            a1[1],
+            b1[0],
+            b1[1],
+            local.a1_add_b1,
+            local.is_compression.into(),
+        );
+        AddDoubleOperation::<AB::F>::eval(
+            builder,
+            local.a1_add_b1.value,
+            local.a1_add_b1.value_hi,
+            local.my[0],
+            local.my[1],
+            local.a2,
+            local.is_compression.into(),
+        );
+        let a2 = [local.a2.value, local.a2.value_hi];
+
+        // Calculate d2 := (d1 xor a2) rightrotate 16.
+        for k in 0..2 {
+            XorOperation::<AB::F>::eval(
+                builder,
+                d1[k],
+                a2[k],
+                local.d1_xor_a2[k],
+                local.is_compression,
+            );
+        }
+        let d2 = rotate_lane_bytes([local.d1_xor_a2[0].value, local.d1_xor_a2[1].value], 2);
+
+        // Calculate c2 := c1 + d2.
+        AddDoubleOperation::<AB::F>::eval(
+            builder,
+            c1[0],
+            c1[1],
+            d2[0],
+            d2[1],
+            local.c2,
+            local.is_compression.into(),
+        );
+        let c2 = [local.c2.value, local.c2.value_hi];
+
+        // Calculate b2 := (b1 xor c2) rightrotate 63.
+        for k in 0..2 {
+            XorOperation::<AB::F>::eval(
+                builder,
+                b1[k],
+                c2[k],
+                local.b1_xor_c2[k],
+                local.is_compression,
+            );
+        }
+        FixedRotateRightDoubleOperation::<AB::F>::eval(
+            builder,
+            local.b1_xor_c2[0].value,
+            local.b1_xor_c2[1].value,
+            63,
+            local.b_rr_63,
+            local.is_compression,
+        );
+        let b2 = [local.b_rr_63.value, local.b_rr_63.value_hi];
+
+        // The next working vector is the current one with the outputs of `G` written back to the
+        // lanes that were mixed.
+        for (g, g_index) in BLAKE2B_G_INDEX.into_iter().enumerate() {
+            let outputs = [a2, b2, c2, d2];
+            for i in 0..16 {
+                let expected = match g_index.iter().position(|&idx| idx == i) {
+                    Some(pos) => outputs[pos],
+                    None => local.v[i],
+                };
+                for k in 0..2 {
+                    builder
+                        .when_transition()
+                        .when(local.is_compression)
+                        .when(local.octet[g])
+                        .assert_word_eq(next.v[i][k], expected[k]);
+                }
+            }
+        }
+    }
+
+    fn eval_finalize_ops<AB: ZKMAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Blake2bCompressCols<AB::Var>,
+        next: &Blake2bCompressCols<AB::Var>,
+    ) {
+        for k in 0..2 {
+            // The final working vector does not change during finalize.
+            for i in 0..16 {
+                builder
+                    .when_transition()
+                    .when(local.is_finalize)
+                    .when_not(local.is_last_row)
+                    .assert_word_eq(local.v[i][k], next.v[i][k]);
+            }
+
+            // In the finalize phase, h[i] := h[i] xor v[i] xor v[i + 8]. The lanes of the working
+            // vector are selected with the octet bitmap, and h[i] is the previous memory value.
+            let v_lo: Vec<Word<AB::Var>> = local.v[..8].iter().map(|lane| lane[k]).collect();
+            let v_hi: Vec<Word<AB::Var>> = local.v[8..].iter().map(|lane| lane[k]).collect();
+            let lhs = builder.index_word_array(&v_lo, &local.octet);
+            let rhs = builder.index_word_array(&v_hi, &local.octet);
+            builder.when(local.is_finalize).assert_word_eq(local.finalize_lhs[k], lhs);
+            builder.when(local.is_finalize).assert_word_eq(local.finalize_rhs[k], rhs);
+
+            XorOperation::<AB::F>::eval(
+                builder,
+                local.finalize_lhs[k],
+                local.finalize_rhs[k],
+                local.finalize_v_xor[k],
+                local.is_finalize,
+            );
+            XorOperation::<AB::F>::eval(
+                builder,
+                local.mem[k].prev_value,
+                local.finalize_v_xor[k].value,
+                local.finalize_h_xor[k],
+                local.is_finalize,
+            );
+        }
+
+        // Memory write is constrained in eval_memory.
+    }
+}
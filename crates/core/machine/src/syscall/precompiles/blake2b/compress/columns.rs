@@ -0,0 +1,92 @@
+use std::mem::size_of;
+
+use zkm_derive::AlignedBorrow;
+use zkm_stark::Word;
+
+use crate::{
+    memory::{MemoryReadCols, MemoryReadWriteCols},
+    operations::{AddDoubleOperation, FixedRotateRightDoubleOperation, XorOperation},
+};
+
+pub const NUM_BLAKE2B_COMPRESS_COLS: usize = size_of::<Blake2bCompressCols<u8>>();
+
+/// A set of columns needed to compute the BLAKE2b compression function.
+///
+/// Each 64-bit lane is stored as a pair of words, low word first. Each blake2b compress syscall is
+/// processed over 120 rows, split into 15 octets. The first two octets read the 16 lanes of the
+/// working vector and the 16 message lanes, one of each per row. Each of the next 12 octets
+/// computes one round, with one `G` function per row. The last octet writes the new chaining value
+/// back to the first 8 lanes of the state.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Blake2bCompressCols<T> {
+    /// Inputs.
+    pub shard: T,
+    pub clk: T,
+    pub state_ptr: T,
+    pub msg_ptr: T,
+
+    pub start: T,
+
+    /// Which row within the octet we are currently processing.
+    pub octet: [T; 8],
+
+    /// This will specify which octet we are currently processing.
+    ///  - The first two octets are for initialize.
+    ///  - The next 12 octets are for compress.
+    ///  - The last octet is for finalize.
+    pub octet_num: [T; 15],
+
+    /// Memory access to the state lane. During initialize this is a read, during finalize this is
+    /// used to write the result into memory.
+    pub mem: [MemoryReadWriteCols<T>; 2],
+    /// Memory access to the message lane. This is only used during initialize.
+    pub msg_mem: [MemoryReadCols<T>; 2],
+
+    /// The working vector at the start of the current row.
+    pub v: [[Word<T>; 2]; 16],
+    /// The message block.
+    pub m: [[Word<T>; 2]; 16],
+
+    /// The inputs of the `G` function computed in the current row.
+    pub a: [Word<T>; 2],
+    pub b: [Word<T>; 2],
+    pub c: [Word<T>; 2],
+    pub d: [Word<T>; 2],
+    pub mx: [Word<T>; 2],
+    pub my: [Word<T>; 2],
+
+    pub a_add_b: AddDoubleOperation<T>,
+    /// `a1 := a + b + mx`.
+    pub a1: AddDoubleOperation<T>,
+    /// `d1 := (d xor a1) rightrotate 32`, which only moves bytes around.
+    pub d_xor_a: [XorOperation<T>; 2],
+    /// `c1 := c + d1`.
+    pub c1: AddDoubleOperation<T>,
+    /// `b1 := (b xor c1) rightrotate 24`, which only moves bytes around.
+    pub b_xor_c: [XorOperation<T>; 2],
+    pub a1_add_b1: AddDoubleOperation<T>,
+    /// `a2 := a1 + b1 + my`.
+    pub a2: AddDoubleOperation<T>,
+    /// `d2 := (d1 xor a2) rightrotate 16`, which only moves bytes around.
+    pub d1_xor_a2: [XorOperation<T>; 2],
+    /// `c2 := c1 + d2`.
+    pub c2: AddDoubleOperation<T>,
+    pub b1_xor_c2: [XorOperation<T>; 2],
+    /// `b2 := (b1 xor c2) rightrotate 63`.
+    pub b_rr_63: FixedRotateRightDoubleOperation<T>,
+
+    /// During finalize, the two lanes of the working vector xored into the chaining value.
+    pub finalize_lhs: [Word<T>; 2],
+    pub finalize_rhs: [Word<T>; 2],
+    pub finalize_v_xor: [XorOperation<T>; 2],
+    /// `h[i] xor v[i] xor v[i + 8]`, which is written to `mem`.
+    pub finalize_h_xor: [XorOperation<T>; 2],
+
+    pub is_initialize: T,
+    pub is_compression: T,
+    pub is_finalize: T,
+    pub is_last_row: T,
+
+    pub is_real: T,
+}
@@ -0,0 +1,103 @@
+mod air;
+mod columns;
+mod trace;
+
+use zkm_stark::Word;
+
+/// The message lane indices used by each of the 12 BLAKE2b rounds.
+pub const BLAKE2B_SIGMA: [[usize; 16]; 12] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+];
+
+/// The lanes mixed by each of the 8 `G` functions of a round, columns first.
+pub const BLAKE2B_G_INDEX: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// Implements the BLAKE2b compression function. The inputs to the syscall are a pointer to the 16
+/// lane initialized working vector, whose first 8 lanes are overwritten with the new chaining
+/// value, and a pointer to the 16 lane message block.
+///
+/// In the AIR, each BLAKE2b compress syscall takes up 120 rows. The first 16 rows are for
+/// initialization and the last 8 rows for finalize. The middle 96 rows are for compression, with
+/// each row computing a single `G` function.
+#[derive(Default)]
+pub struct Blake2bCompressChip;
+
+impl Blake2bCompressChip {
+    pub const fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Rotates a lane right by a whole number of bytes, which only reorders its limbs.
+fn rotate_lane_bytes<T: Copy>(lane: [Word<T>; 2], nb_bytes: usize) -> [Word<T>; 2] {
+    let bytes = [lane[0].0, lane[1].0].concat();
+    [
+        Word(core::array::from_fn(|i| bytes[(i + nb_bytes) % 8])),
+        Word(core::array::from_fn(|i| bytes[(i + 4 + nb_bytes) % 8])),
+    ]
+}
+
+#[cfg(test)]
+pub mod compress_tests {
+
+    use test_artifacts::BLAKE2B_COMPRESS_ELF;
+    use zkm_core_executor::{syscalls::SyscallCode, Instruction, Opcode, Program};
+    use zkm_stark::CpuProver;
+
+    use crate::utils::{run_test, setup_logger};
+
+    pub fn blake2b_compress_program() -> Program {
+        let state_ptr = 100;
+        let msg_ptr = 1000;
+        let mut instructions = vec![Instruction::new(Opcode::ADD, 29, 0, 5, false, true)];
+        for i in 0..32 {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 30, 0, state_ptr + i * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+                Instruction::new(Opcode::ADD, 30, 0, msg_ptr + i * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            ]);
+        }
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::BLAKE2B_COMPRESS as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 0, state_ptr, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, msg_ptr, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn prove_koalabear() {
+        setup_logger();
+        let program = blake2b_compress_program();
+        run_test::<CpuProver<_, _>>(program).unwrap();
+    }
+
+    #[test]
+    fn test_blake2b_compress_program() {
+        setup_logger();
+        let program = Program::from(BLAKE2B_COMPRESS_ELF).unwrap();
+        run_test::<CpuProver<_, _>>(program).unwrap();
+    }
+}
@@ -0,0 +1,241 @@
+use std::borrow::BorrowMut;
+
+use hashbrown::HashMap;
+use itertools::Itertools;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::{ParallelIterator, ParallelSlice};
+use zkm_core_executor::{
+    events::{Blake2bCompressEvent, ByteLookupEvent, ByteRecord, PrecompileEvent},
+    syscalls::SyscallCode,
+    ExecutionRecord, Program,
+};
+use zkm_stark::{air::MachineAir, Word};
+
+use super::{
+    columns::{Blake2bCompressCols, NUM_BLAKE2B_COMPRESS_COLS},
+    Blake2bCompressChip, BLAKE2B_G_INDEX, BLAKE2B_SIGMA,
+};
+use crate::{operations::XorOperation, utils::pad_rows_fixed};
+
+impl<F: PrimeField32> MachineAir<F> for Blake2bCompressChip {
+    type Record = ExecutionRecord;
+
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Blake2bCompress".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        _: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let rows = Vec::new();
+
+        let mut wrapped_rows = Some(rows);
+        for (_, event) in input.get_precompile_events(SyscallCode::BLAKE2B_COMPRESS) {
+            let event = if let PrecompileEvent::Blake2bCompress(event) = event {
+                event
+            } else {
+                unreachable!()
+            };
+            self.event_to_rows(event, &mut wrapped_rows, &mut Vec::new());
+        }
+        let mut rows = wrapped_rows.unwrap();
+
+        let num_real_rows = rows.len();
+
+        pad_rows_fixed(
+            &mut rows,
+            || [F::ZERO; NUM_BLAKE2B_COMPRESS_COLS],
+            input.fixed_log2_rows::<F, _>(self),
+        );
+
+        // Set the octet_num and octet columns for the padded rows.
+        let mut octet_num = 0;
+        let mut octet = 0;
+        for row in rows[num_real_rows..].iter_mut() {
+            let cols: &mut Blake2bCompressCols<F> = row.as_mut_slice().borrow_mut();
+            cols.octet_num[octet_num] = F::ONE;
+            cols.octet[octet] = F::ONE;
+
+            octet = (octet + 1) % 8;
+            if octet == 0 {
+                octet_num = (octet_num + 1) % 15;
+            }
+
+            cols.is_last_row = cols.octet[7] * cols.octet_num[14];
+        }
+
+        // Convert the trace to a row major matrix.
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_BLAKE2B_COMPRESS_COLS,
+        )
+    }
+
+    fn generate_dependencies(&self, input: &Self::Record, output: &mut Self::Record) {
+        let events = input.get_precompile_events(SyscallCode::BLAKE2B_COMPRESS);
+        let chunk_size = std::cmp::max(events.len() / num_cpus::get(), 1);
+
+        let blu_batches = events
+            .par_chunks(chunk_size)
+            .map(|events| {
+                let mut blu: HashMap<ByteLookupEvent, usize> = HashMap::new();
+                events.iter().for_each(|(_, event)| {
+                    let event = if let PrecompileEvent::Blake2bCompress(event) = event {
+                        event
+                    } else {
+                        unreachable!()
+                    };
+                    self.event_to_rows::<F>(event, &mut None, &mut blu);
+                });
+                blu
+            })
+            .collect::<Vec<_>>();
+
+        output.add_byte_lookup_events_from_maps(blu_batches.iter().collect_vec());
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        if let Some(shape) = shard.shape.as_ref() {
+            shape.included::<F, _>(self)
+        } else {
+            !shard.get_precompile_events(SyscallCode::BLAKE2B_COMPRESS).is_empty()
+        }
+    }
+}
+
+/// Splits a lane into its low and high words.
+fn lane_words<F: PrimeField32>(lane: u64) -> [Word<F>; 2] {
+    [Word::from(lane as u32), Word::from((lane >> 32) as u32)]
+}
+
+impl Blake2bCompressChip {
+    fn event_to_rows<F: PrimeField32>(
+        &self,
+        event: &Blake2bCompressEvent,
+        rows: &mut Option<Vec<[F; NUM_BLAKE2B_COMPRESS_COLS]>>,
+        blu: &mut impl ByteRecord,
+    ) {
+        let new_row = |octet: usize, octet_num: usize, v: &[u64; 16]| {
+            let mut row = [F::ZERO; NUM_BLAKE2B_COMPRESS_COLS];
+            let cols: &mut Blake2bCompressCols<F> = row.as_mut_slice().borrow_mut();
+
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.state_ptr = F::from_canonical_u32(event.state_ptr);
+            cols.msg_ptr = F::from_canonical_u32(event.msg_ptr);
+
+            cols.octet[octet] = F::ONE;
+            cols.octet_num[octet_num] = F::ONE;
+
+            for i in 0..16 {
+                cols.v[i] = lane_words(v[i]);
+                cols.m[i] = lane_words(event.msg[i]);
+            }
+
+            cols.is_real = F::ONE;
+            cols.start = cols.is_real * cols.octet_num[0] * cols.octet[0];
+            row
+        };
+
+        // Read the working vector and the message.
+        for octet_num in 0..2 {
+            for j in 0..8usize {
+                let mut row = new_row(j, octet_num, &event.state);
+                let cols: &mut Blake2bCompressCols<F> = row.as_mut_slice().borrow_mut();
+                cols.is_initialize = F::ONE;
+
+                let idx = 8 * octet_num + j;
+                for k in 0..2 {
+                    cols.mem[k].populate_read(event.state_read_records[2 * idx + k], blu);
+                    cols.msg_mem[k].populate(event.msg_read_records[2 * idx + k], blu);
+                }
+
+                if rows.as_ref().is_some() {
+                    rows.as_mut().unwrap().push(row);
+                }
+            }
+        }
+
+        // Performs the compress operation, one `G` function per row.
+        let mut v = event.state;
+        for (round, sigma) in BLAKE2B_SIGMA.iter().enumerate() {
+            for (g, [a, b, c, d]) in BLAKE2B_G_INDEX.into_iter().enumerate() {
+                let mut row = new_row(g, round + 2, &v);
+                let cols: &mut Blake2bCompressCols<F> = row.as_mut_slice().borrow_mut();
+                cols.is_compression = F::ONE;
+
+                let mx = event.msg[sigma[2 * g]];
+                let my = event.msg[sigma[2 * g + 1]];
+                cols.a = lane_words(v[a]);
+                cols.b = lane_words(v[b]);
+                cols.c = lane_words(v[c]);
+                cols.d = lane_words(v[d]);
+                cols.mx = lane_words(mx);
+                cols.my = lane_words(my);
+
+                let a_add_b = cols.a_add_b.populate(blu, v[a], v[b]);
+                let a1 = cols.a1.populate(blu, a_add_b, mx);
+                let d_xor_a = xor_lanes(&mut cols.d_xor_a, blu, v[d], a1);
+                let d1 = d_xor_a.rotate_right(32);
+                let c1 = cols.c1.populate(blu, v[c], d1);
+                let b_xor_c = xor_lanes(&mut cols.b_xor_c, blu, v[b], c1);
+                let b1 = b_xor_c.rotate_right(24);
+                let a1_add_b1 = cols.a1_add_b1.populate(blu, a1, b1);
+                let a2 = cols.a2.populate(blu, a1_add_b1, my);
+                let d1_xor_a2 = xor_lanes(&mut cols.d1_xor_a2, blu, d1, a2);
+                let d2 = d1_xor_a2.rotate_right(16);
+                let c2 = cols.c2.populate(blu, c1, d2);
+                let b1_xor_c2 = xor_lanes(&mut cols.b1_xor_c2, blu, b1, c2);
+                let b2 = cols.b_rr_63.populate(blu, b1_xor_c2, 63);
+
+                v[a] = a2;
+                v[b] = b2;
+                v[c] = c2;
+                v[d] = d2;
+
+                if rows.as_ref().is_some() {
+                    rows.as_mut().unwrap().push(row);
+                }
+            }
+        }
+
+        // Write the new chaining value.
+        for j in 0..8usize {
+            let mut row = new_row(j, 14, &v);
+            let cols: &mut Blake2bCompressCols<F> = row.as_mut_slice().borrow_mut();
+            cols.is_finalize = F::ONE;
+
+            cols.finalize_lhs = lane_words(v[j]);
+            cols.finalize_rhs = lane_words(v[j + 8]);
+            let v_xor = xor_lanes(&mut cols.finalize_v_xor, blu, v[j], v[j + 8]);
+            xor_lanes(&mut cols.finalize_h_xor, blu, event.state[j], v_xor);
+
+            for k in 0..2 {
+                cols.mem[k].populate_write(event.state_write_records[2 * j + k], blu);
+            }
+
+            cols.is_last_row = cols.octet[7] * cols.octet_num[14];
+
+            if rows.as_ref().is_some() {
+                rows.as_mut().unwrap().push(row);
+            }
+        }
+    }
+}
+
+/// Populates the xor of two lanes, one word at a time.
+fn xor_lanes<F: PrimeField32>(
+    cols: &mut [XorOperation<F>; 2],
+    blu: &mut impl ByteRecord,
+    x: u64,
+    y: u64,
+) -> u64 {
+    let lo = cols[0].populate(blu, x as u32, y as u32);
+    let hi = cols[1].populate(blu, (x >> 32) as u32, (y >> 32) as u32);
+    u64::from(lo) | (u64::from(hi) << 32)
+}
@@ -0,0 +1,459 @@
+use core::borrow::Borrow;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+use zkm_core_executor::syscalls::SyscallCode;
+use zkm_stark::air::{BaseAirBuilder, LookupScope, ZKMAirBuilder};
+
+use super::{
+    columns::{Blake3CompressCols, NUM_BLAKE3_COMPRESS_COLS},
+    Blake3CompressChip, BLAKE3_G_INDEX, BLAKE3_MSG_SCHEDULE,
+};
+use crate::{
+    air::{MemoryAirBuilder, WordAirBuilder},
+    memory::MemoryCols,
+    operations::{AddOperation, FixedRotateRightOperation, XorOperation},
+};
+
+impl<F> BaseAir<F> for Blake3CompressChip {
+    fn width(&self) -> usize {
+        NUM_BLAKE3_COMPRESS_COLS
+    }
+}
+
+impl<AB> Air<AB> for Blake3CompressChip
+where
+    AB: ZKMAirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &Blake3CompressCols<AB::Var> = (*local).borrow();
+        let next: &Blake3CompressCols<AB::Var> = (*next).borrow();
+
+        self.eval_control_flow_flags(builder, local, next);
+
+        self.eval_memory(builder, local, next);
+
+        self.eval_compression_ops(builder, local, next);
+
+        self.eval_finalize_ops(builder, local, next);
+
+        builder.assert_eq(local.start, local.is_real * local.octet[0] * local.octet_num[0]);
+        builder.receive_syscall(
+            local.shard,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::BLAKE3_COMPRESS.syscall_id()),
+            local.state_ptr,
+            local.msg_ptr,
+            local.start,
+            LookupScope::Local,
+        );
+    }
+}
+
+impl Blake3CompressChip {
+    fn eval_control_flow_flags<AB: ZKMAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Blake3CompressCols<AB::Var>,
+        next: &Blake3CompressCols<AB::Var>,
+    ) {
+        // Verify that all of the octet columns are bool.
+        for i in 0..8 {
+            builder.assert_bool(local.octet[i]);
+        }
+
+        // Verify that exactly one of the octet columns is true.
+        let mut octet_sum = AB::Expr::ZERO;
+        for i in 0..8 {
+            octet_sum = octet_sum.clone() + local.octet[i].into();
+        }
+        builder.assert_one(octet_sum);
+
+        // Verify that the first row's octet value is correct.
+        builder.when_first_row().assert_one(local.octet[0]);
+
+        // Verify correct transition for octet column.
+        for i in 0..8 {
+            builder.when_transition().when(local.octet[i]).assert_one(next.octet[(i + 1) % 8])
+        }
+
+        // Verify that all of the octet_num columns are bool.
+        for i in 0..11 {
+            builder.assert_bool(local.octet_num[i]);
+        }
+
+        // Verify that exactly one of the octet_num columns is true.
+        let mut octet_num_sum = AB::Expr::ZERO;
+        for i in 0..11 {
+            octet_num_sum = octet_num_sum.clone() + local.octet_num[i].into();
+        }
+        builder.assert_one(octet_num_sum);
+
+        // The first row should have octet_num[0] = 1 if it's real.
+        builder.when_first_row().assert_one(local.octet_num[0]);
+
+        // If current row is not last of an octet and next row is real, octet_num should be the
+        // same.
+        for i in 0..11 {
+            builder
+                .when_transition()
+                .when_not(local.octet[7])
+                .assert_eq(local.octet_num[i], next.octet_num[i]);
+        }
+
+        // If current row is last of an octet and next row is real, octet_num should rotate by 1.
+        for i in 0..11 {
+            builder
+                .when_transition()
+                .when(local.octet[7])
+                .assert_eq(local.octet_num[i], next.octet_num[(i + 1) % 11]);
+        }
+
+        // Assert that the is_initialize flag is correct.
+        builder.assert_eq(
+            local.is_initialize,
+            (local.octet_num[0] + local.octet_num[1]) * local.is_real,
+        );
+
+        // Assert that the is_compression flag is correct.
+        builder.assert_eq(
+            local.is_compression,
+            (local.octet_num[2]
+                + local.octet_num[3]
+                + local.octet_num[4]
+                + local.octet_num[5]
+                + local.octet_num[6]
+                + local.octet_num[7]
+                + local.octet_num[8])
+                * local.is_real,
+        );
+
+        // Assert that the is_finalize flag is correct.
+        builder.assert_eq(
+            local.is_finalize,
+            (local.octet_num[9] + local.octet_num[10]) * local.is_real,
+        );
+
+        builder.assert_eq(local.is_last_row.into(), local.octet[7] * local.octet_num[10]);
+
+        // If this row is real and not the last cycle, then next row should have same inputs.
+        builder
+            .when_transition()
+            .when(local.is_real)
+            .when_not(local.is_last_row)
+            .assert_eq(local.shard, next.shard);
+        builder
+            .when_transition()
+            .when(local.is_real)
+            .when_not(local.is_last_row)
+            .assert_eq(local.clk, next.clk);
+        builder
+            .when_transition()
+            .when(local.is_real)
+            .when_not(local.is_last_row)
+            .assert_eq(local.state_ptr, next.state_ptr);
+        builder
+            .when_transition()
+            .when(local.is_real)
+            .when_not(local.is_last_row)
+            .assert_eq(local.msg_ptr, next.msg_ptr);
+
+        // The chaining value and the message are also carried over to the next row.
+        for i in 0..8 {
+            builder
+                .when_transition()
+                .when(local.is_real)
+                .when_not(local.is_last_row)
+                .assert_word_eq(local.cv[i], next.cv[i]);
+        }
+        for i in 0..16 {
+            builder
+                .when_transition()
+                .when(local.is_real)
+                .when_not(local.is_last_row)
+                .assert_word_eq(local.m[i], next.m[i]);
+        }
+
+        // Assert that is_real is a bool.
+        builder.assert_bool(local.is_real);
+
+        // If this row is real and not the last cycle, then next row should also be real.
+        builder
+            .when_transition()
+            .when(local.is_real)
+            .when_not(local.is_last_row)
+            .assert_one(next.is_real);
+
+        // Once the is_real flag is changed to false, it should not be changed back.
+        builder.when_transition().when_not(local.is_real).assert_zero(next.is_real);
+
+        // Assert that the table ends in nonreal columns. Since each compress syscall is 88 cycles
+        // and the table is padded to a power of 2, the last row of the table should always be
+        // padding.
+        builder.when_last_row().assert_zero(local.is_real);
+    }
+
+    /// Constrains that memory addresses are correct and that memory is correctly written/read.
+    fn eval_memory<AB: ZKMAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Blake3CompressCols<AB::Var>,
+        next: &Blake3CompressCols<AB::Var>,
+    ) {
+        // Calculate the index of the state and message word accessed in this row. The second
+        // initialize octet and the second finalize octet access the upper half of the words.
+        let mut word_idx =
+            (local.octet_num[1] + local.octet_num[10]) * AB::Expr::from_canonical_u32(8);
+        for i in 0..8 {
+            word_idx = word_idx.clone() + local.octet[i] * AB::Expr::from_canonical_usize(i);
+        }
+
+        builder.eval_memory_access(
+            local.shard,
+            local.clk + local.is_finalize,
+            local.state_ptr + word_idx.clone() * AB::Expr::from_canonical_u32(4),
+            &local.mem,
+            local.is_initialize + local.is_finalize,
+        );
+        builder.eval_memory_access(
+            local.shard,
+            local.clk,
+            local.msg_ptr + word_idx * AB::Expr::from_canonical_u32(4),
+            &local.msg_mem,
+            local.is_initialize,
+        );
+
+        // During initialize, verify that the state is read only.
+        builder
+            .when(local.is_initialize)
+            .assert_word_eq(*local.mem.prev_value(), *local.mem.value());
+
+        // In the initialize phase, verify that the state, chaining value and message words are
+        // correctly read from memory.
+        for n in 0..2 {
+            for i in 0..8 {
+                builder
+                    .when(local.octet_num[n] * local.octet[i])
+                    .assert_word_eq(local.v[8 * n + i], *local.mem.value());
+                builder
+                    .when(local.octet_num[n] * local.octet[i])
+                    .assert_word_eq(local.m[8 * n + i], *local.msg_mem.value());
+            }
+        }
+        for i in 0..8 {
+            builder
+                .when(local.octet_num[0] * local.octet[i])
+                .assert_word_eq(local.cv[i], *local.mem.value());
+        }
+
+        // The state does not change during initialize.
+        for i in 0..16 {
+            builder
+                .when_transition()
+                .when(local.is_initialize)
+                .assert_word_eq(local.v[i], next.v[i]);
+        }
+
+        // In the finalize phase, verify that the correct value is written to memory.
+        builder
+            .when(local.is_finalize)
+            .assert_word_eq(*local.mem.value(), local.finalize_xor.value);
+    }
+
+    fn eval_compression_ops<AB: ZKMAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Blake3CompressCols<AB::Var>,
+        next: &Blake3CompressCols<AB::Var>,
+    ) {
+        // Select the inputs of the `G` function from the state and the message.
+        for (g, [a, b, c, d]) in BLAKE3_G_INDEX.into_iter().enumerate() {
+            let mut builder_g = builder.when(local.is_compression * local.octet[g]);
+            builder_g.assert_word_eq(local.a, local.v[a]);
+            builder_g.assert_word_eq(local.b, local.v[b]);
+            builder_g.assert_word_eq(local.c, local.v[c]);
+            builder_g.assert_word_eq(local.d, local.v[d]);
+        }
+        for (round, schedule) in BLAKE3_MSG_SCHEDULE.iter().enumerate() {
+            for g in 0..8 {
+                builder
+                    .when(local.octet_num[round + 2] * local.octet[g])
+                    .assert_word_eq(local.mx, local.m[schedule[2 * g]]);
+                builder
+                    .when(local.octet_num[round + 2] * local.octet[g])
+                    .assert_word_eq(local.my, local.m[schedule[2 * g + 1]]);
+            }
+        }
+
+        // Calculate a1 := a + b + mx.
+        AddOperation::<AB::F>::eval(
+            builder,
+            local.a,
+            local.b,
+            local.a_add_b,
+            local.is_compression.into(),
+        );
+        AddOperation::<AB::F>::eval(
+            builder,
+            local.a_add_b.value,
+            local.mx,
+            local.a1,
+            local.is_compression.into(),
+        );
+
+        // Calculate d1 := (d xor a1) rightrotate 16.
+        XorOperation::<AB::F>::eval(
+            builder,
+            local.d,
+            local.a1.value,
+            local.d_xor_a,
+            local.is_compression,
+        );
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            local.d_xor_a.value,
+            16,
+            local.d_rr_16,
+            local.is_compression,
+        );
+
+        // Calculate c1 := c + d1.
+        AddOperation::<AB::F>::eval(
+            builder,
+            local.c,
+            local.d_rr_16.value,
+            local.c1,
+            local.is_compression.into(),
+        );
+
+        // Calculate b1 := (b xor c1) rightrotate 12.
+        XorOperation::<AB::F>::eval(
+            builder,
+            local.b,
+            local.c1.value,
+            local.b_xor_c,
+            local.is_compression,
+        );
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            local.b_xor_c.value,
+            12,
+            local.b_rr_12,
+            local.is_compression,
+        );
+
+        // Calculate a2 := a1 + b1 + my.
+        AddOperation::<AB::F>::eval(
+            builder,
+            local.a1.value,
+            local.b_rr_12.value,
+            local.a1_add_b1,
+            local.is_compression.into(),
+        );
+        AddOperation::<AB::F>::eval(
+            builder,
+            local.a1_add_b1.value,
+            local.my,
+            local.a2,
+            local.is_compression.into(),
+        );
+
+        // Calculate d2 := (d1 xor a2) rightrotate 8.
+        XorOperation::<AB::F>::eval(
+            builder,
+            local.d_rr_16.value,
+            local.a2.value,
+            local.d1_xor_a2,
+            local.is_compression,
+        );
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            local.d1_xor_a2.value,
+            8,
+            local.d_rr_8,
+            local.is_compression,
+        );
+
+        // Calculate c2 := c1 + d2.
+        AddOperation::<AB::F>::eval(
+            builder,
+            local.c1.value,
+            local.d_rr_8.value,
+            local.c2,
+            local.is_compression.into(),
+        );
+
+        // Calculate b2 := (b1 xor c2) rightrotate 7.
+        XorOperation::<AB::F>::eval(
+            builder,
+            local.b_rr_12.value,
+            local.c2.value,
+            local.b1_xor_c2,
+            local.is_compression,
+        );
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            local.b1_xor_c2.value,
+            7,
+            local.b_rr_7,
+            local.is_compression,
+        );
+
+        // The next state is the current state with the outputs of `G` written back to the words
+        // that were mixed.
+        for (g, g_index) in BLAKE3_G_INDEX.into_iter().enumerate() {
+            let outputs = [local.a2.value, local.b_rr_7.value, local.c2.value, local.d_rr_8.value];
+            for i in 0..16 {
+                let expected = match g_index.iter().position(|&idx| idx == i) {
+                    Some(pos) => outputs[pos],
+                    None => local.v[i],
+                };
+                builder
+                    .when_transition()
+                    .when(local.is_compression)
+                    .when(local.octet[g])
+                    .assert_word_eq(next.v[i], expected);
+            }
+        }
+    }
+
+    fn eval_finalize_ops<AB: ZKMAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Blake3CompressCols<AB::Var>,
+        next: &Blake3CompressCols<AB::Var>,
+    ) {
+        // The final state does not change during finalize.
+        for i in 0..16 {
+            builder
+                .when_transition()
+                .when(local.is_finalize)
+                .when_not(local.is_last_row)
+                .assert_word_eq(local.v[i], next.v[i]);
+        }
+
+        // In the first finalize octet, output[i] := v[i] xor v[i + 8]. In the second one,
+        // output[i + 8] := v[i + 8] xor cv[i]. The operands are selected with the octet bitmap.
+        let v_lo = builder.index_word_array(&local.v[..8], &local.octet);
+        let v_hi = builder.index_word_array(&local.v[8..], &local.octet);
+        let cv = builder.index_word_array(&local.cv, &local.octet);
+
+        builder.when(local.octet_num[9]).assert_word_eq(local.finalize_lhs, v_lo);
+        builder.when(local.octet_num[9]).assert_word_eq(local.finalize_rhs, v_hi.clone());
+        builder.when(local.octet_num[10]).assert_word_eq(local.finalize_lhs, v_hi);
+        builder.when(local.octet_num[10]).assert_word_eq(local.finalize_rhs, cv);
+
+        XorOperation::<AB::F>::eval(
+            builder,
+            local.finalize_lhs,
+            local.finalize_rhs,
+            local.finalize_xor,
+            local.is_finalize,
+        );
+
+        // Memory write is constrained in eval_memory.
+    }
+}
@@ -0,0 +1,94 @@
+use std::mem::size_of;
+
+use zkm_derive::AlignedBorrow;
+use zkm_stark::Word;
+
+use crate::{
+    memory::{MemoryReadCols, MemoryReadWriteCols},
+    operations::{AddOperation, FixedRotateRightOperation, XorOperation},
+};
+
+pub const NUM_BLAKE3_COMPRESS_COLS: usize = size_of::<Blake3CompressCols<u8>>();
+
+/// A set of columns needed to compute the BLAKE3 compression function.
+///
+/// Each blake3 compress syscall is processed over 88 rows, split into 11 octets. The first two
+/// octets read the 16 state words and the 16 message words, one of each per row. Each of the next
+/// 7 octets computes one round, with one `G` function per row. The last two octets write the 16
+/// output words back to the state.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Blake3CompressCols<T> {
+    /// Inputs.
+    pub shard: T,
+    pub clk: T,
+    pub state_ptr: T,
+    pub msg_ptr: T,
+
+    pub start: T,
+
+    /// Which row within the octet we are currently processing.
+    pub octet: [T; 8],
+
+    /// This will specify which octet we are currently processing.
+    ///  - The first two octets are for initialize.
+    ///  - The next 7 octets are for compress.
+    ///  - The last two octets are for finalize.
+    pub octet_num: [T; 11],
+
+    /// Memory access to the state. During initialize this is a read, during finalize this is
+    /// used to write the output into memory.
+    pub mem: MemoryReadWriteCols<T>,
+    /// Memory access to the message. This is only used during initialize.
+    pub msg_mem: MemoryReadCols<T>,
+
+    /// The working state at the start of the current row.
+    pub v: [Word<T>; 16],
+    /// The input chaining value, which is the first half of the initial state.
+    pub cv: [Word<T>; 8],
+    /// The message block.
+    pub m: [Word<T>; 16],
+
+    /// The inputs of the `G` function computed in the current row.
+    pub a: Word<T>,
+    pub b: Word<T>,
+    pub c: Word<T>,
+    pub d: Word<T>,
+    pub mx: Word<T>,
+    pub my: Word<T>,
+
+    pub a_add_b: AddOperation<T>,
+    /// `a1 := a + b + mx`.
+    pub a1: AddOperation<T>,
+    pub d_xor_a: XorOperation<T>,
+    /// `d1 := (d xor a1) rightrotate 16`.
+    pub d_rr_16: FixedRotateRightOperation<T>,
+    /// `c1 := c + d1`.
+    pub c1: AddOperation<T>,
+    pub b_xor_c: XorOperation<T>,
+    /// `b1 := (b xor c1) rightrotate 12`.
+    pub b_rr_12: FixedRotateRightOperation<T>,
+    pub a1_add_b1: AddOperation<T>,
+    /// `a2 := a1 + b1 + my`.
+    pub a2: AddOperation<T>,
+    pub d1_xor_a2: XorOperation<T>,
+    /// `d2 := (d1 xor a2) rightrotate 8`.
+    pub d_rr_8: FixedRotateRightOperation<T>,
+    /// `c2 := c1 + d2`.
+    pub c2: AddOperation<T>,
+    pub b1_xor_c2: XorOperation<T>,
+    /// `b2 := (b1 xor c2) rightrotate 7`.
+    pub b_rr_7: FixedRotateRightOperation<T>,
+
+    /// During finalize, the two words xored together into the output word written to `mem`.
+    pub finalize_lhs: Word<T>,
+    pub finalize_rhs: Word<T>,
+    pub finalize_xor: XorOperation<T>,
+
+    pub is_initialize: T,
+    pub is_compression: T,
+    pub is_finalize: T,
+    pub is_last_row: T,
+
+    pub is_real: T,
+}
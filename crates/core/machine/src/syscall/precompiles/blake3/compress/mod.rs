@@ -0,0 +1,87 @@
+mod air;
+mod columns;
+mod trace;
+
+/// The message word indices used by each of the 7 BLAKE3 rounds.
+pub const BLAKE3_MSG_SCHEDULE: [[usize; 16]; 7] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8],
+    [3, 4, 10, 12, 13, 2, 7, 14, 6, 5, 9, 0, 11, 15, 8, 1],
+    [10, 7, 12, 9, 14, 3, 13, 15, 4, 0, 11, 2, 5, 8, 1, 6],
+    [12, 13, 9, 11, 15, 10, 14, 8, 7, 2, 5, 3, 0, 1, 6, 4],
+    [9, 14, 11, 5, 8, 12, 15, 1, 13, 3, 0, 10, 2, 6, 4, 7],
+    [11, 15, 5, 0, 1, 9, 8, 6, 14, 10, 2, 12, 3, 4, 7, 13],
+];
+
+/// The state words mixed by each of the 8 `G` functions of a round, columns first.
+pub const BLAKE3_G_INDEX: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// Implements the BLAKE3 compression function. The inputs to the syscall are a pointer to the 16
+/// word initial state, which is overwritten with the 16 word output, and a pointer to the 16 word
+/// message block.
+///
+/// In the AIR, each BLAKE3 compress syscall takes up 88 rows. The first 16 rows are for
+/// initialization and the last 16 rows for finalize. The middle 56 rows are for compression, with
+/// each row computing a single `G` function.
+#[derive(Default)]
+pub struct Blake3CompressChip;
+
+impl Blake3CompressChip {
+    pub const fn new() -> Self {
+        Self {}
+    }
+}
+
+#[cfg(test)]
+pub mod compress_tests {
+
+    use test_artifacts::BLAKE3_COMPRESS_ELF;
+    use zkm_core_executor::{syscalls::SyscallCode, Instruction, Opcode, Program};
+    use zkm_stark::CpuProver;
+
+    use crate::utils::{run_test, setup_logger};
+
+    pub fn blake3_compress_program() -> Program {
+        let state_ptr = 100;
+        let msg_ptr = 1000;
+        let mut instructions = vec![Instruction::new(Opcode::ADD, 29, 0, 5, false, true)];
+        for i in 0..16 {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 30, 0, state_ptr + i * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+                Instruction::new(Opcode::ADD, 30, 0, msg_ptr + i * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            ]);
+        }
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::BLAKE3_COMPRESS as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 0, state_ptr, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, msg_ptr, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn prove_koalabear() {
+        setup_logger();
+        let program = blake3_compress_program();
+        run_test::<CpuProver<_, _>>(program).unwrap();
+    }
+
+    #[test]
+    fn test_blake3_compress_program() {
+        setup_logger();
+        let program = Program::from(BLAKE3_COMPRESS_ELF).unwrap();
+        run_test::<CpuProver<_, _>>(program).unwrap();
+    }
+}
@@ -0,0 +1,238 @@
+use std::borrow::BorrowMut;
+
+use hashbrown::HashMap;
+use itertools::Itertools;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::{ParallelIterator, ParallelSlice};
+use zkm_core_executor::{
+    events::{Blake3CompressEvent, ByteLookupEvent, ByteRecord, PrecompileEvent},
+    syscalls::SyscallCode,
+    ExecutionRecord, Program,
+};
+use zkm_stark::{air::MachineAir, Word};
+
+use super::{
+    columns::{Blake3CompressCols, NUM_BLAKE3_COMPRESS_COLS},
+    Blake3CompressChip, BLAKE3_G_INDEX, BLAKE3_MSG_SCHEDULE,
+};
+use crate::utils::pad_rows_fixed;
+
+impl<F: PrimeField32> MachineAir<F> for Blake3CompressChip {
+    type Record = ExecutionRecord;
+
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Blake3Compress".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        _: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let rows = Vec::new();
+
+        let mut wrapped_rows = Some(rows);
+        for (_, event) in input.get_precompile_events(SyscallCode::BLAKE3_COMPRESS) {
+            let event = if let PrecompileEvent::Blake3Compress(event) = event {
+                event
+            } else {
+                unreachable!()
+            };
+            self.event_to_rows(event, &mut wrapped_rows, &mut Vec::new());
+        }
+        let mut rows = wrapped_rows.unwrap();
+
+        let num_real_rows = rows.len();
+
+        pad_rows_fixed(
+            &mut rows,
+            || [F::ZERO; NUM_BLAKE3_COMPRESS_COLS],
+            input.fixed_log2_rows::<F, _>(self),
+        );
+
+        // Set the octet_num and octet columns for the padded rows.
+        let mut octet_num = 0;
+        let mut octet = 0;
+        for row in rows[num_real_rows..].iter_mut() {
+            let cols: &mut Blake3CompressCols<F> = row.as_mut_slice().borrow_mut();
+            cols.octet_num[octet_num] = F::ONE;
+            cols.octet[octet] = F::ONE;
+
+            octet = (octet + 1) % 8;
+            if octet == 0 {
+                octet_num = (octet_num + 1) % 11;
+            }
+
+            cols.is_last_row = cols.octet[7] * cols.octet_num[10];
+        }
+
+        // Convert the trace to a row major matrix.
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_BLAKE3_COMPRESS_COLS,
+        )
+    }
+
+    fn generate_dependencies(&self, input: &Self::Record, output: &mut Self::Record) {
+        let events = input.get_precompile_events(SyscallCode::BLAKE3_COMPRESS);
+        let chunk_size = std::cmp::max(events.len() / num_cpus::get(), 1);
+
+        let blu_batches = events
+            .par_chunks(chunk_size)
+            .map(|events| {
+                let mut blu: HashMap<ByteLookupEvent, usize> = HashMap::new();
+                events.iter().for_each(|(_, event)| {
+                    let event = if let PrecompileEvent::Blake3Compress(event) = event {
+                        event
+                    } else {
+                        unreachable!()
+                    };
+                    self.event_to_rows::<F>(event, &mut None, &mut blu);
+                });
+                blu
+            })
+            .collect::<Vec<_>>();
+
+        output.add_byte_lookup_events_from_maps(blu_batches.iter().collect_vec());
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        if let Some(shape) = shard.shape.as_ref() {
+            shape.included::<F, _>(self)
+        } else {
+            !shard.get_precompile_events(SyscallCode::BLAKE3_COMPRESS).is_empty()
+        }
+    }
+}
+
+impl Blake3CompressChip {
+    fn event_to_rows<F: PrimeField32>(
+        &self,
+        event: &Blake3CompressEvent,
+        rows: &mut Option<Vec<[F; NUM_BLAKE3_COMPRESS_COLS]>>,
+        blu: &mut impl ByteRecord,
+    ) {
+        let new_row = |octet: usize, octet_num: usize| {
+            let mut row = [F::ZERO; NUM_BLAKE3_COMPRESS_COLS];
+            let cols: &mut Blake3CompressCols<F> = row.as_mut_slice().borrow_mut();
+
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.state_ptr = F::from_canonical_u32(event.state_ptr);
+            cols.msg_ptr = F::from_canonical_u32(event.msg_ptr);
+
+            cols.octet[octet] = F::ONE;
+            cols.octet_num[octet_num] = F::ONE;
+
+            for i in 0..8 {
+                cols.cv[i] = Word::from(event.state[i]);
+            }
+            for i in 0..16 {
+                cols.m[i] = Word::from(event.msg[i]);
+            }
+
+            cols.is_real = F::ONE;
+            cols.start = cols.is_real * cols.octet_num[0] * cols.octet[0];
+            row
+        };
+
+        // Read the state and the message.
+        for octet_num in 0..2 {
+            for j in 0..8usize {
+                let mut row = new_row(j, octet_num);
+                let cols: &mut Blake3CompressCols<F> = row.as_mut_slice().borrow_mut();
+                cols.is_initialize = F::ONE;
+
+                let idx = 8 * octet_num + j;
+                cols.mem.populate_read(event.state_read_records[idx], blu);
+                cols.msg_mem.populate(event.msg_read_records[idx], blu);
+
+                for i in 0..16 {
+                    cols.v[i] = Word::from(event.state[i]);
+                }
+
+                if rows.as_ref().is_some() {
+                    rows.as_mut().unwrap().push(row);
+                }
+            }
+        }
+
+        // Performs the compress operation, one `G` function per row.
+        let mut v = event.state;
+        for (round, schedule) in BLAKE3_MSG_SCHEDULE.iter().enumerate() {
+            for (g, [a, b, c, d]) in BLAKE3_G_INDEX.into_iter().enumerate() {
+                let mut row = new_row(g, round + 2);
+                let cols: &mut Blake3CompressCols<F> = row.as_mut_slice().borrow_mut();
+                cols.is_compression = F::ONE;
+
+                for i in 0..16 {
+                    cols.v[i] = Word::from(v[i]);
+                }
+
+                let mx = event.msg[schedule[2 * g]];
+                let my = event.msg[schedule[2 * g + 1]];
+                cols.a = Word::from(v[a]);
+                cols.b = Word::from(v[b]);
+                cols.c = Word::from(v[c]);
+                cols.d = Word::from(v[d]);
+                cols.mx = Word::from(mx);
+                cols.my = Word::from(my);
+
+                let a_add_b = cols.a_add_b.populate(blu, v[a], v[b]);
+                let a1 = cols.a1.populate(blu, a_add_b, mx);
+                let d_xor_a = cols.d_xor_a.populate(blu, v[d], a1);
+                let d1 = cols.d_rr_16.populate(blu, d_xor_a, 16);
+                let c1 = cols.c1.populate(blu, v[c], d1);
+                let b_xor_c = cols.b_xor_c.populate(blu, v[b], c1);
+                let b1 = cols.b_rr_12.populate(blu, b_xor_c, 12);
+                let a1_add_b1 = cols.a1_add_b1.populate(blu, a1, b1);
+                let a2 = cols.a2.populate(blu, a1_add_b1, my);
+                let d1_xor_a2 = cols.d1_xor_a2.populate(blu, d1, a2);
+                let d2 = cols.d_rr_8.populate(blu, d1_xor_a2, 8);
+                let c2 = cols.c2.populate(blu, c1, d2);
+                let b1_xor_c2 = cols.b1_xor_c2.populate(blu, b1, c2);
+                let b2 = cols.b_rr_7.populate(blu, b1_xor_c2, 7);
+
+                v[a] = a2;
+                v[b] = b2;
+                v[c] = c2;
+                v[d] = d2;
+
+                if rows.as_ref().is_some() {
+                    rows.as_mut().unwrap().push(row);
+                }
+            }
+        }
+
+        // Write the output words.
+        for octet_num in 9..11 {
+            for j in 0..8usize {
+                let mut row = new_row(j, octet_num);
+                let cols: &mut Blake3CompressCols<F> = row.as_mut_slice().borrow_mut();
+                cols.is_finalize = F::ONE;
+
+                for i in 0..16 {
+                    cols.v[i] = Word::from(v[i]);
+                }
+
+                let (lhs, rhs) =
+                    if octet_num == 9 { (v[j], v[j + 8]) } else { (v[j + 8], event.state[j]) };
+                cols.finalize_lhs = Word::from(lhs);
+                cols.finalize_rhs = Word::from(rhs);
+                cols.finalize_xor.populate(blu, lhs, rhs);
+
+                let idx = 8 * (octet_num - 9) + j;
+                cols.mem.populate_write(event.state_write_records[idx], blu);
+
+                cols.is_last_row = cols.octet[7] * cols.octet_num[10];
+
+                if rows.as_ref().is_some() {
+                    rows.as_mut().unwrap().push(row);
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,12 @@
+pub mod blake2b;
+pub mod blake3;
 pub mod edwards;
 pub mod fptower;
 pub mod keccak_sponge;
 pub mod poseidon2;
 pub mod sha256;
 pub mod sys_linux;
+pub mod sys_read;
 pub mod u256x2048_mul;
 pub mod uint256;
 pub mod weierstrass;
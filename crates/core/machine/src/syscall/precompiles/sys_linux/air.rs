@@ -39,8 +39,10 @@ where
         self.eval_clone(builder, local);
         self.eval_exit_group(builder, local);
         self.eval_fnctl(builder, local);
-        self.eval_read(builder, local);
+        self.eval_getpid(builder, local);
+        self.eval_sched_yield(builder, local);
         self.eval_write(builder, local);
+        self.eval_file(builder, local);
         self.eval_mmap(builder, local);
         self.eval_nop(builder, local);
 
@@ -71,8 +73,12 @@ where
                 local.is_a1_3,
                 local.is_fnctl_a1_1,
                 local.is_fnctl_a1_3,
-                local.is_read,
                 local.is_write,
+                local.is_open,
+                local.is_close,
+                local.is_lseek,
+                local.is_getpid,
+                local.is_sched_yield,
                 local.is_nop,
                 local.is_real,
             ];
@@ -128,22 +134,43 @@ where
                 local.syscall_id,
                 AB::Expr::from_canonical_u32(SyscallCode::SYS_FCNTL as u32),
             );
-            builder.when(local.is_read).assert_eq(
-                local.syscall_id,
-                AB::Expr::from_canonical_u32(SyscallCode::SYS_READ as u32),
-            );
             builder.when(local.is_write).assert_eq(
                 local.syscall_id,
                 AB::Expr::from_canonical_u32(SyscallCode::SYS_WRITE as u32),
             );
+            builder.when(local.is_open).assert_zero(
+                (local.syscall_id - AB::Expr::from_canonical_u32(SyscallCode::SYS_OPEN as u32))
+                    * (local.syscall_id
+                        - AB::Expr::from_canonical_u32(SyscallCode::SYS_OPENAT as u32)),
+            );
+            builder.when(local.is_close).assert_eq(
+                local.syscall_id,
+                AB::Expr::from_canonical_u32(SyscallCode::SYS_CLOSE as u32),
+            );
+            builder.when(local.is_lseek).assert_eq(
+                local.syscall_id,
+                AB::Expr::from_canonical_u32(SyscallCode::SYS_LSEEK as u32),
+            );
+            builder.when(local.is_getpid).assert_eq(
+                local.syscall_id,
+                AB::Expr::from_canonical_u32(SyscallCode::SYS_GETPID as u32),
+            );
+            builder.when(local.is_sched_yield).assert_eq(
+                local.syscall_id,
+                AB::Expr::from_canonical_u32(SyscallCode::SYS_SCHED_YIELD as u32),
+            );
             builder.when(local.is_real).assert_one(
                 local.is_mmap
                     + local.is_clone
                     + local.is_exit_group
                     + local.is_brk
                     + local.is_fnctl
-                    + local.is_read
                     + local.is_write
+                    + local.is_open
+                    + local.is_close
+                    + local.is_lseek
+                    + local.is_getpid
+                    + local.is_sched_yield
                     + local.is_nop,
             );
         }
@@ -286,18 +313,14 @@ impl SysLinuxChip {
             .assert_word_eq(*local.output.value(), Word::<AB::Expr>::from(0x9u32));
     }
 
-    fn eval_read<AB: ZKMAirBuilder>(&self, builder: &mut AB, local: &SysLinuxCols<AB::Var>) {
-        builder.when(local.is_read).when(local.is_a0_0).assert_word_zero(local.result);
-        builder.when(local.is_read).when(local.is_a0_0).assert_word_zero(*local.output.value());
+    fn eval_getpid<AB: ZKMAirBuilder>(&self, builder: &mut AB, local: &SysLinuxCols<AB::Var>) {
+        builder.when(local.is_getpid).assert_word_eq(local.result, Word::<AB::Expr>::from(1u32));
+        builder.when(local.is_getpid).assert_word_zero(*local.output.value());
+    }
 
-        builder
-            .when(local.is_read)
-            .when_not(local.is_a0_0)
-            .assert_word_eq(local.result, Word::<AB::Expr>::from(0xFFFFFFFFu32));
-        builder
-            .when(local.is_read)
-            .when_not(local.is_a0_0)
-            .assert_word_eq(*local.output.value(), Word::<AB::Expr>::from(9));
+    fn eval_sched_yield<AB: ZKMAirBuilder>(&self, builder: &mut AB, local: &SysLinuxCols<AB::Var>) {
+        builder.when(local.is_sched_yield).assert_word_zero(local.result);
+        builder.when(local.is_sched_yield).assert_word_zero(*local.output.value());
     }
 
     fn eval_write<AB: ZKMAirBuilder>(&self, builder: &mut AB, local: &SysLinuxCols<AB::Var>) {
@@ -313,6 +336,28 @@ impl SysLinuxChip {
         builder.when(local.is_write).assert_word_zero(*local.output.value());
     }
 
+    fn eval_file<AB: ZKMAirBuilder>(&self, builder: &mut AB, local: &SysLinuxCols<AB::Var>) {
+        // The open flags of `openat` and the whence of `lseek` are read from a2.
+        builder.eval_memory_access(
+            local.shard,
+            local.clk,
+            AB::Expr::from_canonical_u32(Register::A2 as u32),
+            &local.inorout,
+            local.is_open + local.is_lseek,
+        );
+        builder
+            .when(local.is_open + local.is_lseek)
+            .assert_word_eq(*local.inorout.value(), local.inorout.prev_value);
+
+        // The results depend on the file table of the virtual filesystem, which lives outside of
+        // memory and isn't committed. The host decides them like the contents of the files, which
+        // are a hint, so they are not proven: the errno is only checked to be a valid word.
+        builder.slice_range_check_u8(
+            &local.output.value().0,
+            local.is_open + local.is_close + local.is_lseek,
+        );
+    }
+
     fn eval_nop<AB: ZKMAirBuilder>(&self, builder: &mut AB, local: &SysLinuxCols<AB::Var>) {
         builder.when(local.is_nop).assert_word_zero(*local.output.value());
         builder.when(local.is_nop).assert_word_zero(local.result);
@@ -49,12 +49,24 @@ pub struct SysLinuxCols<T> {
     pub is_fnctl_a1_1: T,
     pub is_fnctl_a1_3: T,
 
-    /// Columns for sys read
-    pub is_read: T,
-
     /// Columns for sys write
     pub is_write: T,
 
+    /// Columns for sys open and openat
+    pub is_open: T,
+
+    /// Columns for sys close
+    pub is_close: T,
+
+    /// Columns for sys lseek
+    pub is_lseek: T,
+
+    /// Columns for sys getpid
+    pub is_getpid: T,
+
+    /// Columns for sys sched_yield
+    pub is_sched_yield: T,
+
     /// Columns for sys nop
     pub is_nop: T,
 
@@ -13,11 +13,17 @@ impl SysLinuxChip {
 
 #[cfg(test)]
 pub mod sys_linux_tests {
+    use std::collections::BTreeMap;
 
-    use zkm_core_executor::{syscalls::SyscallCode, Instruction, Opcode, Program};
-    use zkm_stark::CpuProver;
+    use zkm_core_executor::{
+        syscalls::SyscallCode, Executor, Instruction, Opcode, Program, Register,
+    };
+    use zkm_stark::{CpuProver, ZKMCoreOpts};
 
-    use crate::utils::{run_test, setup_logger};
+    use crate::{
+        io::ZKMStdin,
+        utils::{run_test, run_test_io, setup_logger},
+    };
 
     pub fn sys_linux_program() -> Program {
         let w_ptr = 100;
@@ -130,4 +136,76 @@ pub mod sys_linux_tests {
         let program = sys_linux_program();
         run_test::<CpuProver<_, _>>(program).unwrap();
     }
+
+    pub fn sys_file_program() -> Program {
+        let instructions = vec![
+            // Store the path "a.txt" at 0x400.
+            Instruction::new(Opcode::ADD, 29, 0, 0x78742e61, false, true),
+            Instruction::new(Opcode::ADD, 30, 0, 0x400, false, true),
+            Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            Instruction::new(Opcode::ADD, 29, 0, 0x74, false, true),
+            Instruction::new(Opcode::SW, 29, 30, 4, false, true),
+            // Open it read-only and keep the fd in r16.
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_OPEN as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 0, 0x400, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, 0, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            Instruction::new(Opcode::ADD, 16, 2, 0, false, false),
+            // Read 6 bytes to 0x500.
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_READ as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 16, 0, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, 0x500, false, true),
+            Instruction::new(Opcode::ADD, 6, 0, 6, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            // Seek to 2 bytes before the end.
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_LSEEK as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 16, 0, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, -2i32 as u32, false, true),
+            Instruction::new(Opcode::ADD, 6, 0, 2, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            // Read the rest to 0x600.
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_READ as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 16, 0, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, 0x600, false, true),
+            Instruction::new(Opcode::ADD, 6, 0, 8, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            // Close it, after which reads fail.
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_CLOSE as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 16, 0, false, false),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_READ as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 16, 0, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, 0x700, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            Instruction::new(Opcode::ADD, 17, 2, 0, false, false),
+            Instruction::new(Opcode::ADD, 18, 7, 0, false, false),
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_EXT_GROUP as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 0, 0, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+        ];
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_sys_file() {
+        let mut runtime = Executor::new(sys_file_program(), ZKMCoreOpts::default());
+        runtime.write_files(&BTreeMap::from([("a.txt".to_string(), b"hello, world".to_vec())]));
+        runtime.run().unwrap();
+
+        assert_eq!(runtime.register(Register::S0), 3);
+        assert_eq!(runtime.word(0x500), 0x6c6c6568);
+        assert_eq!(runtime.word(0x504), 0x00002c6f);
+        assert_eq!(runtime.word(0x600), 0x0000646c);
+        // The read after closing the file fails.
+        assert_eq!(runtime.register(Register::S1), 0xffffffff);
+        assert_eq!(runtime.register(Register::S2), 9);
+    }
+
+    #[test]
+    fn prove_sys_file() {
+        setup_logger();
+        let mut stdin = ZKMStdin::new();
+        stdin.write_file("a.txt", b"hello, world".to_vec());
+        run_test_io::<CpuProver<_, _>>(sys_file_program(), stdin).unwrap();
+    }
 }
@@ -145,14 +145,28 @@ impl SysLinuxChip {
                     cols.inorout.populate_write(event.write_records[1], blu);
                 }
             }
-            4003 => {
-                cols.is_read = F::ONE;
+            4020 => {
+                cols.is_getpid = F::ONE;
+            }
+            4162 => {
+                cols.is_sched_yield = F::ONE;
             }
             4004 => {
                 assert!(event.read_records.len() == 1);
                 cols.inorout.populate_read(event.read_records[0], blu);
                 cols.is_write = F::ONE;
             }
+            4005 | 4288 | 4019 => {
+                assert!(event.read_records.len() == 1);
+                cols.inorout.populate_read(event.read_records[0], blu);
+                cols.is_open = F::from_bool(event.syscall_code != 4019);
+                cols.is_lseek = F::from_bool(event.syscall_code == 4019);
+                blu.add_u8_range_checks(&event.write_records[0].value.to_le_bytes());
+            }
+            4006 => {
+                cols.is_close = F::ONE;
+                blu.add_u8_range_checks(&event.write_records[0].value.to_le_bytes());
+            }
             _ => {
                 cols.is_nop = F::ONE;
             }
@@ -0,0 +1,447 @@
+use core::borrow::Borrow;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+use zkm_core_executor::{
+    events::{CLOCK_TICK_NANOS, RANDOM_WORD_KEY},
+    syscalls::SyscallCode,
+    Register,
+};
+use zkm_primitives::consts::errno::{MIPS_EBADF, MIPS_EFAULT};
+use zkm_stark::{
+    air::{LookupScope, PublicValues, ZKMAirBuilder, ZKM_PROOF_NUM_PV_ELTS},
+    Word,
+};
+
+use super::{
+    columns::{SysReadCols, NUM_SYS_READ_COLS},
+    SysReadChip,
+};
+use crate::{
+    air::{MemoryAirBuilder, WordAirBuilder},
+    memory::MemoryCols,
+    operations::{
+        AddOperation, GtColsBytes, IsZeroOperation, IsZeroWordOperation, RandomWordOperation,
+    },
+};
+use zkm_stark::air::BaseAirBuilder;
+
+impl<F> BaseAir<F> for SysReadChip {
+    fn width(&self) -> usize {
+        NUM_SYS_READ_COLS
+    }
+}
+
+impl<AB> Air<AB> for SysReadChip
+where
+    AB: ZKMAirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &SysReadCols<AB::Var> = (*local).borrow();
+        let next: &SysReadCols<AB::Var> = (*next).borrow();
+
+        let public_values_slice: [AB::PublicVar; ZKM_PROOF_NUM_PV_ELTS] =
+            core::array::from_fn(|i| builder.public_values()[i]);
+        let public_values: &PublicValues<Word<AB::PublicVar>, AB::PublicVar> =
+            public_values_slice.as_slice().borrow();
+
+        self.eval_flags(builder, local);
+        self.eval_read(builder, local);
+        self.eval_getrandom(builder, local, public_values);
+        self.eval_clock_gettime(builder, local, public_values);
+        self.eval_buffer(builder, local);
+        self.eval_rows(builder, local, next);
+        self.eval_counter(builder, local, next);
+        self.eval_word(builder, local);
+
+        // Check that the a3 memory access.
+        builder.eval_memory_access(
+            local.shard,
+            local.clk,
+            AB::Expr::from_canonical_u32(Register::A3 as u32),
+            &local.output,
+            local.is_first,
+        );
+
+        builder.receive_syscall(
+            local.shard,
+            local.clk,
+            local.syscall_id,
+            local.a0.reduce::<AB>(),
+            local.a1.reduce::<AB>(),
+            local.is_first,
+            LookupScope::Local,
+        );
+    }
+}
+
+impl SysReadChip {
+    fn eval_flags<AB: ZKMAirBuilder>(&self, builder: &mut AB, local: &SysReadCols<AB::Var>) {
+        // Check that the flags are boolean.
+        let bool_flags = [
+            local.is_read,
+            local.is_getrandom,
+            local.is_clock_gettime,
+            local.is_data,
+            local.is_counted,
+            local.is_first,
+            local.is_last,
+            local.is_word,
+            local.is_random,
+            local.is_clock,
+            local.is_real,
+        ];
+        for flag in bool_flags.into_iter().chain(local.is_offset).chain(local.is_byte) {
+            builder.assert_bool(flag);
+        }
+        builder.when(local.is_first).assert_one(local.is_real);
+        builder.when(local.is_last).assert_one(local.is_real);
+        builder.when(local.is_word).assert_one(local.is_real);
+        builder.when(local.is_random).assert_one(local.is_real);
+        builder.when(local.is_clock).assert_one(local.is_real);
+
+        // The syscall flags are set on the first row of each call.
+        builder
+            .assert_eq(local.is_read + local.is_getrandom + local.is_clock_gettime, local.is_first);
+        builder.when(local.is_data).assert_one(local.is_first);
+        builder.assert_eq(local.is_counted, local.is_data * (local.is_read + local.is_getrandom));
+
+        builder.when(local.is_read).assert_eq(
+            local.syscall_id,
+            AB::Expr::from_canonical_u32(SyscallCode::SYS_READ as u32),
+        );
+        builder.when(local.is_getrandom).assert_eq(
+            local.syscall_id,
+            AB::Expr::from_canonical_u32(SyscallCode::SYS_GETRANDOM as u32),
+        );
+        builder.when(local.is_clock_gettime).assert_eq(
+            local.syscall_id,
+            AB::Expr::from_canonical_u32(SyscallCode::SYS_CLOCK_GETTIME as u32),
+        );
+    }
+
+    fn eval_read<AB: ZKMAirBuilder>(&self, builder: &mut AB, local: &SysReadCols<AB::Var>) {
+        // The count is read from a2.
+        builder.eval_memory_access(
+            local.shard,
+            local.clk,
+            AB::Expr::from_canonical_u32(Register::A2 as u32),
+            &local.count_access,
+            local.is_read,
+        );
+        builder
+            .when(local.is_read)
+            .assert_word_eq(*local.count_access.value(), local.count_access.prev_value);
+
+        // The bytes are read to the buffer at a1, and at most as many as the count in a2.
+        builder.when(local.is_read).assert_word_eq(local.buffer, local.a1);
+        builder.when(local.is_read).assert_word_eq(local.count, *local.count_access.value());
+
+        // Reads from stdin always succeed. Whether any other fd is an open file depends on the
+        // file table of the virtual filesystem, which lives outside of memory and isn't
+        // committed, so the host decides it like the data it reads: files are a hint that isn't
+        // proven, see `ZKMStdin::write_file`.
+        IsZeroWordOperation::<AB::F>::eval(
+            builder,
+            local.a0.map(|x| x.into()),
+            local.is_stdin,
+            local.is_read.into(),
+        );
+        builder.when(local.is_read).when(local.is_stdin.result).assert_one(local.is_data);
+
+        builder
+            .when(local.is_read)
+            .when_not(local.is_data)
+            .assert_word_eq(local.result, Word::<AB::Expr>::from(0xFFFFFFFFu32));
+        builder
+            .when(local.is_read)
+            .when_not(local.is_data)
+            .assert_word_eq(*local.output.value(), Word::<AB::Expr>::from(MIPS_EBADF));
+    }
+
+    fn eval_getrandom<AB: ZKMAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &SysReadCols<AB::Var>,
+        public_values: &PublicValues<Word<AB::PublicVar>, AB::PublicVar>,
+    ) {
+        // The bytes are written to the buffer at a0, and at most as many as the count in a1.
+        builder.when(local.is_getrandom).assert_word_eq(local.buffer, local.a0);
+        builder.when(local.is_getrandom).assert_word_eq(local.count, local.a1);
+        builder.when(local.is_getrandom).assert_one(local.is_data);
+
+        // Every row of the call takes its bytes from the word of the stream of the committed
+        // seed at its counter.
+        for (seed, committed) in local.seed.iter().zip(public_values.random_seed.iter()) {
+            builder.when(local.is_random).assert_word_eq(*seed, *committed);
+        }
+        builder
+            .when(local.is_random)
+            .assert_word_eq(local.key, Word::<AB::Expr>::from(RANDOM_WORD_KEY));
+        RandomWordOperation::<AB::F>::eval(
+            builder,
+            [local.seed[0], local.seed[1], local.key, local.counter],
+            local.random,
+            local.is_random,
+        );
+        for k in 0..4 {
+            builder
+                .when(local.is_random)
+                .when(local.is_byte[k])
+                .assert_eq(local.word.value()[k], local.random.d4.value[k]);
+        }
+    }
+
+    fn eval_clock_gettime<AB: ZKMAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &SysReadCols<AB::Var>,
+        public_values: &PublicValues<Word<AB::PublicVar>, AB::PublicVar>,
+    ) {
+        // Both words of the `timespec` at a1 are written, unless it is misaligned.
+        builder.when(local.is_clock_gettime).assert_word_eq(local.buffer, local.a1);
+        builder.when(local.is_clock_gettime).assert_eq(local.is_data, local.is_offset[0]);
+        builder
+            .when(local.is_clock_gettime)
+            .when(local.is_data)
+            .assert_eq(local.len, AB::Expr::from_canonical_u32(8));
+        builder.when(local.is_clock_gettime).when(local.is_data).assert_word_zero(local.result);
+        builder
+            .when(local.is_clock_gettime)
+            .when_not(local.is_data)
+            .assert_word_eq(local.result, Word::<AB::Expr>::from(0xFFFFFFFFu32));
+        builder
+            .when(local.is_clock_gettime)
+            .when_not(local.is_data)
+            .assert_word_eq(*local.output.value(), Word::<AB::Expr>::from(MIPS_EFAULT));
+
+        // The real-time clocks start at the committed epoch, and all others at zero.
+        let clock_id = local.a0.reduce::<AB>();
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            clock_id.clone(),
+            local.is_realtime,
+            local.is_clock_gettime.into(),
+        );
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            clock_id - AB::Expr::from_canonical_u32(5),
+            local.is_realtime_coarse,
+            local.is_clock_gettime.into(),
+        );
+        let is_realtime = local.is_realtime.result + local.is_realtime_coarse.result;
+        let epoch: Word<AB::Expr> = public_values.epoch.map(Into::into);
+        for (base, epoch) in local.clock_base.into_iter().zip(epoch) {
+            builder.when(local.is_clock_gettime).assert_eq(base, is_realtime.clone() * epoch);
+        }
+
+        // The seconds are the base plus the whole seconds of the ticks before the call, which are
+        // the high half of the counter.
+        builder.when(local.is_clock_gettime).assert_word_eq(
+            local.elapsed,
+            Word([
+                local.counter[2].into(),
+                local.counter[3].into(),
+                AB::Expr::ZERO,
+                AB::Expr::ZERO,
+            ]),
+        );
+        AddOperation::<AB::F>::eval(
+            builder,
+            local.clock_base,
+            local.elapsed,
+            local.seconds,
+            local.is_clock_gettime.into(),
+        );
+        builder
+            .when(local.is_clock_gettime)
+            .when(local.is_data)
+            .assert_word_eq(*local.word.value(), local.seconds.value);
+
+        // The nanoseconds on the second row are the ticks of the low half of the counter. They
+        // are less than 2^30, so bounding the top byte makes the word the canonical value.
+        let is_nanos = local.is_clock - local.is_clock_gettime;
+        builder.when(is_nanos.clone()).assert_eq(
+            local.word.value().reduce::<AB>(),
+            (local.counter[0] + local.counter[1] * AB::Expr::from_canonical_u32(256))
+                * AB::Expr::from_canonical_u32(CLOCK_TICK_NANOS),
+        );
+        builder.slice_range_check_u8(
+            &[local.word.value()[3] * AB::Expr::from_canonical_u32(4)],
+            is_nanos,
+        );
+    }
+
+    /// Checks the counter register of getrandom and clock_gettime.
+    ///
+    /// A getrandom call takes one word of its stream per row, and a clock_gettime call advances
+    /// its clock by one tick, so each call adds its number of rows or one to the counter.
+    fn eval_counter<AB: ZKMAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &SysReadCols<AB::Var>,
+        next: &SysReadCols<AB::Var>,
+    ) {
+        let is_counter = local.is_random + local.is_clock;
+        let is_call = local.is_getrandom + local.is_clock_gettime;
+        builder.when(local.is_first).assert_eq(local.is_random, local.is_getrandom);
+        builder.when(local.is_first).assert_eq(local.is_clock, local.is_clock_gettime);
+
+        // The counter is read and updated on the first row of the call.
+        builder.eval_memory_access(
+            local.shard,
+            local.clk,
+            local.is_getrandom * AB::Expr::from_canonical_u32(Register::RNG as u32)
+                + local.is_clock_gettime * AB::Expr::from_canonical_u32(Register::CLOCK as u32),
+            &local.counter_access,
+            is_call.clone(),
+        );
+        builder
+            .when(is_call.clone())
+            .assert_word_eq(local.counter, local.counter_access.prev_value);
+        builder.when(is_call).assert_word_eq(local.counter_end, *local.counter_access.value());
+
+        builder.when(is_counter.clone()).assert_word_eq(local.one, Word::<AB::Expr>::from(1u32));
+        AddOperation::<AB::F>::eval(
+            builder,
+            local.counter,
+            local.one,
+            local.next_counter,
+            is_counter,
+        );
+
+        let mut transition_builder = builder.when_transition();
+        let mut continue_builder = transition_builder.when(local.is_real - local.is_last);
+        continue_builder.assert_eq(local.is_random, next.is_random);
+        continue_builder.assert_eq(local.is_clock, next.is_clock);
+        continue_builder.assert_word_eq(local.counter_end, next.counter_end);
+        continue_builder
+            .when(local.is_random)
+            .assert_word_eq(local.next_counter.value, next.counter);
+        continue_builder.when(local.is_clock).assert_word_eq(local.counter, next.counter);
+
+        builder
+            .when(local.is_random)
+            .when(local.is_last)
+            .assert_word_eq(local.counter_end, local.next_counter.value);
+        builder
+            .when(local.is_clock_gettime)
+            .assert_word_eq(local.counter_end, local.next_counter.value);
+    }
+
+    /// Checks the buffer of a call on its first row.
+    fn eval_buffer<AB: ZKMAirBuilder>(&self, builder: &mut AB, local: &SysReadCols<AB::Var>) {
+        builder.when(local.is_data).assert_word_zero(*local.output.value());
+
+        // Split the low byte of the buffer address into its aligned base and its offset, where
+        // the first word is written.
+        let offset =
+            local.is_offset.iter().enumerate().fold(AB::Expr::ZERO, |acc, (i, &flag)| {
+                acc + flag * AB::Expr::from_canonical_usize(i)
+            });
+        builder.slice_range_check_u8(&local.buffer.0, local.is_first);
+        builder.slice_range_check_u8(&[local.base_quotient], local.is_first);
+        builder.when(local.is_first).assert_eq(
+            local.buffer[0],
+            local.base_quotient * AB::Expr::from_canonical_u32(4) + offset.clone(),
+        );
+        builder.when(local.is_first).assert_eq(local.addr, local.buffer.reduce::<AB>() - offset);
+
+        // The result of read and getrandom is the number of bytes written, at most the count.
+        builder.when(local.is_counted).assert_eq(local.len, local.result.reduce::<AB>());
+        GtColsBytes::<AB::F>::eval(
+            builder,
+            local.result,
+            local.count,
+            local.is_counted,
+            local.is_result_gt_count,
+        );
+        builder.when(local.is_counted).assert_zero(local.is_result_gt_count.result);
+
+        // Failed calls write nothing.
+        builder.when(local.is_first).when_not(local.is_data).assert_zero(local.len);
+    }
+
+    /// Checks that the rows of each call write the `len` bytes from the buffer, one word each.
+    fn eval_rows<AB: ZKMAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &SysReadCols<AB::Var>,
+        next: &SysReadCols<AB::Var>,
+    ) {
+        let num_bytes = local.is_byte.iter().fold(AB::Expr::ZERO, |acc, &x| acc + x);
+
+        // All but the first row of a call start at the beginning of their word.
+        let offset_flags = local.is_offset.iter().fold(AB::Expr::ZERO, |acc, &x| acc + x);
+        builder.assert_eq(offset_flags, local.is_real);
+        builder.when(local.is_real).when_not(local.is_first).assert_one(local.is_offset[0]);
+
+        // The real rows come first, and each call starts on a first row and ends on a last row.
+        builder.when_first_row().assert_eq(local.is_first, local.is_real);
+        builder.when_last_row().when(local.is_real).assert_one(local.is_last);
+        builder.when_transition().when_not(local.is_real).assert_zero(next.is_real);
+        builder.when_transition().when(local.is_last).assert_eq(next.is_first, next.is_real);
+
+        // A call that goes on writes to the end of the word, and the next row writes the next one.
+        builder.when(local.is_real - local.is_last).assert_one(local.is_byte[3]);
+        let mut transition_builder = builder.when_transition();
+        let mut continue_builder = transition_builder.when(local.is_real - local.is_last);
+        continue_builder.assert_one(next.is_real);
+        continue_builder.assert_zero(next.is_first);
+        continue_builder.assert_eq(local.shard, next.shard);
+        continue_builder.assert_eq(local.clk, next.clk);
+        continue_builder.assert_eq(local.len, next.len);
+        continue_builder.assert_eq(local.addr + AB::Expr::from_canonical_u32(4), next.addr);
+        continue_builder.assert_eq(local.written + num_bytes.clone(), next.written);
+
+        // The call writes `len` bytes in total.
+        builder.when(local.is_first).assert_zero(local.written);
+        builder.when(local.is_last).assert_eq(local.written + num_bytes, local.len);
+    }
+
+    /// Checks the word written by a row.
+    fn eval_word<AB: ZKMAirBuilder>(&self, builder: &mut AB, local: &SysReadCols<AB::Var>) {
+        // The written bytes are a contiguous run starting at the offset.
+        for (p, &flag) in local.is_byte.iter().enumerate() {
+            builder.when(flag).assert_one(local.is_word);
+
+            // No byte before the offset is written.
+            let offset_after =
+                local.is_offset[p + 1..].iter().fold(AB::Expr::ZERO, |acc, &x| acc + x);
+            builder.when(flag).assert_zero(offset_after);
+
+            // A byte after the offset is only written if the previous one is.
+            if p > 0 {
+                let offset_before =
+                    local.is_offset[..p].iter().fold(AB::Expr::ZERO, |acc, &x| acc + x);
+                builder.when(flag).when(offset_before).assert_one(local.is_byte[p - 1]);
+            }
+        }
+
+        // A written word has the byte at the offset written, and only a call writing nothing has
+        // a row without a word.
+        let byte_at_offset = local
+            .is_offset
+            .iter()
+            .zip(local.is_byte.iter())
+            .fold(AB::Expr::ZERO, |acc, (&offset, &byte)| acc + offset * byte);
+        builder.assert_eq(byte_at_offset, local.is_word);
+        builder.when(local.is_real).when_not(local.is_word).assert_one(local.is_first);
+        builder.when(local.is_real).when_not(local.is_word).assert_one(local.is_last);
+
+        // Write the word, keeping the bytes that aren't written.
+        let word = &local.word;
+        for k in 0..4 {
+            builder
+                .when(local.is_word)
+                .when_not(local.is_byte[k])
+                .assert_eq(word.value()[k], word.prev_value[k]);
+        }
+        builder.slice_range_check_u8(&word.value().0, local.is_word);
+
+        builder.eval_memory_access(local.shard, local.clk, local.addr, word, local.is_word);
+    }
+}
@@ -0,0 +1,109 @@
+use std::mem::size_of;
+
+use zkm_derive::AlignedBorrow;
+use zkm_stark::Word;
+
+use crate::{
+    memory::MemoryReadWriteCols,
+    operations::{
+        AddOperation, GtColsBytes, IsZeroOperation, IsZeroWordOperation, RandomWordOperation,
+    },
+};
+
+pub const NUM_SYS_READ_COLS: usize = size_of::<SysReadCols<u8>>();
+
+/// A set of columns needed to write the buffer of a read, getrandom or clock_gettime syscall.
+///
+/// Each call takes one row per written word, and one row if it writes nothing. The columns of the
+/// call are set on all its rows, and its flags only on the first one.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SysReadCols<T> {
+    /// Common Inputs.
+    pub shard: T,
+    pub clk: T,
+    pub syscall_id: T,
+    pub a0: Word<T>,
+    pub a1: Word<T>,
+    pub result: Word<T>,
+    /// The access to a2, which holds the count of sys read.
+    pub count_access: MemoryReadWriteCols<T>,
+    /// The access to a3, which is set to the errno.
+    pub output: MemoryReadWriteCols<T>,
+
+    /// Columns for sys read
+    pub is_read: T,
+    /// Whether the read is from stdin.
+    pub is_stdin: IsZeroWordOperation<T>,
+
+    /// Columns for sys getrandom
+    pub is_getrandom: T,
+
+    /// Columns for sys clock_gettime
+    pub is_clock_gettime: T,
+    /// Whether the clock id in a0 is `CLOCK_REALTIME`.
+    pub is_realtime: IsZeroOperation<T>,
+    /// Whether the clock id in a0 is `CLOCK_REALTIME_COARSE`.
+    pub is_realtime_coarse: IsZeroOperation<T>,
+    /// The time of the clock when the counter is zero: the epoch for the real-time clocks, and
+    /// zero for the others.
+    pub clock_base: Word<T>,
+    /// The whole seconds of the ticks in the counter.
+    pub elapsed: Word<T>,
+    /// The seconds of the `timespec`.
+    pub seconds: AddOperation<T>,
+
+    /// The access to the counter register of getrandom or clock_gettime.
+    pub counter_access: MemoryReadWriteCols<T>,
+    /// The counter after the call.
+    pub counter_end: Word<T>,
+
+    /// Whether the call writes to the buffer, rather than failing.
+    pub is_data: T,
+    /// Whether the result is the number of bytes written, for read and getrandom.
+    pub is_counted: T,
+    /// The address of the buffer.
+    pub buffer: Word<T>,
+    /// The maximum number of bytes to write, for read and getrandom.
+    pub count: Word<T>,
+    pub is_result_gt_count: GtColsBytes<T>,
+    /// The low byte of the buffer address divided by four.
+    pub base_quotient: T,
+    /// The number of bytes written by the call.
+    pub len: T,
+
+    /// Columns for the word of the row
+    pub is_first: T,
+    pub is_last: T,
+    /// The address of the word.
+    pub addr: T,
+    /// One-hot flags for the offset of the first byte of the row in its word, which is zero on
+    /// all but the first row.
+    pub is_offset: [T; 4],
+    /// Whether each byte of the word is written.
+    pub is_byte: [T; 4],
+    /// Whether the word is written.
+    pub is_word: T,
+    pub word: MemoryReadWriteCols<T>,
+    /// The number of bytes written by the previous rows of the call.
+    pub written: T,
+
+    /// Columns for the counter of the row
+    pub is_random: T,
+    /// Whether the row belongs to a clock_gettime call, like `is_random` for getrandom.
+    pub is_clock: T,
+    /// The constant one, to increment the counter.
+    pub one: Word<T>,
+    /// The index in the random stream of the word of the row for getrandom, and the number of
+    /// ticks before the call for clock_gettime.
+    pub counter: Word<T>,
+    pub next_counter: AddOperation<T>,
+    /// The seed of the random stream, as its low and high words.
+    pub seed: [Word<T>; 2],
+    /// The constant [`zkm_core_executor::events::RANDOM_WORD_KEY`].
+    pub key: Word<T>,
+    /// The word of the random stream at `counter`.
+    pub random: RandomWordOperation<T>,
+
+    pub is_real: T,
+}
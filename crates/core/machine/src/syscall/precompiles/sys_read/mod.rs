@@ -0,0 +1,141 @@
+mod air;
+mod columns;
+mod trace;
+
+/// The chip of the syscalls that write a buffer: read, getrandom and clock_gettime.
+///
+/// Each call takes one row per written word, like the other precompiles writing to memory, so
+/// the `SysLinux` chip keeps a single narrow row per syscall.
+#[derive(Default)]
+pub struct SysReadChip;
+
+impl SysReadChip {
+    pub const fn new() -> Self {
+        Self {}
+    }
+}
+
+#[cfg(test)]
+pub mod sys_read_tests {
+    use zkm_core_executor::{
+        events::random_word, syscalls::SyscallCode, Executor, Instruction, Opcode, Program,
+        Register, ZKMContext,
+    };
+    use zkm_stark::{CpuProver, ZKMCoreOpts};
+
+    use crate::{
+        io::ZKMStdin,
+        utils::{run_test, run_test_io, setup_logger},
+    };
+
+    pub fn sys_read_program() -> Program {
+        let mut instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 0x11223344, false, true),
+            Instruction::new(Opcode::ADD, 30, 0, 0x100, false, true),
+            Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+        ];
+        for (buf, count) in [(0x102, 7), (0x200, 7), (0x301, 40), (0x400, 4)] {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_READ as u32, false, true),
+                Instruction::new(Opcode::ADD, 4, 0, 0, false, true),
+                Instruction::new(Opcode::ADD, 5, 0, buf, false, true),
+                Instruction::new(Opcode::ADD, 6, 0, count, false, true),
+                Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            ]);
+        }
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_EXT_GROUP as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 0, 0, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_sys_read_stdin() {
+        let mut runtime = Executor::new(sys_read_program(), ZKMCoreOpts::default());
+        runtime.write_vecs(&[(1..=30).collect()]);
+        runtime.run().unwrap();
+
+        assert_eq!(runtime.word(0x100), 0x02013344);
+        assert_eq!(runtime.word(0x104), 0x06050403);
+        assert_eq!(runtime.word(0x108), 0x00000007);
+        assert_eq!(runtime.word(0x200), 0x0b0a0908);
+        assert_eq!(runtime.word(0x204), 0x000e0d0c);
+        // The third read gets the remaining 16 bytes, over five words.
+        assert_eq!(runtime.word(0x300), 0x11100f00);
+        assert_eq!(runtime.word(0x30c), 0x1d1c1b1a);
+        assert_eq!(runtime.word(0x310), 0x0000001e);
+        // The last read is at the end of the input.
+        assert_eq!(runtime.register(Register::V0), 0);
+    }
+
+    #[test]
+    fn prove_sys_read_stdin() {
+        setup_logger();
+        let mut stdin = ZKMStdin::new();
+        stdin.write_slice(&(1..=30).collect::<Vec<u8>>());
+        run_test_io::<CpuProver<_, _>>(sys_read_program(), stdin).unwrap();
+    }
+
+    pub fn sys_env_program() -> Program {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_GETPID as u32, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            Instruction::new(Opcode::ADD, 16, 2, 0, false, false),
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_SCHED_YIELD as u32, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            // clock_gettime(CLOCK_REALTIME, 0x800)
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_CLOCK_GETTIME as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 0, 0, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, 0x800, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            // getrandom(0x902, 20, 0)
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_GETRANDOM as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 0, 0x902, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, 20, false, true),
+            Instruction::new(Opcode::ADD, 6, 0, 0, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+            Instruction::new(Opcode::ADD, 17, 2, 0, false, false),
+            Instruction::new(Opcode::ADD, 2, 0, SyscallCode::SYS_EXT_GROUP as u32, false, true),
+            Instruction::new(Opcode::ADD, 4, 0, 0, false, true),
+            Instruction::new(Opcode::SYSCALL, 2, 4, 5, false, false),
+        ];
+        Program::new(instructions, 0, 0)
+    }
+
+    fn run_sys_env(epoch: u32, seed: u64) -> Executor<'static> {
+        let context = ZKMContext::builder().epoch(epoch).random_seed(seed).build();
+        let mut runtime =
+            Executor::with_context(sys_env_program(), ZKMCoreOpts::default(), context);
+        runtime.run().unwrap();
+        runtime
+    }
+
+    #[test]
+    fn test_sys_env() {
+        let mut runtime = run_sys_env(1_700_000_000, 7);
+        assert_eq!(runtime.register(Register::S0), 1);
+        // The first call is at the epoch, and advances the clock by one tick.
+        assert_eq!(runtime.word(0x800), 1_700_000_000);
+        assert_eq!(runtime.word(0x804), 0);
+        assert_eq!(runtime.register(Register::CLOCK), 1);
+        // The buffer starts in the middle of the first word of the stream, and takes six words.
+        assert_eq!(runtime.register(Register::S1), 20);
+        assert_eq!(runtime.word(0x900) >> 16, random_word(7, 0) >> 16);
+        assert_eq!(runtime.word(0x914), random_word(7, 5) & 0xffff);
+        assert_eq!(runtime.register(Register::RNG), 6);
+
+        let random = [runtime.word(0x900), runtime.word(0x904)];
+        let mut again = run_sys_env(1_700_000_000, 7);
+        assert_eq!([again.word(0x900), again.word(0x904)], random);
+        let mut other = run_sys_env(1_700_000_000, 8);
+        assert_ne!([other.word(0x900), other.word(0x904)], random);
+    }
+
+    #[test]
+    fn prove_sys_env() {
+        setup_logger();
+        run_test::<CpuProver<_, _>>(sys_env_program()).unwrap();
+    }
+}
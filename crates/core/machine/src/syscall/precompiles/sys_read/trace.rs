@@ -0,0 +1,251 @@
+use std::borrow::BorrowMut;
+
+use hashbrown::HashMap;
+use itertools::Itertools;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::{ParallelIterator, ParallelSlice};
+use zkm_core_executor::{
+    events::{ByteLookupEvent, ByteRecord, LinuxEvent, PrecompileEvent, RANDOM_WORD_KEY},
+    syscalls::SyscallCode,
+    ExecutionRecord, LinuxEnv, Program,
+};
+use zkm_stark::{air::MachineAir, Word};
+
+use super::{
+    columns::{SysReadCols, NUM_SYS_READ_COLS},
+    SysReadChip,
+};
+use crate::utils::pad_rows_fixed;
+
+impl<F: PrimeField32> MachineAir<F> for SysReadChip {
+    type Record = ExecutionRecord;
+
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "SysRead".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        _: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let env = linux_env(input);
+        let mut wrapped_rows = Some(Vec::new());
+        for (_, event) in input.get_precompile_events(SyscallCode::SYS_READ) {
+            let event = if let PrecompileEvent::Linux(event) = event {
+                event
+            } else {
+                unreachable!();
+            };
+            self.event_to_rows(event, &env, &mut wrapped_rows, &mut Vec::new());
+        }
+        let mut rows = wrapped_rows.unwrap();
+
+        pad_rows_fixed(
+            &mut rows,
+            || [F::ZERO; NUM_SYS_READ_COLS],
+            input.fixed_log2_rows::<F, _>(self),
+        );
+
+        // Convert the trace to a row major matrix.
+        RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_SYS_READ_COLS)
+    }
+
+    fn generate_dependencies(&self, input: &Self::Record, output: &mut Self::Record) {
+        let env = linux_env(input);
+        let events = input.get_precompile_events(SyscallCode::SYS_READ);
+        let chunk_size = std::cmp::max(events.len() / num_cpus::get(), 1);
+
+        let blu_batches = events
+            .par_chunks(chunk_size)
+            .map(|events| {
+                let mut blu: HashMap<ByteLookupEvent, usize> = HashMap::new();
+                events.iter().for_each(|(_, event)| {
+                    let event = if let PrecompileEvent::Linux(event) = event {
+                        event
+                    } else {
+                        unreachable!()
+                    };
+                    self.event_to_rows::<F>(event, &env, &mut None, &mut blu);
+                });
+                blu
+            })
+            .collect::<Vec<_>>();
+
+        output.add_byte_lookup_events_from_maps(blu_batches.iter().collect_vec());
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        if let Some(shape) = shard.shape.as_ref() {
+            shape.included::<F, _>(self)
+        } else {
+            !shard.get_precompile_events(SyscallCode::SYS_READ).is_empty()
+        }
+    }
+}
+
+impl SysReadChip {
+    /// Populates the rows of a call, one per word written to the buffer, or a single one if it
+    /// writes nothing.
+    pub fn event_to_rows<F: PrimeField32>(
+        &self,
+        event: &LinuxEvent,
+        env: &LinuxEnv,
+        rows: &mut Option<Vec<[F; NUM_SYS_READ_COLS]>>,
+        blu: &mut impl ByteRecord,
+    ) {
+        let failed = event.v0 == 0xffffffff;
+        let (buffer, count, len) = match event.syscall_code {
+            4003 => (event.a1, event.read_records[0].value, event.v0),
+            4353 => (event.a0, event.a1, event.v0),
+            4263 => (event.a1, 0, 8),
+            _ => unreachable!(),
+        };
+        let len = if failed { 0 } else { len };
+        let offset = buffer % 4;
+        let words = &event.write_records[event.buffer_start()..];
+
+        let mut written = 0;
+        for i in 0..event.num_rows() {
+            let mut row = [F::ZERO; NUM_SYS_READ_COLS];
+            let cols: &mut SysReadCols<F> = row.as_mut_slice().borrow_mut();
+
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.len = F::from_canonical_u32(len);
+            cols.is_real = F::ONE;
+            cols.is_first = F::from_bool(i == 0);
+            cols.is_last = F::from_bool(i == event.num_rows() - 1);
+            cols.written = F::from_canonical_u32(written);
+
+            let row_offset = if i == 0 { offset } else { 0 };
+            cols.is_offset[row_offset as usize] = F::ONE;
+            cols.addr = F::from_canonical_u32((buffer - offset).wrapping_add(4 * i as u32));
+            if let Some(record) = words.get(i) {
+                let end = (offset + len - 4 * i as u32).min(4);
+                for k in row_offset..end {
+                    cols.is_byte[k as usize] = F::ONE;
+                }
+                written += end - row_offset;
+                cols.is_word = F::ONE;
+                cols.word.populate_write(*record, blu);
+                blu.add_u8_range_checks(&record.value.to_le_bytes());
+            }
+
+            if event.syscall_code != 4003 {
+                self.populate_counter(event, env, i, cols, blu);
+            }
+
+            if i == 0 {
+                self.populate_call(event, env, buffer, count, cols, blu);
+            }
+
+            if let Some(rows) = rows.as_mut() {
+                rows.push(row);
+            }
+        }
+    }
+
+    /// Populates the columns of the call, which are only set on its first row.
+    fn populate_call<F: PrimeField32>(
+        &self,
+        event: &LinuxEvent,
+        env: &LinuxEnv,
+        buffer: u32,
+        count: u32,
+        cols: &mut SysReadCols<F>,
+        blu: &mut impl ByteRecord,
+    ) {
+        let failed = event.v0 == 0xffffffff;
+        cols.a0 = event.a0.into();
+        cols.a1 = event.a1.into();
+        cols.syscall_id = F::from_canonical_u32(event.syscall_code);
+        cols.result = event.v0.into();
+        cols.output.populate_write(event.write_records[0], blu);
+        cols.is_data = F::from_bool(!failed);
+
+        cols.buffer = buffer.into();
+        cols.count = count.into();
+        let quotient = (buffer & 0xFF) / 4;
+        cols.base_quotient = F::from_canonical_u32(quotient);
+        blu.add_u8_range_checks(&buffer.to_le_bytes());
+        blu.add_u8_range_checks(&[quotient as u8]);
+
+        match event.syscall_code {
+            4003 => {
+                cols.is_read = F::ONE;
+                cols.count_access.populate_read(event.read_records[0], blu);
+                cols.is_stdin.populate(event.a0);
+            }
+            4353 => {
+                cols.is_getrandom = F::ONE;
+                cols.counter_access.populate_write(event.write_records[1], blu);
+            }
+            4263 => {
+                cols.is_clock_gettime = F::ONE;
+                cols.counter_access.populate_write(event.write_records[1], blu);
+                let clock_id = F::from_wrapped_u32(event.a0);
+                let is_realtime = cols.is_realtime.populate_from_field_element(clock_id);
+                let is_realtime_coarse = cols
+                    .is_realtime_coarse
+                    .populate_from_field_element(clock_id - F::from_canonical_u32(5));
+                let base = if is_realtime + is_realtime_coarse == 1 { env.epoch } else { 0 };
+                let elapsed = event.write_records[1].prev_value >> 16;
+                cols.clock_base = base.into();
+                cols.elapsed = elapsed.into();
+                cols.seconds.populate(blu, base, elapsed);
+            }
+            _ => unreachable!(),
+        }
+
+        if !failed && event.syscall_code != 4263 {
+            cols.is_counted = F::ONE;
+            cols.is_result_gt_count.populate(event.v0, count, blu);
+        }
+    }
+
+    /// Populates the counter of the `i`-th row of a getrandom or clock_gettime call, and the
+    /// random word of a getrandom row.
+    fn populate_counter<F: PrimeField32>(
+        &self,
+        event: &LinuxEvent,
+        env: &LinuxEnv,
+        i: usize,
+        cols: &mut SysReadCols<F>,
+        blu: &mut impl ByteRecord,
+    ) {
+        let record = event.write_records[1];
+        let is_random = event.syscall_code == 4353;
+        let counter =
+            if is_random { record.prev_value.wrapping_add(i as u32) } else { record.prev_value };
+
+        cols.is_random = F::from_bool(is_random);
+        cols.is_clock = F::from_bool(!is_random);
+        cols.one = 1.into();
+        cols.counter = counter.into();
+        cols.next_counter.populate(blu, counter, 1);
+        cols.counter_end = record.value.into();
+
+        if is_random {
+            cols.seed = [env.random_seed as u32, (env.random_seed >> 32) as u32].map(Word::from);
+            cols.key = RANDOM_WORD_KEY.into();
+            cols.random.populate(blu, env.random_seed, counter);
+        } else if i == 1 {
+            // The nanoseconds are less than 2^30, so the top byte is less than 64.
+            let nsec = event.write_records[event.buffer_start() + 1].value;
+            blu.add_u8_range_checks(&[(nsec >> 24) as u8 * 4]);
+        }
+    }
+}
+
+/// The environment committed in the public values of the shard.
+fn linux_env(input: &ExecutionRecord) -> LinuxEnv {
+    let [lo, hi] = input.public_values.random_seed;
+    LinuxEnv {
+        epoch: input.public_values.epoch,
+        random_seed: u64::from(lo) | (u64::from(hi) << 32),
+    }
+}
@@ -7,10 +7,10 @@ use std::{
 };
 use web_time::Instant;
 
-use crate::mips::MipsAir;
+use crate::mips::{MipsAir, MipsExtension, NoExtension};
 use p3_maybe_rayon::prelude::*;
 use p3_uni_stark::SymbolicAirBuilder;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use size::Size;
 use std::thread::ScopedJoinHandle;
 use thiserror::Error;
@@ -24,11 +24,12 @@ use p3_koala_bear::KoalaBear;
 use crate::shape::CoreShapeConfig;
 use crate::{
     io::ZKMStdin,
-    utils::{chunk_vec, concurrency::TurnBasedSync},
+    utils::{chunk_vec, concurrency::TurnBasedSync, ProvingWorkDir},
 };
 use zkm_core_executor::{
     events::{format_table_line, sorted_table_lines},
     subproof::NoOpSubproofVerifier,
+    syscalls::{Syscall, SyscallCode},
     ExecutionError, ExecutionRecord, ExecutionReport, ExecutionState, Executor, Program,
     ZKMContext,
 };
@@ -37,9 +38,9 @@ use zkm_primitives::io::ZKMPublicValues;
 use zkm_stark::{
     air::{MachineAir, PublicValues},
     Com, CpuProver, DebugConstraintBuilder, LookupBuilder, MachineProof, MachineProver,
-    MachineRecord, OpeningProof, PcsProverData, ProverConstraintFolder, StarkGenericConfig,
-    StarkMachine, StarkProvingKey, StarkVerifyingKey, UniConfig, Val, VerifierConstraintFolder,
-    ZKMCoreOpts,
+    MachineRecord, OpeningProof, PcsProverData, ProverConstraintFolder, ShardProof,
+    StarkGenericConfig, StarkMachine, StarkProvingKey, StarkVerifyingKey, UniConfig, Val,
+    VerifierConstraintFolder, ZKMCoreOpts,
 };
 
 #[derive(Error, Debug)]
@@ -111,7 +112,7 @@ where
     let machine = MipsAir::machine(config);
     let prover = P::new(machine);
     let (pk, _) = prover.setup(&program);
-    prove_with_context::<SC, _>(
+    prove_with_context::<SC, _, _>(
         &prover,
         &pk,
         program,
@@ -122,14 +123,40 @@ where
     )
 }
 
-pub fn prove_with_context<SC: StarkGenericConfig, P: MachineProver<SC, MipsAir<SC::Val>>>(
+pub fn prove_with_context<SC: StarkGenericConfig, E, P: MachineProver<SC, MipsAir<SC::Val, E>>>(
     prover: &P,
     pk: &P::DeviceProvingKey,
     program: Program,
     stdin: &ZKMStdin,
     opts: ZKMCoreOpts,
     context: ZKMContext,
-    shape_config: Option<&CoreShapeConfig<SC::Val>>,
+    shape_config: Option<&CoreShapeConfig<SC::Val, E>>,
+) -> Result<(MachineProof<SC>, Vec<u8>, u64), ZKMCoreProverError>
+where
+    SC::Val: PrimeField32,
+    SC::Challenger: 'static + Clone + Send,
+    OpeningProof<SC>: Send,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    E: MipsExtension<SC::Val>,
+    // Required to debug the constraints with the `debug` feature.
+    MipsAir<SC::Val, E>: for<'a> Air<DebugConstraintBuilder<'a, SC::Val, SC::Challenge>>,
+{
+    prove_with_work_dir::<SC, E, P>(prover, pk, program, stdin, opts, context, shape_config, None)
+}
+
+/// Like [`prove_with_context`], but persists checkpoints and shard proofs to `work_dir` (if any)
+/// and skips the shards a previous, interrupted run already proved.
+#[allow(clippy::too_many_arguments)]
+pub fn prove_with_work_dir<SC: StarkGenericConfig, E, P: MachineProver<SC, MipsAir<SC::Val, E>>>(
+    prover: &P,
+    pk: &P::DeviceProvingKey,
+    program: Program,
+    stdin: &ZKMStdin,
+    opts: ZKMCoreOpts,
+    context: ZKMContext,
+    shape_config: Option<&CoreShapeConfig<SC::Val, E>>,
+    work_dir: Option<&ProvingWorkDir>,
 ) -> Result<(MachineProof<SC>, Vec<u8>, u64), ZKMCoreProverError>
 where
     SC::Val: PrimeField32,
@@ -137,13 +164,116 @@ where
     OpeningProof<SC>: Send,
     Com<SC>: Send + Sync,
     PcsProverData<SC>: Send + Sync,
+    E: MipsExtension<SC::Val>,
+    // Required to debug the constraints with the `debug` feature.
+    MipsAir<SC::Val, E>: for<'a> Air<DebugConstraintBuilder<'a, SC::Val, SC::Challenge>>,
+{
+    let (proof, public_values_stream, cycles, _) = prove_from_checkpoint::<SC, E, P>(
+        prover,
+        pk,
+        program,
+        stdin,
+        opts,
+        context,
+        shape_config,
+        work_dir,
+        None,
+    )?;
+    Ok((proof, public_values_stream, cycles))
+}
+
+/// Where a step of an incremental computation ended, see [`prove_step_with_context`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepCheckpoint {
+    /// The execution state the next step resumes from.
+    pub state: ExecutionState,
+    /// The public values of the last shard of the step, which the first shard of the next step
+    /// is chained to.
+    pub public_values: PublicValues<u32, u32>,
+}
+
+/// Like [`prove_with_context`], but proves one step of an incremental computation.
+///
+/// A step runs until the program ends it with the `END_STEP` syscall or halts. The first step
+/// starts at the entrypoint, every later one resumes from the [`StepCheckpoint`] of the step
+/// before it, with `stdin` appended to the input. The shards of a step continue the shards of the
+/// steps before it, so the shard proofs of all the steps together form the proof of one
+/// execution. Returns the checkpoint of the step, or `None` if the program halted.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn prove_step_with_context<
+    SC: StarkGenericConfig,
+    E,
+    P: MachineProver<SC, MipsAir<SC::Val, E>>,
+>(
+    prover: &P,
+    pk: &P::DeviceProvingKey,
+    program: Program,
+    stdin: &ZKMStdin,
+    opts: ZKMCoreOpts,
+    context: ZKMContext,
+    shape_config: Option<&CoreShapeConfig<SC::Val, E>>,
+    resume: Option<StepCheckpoint>,
+) -> Result<(MachineProof<SC>, Vec<u8>, u64, Option<StepCheckpoint>), ZKMCoreProverError>
+where
+    SC::Val: PrimeField32,
+    SC::Challenger: 'static + Clone + Send,
+    OpeningProof<SC>: Send,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    E: MipsExtension<SC::Val>,
+    // Required to debug the constraints with the `debug` feature.
+    MipsAir<SC::Val, E>: for<'a> Air<DebugConstraintBuilder<'a, SC::Val, SC::Challenge>>,
 {
+    prove_from_checkpoint::<SC, E, P>(
+        prover,
+        pk,
+        program,
+        stdin,
+        opts,
+        context,
+        shape_config,
+        None,
+        resume,
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn prove_from_checkpoint<SC: StarkGenericConfig, E, P: MachineProver<SC, MipsAir<SC::Val, E>>>(
+    prover: &P,
+    pk: &P::DeviceProvingKey,
+    program: Program,
+    stdin: &ZKMStdin,
+    opts: ZKMCoreOpts,
+    context: ZKMContext,
+    shape_config: Option<&CoreShapeConfig<SC::Val, E>>,
+    work_dir: Option<&ProvingWorkDir>,
+    resume: Option<StepCheckpoint>,
+) -> Result<(MachineProof<SC>, Vec<u8>, u64, Option<StepCheckpoint>), ZKMCoreProverError>
+where
+    SC::Val: PrimeField32,
+    SC::Challenger: 'static + Clone + Send,
+    OpeningProof<SC>: Send,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    E: MipsExtension<SC::Val>,
+    // Required to debug the constraints with the `debug` feature.
+    MipsAir<SC::Val, E>: for<'a> Air<DebugConstraintBuilder<'a, SC::Val, SC::Challenge>>,
+{
+    if let Some(work_dir) = work_dir {
+        work_dir.check_shard_size(opts.shard_size).map_err(ZKMCoreProverError::IoError)?;
+    }
+
     // Setup the runtime.
+    let precompiles = context.precompiles.clone();
     let mut runtime = Executor::with_context(program.clone(), opts, context);
     runtime.maximal_shapes = shape_config.map(|config| {
         config.maximal_core_shapes(opts.shard_size.ilog2() as usize).into_iter().collect()
     });
+    if let Some(resume) = &resume {
+        runtime.resume_step(resume.state.clone());
+    }
     runtime.write_vecs(&stdin.buffer);
+    runtime.write_files(&stdin.files);
     for proof in stdin.proofs.iter() {
         let (proof, vk) = proof.clone();
         runtime.write_proof(proof, vk);
@@ -166,6 +296,19 @@ where
             s.spawn(move || {
                 let _span = checkpoint_generator_span.enter();
                 tracing::debug_span!("checkpoint generator").in_scope(|| {
+                    // If a previous run already finished executing, replay its checkpoints.
+                    if let Some(execution) = work_dir.and_then(|dir| dir.completed_execution()) {
+                        for index in 0..execution.num_checkpoints {
+                            let checkpoint_file = work_dir
+                                .unwrap()
+                                .open_checkpoint(index)
+                                .map_err(ZKMCoreProverError::IoError)?;
+                            let done = index + 1 == execution.num_checkpoints;
+                            checkpoints_tx.send((index, checkpoint_file, done)).unwrap();
+                        }
+                        return Ok((execution.public_values_stream, None));
+                    }
+
                     let mut index = 0;
                     loop {
                         // Enter the span.
@@ -177,9 +320,12 @@ where
                             .execute_state(false)
                             .map_err(ZKMCoreProverError::ExecutionError)?;
 
-                        // Save the checkpoint to a temp file.
-                        let mut checkpoint_file =
-                            tempfile::tempfile().map_err(ZKMCoreProverError::IoError)?;
+                        // Save the checkpoint to the work directory or a temp file.
+                        let mut checkpoint_file = match work_dir {
+                            Some(work_dir) => work_dir.create_checkpoint(index),
+                            None => tempfile::tempfile(),
+                        }
+                        .map_err(ZKMCoreProverError::IoError)?;
                         checkpoint
                             .save(&mut checkpoint_file)
                             .map_err(ZKMCoreProverError::IoError)?;
@@ -189,7 +335,17 @@ where
 
                         // If we've reached the final checkpoint, break out of the loop.
                         if done {
-                            break Ok(runtime.state.public_values_stream);
+                            if let Some(work_dir) = work_dir {
+                                work_dir
+                                    .complete_execution(
+                                        index + 1,
+                                        runtime.state.public_values_stream.clone(),
+                                    )
+                                    .map_err(ZKMCoreProverError::IoError)?;
+                            }
+                            // A program that ended a step is resumed from its final state.
+                            let paused = runtime.state.paused.then(|| runtime.state.clone());
+                            break Ok((runtime.state.public_values_stream, paused));
                         }
 
                         // Update the index.
@@ -213,7 +369,10 @@ where
         let p2_records_and_traces_tx = Arc::new(Mutex::new(p2_records_and_traces_tx));
 
         let report_aggregate = Arc::new(Mutex::new(ExecutionReport::default()));
-        let state = Arc::new(Mutex::new(PublicValues::<u32, u32>::default().reset()));
+        let state = Arc::new(Mutex::new(match &resume {
+            Some(resume) => resume.public_values,
+            None => PublicValues::<u32, u32>::default().reset(),
+        }));
         let deferred = Arc::new(Mutex::new(ExecutionRecord::new(program.clone().into())));
         let mut p2_record_and_trace_gen_handles = Vec::new();
         for _ in 0..opts.trace_gen_workers {
@@ -226,6 +385,7 @@ where
             let state = Arc::clone(&state);
             let deferred = Arc::clone(&deferred);
             let program = program.clone();
+            let precompiles = precompiles.clone();
 
             let span = tracing::Span::current().clone();
 
@@ -246,11 +406,12 @@ where
                                     .expect("failed to deserialize state");
                             let (mut records, report) = tracing::debug_span!("trace checkpoint")
                                 .in_scope(|| {
-                                    trace_checkpoint::<SC>(
+                                    trace_checkpoint_with_precompiles::<SC, E>(
                                         program.clone(),
                                         execution_state,
                                         opts,
                                         shape_config,
+                                        &precompiles,
                                     )
                                 });
                             log::debug!("generated {} records", records.len());
@@ -272,6 +433,8 @@ where
                                     record.public_values.committed_value_digest;
                                 state.deferred_proofs_digest =
                                     record.public_values.deferred_proofs_digest;
+                                state.random_seed = record.public_values.random_seed;
+                                state.epoch = record.public_values.epoch;
                                 record.public_values = *state;
                             }
 
@@ -327,7 +490,15 @@ where
                             tracing::debug_span!("generate main traces", index).in_scope(|| {
                                 main_traces = records
                                     .par_iter()
-                                    .map(|record| prover.generate_traces(record))
+                                    .map(|record| {
+                                        // Shards proven by a previous run don't need traces.
+                                        let shard = record.public_values.shard;
+                                        if work_dir.is_some_and(|dir| dir.has_shard_proof(shard)) {
+                                            Vec::new()
+                                        } else {
+                                            prover.generate_traces(record)
+                                        }
+                                    })
                                     .collect::<Vec<_>>();
                             });
 
@@ -374,6 +545,17 @@ where
                                 |(record, main_traces)| {
                                     let _span = span.enter();
 
+                                    // Reuse the proof written by a previous run, if any.
+                                    let shard = record.public_values.shard;
+                                    if let Some(work_dir) = work_dir {
+                                        if let Some(proof) = work_dir
+                                            .load_shard_proof::<ShardProof<SC>>(shard)
+                                            .map_err(ZKMCoreProverError::IoError)?
+                                        {
+                                            return Ok(proof);
+                                        }
+                                    }
+
                                     let main_data = prover.commit(&record, main_traces);
 
                                     let opening_span = tracing::debug_span!("opening").entered();
@@ -400,7 +582,13 @@ where
                                         drop(record);
                                     });
 
-                                    proof
+                                    if let Some(work_dir) = work_dir {
+                                        work_dir
+                                            .save_shard_proof(shard, &proof)
+                                            .map_err(ZKMCoreProverError::IoError)?;
+                                    }
+
+                                    Ok(proof)
                                 },
                             ),
                         );
@@ -411,13 +599,17 @@ where
         });
 
         // Wait until the checkpoint generator handle has fully finished.
-        let public_values_stream = checkpoint_generator_handle.join().unwrap().unwrap();
+        let (public_values_stream, paused) = checkpoint_generator_handle.join().unwrap()?;
 
         // Wait until the records and traces have been fully generated for phase 2.
         p2_record_and_trace_gen_handles.into_iter().for_each(|handle| handle.join().unwrap());
 
         // Wait until the phase 2 prover has finished.
-        let shard_proofs = p2_prover_handle.join().unwrap();
+        let shard_proofs = p2_prover_handle
+            .join()
+            .unwrap()
+            .into_iter()
+            .collect::<Result<Vec<_>, ZKMCoreProverError>>()?;
 
         // Log some of the `ExecutionReport` information.
         let report_aggregate = report_aggregate.lock().unwrap();
@@ -471,7 +663,12 @@ where
             prover.machine().debug_constraints(&pk_host, all_records, &mut challenger);
         }
 
-        Ok((proof, public_values_stream, cycles))
+        let step = paused.map(|execution_state| StepCheckpoint {
+            state: execution_state,
+            public_values: *state.lock().unwrap(),
+        });
+
+        Ok((proof, public_values_stream, cycles, step))
     })
 }
 
@@ -485,6 +682,7 @@ pub fn run_test_io<P: MachineProver<KoalaBearPoseidon2, MipsAir<KoalaBear>>>(
     let runtime = tracing::debug_span!("runtime.run(...)").in_scope(|| {
         let mut runtime = Executor::new(program, ZKMCoreOpts::default());
         runtime.write_vecs(&inputs.buffer);
+        runtime.write_files(&inputs.files);
         runtime.run().unwrap();
         runtime
     });
@@ -497,7 +695,7 @@ pub fn run_test_io<P: MachineProver<KoalaBearPoseidon2, MipsAir<KoalaBear>>>(
 pub fn run_test<P: MachineProver<KoalaBearPoseidon2, MipsAir<KoalaBear>>>(
     mut program: Program,
 ) -> Result<MachineProof<KoalaBearPoseidon2>, MachineVerificationError<KoalaBearPoseidon2>> {
-    let shape_config = CoreShapeConfig::default();
+    let shape_config = CoreShapeConfig::<KoalaBear>::default();
     shape_config.fix_preprocessed_shape(&mut program).unwrap();
     let runtime = tracing::debug_span!("runtime.run(...)").in_scope(|| {
         let mut runtime = Executor::new(program, ZKMCoreOpts::default());
@@ -611,12 +809,30 @@ pub fn trace_checkpoint<SC: StarkGenericConfig>(
     opts: ZKMCoreOpts,
     shape_config: Option<&CoreShapeConfig<SC::Val>>,
 ) -> (Vec<ExecutionRecord>, ExecutionReport)
+where
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+{
+    trace_checkpoint_with_precompiles::<SC, NoExtension>(program, state, opts, shape_config, &[])
+}
+
+/// Like [`trace_checkpoint`], but for a program using the given precompiles from outside of
+/// Ziren.
+pub fn trace_checkpoint_with_precompiles<SC: StarkGenericConfig, E: MipsExtension<SC::Val>>(
+    program: Program,
+    state: ExecutionState,
+    opts: ZKMCoreOpts,
+    shape_config: Option<&CoreShapeConfig<SC::Val, E>>,
+    precompiles: &[(SyscallCode, Arc<dyn Syscall>)],
+) -> (Vec<ExecutionRecord>, ExecutionReport)
 where
     <SC as StarkGenericConfig>::Val: PrimeField32,
 {
     let noop = NoOpSubproofVerifier;
 
     let mut runtime = Executor::recover(program, state, opts);
+    for (code, syscall) in precompiles {
+        runtime.register_precompile(*code, syscall.clone());
+    }
     runtime.maximal_shapes = shape_config.map(|config| {
         config.maximal_core_shapes(opts.shard_size.ilog2() as usize).into_iter().collect()
     });
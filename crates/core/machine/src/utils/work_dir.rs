@@ -0,0 +1,287 @@
+use std::{
+    collections::BTreeSet,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{io::ZKMStdin, ZKM_CIRCUIT_VERSION};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const CHECKPOINTS_DIR: &str = "checkpoints";
+const SHARDS_DIR: &str = "shards";
+const REDUCE_DIR: &str = "reduce";
+
+/// The on-disk record of which proving artifacts in a [`ProvingWorkDir`] are complete.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkDirManifest {
+    /// The circuit version the artifacts were generated with.
+    pub version: String,
+    /// A digest of the program and inputs being proven.
+    pub fingerprint: String,
+    /// The shard size the checkpoints and shard proofs were generated with.
+    pub shard_size: Option<usize>,
+    /// Set once execution has finished and every checkpoint has been written.
+    pub execution: Option<CompletedExecution>,
+    /// The shard numbers whose core proofs have been written.
+    pub shards: BTreeSet<u32>,
+    /// The indices of the compress tree nodes whose reduce proofs have been written.
+    pub reduce_nodes: BTreeSet<usize>,
+}
+
+/// The result of a finished execution, so it can be replayed from disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedExecution {
+    /// The number of checkpoints written.
+    pub num_checkpoints: usize,
+    /// The public values stream produced by the program.
+    pub public_values_stream: Vec<u8>,
+}
+
+/// A directory that persists checkpoints, shard proofs and compress tree proofs so that an
+/// interrupted proof can be resumed.
+///
+/// Every artifact is written to a temporary file and renamed into place before the manifest is
+/// updated, so a crash never leaves the manifest pointing at a partially written file.
+pub struct ProvingWorkDir {
+    root: PathBuf,
+    manifest: Mutex<WorkDirManifest>,
+}
+
+impl ProvingWorkDir {
+    /// Opens (or creates) a work directory for proving `elf` with `stdin`.
+    ///
+    /// Fails if the directory already holds artifacts for a different program, different inputs
+    /// or a different circuit version.
+    pub fn open(path: impl AsRef<Path>, elf: &[u8], stdin: &ZKMStdin) -> io::Result<Self> {
+        let root = path.as_ref().to_path_buf();
+        for dir in [CHECKPOINTS_DIR, SHARDS_DIR, REDUCE_DIR] {
+            fs::create_dir_all(root.join(dir))?;
+        }
+
+        let fingerprint = Self::fingerprint(elf, stdin)?;
+        let manifest_path = root.join(MANIFEST_FILE);
+        let manifest = if manifest_path.exists() {
+            let manifest: WorkDirManifest =
+                serde_json::from_reader(BufReader::new(File::open(&manifest_path)?))
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            if manifest.version != ZKM_CIRCUIT_VERSION {
+                return Err(invalid_input(format!(
+                    "work directory was created with circuit version {}, expected {}",
+                    manifest.version, ZKM_CIRCUIT_VERSION
+                )));
+            }
+            if manifest.fingerprint != fingerprint {
+                return Err(invalid_input(
+                    "work directory belongs to a different program or input".to_string(),
+                ));
+            }
+            manifest
+        } else {
+            WorkDirManifest {
+                version: ZKM_CIRCUIT_VERSION.to_string(),
+                fingerprint,
+                ..Default::default()
+            }
+        };
+
+        let work_dir = Self { root, manifest: Mutex::new(manifest) };
+        work_dir.write_manifest(&work_dir.manifest.lock().unwrap())?;
+        Ok(work_dir)
+    }
+
+    /// The root path of the work directory.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    /// A snapshot of the current manifest.
+    #[must_use]
+    pub fn manifest(&self) -> WorkDirManifest {
+        self.manifest.lock().unwrap().clone()
+    }
+
+    /// Records the shard size used for proving, failing if previous artifacts used another one.
+    pub fn check_shard_size(&self, shard_size: usize) -> io::Result<()> {
+        let mut manifest = self.manifest.lock().unwrap();
+        match manifest.shard_size {
+            Some(existing) if existing != shard_size => Err(invalid_input(format!(
+                "work directory was created with shard size {existing}, expected {shard_size}"
+            ))),
+            Some(_) => Ok(()),
+            None => {
+                manifest.shard_size = Some(shard_size);
+                self.write_manifest(&manifest)
+            }
+        }
+    }
+
+    /// Creates (or truncates) the file for the checkpoint at `index`.
+    pub fn create_checkpoint(&self, index: usize) -> io::Result<File> {
+        OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(self.checkpoint_path(index))
+    }
+
+    /// Opens the previously written checkpoint at `index`.
+    pub fn open_checkpoint(&self, index: usize) -> io::Result<File> {
+        File::open(self.checkpoint_path(index))
+    }
+
+    /// Returns the finished execution, if every checkpoint has already been written.
+    #[must_use]
+    pub fn completed_execution(&self) -> Option<CompletedExecution> {
+        self.manifest.lock().unwrap().execution.clone()
+    }
+
+    /// Marks execution as finished after `num_checkpoints` checkpoints have been written.
+    pub fn complete_execution(
+        &self,
+        num_checkpoints: usize,
+        public_values_stream: Vec<u8>,
+    ) -> io::Result<()> {
+        let mut manifest = self.manifest.lock().unwrap();
+        manifest.execution = Some(CompletedExecution { num_checkpoints, public_values_stream });
+        self.write_manifest(&manifest)
+    }
+
+    /// Whether the core proof for `shard` has already been written.
+    #[must_use]
+    pub fn has_shard_proof(&self, shard: u32) -> bool {
+        self.manifest.lock().unwrap().shards.contains(&shard)
+    }
+
+    /// Loads the core proof for `shard`, if it has been written.
+    pub fn load_shard_proof<T: DeserializeOwned>(&self, shard: u32) -> io::Result<Option<T>> {
+        if !self.has_shard_proof(shard) {
+            return Ok(None);
+        }
+        self.read_artifact(&self.shard_path(shard)).map(Some)
+    }
+
+    /// Writes the core proof for `shard` and records it in the manifest.
+    pub fn save_shard_proof<T: Serialize>(&self, shard: u32, proof: &T) -> io::Result<()> {
+        self.write_artifact(&self.shard_path(shard), proof)?;
+        let mut manifest = self.manifest.lock().unwrap();
+        manifest.shards.insert(shard);
+        self.write_manifest(&manifest)
+    }
+
+    /// Whether the reduce proof for the compress tree node at `index` has already been written.
+    #[must_use]
+    pub fn has_reduce_proof(&self, index: usize) -> bool {
+        self.manifest.lock().unwrap().reduce_nodes.contains(&index)
+    }
+
+    /// Loads the reduce proof for the compress tree node at `index`, if it has been written.
+    pub fn load_reduce_proof<T: DeserializeOwned>(&self, index: usize) -> io::Result<Option<T>> {
+        if !self.has_reduce_proof(index) {
+            return Ok(None);
+        }
+        self.read_artifact(&self.reduce_path(index)).map(Some)
+    }
+
+    /// Writes the reduce proof for the compress tree node at `index` and records it in the
+    /// manifest.
+    pub fn save_reduce_proof<T: Serialize>(&self, index: usize, proof: &T) -> io::Result<()> {
+        self.write_artifact(&self.reduce_path(index), proof)?;
+        let mut manifest = self.manifest.lock().unwrap();
+        manifest.reduce_nodes.insert(index);
+        self.write_manifest(&manifest)
+    }
+
+    fn fingerprint(elf: &[u8], stdin: &ZKMStdin) -> io::Result<String> {
+        let stdin = bincode::serialize(stdin).map_err(|e| io::Error::other(e))?;
+        let mut hasher = Keccak::v256();
+        hasher.update(&(elf.len() as u64).to_le_bytes());
+        hasher.update(elf);
+        hasher.update(&stdin);
+        let mut digest = [0u8; 32];
+        hasher.finalize(&mut digest);
+        Ok(hex::encode(digest))
+    }
+
+    fn checkpoint_path(&self, index: usize) -> PathBuf {
+        self.root.join(CHECKPOINTS_DIR).join(format!("checkpoint-{index}.bin"))
+    }
+
+    fn shard_path(&self, shard: u32) -> PathBuf {
+        self.root.join(SHARDS_DIR).join(format!("shard-{shard}.bin"))
+    }
+
+    fn reduce_path(&self, index: usize) -> PathBuf {
+        self.root.join(REDUCE_DIR).join(format!("node-{index}.bin"))
+    }
+
+    fn read_artifact<T: DeserializeOwned>(&self, path: &Path) -> io::Result<T> {
+        bincode::deserialize_from(BufReader::new(File::open(path)?))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn write_artifact<T: Serialize>(&self, path: &Path, value: &T) -> io::Result<()> {
+        write_atomically(path, |writer| {
+            bincode::serialize_into(writer, value).map_err(|e| io::Error::other(e))
+        })
+    }
+
+    fn write_manifest(&self, manifest: &WorkDirManifest) -> io::Result<()> {
+        write_atomically(&self.root.join(MANIFEST_FILE), |writer| {
+            serde_json::to_writer_pretty(writer, manifest).map_err(io::Error::from)
+        })
+    }
+}
+
+/// Writes to a sibling temporary file and renames it over `path` once it has been synced.
+fn write_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    write(&mut writer)?;
+    let file = writer.into_inner().map_err(|e| e.into_error())?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)
+}
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_work_dir_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut stdin = ZKMStdin::new();
+        stdin.write(&42u32);
+
+        let work_dir = ProvingWorkDir::open(dir.path(), b"elf", &stdin).unwrap();
+        work_dir.check_shard_size(1 << 20).unwrap();
+        work_dir.save_shard_proof(1, &vec![1u32, 2, 3]).unwrap();
+        work_dir.save_reduce_proof(4, &7u64).unwrap();
+        work_dir.complete_execution(2, vec![9, 9]).unwrap();
+        drop(work_dir);
+
+        let work_dir = ProvingWorkDir::open(dir.path(), b"elf", &stdin).unwrap();
+        assert!(work_dir.check_shard_size(1 << 21).is_err());
+        assert_eq!(work_dir.load_shard_proof::<Vec<u32>>(1).unwrap(), Some(vec![1, 2, 3]));
+        assert_eq!(work_dir.load_shard_proof::<Vec<u32>>(2).unwrap(), None);
+        assert_eq!(work_dir.load_reduce_proof::<u64>(4).unwrap(), Some(7));
+        let execution = work_dir.completed_execution().unwrap();
+        assert_eq!(execution.num_checkpoints, 2);
+        assert_eq!(execution.public_values_stream, vec![9, 9]);
+
+        assert!(ProvingWorkDir::open(dir.path(), b"other elf", &stdin).is_err());
+    }
+}
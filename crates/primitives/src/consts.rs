@@ -4,6 +4,27 @@ pub const MAXIMUM_MEMORY_SIZE: u32 = u32::MAX;
 /// The size of a word in bytes.
 pub const WORD_SIZE: usize = 4;
 
+/// The errors returned by the Linux syscalls, as numbered on MIPS.
+pub mod errno {
+    /// No such file or directory.
+    pub const MIPS_ENOENT: u32 = 2;
+
+    /// Bad file descriptor.
+    pub const MIPS_EBADF: u32 = 9;
+
+    /// Bad address.
+    pub const MIPS_EFAULT: u32 = 14;
+
+    /// Invalid argument.
+    pub const MIPS_EINVAL: u32 = 22;
+
+    /// Read-only file system.
+    pub const MIPS_EROFS: u32 = 30;
+
+    /// File name too long.
+    pub const MIPS_ENAMETOOLONG: u32 = 78;
+}
+
 pub mod fd {
     /// The file descriptor for stdin.
     pub const FD_STDIN: u32 = 0;
@@ -0,0 +1,378 @@
+//! Emulation of the MIPS32 floating-point unit (COP1).
+//!
+//! The same emulation is used by the executor, which can run COP1 instructions directly in
+//! `ExecutorMode::Simple`, and by the guest trap handler of `zkm-zkvm`, which runs them in
+//! software so that they are proven like any other guest code.
+//!
+//! The FPU runs in 32-bit mode (`Status.FR = 0`): a double occupies an even/odd register pair,
+//! with the low word in the even register. Arithmetic is IEEE 754 with round-to-nearest-even,
+//! results that are NaN are canonicalized to the MIPS default NaN, and no exceptions are raised.
+
+use serde::{Deserialize, Serialize};
+
+/// The MIPS default (legacy) single-precision quiet NaN.
+const DEFAULT_NAN_S: u32 = 0x7fbf_ffff;
+
+/// The MIPS default (legacy) double-precision quiet NaN.
+const DEFAULT_NAN_D: u64 = 0x7ff7_ffff_ffff_ffff;
+
+/// The result of a float to integer conversion that is out of range or NaN.
+const INVALID_W: u32 = 0x7fff_ffff;
+
+/// The implementation register (FIR): single, double and word formats, no 64-bit FPU.
+const FIR: u32 = 0x0003_0000;
+
+/// The general-purpose registers and memory that FPU instructions access.
+pub trait FpuBus {
+    /// Read the general-purpose register `reg`.
+    fn read_register(&mut self, reg: u32) -> u32;
+
+    /// Write `value` to the general-purpose register `reg`.
+    fn write_register(&mut self, reg: u32, value: u32);
+
+    /// Load the word at the aligned address `addr`.
+    fn load(&mut self, addr: u32) -> u32;
+
+    /// Store `value` at the aligned address `addr`.
+    fn store(&mut self, addr: u32, value: u32);
+}
+
+/// An FPU instruction that could not be executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FpuError {
+    /// The instruction is not a supported FPU instruction.
+    Unsupported(u32),
+    /// A load or store of a floating-point register at a misaligned address.
+    Misaligned(u32),
+}
+
+/// The architectural state of the FPU.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FpuState {
+    /// The floating-point registers `$f0`..`$f31`.
+    pub fpr: [u32; 32],
+    /// The control and status register (FCSR).
+    pub fcsr: u32,
+}
+
+impl FpuState {
+    /// The state after reset, with all registers zero.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { fpr: [0; 32], fcsr: 0 }
+    }
+
+    fn single(&self, reg: usize) -> f32 {
+        f32::from_bits(self.fpr[reg])
+    }
+
+    fn set_single(&mut self, reg: usize, value: f32) {
+        self.fpr[reg] = if value.is_nan() { DEFAULT_NAN_S } else { value.to_bits() };
+    }
+
+    fn double(&self, reg: usize) -> f64 {
+        let reg = reg & !1;
+        f64::from_bits(u64::from(self.fpr[reg]) | (u64::from(self.fpr[reg + 1]) << 32))
+    }
+
+    fn set_double(&mut self, reg: usize, value: f64) {
+        let bits = if value.is_nan() { DEFAULT_NAN_D } else { value.to_bits() };
+        let reg = reg & !1;
+        self.fpr[reg] = bits as u32;
+        self.fpr[reg + 1] = (bits >> 32) as u32;
+    }
+
+    /// Returns the floating-point condition code `cc`.
+    #[must_use]
+    pub fn condition(&self, cc: u32) -> bool {
+        let bit = if cc == 0 { 23 } else { 24 + cc };
+        self.fcsr & (1 << bit) != 0
+    }
+
+    fn set_condition(&mut self, cc: u32, value: bool) {
+        let bit = if cc == 0 { 23 } else { 24 + cc };
+        self.fcsr = (self.fcsr & !(1 << bit)) | (u32::from(value) << bit);
+    }
+
+    /// Read the control register `reg`.
+    fn control(&self, reg: usize) -> Option<u32> {
+        match reg {
+            0 => Some(FIR),
+            // FCCR: the condition codes.
+            25 => Some(((self.fcsr >> 24) & 0xfe) | ((self.fcsr >> 23) & 1)),
+            // FEXR: the cause and flag bits.
+            26 => Some(self.fcsr & 0x0003_f07c),
+            // FENR: the enable bits, the flush-to-zero bit and the rounding mode.
+            28 => Some((self.fcsr & 0xf83) | ((self.fcsr >> 22) & 0x4)),
+            31 => Some(self.fcsr),
+            _ => None,
+        }
+    }
+
+    /// Write the control register `reg`.
+    fn set_control(&mut self, reg: usize, value: u32) -> Option<()> {
+        match reg {
+            25 => {
+                for cc in 0..8 {
+                    self.set_condition(cc, value & (1 << cc) != 0);
+                }
+            }
+            26 => self.fcsr = (self.fcsr & !0x0003_f07c) | (value & 0x0003_f07c),
+            28 => {
+                self.fcsr = (self.fcsr & !0x0100_0f83) | (value & 0xf83) | ((value & 0x4) << 22);
+            }
+            31 => self.fcsr = value,
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Convert `value` to a word, rounding as given by `rm` (the FCSR rounding mode encoding).
+    fn to_word(value: f64, rm: u32) -> u32 {
+        let rounded = match rm & 3 {
+            // Ties are rounded to even by rounding half the value, which is exact.
+            0 if (value - value.trunc()).abs() == 0.5 => 2.0 * (value / 2.0).round(),
+            0 => value.round(),
+            1 => value.trunc(),
+            2 => value.ceil(),
+            _ => value.floor(),
+        };
+        if rounded.is_nan() || rounded < f64::from(i32::MIN) || rounded > f64::from(i32::MAX) {
+            INVALID_W
+        } else {
+            rounded as i32 as u32
+        }
+    }
+
+    /// Execute the FPU instruction `word`, returning the branch target if it is a taken branch.
+    ///
+    /// `next_pc` is the address of the branch delay slot, which branch offsets are relative to.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FpuError::Unsupported`] if the instruction is not supported, and
+    /// [`FpuError::Misaligned`] for a misaligned load or store.
+    #[allow(clippy::too_many_lines)]
+    pub fn execute(
+        &mut self,
+        word: u32,
+        next_pc: u32,
+        bus: &mut impl FpuBus,
+    ) -> Result<Option<u32>, FpuError> {
+        let unsupported = FpuError::Unsupported(word);
+        if !is_fpu_instruction(word) {
+            return Err(unsupported);
+        }
+
+        let op = word >> 26;
+        let fmt = (word >> 21) & 0x1f;
+        let rt = (word >> 16) & 0x1f;
+        let (fs, fd) = (((word >> 11) & 0x1f) as usize, ((word >> 6) & 0x1f) as usize);
+        let ft = rt as usize;
+        let funct = word & 0x3f;
+        let offset = word as u16 as i16 as i32 as u32;
+        let rm = self.fcsr & 3;
+
+        let mut target = None;
+        match op {
+            // MOVF/MOVT: rd = rs if the condition code is false/true.
+            0x00 => {
+                let (rs, rd) = ((word >> 21) & 0x1f, (word >> 11) & 0x1f);
+                if self.condition(rt >> 2) == (rt & 1 != 0) {
+                    let value = bus.read_register(rs);
+                    bus.write_register(rd, value);
+                }
+            }
+            // LWC1, LDC1, SWC1, SDC1.
+            0x31 | 0x35 | 0x39 | 0x3d => {
+                let base = bus.read_register(fmt);
+                self.memory(op, base.wrapping_add(offset), ft, bus)?;
+            }
+            0x13 => match funct {
+                // LWXC1, LDXC1, SWXC1, SDXC1.
+                0x00 | 0x01 | 0x08 | 0x09 => {
+                    let base = bus.read_register(fmt);
+                    let index = bus.read_register(rt);
+                    let op = [0x31, 0x35, 0x39, 0x3d][((funct >> 2) | (funct & 1)) as usize];
+                    let reg = if op < 0x39 { fd } else { fs };
+                    self.memory(op, base.wrapping_add(index), reg, bus)?;
+                }
+                // MADD, MSUB, NMADD, NMSUB for singles and doubles. These are not fused on MIPS32.
+                0x20 | 0x21 | 0x28 | 0x29 | 0x30 | 0x31 | 0x38 | 0x39 => {
+                    let fr = fmt as usize;
+                    let negate = funct & 0x10 != 0;
+                    let subtract = funct & 0x08 != 0;
+                    if funct & 1 == 0 {
+                        let product = self.single(fs) * self.single(ft);
+                        let result = if subtract {
+                            product - self.single(fr)
+                        } else {
+                            product + self.single(fr)
+                        };
+                        self.set_single(fd, if negate { -result } else { result });
+                    } else {
+                        let product = self.double(fs) * self.double(ft);
+                        let result = if subtract {
+                            product - self.double(fr)
+                        } else {
+                            product + self.double(fr)
+                        };
+                        self.set_double(fd, if negate { -result } else { result });
+                    }
+                }
+                _ => return Err(unsupported),
+            },
+            _ => match fmt {
+                // MFC1, MFHC1.
+                0x00 | 0x03 => {
+                    let value = self.fpr[if fmt == 0x00 { fs } else { fs | 1 }];
+                    bus.write_register(rt, value);
+                }
+                // CFC1.
+                0x02 => {
+                    let value = self.control(fs).ok_or(unsupported)?;
+                    bus.write_register(rt, value);
+                }
+                // MTC1, MTHC1.
+                0x04 | 0x07 => {
+                    let value = bus.read_register(rt);
+                    self.fpr[if fmt == 0x04 { fs } else { fs | 1 }] = value;
+                }
+                // CTC1.
+                0x06 => {
+                    let value = bus.read_register(rt);
+                    self.set_control(fs, value).ok_or(unsupported)?;
+                }
+                // BC1F, BC1T. The branch-likely variants are not supported.
+                0x08 if rt & 2 == 0 => {
+                    if self.condition(rt >> 2) == (rt & 1 != 0) {
+                        target = Some(next_pc.wrapping_add(offset << 2));
+                    }
+                }
+                // Single and double precision.
+                0x10 | 0x11 => {
+                    let gpr = if matches!(funct, 0x12 | 0x13) { bus.read_register(rt) } else { 0 };
+                    let double = fmt == 0x11;
+                    let (a, b) = if double {
+                        (self.double(fs), self.double(ft))
+                    } else {
+                        (f64::from(self.single(fs)), f64::from(self.single(ft)))
+                    };
+                    // Computing a single-precision result in double precision and rounding it
+                    // once is exact for add, sub, mul, div and sqrt.
+                    let result = match funct {
+                        0x00 => a + b,
+                        0x01 => a - b,
+                        0x02 => a * b,
+                        0x03 => a / b,
+                        0x04 => a.sqrt(),
+                        0x05 => a.abs(),
+                        0x07 => -a,
+                        // MOV, MOVF/MOVT, MOVZ, MOVN copy the raw bits.
+                        0x06 | 0x11 | 0x12 | 0x13 => {
+                            let copy = match funct {
+                                0x06 => true,
+                                0x11 => self.condition(rt >> 2) == (rt & 1 != 0),
+                                _ => (gpr == 0) == (funct == 0x12),
+                            };
+                            if copy {
+                                self.fpr[fd] = self.fpr[fs];
+                                if double {
+                                    self.fpr[fd | 1] = self.fpr[fs | 1];
+                                }
+                            }
+                            return Ok(None);
+                        }
+                        // ROUND.W, TRUNC.W, CEIL.W, FLOOR.W and CVT.W.
+                        0x0c..=0x0f | 0x24 => {
+                            let rm = if funct == 0x24 { rm } else { funct & 3 };
+                            self.fpr[fd] = Self::to_word(a, rm);
+                            return Ok(None);
+                        }
+                        // CVT.S, CVT.D.
+                        0x20 | 0x21 => a,
+                        // C.cond: bit 0 is "unordered", bit 1 "equal" and bit 2 "less than".
+                        0x30..=0x3f => {
+                            let cond = funct & 0x7;
+                            let result = if a.is_nan() || b.is_nan() {
+                                cond & 1 != 0
+                            } else {
+                                (cond & 2 != 0 && a == b) || (cond & 4 != 0 && a < b)
+                            };
+                            self.set_condition((fd >> 2) as u32, result);
+                            return Ok(None);
+                        }
+                        _ => return Err(unsupported),
+                    };
+                    let single = if matches!(funct, 0x20 | 0x21) { funct == 0x20 } else { !double };
+                    if single {
+                        self.set_single(fd, result as f32);
+                    } else {
+                        self.set_double(fd, result);
+                    }
+                }
+                // CVT.S.W, CVT.D.W.
+                0x14 => {
+                    let value = self.fpr[fs] as i32;
+                    match funct {
+                        0x20 => self.set_single(fd, value as f32),
+                        0x21 => self.set_double(fd, f64::from(value)),
+                        _ => return Err(unsupported),
+                    }
+                }
+                _ => return Err(unsupported),
+            },
+        }
+        Ok(target)
+    }
+
+    /// Execute the load or store `op` (LWC1, LDC1, SWC1 or SDC1) of `reg` at `addr`.
+    fn memory(
+        &mut self,
+        op: u32,
+        addr: u32,
+        reg: usize,
+        bus: &mut impl FpuBus,
+    ) -> Result<(), FpuError> {
+        let size = if matches!(op, 0x31 | 0x39) { 4 } else { 8 };
+        if addr % size != 0 {
+            return Err(FpuError::Misaligned(addr));
+        }
+        let regs = if size == 4 { reg..reg + 1 } else { reg & !1..(reg & !1) + 2 };
+        for (i, reg) in regs.enumerate() {
+            let addr = addr + 4 * i as u32;
+            if op < 0x39 {
+                self.fpr[reg] = bus.load(addr);
+            } else {
+                bus.store(addr, self.fpr[reg]);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `word` is an instruction handled by the FPU emulation.
+#[must_use]
+pub const fn is_fpu_instruction(word: u32) -> bool {
+    match word >> 26 {
+        // COP1, COP1X, LWC1, LDC1, SWC1, SDC1.
+        0x11 | 0x13 | 0x31 | 0x35 | 0x39 | 0x3d => true,
+        // MOVF/MOVT on general-purpose registers.
+        0x00 => word & 0x3f == 0x01,
+        _ => false,
+    }
+}
+
+/// Whether `word` is a BC1F or BC1T branch on condition code 0, and if so, whether it is BC1T.
+///
+/// Only these branches can be run without [`FpuState::execute`], by testing a register that
+/// mirrors the condition code.
+#[must_use]
+pub const fn fcc0_branch(word: u32) -> Option<bool> {
+    // COP1, fmt = BC, cc = 0, nd = 0.
+    if word >> 21 == (0x11 << 5) | 0x08 && (word >> 16) & 0x1e == 0 {
+        Some((word >> 16) & 1 != 0)
+    } else {
+        None
+    }
+}
@@ -8,6 +8,7 @@ use p3_poseidon2::{ExternalLayerConstants, Poseidon2};
 //use p3_monty_31::{Poseidon2InternalLayerMonty31, Poseidon2ExternalLayerMonty31};
 
 pub mod consts;
+pub mod fpu;
 pub mod io;
 pub mod types;
 
@@ -0,0 +1,79 @@
+use ethers::types::{Address, Signature};
+
+use crate::stage_service::GenerateProofRequest;
+
+/// The message the SDK signs for a request, see `NetworkProver::sign_ecdsa`.
+pub fn signed_message(request: &GenerateProofRequest) -> String {
+    match request.block_no {
+        Some(block_no) => format!("{}&{}&{}", request.proof_id, block_no, request.seg_size),
+        None => format!("{}&{}", request.proof_id, request.seg_size),
+    }
+}
+
+/// Checks the ECDSA signatures attached to proof requests.
+///
+/// The default verifier accepts no one.
+#[derive(Debug, Clone, Default)]
+pub struct SignatureVerifier {
+    allowed_signers: Vec<Address>,
+    allow_any: bool,
+}
+
+impl SignatureVerifier {
+    /// Creates a verifier that accepts requests signed by `allowed_signers` only, so that an
+    /// empty list rejects every request.
+    pub fn new(allowed_signers: Vec<Address>) -> Self {
+        Self { allowed_signers, allow_any: false }
+    }
+
+    /// Creates a verifier that accepts requests from anyone with a valid signature.
+    pub fn allow_any() -> Self {
+        Self { allowed_signers: Vec::new(), allow_any: true }
+    }
+
+    /// Recovers the signer of `request` and checks that it is allowed to request proofs.
+    pub fn verify(&self, request: &GenerateProofRequest) -> Result<Address, String> {
+        let signature: Signature =
+            request.signature.parse().map_err(|e| format!("malformed signature: {e}"))?;
+        let signer = signature
+            .recover(signed_message(request))
+            .map_err(|e| format!("invalid signature: {e}"))?;
+        if !self.allow_any && !self.allowed_signers.contains(&signer) {
+            return Err(format!("signer {signer:?} is not allowed to request proofs"));
+        }
+        Ok(signer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::signers::{LocalWallet, Signer};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_verify_signature() {
+        let wallet: LocalWallet =
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let mut request = GenerateProofRequest {
+            proof_id: "8d5f2c1e-0f7a-4b9e-9a57-3c1f2e6d4b10".to_string(),
+            seg_size: 1 << 20,
+            ..Default::default()
+        };
+        request.signature =
+            wallet.sign_message(signed_message(&request)).await.unwrap().to_string();
+
+        assert_eq!(SignatureVerifier::allow_any().verify(&request), Ok(wallet.address()));
+        assert!(SignatureVerifier::default().verify(&request).is_err());
+        assert!(SignatureVerifier::new(vec![]).verify(&request).is_err());
+        assert_eq!(
+            SignatureVerifier::new(vec![wallet.address()]).verify(&request),
+            Ok(wallet.address())
+        );
+        assert!(SignatureVerifier::new(vec![Address::zero()]).verify(&request).is_err());
+
+        // Changing a signed field invalidates the signature.
+        request.seg_size = 1 << 21;
+        assert_ne!(SignatureVerifier::allow_any().verify(&request), Ok(wallet.address()));
+    }
+}
@@ -0,0 +1,56 @@
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+
+use crate::{runner::RESULT_FILES, store::is_valid_proof_id};
+
+/// Serves the results of finished jobs over HTTP as `GET /<proof_id>/<file>`.
+pub async fn serve(addr: SocketAddr, results_root: PathBuf) -> hyper::Result<()> {
+    let results_root = Arc::new(results_root);
+    let make_service = make_service_fn(move |_| {
+        let results_root = results_root.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let results_root = results_root.clone();
+                async move { Ok::<_, Infallible>(handle(&results_root, request).await) }
+            }))
+        }
+    });
+    tracing::info!("serving results on http://{}", addr);
+    Server::bind(&addr).serve(make_service).await
+}
+
+async fn handle(results_root: &Path, request: Request<Body>) -> Response<Body> {
+    if request.method() != Method::GET {
+        return status_response(StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    // Only the files a job produces can be requested, so no path ever leaves the results root.
+    let mut segments = request.uri().path().trim_start_matches('/').split('/');
+    let (Some(proof_id), Some(file), None) = (segments.next(), segments.next(), segments.next())
+    else {
+        return status_response(StatusCode::NOT_FOUND);
+    };
+    if !is_valid_proof_id(proof_id) || !RESULT_FILES.contains(&file) {
+        return status_response(StatusCode::NOT_FOUND);
+    }
+
+    match tokio::fs::read(results_root.join(proof_id).join(file)).await {
+        Ok(bytes) => Response::new(Body::from(bytes)),
+        Err(_) => status_response(StatusCode::NOT_FOUND),
+    }
+}
+
+fn status_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
@@ -0,0 +1,14 @@
+//! A self-hostable implementation of the `stage.v1.StageService` proving server used by the SDK's
+//! `NetworkProver`.
+//!
+//! Requests are checked against the ECDSA signature the SDK attaches to them, persisted to a job
+//! directory and proven one at a time by a [`runner::Runner`] on top of [`zkm_prover::ZKMProver`].
+//! The resulting proofs and public values are served over HTTP from a local directory.
+
+pub mod auth;
+pub mod files;
+pub mod runner;
+pub mod service;
+pub mod store;
+
+pub use zkm_sdk::network::prover::stage_service;
@@ -0,0 +1,89 @@
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, thread};
+
+use anyhow::Result;
+use clap::Parser;
+use ethers::types::Address;
+use tonic::transport::{Certificate, Identity, Server, ServerTlsConfig};
+use zkm_core_machine::utils::setup_logger;
+use zkm_prover::{components::DefaultProverComponents, ZKMProver};
+use zkm_prover_server::{
+    auth::SignatureVerifier, files, runner::Runner, service::StageServer,
+    stage_service::stage_service_server::StageServiceServer, store::JobStore,
+};
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The address the gRPC `StageService` listens on.
+    #[clap(long, default_value = "0.0.0.0:50000")]
+    grpc_addr: SocketAddr,
+    /// The address results are served from over HTTP.
+    #[clap(long, default_value = "0.0.0.0:50001")]
+    http_addr: SocketAddr,
+    /// The URL clients reach the HTTP server at, used in the result URLs.
+    #[clap(long, default_value = "http://127.0.0.1:50001")]
+    public_url: String,
+    /// The directory holding the job queue, cached ELFs and results.
+    #[clap(long, default_value = "./data")]
+    data_dir: PathBuf,
+    /// The addresses allowed to request proofs. Every request is rejected if empty, unless
+    /// `--allow-any-signer` is set.
+    #[clap(long, value_delimiter = ',')]
+    allowed_signers: Vec<Address>,
+    /// Accept requests from anyone with a valid signature.
+    #[clap(long, conflicts_with = "allowed_signers")]
+    allow_any_signer: bool,
+    /// The PEM certificate of the gRPC server, to serve over TLS.
+    #[clap(long, requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+    /// The PEM private key of the gRPC server.
+    #[clap(long, requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+    /// The PEM CA certificate that client certificates must be signed by.
+    #[clap(long, requires = "tls_cert")]
+    client_ca: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    setup_logger();
+    let args = Args::parse();
+
+    let store = Arc::new(JobStore::open(&args.data_dir)?);
+    let (jobs_tx, jobs_rx) = std::sync::mpsc::channel();
+    let runner_store = store.clone();
+    thread::spawn(move || {
+        Runner::new(ZKMProver::<DefaultProverComponents>::new(), runner_store).run(jobs_rx)
+    });
+
+    let results_root = store.results_root();
+    tokio::spawn(async move {
+        if let Err(e) = files::serve(args.http_addr, results_root).await {
+            tracing::error!("result server failed: {}", e);
+        }
+    });
+
+    let mut server = Server::builder();
+    if let (Some(cert), Some(key)) = (&args.tls_cert, &args.tls_key) {
+        let mut tls_config = ServerTlsConfig::new()
+            .identity(Identity::from_pem(std::fs::read(cert)?, std::fs::read(key)?));
+        if let Some(client_ca) = &args.client_ca {
+            tls_config =
+                tls_config.client_ca_root(Certificate::from_pem(std::fs::read(client_ca)?));
+        }
+        server = server.tls_config(tls_config)?;
+    }
+
+    let verifier = if args.allow_any_signer {
+        SignatureVerifier::allow_any()
+    } else {
+        if args.allowed_signers.is_empty() {
+            tracing::warn!("no --allowed-signers are set, every proof request will be rejected");
+        }
+        SignatureVerifier::new(args.allowed_signers)
+    };
+    let service = StageServer::new(store, verifier, jobs_tx, args.public_url);
+    tracing::info!("serving StageService on {}", args.grpc_addr);
+    server.add_service(StageServiceServer::new(service)).serve(args.grpc_addr).await?;
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use std::{
+    fs,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc::Receiver, Arc},
+};
+
+use zkm_core_executor::ZKMContext;
+use zkm_core_machine::{io::ZKMStdin, reduce::ZKMReduceProof};
+use zkm_prover::{components::DefaultProverComponents, Groth16Bn254Proof, InnerSC, ZKMProver};
+use zkm_sdk::{install::try_install_circuit_artifacts, ZKMProof, ZKMPublicValues};
+use zkm_stark::{StarkVerifyingKey, ZKMProverOpts};
+
+use crate::{
+    stage_service::{GenerateProofRequest, Status, Step},
+    store::JobStore,
+};
+
+/// The `ZKMProof` of a finished job, as JSON.
+pub const PROOF_FILE: &str = "proof.json";
+/// The committed public values of a finished job.
+pub const PUBLIC_VALUES_FILE: &str = "public_values.bin";
+/// The bincode-encoded compressed proof of a finished job, if it reached `InAgg`.
+pub const STARK_PROOF_FILE: &str = "stark_proof.bin";
+/// The files a job can leave in its results directory.
+pub const RESULT_FILES: [&str; 3] = [PROOF_FILE, PUBLIC_VALUES_FILE, STARK_PROOF_FILE];
+
+/// A failed step, reported as the given status.
+struct JobError {
+    status: Status,
+    message: String,
+}
+
+impl JobError {
+    fn new(status: Status, message: impl ToString) -> Self {
+        Self { status, message: message.to_string() }
+    }
+}
+
+/// Proves queued jobs one at a time.
+pub struct Runner {
+    prover: ZKMProver<DefaultProverComponents>,
+    store: Arc<JobStore>,
+}
+
+impl Runner {
+    pub fn new(prover: ZKMProver<DefaultProverComponents>, store: Arc<JobStore>) -> Self {
+        Self { prover, store }
+    }
+
+    /// Proves the jobs left over from a previous run, then every job id received on `jobs` until
+    /// all senders are dropped.
+    pub fn run(&self, jobs: Receiver<String>) {
+        for proof_id in self.store.pending() {
+            self.run_job(&proof_id);
+        }
+        for proof_id in jobs {
+            self.run_job(&proof_id);
+        }
+    }
+
+    /// Proves a single job, recording its progress and outcome in the store.
+    pub fn run_job(&self, proof_id: &str) {
+        tracing::info!("proving {}", proof_id);
+        let result =
+            panic::catch_unwind(AssertUnwindSafe(|| self.prove(proof_id))).unwrap_or_else(|e| {
+                let message = e
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .or_else(|| e.downcast_ref::<&str>().map(|s| s.to_string()))
+                    .unwrap_or_else(|| "prover panicked".to_string());
+                Err(JobError::new(Status::InternalError, message))
+            });
+
+        let update = match result {
+            Ok(()) => self.store.update(proof_id, |status| {
+                status.status = Status::Success as i32;
+                status.step = Step::End as i32;
+            }),
+            Err(e) => {
+                tracing::error!("failed to prove {}: {}", proof_id, e.message);
+                self.store.update(proof_id, |status| {
+                    status.status = e.status as i32;
+                    status.error_message = e.message;
+                })
+            }
+        };
+        if let Err(e) = update {
+            tracing::error!("failed to record the status of {}: {}", proof_id, e);
+        }
+    }
+
+    fn prove(&self, proof_id: &str) -> Result<(), JobError> {
+        let request = self.store.request(proof_id).map_err(|e| {
+            JobError::new(Status::InternalError, format!("failed to load job: {e}"))
+        })?;
+        let results_dir = self.store.results_dir(proof_id);
+        fs::create_dir_all(&results_dir).map_err(|e| JobError::new(Status::InternalError, e))?;
+        let write_result = |name: &str, bytes: &[u8]| {
+            fs::write(results_dir.join(name), bytes)
+                .map_err(|e| JobError::new(Status::InternalError, e))
+        };
+
+        let target_step = request.target_step.and_then(Step::from_i32).unwrap_or(Step::InSnark);
+        let from_step = request.from_step.and_then(Step::from_i32);
+        let mut opts = ZKMProverOpts::default();
+        if request.seg_size > 0 {
+            if !request.seg_size.is_power_of_two() {
+                return Err(JobError::new(
+                    Status::InvalidParameter,
+                    "seg_size must be a power of two",
+                ));
+            }
+            opts.core_opts.shard_size = request.seg_size as usize;
+        }
+
+        self.set_step(proof_id, Step::InSplit)?;
+        let stdin = Self::stdin(&request)?;
+
+        // A `CompressToGroth16` request carries the compressed proof and its public values.
+        let (compressed, public_values) = if from_step == Some(Step::InAgg) {
+            let mut stdin = stdin;
+            if stdin.buffer.len() != 1 || stdin.proofs.len() != 1 {
+                return Err(JobError::new(
+                    Status::InvalidParameter,
+                    "expected a single compressed proof and its public values",
+                ));
+            }
+            let public_values: ZKMPublicValues = bincode::deserialize(&stdin.buffer[0])
+                .map_err(|e| JobError::new(Status::InvalidParameter, e))?;
+            let (proof, _) = stdin.proofs.pop().unwrap();
+
+            // Only wrap proofs of the requested program that commit to the given public values.
+            let elf = self.elf(&request)?;
+            let (_, vk) = panic::catch_unwind(AssertUnwindSafe(|| self.prover.setup(&elf)))
+                .map_err(|_| JobError::new(Status::InvalidParameter, "invalid elf"))?;
+            self.prover
+                .verify_compressed(&proof, &vk)
+                .and_then(|()| self.prover.verify_compressed_public_values(&proof, &public_values))
+                .map_err(|e| {
+                    JobError::new(
+                        Status::InvalidParameter,
+                        format!("invalid compressed proof: {e}"),
+                    )
+                })?;
+            (proof, public_values)
+        } else {
+            let elf = self.elf(&request)?;
+            let (pk, vk) = panic::catch_unwind(AssertUnwindSafe(|| self.prover.setup(&elf)))
+                .map_err(|_| JobError::new(Status::SplitError, "invalid elf"))?;
+            let (_, report) = self
+                .prover
+                .execute(&elf, &stdin, ZKMContext::default())
+                .map_err(|e| JobError::new(Status::SplitError, e))?;
+            self.store
+                .update(proof_id, |status| status.total_steps = report.total_instruction_count())
+                .map_err(|e| JobError::new(Status::InternalError, e))?;
+
+            self.set_step(proof_id, Step::InProve)?;
+            let core_proof = self
+                .prover
+                .prove_core(&pk, &stdin, opts, ZKMContext::default())
+                .map_err(|e| JobError::new(Status::ProveError, e))?;
+            let public_values = core_proof.public_values.clone();
+
+            self.set_step(proof_id, Step::InAgg)?;
+            let deferred_proofs =
+                stdin.proofs.iter().map(|(proof, _)| proof.clone()).collect::<Vec<_>>();
+            let compressed = self
+                .prover
+                .compress(&vk, core_proof, deferred_proofs, opts)
+                .map_err(|e| JobError::new(Status::AggError, e))?;
+            (compressed, public_values)
+        };
+        write_result(PUBLIC_VALUES_FILE, public_values.as_slice())?;
+        write_result(
+            STARK_PROOF_FILE,
+            &bincode::serialize(&compressed)
+                .map_err(|e| JobError::new(Status::InternalError, e))?,
+        )?;
+
+        let proof = if target_step == Step::InSnark {
+            self.set_step(proof_id, Step::InSnark)?;
+            ZKMProof::Groth16(self.wrap_groth16(compressed, opts)?)
+        } else {
+            ZKMProof::Compressed(Box::new(compressed))
+        };
+        let proof_json =
+            serde_json::to_vec(&proof).map_err(|e| JobError::new(Status::InternalError, e))?;
+        write_result(PROOF_FILE, &proof_json)
+    }
+
+    fn set_step(&self, proof_id: &str, step: Step) -> Result<(), JobError> {
+        tracing::info!("{}: {:?}", proof_id, step);
+        self.store
+            .update(proof_id, |status| status.step = step as i32)
+            .map_err(|e| JobError::new(Status::InternalError, e))
+    }
+
+    /// The ELF of a request, either sent inline or referenced by the id of a cached one.
+    fn elf(&self, request: &GenerateProofRequest) -> Result<Vec<u8>, JobError> {
+        match &request.elf_id {
+            Some(elf_id) if request.elf_data.is_empty() => self
+                .store
+                .load_elf(elf_id)
+                .map_err(|e| JobError::new(Status::InvalidParameter, format!("unknown elf: {e}"))),
+            _ => Ok(request.elf_data.clone()),
+        }
+    }
+
+    /// Rebuilds the stdin the SDK encoded into the request.
+    fn stdin(request: &GenerateProofRequest) -> Result<ZKMStdin, JobError> {
+        let mut stdin = ZKMStdin::new();
+        if !request.private_input_stream.is_empty() {
+            stdin.buffer = bincode::deserialize(&request.private_input_stream)
+                .map_err(|e| JobError::new(Status::InvalidParameter, e))?;
+        }
+        for receipt in request.receipt_inputs.iter().chain(request.receipts.iter()) {
+            let proof: (ZKMReduceProof<InnerSC>, StarkVerifyingKey<InnerSC>) =
+                bincode::deserialize(receipt)
+                    .map_err(|e| JobError::new(Status::InvalidParameter, e))?;
+            stdin.proofs.push(proof);
+        }
+        Ok(stdin)
+    }
+
+    fn wrap_groth16(
+        &self,
+        compressed: ZKMReduceProof<InnerSC>,
+        opts: ZKMProverOpts,
+    ) -> Result<Groth16Bn254Proof, JobError> {
+        let shrink_proof = self
+            .prover
+            .shrink(compressed, opts)
+            .map_err(|e| JobError::new(Status::SnarkError, e))?;
+        let outer_proof = self
+            .prover
+            .wrap_bn254(shrink_proof, opts)
+            .map_err(|e| JobError::new(Status::SnarkError, e))?;
+        let artifacts = if zkm_prover::build::zkm_dev_mode() {
+            zkm_prover::build::try_build_groth16_bn254_artifacts_dev(
+                &outer_proof.vk,
+                &outer_proof.proof,
+            )
+        } else {
+            try_install_circuit_artifacts("groth16")
+        };
+        Ok(self.prover.wrap_groth16_bn254(outer_proof, &artifacts))
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    io,
+    sync::{mpsc::Sender, Arc},
+};
+
+use tonic::{Request, Response};
+
+use crate::{
+    auth::SignatureVerifier,
+    runner::{PROOF_FILE, PUBLIC_VALUES_FILE, STARK_PROOF_FILE},
+    stage_service::{
+        stage_service_server::StageService, GenerateProofRequest, GenerateProofResponse,
+        GetStatusRequest, GetStatusResponse, Status as ProofStatus,
+    },
+    store::{is_valid_proof_id, JobStore},
+};
+
+/// The `StageService` gRPC handlers, which queue requests for a [`crate::runner::Runner`].
+pub struct StageServer {
+    store: Arc<JobStore>,
+    verifier: SignatureVerifier,
+    jobs: Sender<String>,
+    public_url: String,
+}
+
+impl StageServer {
+    /// Creates a server that queues jobs on `jobs` and links to results under `public_url`, where
+    /// the [`crate::files`] server is reachable.
+    pub fn new(
+        store: Arc<JobStore>,
+        verifier: SignatureVerifier,
+        jobs: Sender<String>,
+        public_url: impl Into<String>,
+    ) -> Self {
+        Self { store, verifier, jobs, public_url: public_url.into().trim_end_matches('/').into() }
+    }
+
+    fn result_url(&self, proof_id: &str, file: &str) -> String {
+        format!("{}/{}/{}", self.public_url, proof_id, file)
+    }
+
+    /// Checks and persists a request, returning why it was rejected if it was.
+    ///
+    /// This blocks on the file system, so it must run on a blocking thread.
+    fn accept(
+        store: &JobStore,
+        verifier: &SignatureVerifier,
+        request: &GenerateProofRequest,
+    ) -> Result<(), String> {
+        if !is_valid_proof_id(&request.proof_id) {
+            return Err("proof_id must be 1 to 128 alphanumeric characters, '-' or '_'".into());
+        }
+        let signer = verifier.verify(request)?;
+        tracing::info!("{} requested by {:?}", request.proof_id, signer);
+
+        // Even a `CompressToGroth16` request needs the program, to verify the proof against it.
+        if !request.elf_data.is_empty() {
+            store.store_elf(&request.elf_data).map_err(|e| e.to_string())?;
+        } else {
+            let elf_id =
+                request.elf_id.as_deref().ok_or("either elf_data or elf_id is required")?;
+            store.load_elf(elf_id).map_err(|e| format!("unknown elf {elf_id}: {e}"))?;
+        }
+
+        store.insert(request).map_err(|e| match e.kind() {
+            io::ErrorKind::AlreadyExists => format!("proof {} already exists", request.proof_id),
+            _ => e.to_string(),
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl StageService for StageServer {
+    async fn generate_proof(
+        &self,
+        request: Request<GenerateProofRequest>,
+    ) -> Result<Response<GenerateProofResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let proof_id = request.proof_id.clone();
+        let (store, verifier) = (self.store.clone(), self.verifier.clone());
+        let accepted =
+            tokio::task::spawn_blocking(move || Self::accept(&store, &verifier, &request))
+                .await
+                .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        if let Err(error_message) = accepted {
+            tracing::warn!("rejected {}: {}", proof_id, error_message);
+            return Ok(Response::new(GenerateProofResponse {
+                status: ProofStatus::InvalidParameter as i32,
+                error_message,
+                proof_id,
+                ..Default::default()
+            }));
+        }
+
+        self.jobs
+            .send(proof_id.clone())
+            .map_err(|_| tonic::Status::unavailable("the prover has stopped"))?;
+        Ok(Response::new(GenerateProofResponse {
+            status: ProofStatus::Computing as i32,
+            proof_id,
+            ..Default::default()
+        }))
+    }
+
+    async fn get_status(
+        &self,
+        request: Request<GetStatusRequest>,
+    ) -> Result<Response<GetStatusResponse>, tonic::Status> {
+        let proof_id = request.into_inner().proof_id;
+        let Some(status) = self.store.status(&proof_id) else {
+            return Err(tonic::Status::not_found(format!("unknown proof {proof_id}")));
+        };
+
+        let mut response = GetStatusResponse {
+            proof_id: proof_id.clone(),
+            status: status.status,
+            step: status.step,
+            total_steps: status.total_steps,
+            ..Default::default()
+        };
+        if status.status == ProofStatus::Success as i32 {
+            let results_dir = self.store.results_dir(&proof_id);
+            response.proof_with_public_inputs = tokio::fs::read(results_dir.join(PROOF_FILE))
+                .await
+                .map_err(|e| tonic::Status::internal(e.to_string()))?;
+            response.output_stream = tokio::fs::read(results_dir.join(PUBLIC_VALUES_FILE))
+                .await
+                .map_err(|e| tonic::Status::internal(e.to_string()))?;
+            response.snark_proof_url = self.result_url(&proof_id, PROOF_FILE);
+            response.stark_proof_url = self.result_url(&proof_id, STARK_PROOF_FILE);
+            response.public_values_url = self.result_url(&proof_id, PUBLIC_VALUES_FILE);
+        }
+        Ok(Response::new(response))
+    }
+}
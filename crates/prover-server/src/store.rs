@@ -0,0 +1,214 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::stage_service::{GenerateProofRequest, Status, Step};
+
+const REQUEST_FILE: &str = "request.bin";
+const STATUS_FILE: &str = "status.json";
+
+/// The persisted state of a proof request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStatus {
+    /// The `stage.v1.Status` of the job.
+    pub status: i32,
+    /// The `stage.v1.Step` the job is at.
+    pub step: i32,
+    /// Why the job failed, if it did.
+    pub error_message: String,
+    /// The number of cycles the program ran for, once known.
+    pub total_steps: u64,
+    /// When the job was submitted, in milliseconds since the Unix epoch.
+    pub created_at: u128,
+}
+
+impl JobStatus {
+    /// Whether the job still has to be (or is being) proven.
+    pub fn is_pending(&self) -> bool {
+        self.status == Status::Computing as i32
+    }
+}
+
+/// Whether `proof_id` can safely be used as a file name.
+pub fn is_valid_proof_id(proof_id: &str) -> bool {
+    !proof_id.is_empty()
+        && proof_id.len() <= 128
+        && proof_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The on-disk job queue, ELF cache and result directory of the server.
+///
+/// ```text
+/// <root>/jobs/<proof_id>/{request.bin,status.json}
+/// <root>/results/<proof_id>/...
+/// <root>/elfs/<sha256>.elf
+/// ```
+pub struct JobStore {
+    root: PathBuf,
+    statuses: Mutex<HashMap<String, JobStatus>>,
+}
+
+impl JobStore {
+    /// Opens the store at `root`, loading the status of every job submitted so far.
+    pub fn open(root: impl AsRef<Path>) -> io::Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        for dir in ["jobs", "results", "elfs"] {
+            fs::create_dir_all(root.join(dir))?;
+        }
+
+        let mut statuses = HashMap::new();
+        for entry in fs::read_dir(root.join("jobs"))? {
+            let entry = entry?;
+            let proof_id = entry.file_name().to_string_lossy().to_string();
+            let status_path = entry.path().join(STATUS_FILE);
+            if !is_valid_proof_id(&proof_id) || !status_path.exists() {
+                continue;
+            }
+            let status: JobStatus = serde_json::from_slice(&fs::read(status_path)?)?;
+            statuses.insert(proof_id, status);
+        }
+
+        Ok(Self { root, statuses: Mutex::new(statuses) })
+    }
+
+    /// Persists a new request and queues it, failing if its proof id is already taken.
+    pub fn insert(&self, request: &GenerateProofRequest) -> io::Result<()> {
+        let mut statuses = self.statuses.lock().unwrap();
+        if statuses.contains_key(&request.proof_id) {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "proof id is already used"));
+        }
+
+        let dir = self.job_dir(&request.proof_id);
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(REQUEST_FILE), request.encode_to_vec())?;
+
+        let created_at =
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let status = JobStatus {
+            status: Status::Computing as i32,
+            step: Step::Init as i32,
+            error_message: String::new(),
+            total_steps: 0,
+            created_at,
+        };
+        self.write_status(&request.proof_id, &status)?;
+        statuses.insert(request.proof_id.clone(), status);
+        Ok(())
+    }
+
+    /// Loads the request of a job.
+    pub fn request(&self, proof_id: &str) -> io::Result<GenerateProofRequest> {
+        let bytes = fs::read(self.job_dir(proof_id).join(REQUEST_FILE))?;
+        GenerateProofRequest::decode(bytes.as_slice())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// The status of a job, if it exists.
+    pub fn status(&self, proof_id: &str) -> Option<JobStatus> {
+        self.statuses.lock().unwrap().get(proof_id).cloned()
+    }
+
+    /// Updates and persists the status of a job.
+    pub fn update(&self, proof_id: &str, f: impl FnOnce(&mut JobStatus)) -> io::Result<()> {
+        let mut statuses = self.statuses.lock().unwrap();
+        let status = statuses
+            .get_mut(proof_id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "unknown proof id"))?;
+        f(status);
+        self.write_status(proof_id, status)
+    }
+
+    /// The jobs that haven't finished, in submission order.
+    pub fn pending(&self) -> Vec<String> {
+        let statuses = self.statuses.lock().unwrap();
+        let mut pending = statuses
+            .iter()
+            .filter(|(_, status)| status.is_pending())
+            .map(|(proof_id, status)| (status.created_at, proof_id.clone()))
+            .collect::<Vec<_>>();
+        pending.sort();
+        pending.into_iter().map(|(_, proof_id)| proof_id).collect()
+    }
+
+    /// The directory results are served from.
+    pub fn results_root(&self) -> PathBuf {
+        self.root.join("results")
+    }
+
+    /// The directory holding the results of a job.
+    pub fn results_dir(&self, proof_id: &str) -> PathBuf {
+        self.results_root().join(proof_id)
+    }
+
+    /// Caches an ELF and returns its id, the hex-encoded SHA-256 hash of its contents.
+    pub fn store_elf(&self, elf: &[u8]) -> io::Result<String> {
+        let elf_id = hex::encode(Sha256::digest(elf));
+        let path = self.elf_path(&elf_id);
+        if !path.exists() {
+            fs::write(path, elf)?;
+        }
+        Ok(elf_id)
+    }
+
+    /// Loads a cached ELF by id.
+    pub fn load_elf(&self, elf_id: &str) -> io::Result<Vec<u8>> {
+        let elf_id = elf_id.trim_start_matches("0x").to_lowercase();
+        if elf_id.len() != 64 || !elf_id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "malformed elf id"));
+        }
+        fs::read(self.elf_path(&elf_id))
+    }
+
+    fn job_dir(&self, proof_id: &str) -> PathBuf {
+        self.root.join("jobs").join(proof_id)
+    }
+
+    fn elf_path(&self, elf_id: &str) -> PathBuf {
+        self.root.join("elfs").join(format!("{elf_id}.elf"))
+    }
+
+    fn write_status(&self, proof_id: &str, status: &JobStatus) -> io::Result<()> {
+        let path = self.job_dir(proof_id).join(STATUS_FILE);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(status)?)?;
+        fs::rename(tmp_path, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_store_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = JobStore::open(dir.path()).unwrap();
+
+        for proof_id in ["first", "second", "third"] {
+            let request =
+                GenerateProofRequest { proof_id: proof_id.to_string(), ..Default::default() };
+            store.insert(&request).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        assert!(store
+            .insert(&GenerateProofRequest { proof_id: "first".to_string(), ..Default::default() })
+            .is_err());
+        store.update("second", |status| status.status = Status::Success as i32).unwrap();
+        let elf_id = store.store_elf(b"elf").unwrap();
+        drop(store);
+
+        let store = JobStore::open(dir.path()).unwrap();
+        assert_eq!(store.pending(), vec!["first".to_string(), "third".to_string()]);
+        assert_eq!(store.request("third").unwrap().proof_id, "third");
+        assert_eq!(store.load_elf(&elf_id).unwrap(), b"elf");
+        assert!(!is_valid_proof_id("../escape"));
+    }
+}
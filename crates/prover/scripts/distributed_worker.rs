@@ -0,0 +1,20 @@
+use std::net::TcpListener;
+
+use clap::Parser;
+use zkm_core_machine::utils::setup_logger;
+use zkm_prover::{components::DefaultProverComponents, distributed::Worker, ZKMProver};
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    #[clap(short, long, default_value = "127.0.0.1:3000")]
+    listen: String,
+}
+
+fn main() -> std::io::Result<()> {
+    setup_logger();
+    let args = Args::parse();
+    let listener = TcpListener::bind(&args.listen)?;
+    tracing::info!("worker listening on {}", listener.local_addr()?);
+    Worker::new(ZKMProver::<DefaultProverComponents>::new()).serve(listener)
+}
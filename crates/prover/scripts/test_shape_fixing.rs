@@ -95,6 +95,7 @@ fn test_shape_fixing(
         shape_config.maximal_core_shapes(log2_ceil_usize(opts.shard_size)).into_iter().collect(),
     );
     executor.write_vecs(&stdin.buffer);
+    executor.write_files(&stdin.files);
     for (proof, vkey) in stdin.proofs.iter() {
         executor.write_proof(proof.clone(), vkey.clone());
     }
@@ -0,0 +1,311 @@
+use std::{borrow::Borrow, sync::Arc};
+
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_koala_bear::KoalaBear;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::instrument;
+use zkm_core_machine::reduce::ZKMReduceProof;
+use zkm_primitives::poseidon2_hash;
+use zkm_recursion_circuit::{
+    hash::FieldHasher,
+    machine::{
+        ZKMAggregateVerifier, ZKMAggregateWitnessValues, ZKMCompressWithVKeyWitnessValues,
+        ZKMCompressWitnessValues,
+    },
+    witness::Witnessable,
+};
+use zkm_recursion_compiler::{circuit::AsmCompiler, config::InnerConfig, ir::Builder};
+use zkm_recursion_core::{
+    air::RecursionPublicValues, RecursionProgram, Runtime as RecursionRuntime,
+};
+use zkm_stark::{Challenge, MachineProver, StarkGenericConfig, Val, ZKMProverOpts, DIGEST_SIZE};
+
+use crate::{
+    components::ZKMProverComponents, utils::words_to_bytes, HashableKey, InnerSC, ZKMProver,
+    ZKMRecursionProverError, REDUCE_BATCH_SIZE,
+};
+
+/// What a complete compressed proof attests to: the program that ran and the digest of the
+/// public values it committed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregationClaim {
+    /// The digest of the program's verifying key, as returned by [`HashableKey::hash_u32`].
+    pub vk_digest: [u32; DIGEST_SIZE],
+    /// The SHA-256 digest of the program's public values.
+    pub public_values_digest: [u8; 32],
+}
+
+impl AggregationClaim {
+    /// The size of an encoded claim in bytes.
+    pub const SIZE: usize = 4 * DIGEST_SIZE + 32;
+
+    /// Reads the claim from the public values of a compressed proof.
+    pub fn from_proof(proof: &ZKMReduceProof<InnerSC>) -> Self {
+        let public_values: &RecursionPublicValues<KoalaBear> =
+            proof.proof.public_values.as_slice().borrow();
+        Self {
+            vk_digest: public_values.zkm_vk_digest.map(|x| x.as_canonical_u32()),
+            public_values_digest: words_to_bytes(&public_values.committed_value_digest)
+                .into_iter()
+                .map(|byte| byte.as_canonical_u32() as u8)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        }
+    }
+
+    /// The Merkle leaf of the claim, matching the one computed by [`ZKMAggregateVerifier`].
+    pub fn leaf(&self) -> [KoalaBear; DIGEST_SIZE] {
+        let inputs = self
+            .vk_digest
+            .iter()
+            .map(|&x| KoalaBear::from_canonical_u32(x))
+            .chain(self.public_values_digest.iter().map(|&x| KoalaBear::from_canonical_u8(x)))
+            .collect();
+        poseidon2_hash(inputs)
+    }
+
+    /// Encodes the claim as the big-endian words of the vk digest followed by the public values
+    /// digest.
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(self.vk_digest.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        bytes[4 * DIGEST_SIZE..].copy_from_slice(&self.public_values_digest);
+        bytes
+    }
+
+    /// Decodes a sequence of claims encoded with [`AggregationClaim::to_bytes`].
+    pub fn decode_all(bytes: &[u8]) -> Option<Vec<Self>> {
+        if bytes.is_empty() || bytes.len() % Self::SIZE != 0 {
+            return None;
+        }
+        let claims = bytes
+            .chunks_exact(Self::SIZE)
+            .map(|chunk| {
+                let (vk_digest, public_values_digest) = chunk.split_at(4 * DIGEST_SIZE);
+                let mut words = vk_digest.chunks_exact(4);
+                Self {
+                    vk_digest: std::array::from_fn(|_| {
+                        u32::from_be_bytes(words.next().unwrap().try_into().unwrap())
+                    }),
+                    public_values_digest: public_values_digest.try_into().unwrap(),
+                }
+            })
+            .collect();
+        Some(claims)
+    }
+}
+
+/// The commitment an aggregated proof exposes as its public values digest.
+///
+/// This is the Merkle root of the leaves of `claims`, padded with zero digests to a power of two,
+/// encoded as the big-endian bytes of its elements.
+pub fn aggregation_commitment(claims: &[AggregationClaim]) -> [u8; 32] {
+    assert!(!claims.is_empty(), "cannot commit to an empty aggregation");
+    let mut layer = claims.iter().map(AggregationClaim::leaf).collect::<Vec<_>>();
+    layer.resize(layer.len().next_power_of_two(), [KoalaBear::ZERO; DIGEST_SIZE]);
+    while layer.len() > 1 {
+        layer = layer
+            .chunks_exact(2)
+            .map(|pair| InnerSC::constant_compress([pair[0], pair[1]]))
+            .collect();
+    }
+
+    let mut commitment = [0u8; 32];
+    for (chunk, element) in commitment.chunks_exact_mut(4).zip(layer[0].iter()) {
+        chunk.copy_from_slice(&element.as_canonical_u32().to_be_bytes());
+    }
+    commitment
+}
+
+#[derive(Error, Debug)]
+pub enum ZKMAggregationError {
+    #[error("there are no proofs to aggregate")]
+    NoProofs,
+    #[error("proof {0} is not a complete compressed proof")]
+    Incomplete(usize),
+    #[error(
+        "proof {0} was executed with a nonzero random seed or epoch, which are not aggregated"
+    )]
+    UncommittedLinuxEnv(usize),
+    #[error("proof {0} was made against a different set of recursion verifying keys")]
+    VkRootMismatch(usize),
+    #[error("the verifying key of proof {0} is not an allowed recursion verifying key")]
+    VkNotAllowed(usize),
+    #[error("at most {0} proofs can be aggregated with an allowed aggregation program")]
+    TooManyProofs(usize),
+    #[error("the aggregation program's verifying key is not an allowed recursion verifying key")]
+    AggregationVkNotAllowed,
+    #[error("failed to prove the aggregation program: {0}")]
+    Prove(String),
+    #[error(transparent)]
+    Recursion(#[from] ZKMRecursionProverError),
+}
+
+impl<C: ZKMProverComponents> ZKMProver<C> {
+    pub fn aggregate_program(
+        &self,
+        input: &ZKMAggregateWitnessValues<InnerSC>,
+    ) -> Arc<RecursionProgram<KoalaBear>> {
+        // Get the operations.
+        let builder_span = tracing::debug_span!("build aggregate program").entered();
+        let mut builder = Builder::<InnerConfig>::default();
+        let num_proofs = input.vks_and_proofs.len();
+        let input = input.read(&mut builder);
+        // Verify the proofs.
+        ZKMAggregateVerifier::verify(
+            &mut builder,
+            self.compress_prover.machine(),
+            input,
+            self.vk_verification,
+        );
+        let operations = builder.into_operations();
+        builder_span.exit();
+
+        // Compile the program. Like the compress programs, the programs for up to
+        // `REDUCE_BATCH_SIZE` proofs are fixed to a compress shape, so that their verifying keys
+        // are among the allowed ones.
+        let compiler_span = tracing::debug_span!("compile aggregate program").entered();
+        let mut compiler = AsmCompiler::<InnerConfig>::default();
+        let mut program = compiler.compile(operations);
+        if let Some(recursion_shape_config) = &self.compress_shape_config {
+            if num_proofs <= REDUCE_BATCH_SIZE {
+                recursion_shape_config.fix_shape(&mut program);
+            }
+        }
+        compiler_span.exit();
+        Arc::new(program)
+    }
+
+    /// Fold complete compressed proofs of possibly different programs into one compressed proof.
+    ///
+    /// The resulting proof has a zero Ziren vk digest and commits to
+    /// [`aggregation_commitment`] of the [`AggregationClaim`] of every proof, in order. It can be
+    /// passed to [`ZKMProver::shrink`] and wrapped like any other compressed proof.
+    ///
+    /// With vk verification, at most [`REDUCE_BATCH_SIZE`] proofs can be aggregated at once, as
+    /// only the aggregation programs of those are among the allowed recursion verifying keys.
+    #[instrument(name = "aggregate", level = "info", skip_all)]
+    pub fn aggregate(
+        &self,
+        proofs: Vec<ZKMReduceProof<InnerSC>>,
+        opts: ZKMProverOpts,
+    ) -> Result<ZKMReduceProof<InnerSC>, ZKMAggregationError> {
+        if proofs.is_empty() {
+            return Err(ZKMAggregationError::NoProofs);
+        }
+        if self.vk_verification && proofs.len() > REDUCE_BATCH_SIZE {
+            return Err(ZKMAggregationError::TooManyProofs(REDUCE_BATCH_SIZE));
+        }
+        for (i, proof) in proofs.iter().enumerate() {
+            let public_values: &RecursionPublicValues<KoalaBear> =
+                proof.proof.public_values.as_slice().borrow();
+            if public_values.is_complete != KoalaBear::ONE {
+                return Err(ZKMAggregationError::Incomplete(i));
+            }
+            if public_values.has_linux_env() {
+                return Err(ZKMAggregationError::UncommittedLinuxEnv(i));
+            }
+            if public_values.vk_root != self.recursion_vk_root {
+                return Err(ZKMAggregationError::VkRootMismatch(i));
+            }
+            if self.vk_verification
+                && !self.recursion_vk_map.contains_key(&proof.vk.hash_koalabear())
+            {
+                return Err(ZKMAggregationError::VkNotAllowed(i));
+            }
+        }
+
+        let vks_and_proofs = proofs.into_iter().map(|proof| (proof.vk, proof.proof)).collect();
+        let input = ZKMCompressWitnessValues { vks_and_proofs, is_complete: true };
+        let ZKMCompressWithVKeyWitnessValues { compress_val, merkle_val } =
+            self.make_merkle_proofs(input);
+        let input = ZKMAggregateWitnessValues {
+            vks_and_proofs: compress_val.vks_and_proofs,
+            vk_merkle_data: merkle_val,
+        };
+
+        let program = self.aggregate_program(&input);
+
+        // Run the aggregate program.
+        let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
+            program.clone(),
+            self.compress_prover.config().perm.clone(),
+        );
+
+        let mut witness_stream = Vec::new();
+        Witnessable::<InnerConfig>::write(&input, &mut witness_stream);
+
+        runtime.witness_stream = witness_stream.into();
+
+        runtime.run().map_err(|e| ZKMRecursionProverError::RuntimeError(e.to_string()))?;
+
+        runtime.print_stats();
+        tracing::debug!("aggregate program executed successfully");
+
+        let (aggregate_pk, aggregate_vk) = tracing::debug_span!("setup aggregate")
+            .in_scope(|| self.compress_prover.setup(&program));
+
+        // The shrink program only accepts proofs of allowed recursion programs.
+        if self.vk_verification
+            && !self.recursion_vk_map.contains_key(&aggregate_vk.hash_koalabear())
+        {
+            return Err(ZKMAggregationError::AggregationVkNotAllowed);
+        }
+
+        // Prove the aggregate program.
+        let mut challenger = self.compress_prover.config().challenger();
+        let mut aggregate_proof = self
+            .compress_prover
+            .prove(&aggregate_pk, vec![runtime.record], &mut challenger, opts.recursion_opts)
+            .map_err(|e| ZKMAggregationError::Prove(e.to_string()))?;
+
+        Ok(ZKMReduceProof { vk: aggregate_vk, proof: aggregate_proof.shard_proofs.pop().unwrap() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claim(seed: u8) -> AggregationClaim {
+        AggregationClaim {
+            vk_digest: std::array::from_fn(|i| seed as u32 * 1000 + i as u32),
+            public_values_digest: [seed; 32],
+        }
+    }
+
+    #[test]
+    fn test_claims_roundtrip() {
+        let claims = vec![claim(1), claim(2), claim(3)];
+        let bytes = claims.iter().flat_map(AggregationClaim::to_bytes).collect::<Vec<_>>();
+        assert_eq!(AggregationClaim::decode_all(&bytes), Some(claims));
+        assert_eq!(AggregationClaim::decode_all(&bytes[1..]), None);
+        assert_eq!(AggregationClaim::decode_all(&[]), None);
+    }
+
+    #[test]
+    fn test_aggregation_commitment() {
+        // A single claim commits to its own leaf.
+        let leaf = claim(1).leaf();
+        let commitment = aggregation_commitment(&[claim(1)]);
+        for (chunk, element) in commitment.chunks_exact(4).zip(leaf.iter()) {
+            assert_eq!(u32::from_be_bytes(chunk.try_into().unwrap()), element.as_canonical_u32());
+        }
+
+        // Three claims are padded to four leaves.
+        let three = aggregation_commitment(&[claim(1), claim(2), claim(3)]);
+        let left = InnerSC::constant_compress([claim(1).leaf(), claim(2).leaf()]);
+        let right = InnerSC::constant_compress([claim(3).leaf(), [KoalaBear::ZERO; DIGEST_SIZE]]);
+        let root = InnerSC::constant_compress([left, right]);
+        for (chunk, element) in three.chunks_exact(4).zip(root.iter()) {
+            assert_eq!(u32::from_be_bytes(chunk.try_into().unwrap()), element.as_canonical_u32());
+        }
+
+        // The order of the claims matters.
+        assert_ne!(three, aggregation_commitment(&[claim(2), claim(1), claim(3)]));
+    }
+}
@@ -1,4 +1,7 @@
-use std::{borrow::Borrow, path::PathBuf};
+use std::{
+    borrow::Borrow,
+    path::{Path, PathBuf},
+};
 
 use p3_koala_bear::KoalaBear;
 use zkm_core_executor::ZKMContext;
@@ -58,6 +61,34 @@ pub fn groth16_bn254_artifacts_dev_dir() -> PathBuf {
     dirs::home_dir().unwrap().join(".zkm").join("circuits").join("dev")
 }
 
+/// Gets the directory where the groth16 artifacts of the arkworks backend are built.
+///
+/// The backend can't use the keys of the released gnark artifacts, so it sets up its own keys,
+/// once per circuit version.
+#[cfg(feature = "ark-groth16")]
+pub fn groth16_bn254_ark_artifacts_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap()
+        .join(".zkm")
+        .join("circuits/groth16-ark")
+        .join(crate::ZKM_CIRCUIT_VERSION)
+}
+
+/// Builds the groth16 artifacts of the arkworks backend for the given verification key and
+/// template proof, unless they were built before.
+#[cfg(feature = "ark-groth16")]
+pub fn try_build_groth16_bn254_ark_artifacts(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+) -> PathBuf {
+    let build_dir = groth16_bn254_ark_artifacts_dir();
+    if !zkm_recursion_gnark_ffi::groth16_bn254_ark_artifacts_exist(&build_dir) {
+        println!("[zkm] building groth16 bn254 artifacts with the arkworks backend");
+        build_groth16_bn254_artifacts(template_vk, template_proof, &build_dir);
+    }
+    build_dir
+}
+
 /// Build the plonk bn254 artifacts to the given directory for the given verification key and
 /// template proof.
 pub fn build_plonk_bn254_artifacts(
@@ -84,6 +115,23 @@ pub fn build_groth16_bn254_artifacts(
     Groth16Bn254Prover::build(constraints, witness, build_dir);
 }
 
+/// Writes the Solidity verifier contracts of the plonk bn254 artifacts in `build_dir` to `out_dir`.
+pub fn export_plonk_bn254_contracts(
+    build_dir: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+) -> std::io::Result<()> {
+    PlonkBn254Prover::export_contracts(build_dir.as_ref(), out_dir.as_ref())
+}
+
+/// Writes the Solidity verifier contracts of the groth16 bn254 artifacts in `build_dir` to
+/// `out_dir`.
+pub fn export_groth16_bn254_contracts(
+    build_dir: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+) -> std::io::Result<()> {
+    Groth16Bn254Prover::export_contracts(build_dir.as_ref(), out_dir.as_ref())
+}
+
 /// Builds the plonk bn254 artifacts to the given directory.
 ///
 /// This may take a while as it needs to first generate a dummy proof and then it needs to compile
@@ -1,11 +1,33 @@
-use zkm_core_machine::mips::MipsAir;
-use zkm_stark::{CpuProver, MachineProver, StarkGenericConfig};
+use std::sync::Arc;
+
+use p3_air::Air;
+use zkm_core_executor::syscalls::{Syscall, SyscallCode};
+use zkm_core_machine::mips::{MipsAir, MipsExtension, NoExtension};
+use zkm_recursion_circuit::constraints::RecursiveVerifierConstraintFolder;
+use zkm_recursion_compiler::config::InnerConfig;
+use zkm_stark::{
+    CpuProver, DebugConstraintBuilder, MachineProver, StarkGenericConfig, VerifierConstraintFolder,
+};
 
 use crate::{CompressAir, CoreSC, InnerSC, OuterSC, ShrinkAir, WrapAir};
 
 pub trait ZKMProverComponents: Send + Sync {
+    /// The chips of the core machine from outside of Ziren, see
+    /// [`MipsAir::machine_with_extensions`].
+    type Extension: MipsExtension<<CoreSC as StarkGenericConfig>::Val>
+        + for<'a> Air<VerifierConstraintFolder<'a, CoreSC>>
+        + for<'a> Air<
+            DebugConstraintBuilder<
+                'a,
+                <CoreSC as StarkGenericConfig>::Val,
+                <CoreSC as StarkGenericConfig>::Challenge,
+            >,
+        > + for<'a> Air<RecursiveVerifierConstraintFolder<'a, InnerConfig>>
+        + Send
+        + Sync;
+
     /// The prover for making Ziren core proofs.
-    type CoreProver: MachineProver<CoreSC, MipsAir<<CoreSC as StarkGenericConfig>::Val>>
+    type CoreProver: MachineProver<CoreSC, MipsAir<<CoreSC as StarkGenericConfig>::Val, Self::Extension>>
         + Send
         + Sync;
 
@@ -23,11 +45,25 @@ pub trait ZKMProverComponents: Send + Sync {
     type WrapProver: MachineProver<OuterSC, WrapAir<<OuterSC as StarkGenericConfig>::Val>>
         + Send
         + Sync;
+
+    /// The extension chips of the core machine.
+    ///
+    /// They are added to the core shapes too, so the recursion verification keys must be
+    /// regenerated for them, see `build_vk_map`.
+    fn extensions() -> Vec<Self::Extension> {
+        Vec::new()
+    }
+
+    /// The precompiles whose syscalls the extension chips prove, registered on every execution.
+    fn precompiles() -> Vec<(SyscallCode, Arc<dyn Syscall>)> {
+        Vec::new()
+    }
 }
 
 pub struct DefaultProverComponents;
 
 impl ZKMProverComponents for DefaultProverComponents {
+    type Extension = NoExtension;
     type CoreProver = CpuProver<CoreSC, MipsAir<<CoreSC as StarkGenericConfig>::Val>>;
     type CompressProver = CpuProver<InnerSC, CompressAir<<InnerSC as StarkGenericConfig>::Val>>;
     type ShrinkProver = CpuProver<InnerSC, ShrinkAir<<InnerSC as StarkGenericConfig>::Val>>;
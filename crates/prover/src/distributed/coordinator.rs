@@ -0,0 +1,337 @@
+use std::{
+    collections::VecDeque,
+    net::{TcpStream, ToSocketAddrs},
+    sync::{Condvar, Mutex},
+    thread,
+};
+
+use zkm_core_executor::{ExecutionRecord, ExecutionReport, Executor, ZKMContext};
+use zkm_core_machine::{io::ZKMStdin, reduce::ZKMReduceProof};
+use zkm_primitives::io::ZKMPublicValues;
+use zkm_recursion_circuit::machine::ZKMCompressWitnessValues;
+use zkm_stark::{air::PublicValues, MachineProver, StarkGenericConfig, ZKMProverOpts};
+
+use super::{read_message, write_message, DistributedProverError, WorkerRequest, WorkerResponse};
+use crate::{
+    components::ZKMProverComponents, InnerSC, ZKMCircuitWitness, ZKMCoreProof, ZKMCoreProofData,
+    ZKMProver, ZKMProvingKey, ZKMVerifyingKey, REDUCE_BATCH_SIZE,
+};
+
+/// Jobs that have not been handed to a worker yet, shared by the threads driving the workers.
+struct JobQueue {
+    jobs: VecDeque<(usize, WorkerRequest)>,
+    in_flight: usize,
+    failed: bool,
+}
+
+/// Splits proofs across a set of [`super::Worker`]s.
+///
+/// Each connection runs one job at a time; connect to the same worker several times to run
+/// several jobs on it concurrently. When a connection drops, its job is handed to another worker.
+pub struct Coordinator<'a, C: ZKMProverComponents> {
+    prover: &'a ZKMProver<C>,
+    connections: Vec<Mutex<Option<TcpStream>>>,
+}
+
+impl<'a, C: ZKMProverComponents> Coordinator<'a, C> {
+    /// Connects to the workers listening on `workers`.
+    pub fn connect<A: ToSocketAddrs>(
+        prover: &'a ZKMProver<C>,
+        workers: &[A],
+    ) -> Result<Self, DistributedProverError> {
+        let connections = workers
+            .iter()
+            .map(|addr| Ok(Mutex::new(Some(TcpStream::connect(addr)?))))
+            .collect::<Result<Vec<_>, DistributedProverError>>()?;
+        Ok(Self { prover, connections })
+    }
+
+    /// Generate shard proofs like [`ZKMProver::prove_core`], tracing and proving the checkpoints
+    /// on the workers.
+    pub fn prove_core(
+        &self,
+        pk: &ZKMProvingKey,
+        stdin: &ZKMStdin,
+        opts: ZKMProverOpts,
+        mut context: ZKMContext<'a>,
+    ) -> Result<ZKMCoreProof, DistributedProverError> {
+        if opts.security != self.prover.core_prover.config().security() {
+            return Err(DistributedProverError::UnsupportedSecurity(opts.security));
+        }
+        context.subproof_verifier = Some(self.prover);
+        context.precompiles.extend(C::precompiles());
+        let program = self
+            .prover
+            .get_program(&pk.elf)
+            .map_err(|e| DistributedProverError::InvalidProgram(e.to_string()))?;
+        self.broadcast(&WorkerRequest::Setup { elf: pk.elf.clone(), opts })?;
+
+        // Execute the program, collecting the checkpoints.
+        let core_opts = opts.core_opts;
+        let mut runtime = Executor::with_context(program.clone(), core_opts, context);
+        runtime.maximal_shapes = self.prover.core_shape_config.as_ref().map(|config| {
+            config.maximal_core_shapes(core_opts.shard_size.ilog2() as usize).into_iter().collect()
+        });
+        runtime.write_vecs(&stdin.buffer);
+        runtime.write_files(&stdin.files);
+        for (proof, vk) in stdin.proofs.iter() {
+            runtime.write_proof(proof.clone(), vk.clone());
+        }
+        let mut checkpoints = Vec::new();
+        loop {
+            let (checkpoint, done) = runtime.execute_state(false)?;
+            checkpoints.push(bincode::serialize(&checkpoint)?);
+            if done {
+                break;
+            }
+        }
+        let public_values_stream = runtime.state.public_values_stream;
+
+        // Trace every checkpoint to learn its shards and deferred events.
+        let traced = self.run_jobs(
+            checkpoints
+                .iter()
+                .map(|checkpoint| WorkerRequest::TraceCheckpoint { checkpoint: checkpoint.clone() })
+                .collect(),
+        )?;
+
+        // Chain the public values across the checkpoints, in the same way as `prove_with_context`.
+        let num_checkpoints = checkpoints.len();
+        let mut report = ExecutionReport::default();
+        let mut state = PublicValues::<u32, u32>::default().reset();
+        let mut deferred = ExecutionRecord::new(program.into());
+        let mut jobs = Vec::new();
+        for (index, (checkpoint, traced)) in checkpoints.into_iter().zip(traced).enumerate() {
+            let WorkerResponse::Traced {
+                public_values,
+                deferred: mut checkpoint_deferred,
+                report: checkpoint_report,
+            } = traced
+            else {
+                return Err(DistributedProverError::UnexpectedResponse);
+            };
+            report += checkpoint_report;
+            let done = index + 1 == num_checkpoints;
+
+            // Update the public values for the shards which contain "cpu events".
+            let public_values = public_values
+                .into_iter()
+                .map(|public_values| {
+                    state.shard += 1;
+                    state.execution_shard = public_values.execution_shard;
+                    state.start_pc = public_values.start_pc;
+                    state.next_pc = public_values.next_pc;
+                    state.committed_value_digest = public_values.committed_value_digest;
+                    state.deferred_proofs_digest = public_values.deferred_proofs_digest;
+                    state.random_seed = public_values.random_seed;
+                    state.epoch = public_values.epoch;
+                    state
+                })
+                .collect();
+            jobs.push(WorkerRequest::ProveCheckpoint { checkpoint, public_values });
+
+            // See if any deferred shards are ready to be committed to.
+            deferred.append(&mut checkpoint_deferred);
+            let mut records = deferred.split(done, core_opts.split_opts);
+
+            // Update the public values for the shards which do not contain "cpu events".
+            if !done {
+                state.execution_shard += 1;
+            }
+            for record in records.iter_mut() {
+                state.shard += 1;
+                state.previous_init_addr_bits = record.public_values.previous_init_addr_bits;
+                state.last_init_addr_bits = record.public_values.last_init_addr_bits;
+                state.previous_finalize_addr_bits =
+                    record.public_values.previous_finalize_addr_bits;
+                state.last_finalize_addr_bits = record.public_values.last_finalize_addr_bits;
+                state.start_pc = state.next_pc;
+                record.public_values = state;
+            }
+            if !records.is_empty() {
+                jobs.push(WorkerRequest::ProveRecords { records });
+            }
+        }
+
+        // Prove the shards, keeping them in shard order.
+        let mut shard_proofs = Vec::new();
+        for response in self.run_jobs(jobs)? {
+            let WorkerResponse::ShardProofs(proofs) = response else {
+                return Err(DistributedProverError::UnexpectedResponse);
+            };
+            shard_proofs.extend(proofs);
+        }
+
+        Ok(ZKMCoreProof {
+            proof: ZKMCoreProofData(shard_proofs),
+            stdin: stdin.clone(),
+            public_values: ZKMPublicValues::from(&public_values_stream),
+            cycles: report.total_instruction_count(),
+        })
+    }
+
+    /// Reduce shard proofs like [`ZKMProver::compress`], proving each layer of the tree on the
+    /// workers.
+    pub fn compress(
+        &self,
+        vk: &ZKMVerifyingKey,
+        proof: ZKMCoreProof,
+        deferred_proofs: Vec<ZKMReduceProof<InnerSC>>,
+        opts: ZKMProverOpts,
+    ) -> Result<ZKMReduceProof<InnerSC>, DistributedProverError> {
+        let first_layer_inputs =
+            self.prover.get_first_layer_inputs(vk, &proof.proof.0, &deferred_proofs, 1);
+        let mut layer = self.reduce_layer(first_layer_inputs, opts)?;
+
+        // Join batches of proofs until a single proof is left.
+        while layer.len() > 1 {
+            let is_complete = layer.len() <= REDUCE_BATCH_SIZE;
+            let inputs = layer
+                .chunks(REDUCE_BATCH_SIZE)
+                .map(|batch| {
+                    ZKMCircuitWitness::Compress(ZKMCompressWitnessValues {
+                        vks_and_proofs: batch
+                            .iter()
+                            .map(|proof| (proof.vk.clone(), proof.proof.clone()))
+                            .collect(),
+                        is_complete,
+                    })
+                })
+                .collect();
+            layer = self.reduce_layer(inputs, opts)?;
+        }
+
+        Ok(layer.pop().unwrap())
+    }
+
+    fn reduce_layer(
+        &self,
+        inputs: Vec<ZKMCircuitWitness>,
+        opts: ZKMProverOpts,
+    ) -> Result<Vec<ZKMReduceProof<InnerSC>>, DistributedProverError> {
+        let jobs = inputs
+            .into_iter()
+            .map(|input| WorkerRequest::Reduce { input, opts: opts.recursion_opts })
+            .collect();
+        self.run_jobs(jobs)?
+            .into_iter()
+            .map(|response| match response {
+                WorkerResponse::Reduced(proof) => Ok(proof),
+                _ => Err(DistributedProverError::UnexpectedResponse),
+            })
+            .collect()
+    }
+
+    /// Sends `request` to every worker, dropping the ones that can't be reached.
+    fn broadcast(&self, request: &WorkerRequest) -> Result<(), DistributedProverError> {
+        for connection in self.connections.iter() {
+            let mut connection = connection.lock().unwrap();
+            let Some(stream) = connection.as_mut() else {
+                continue;
+            };
+            match Self::call(stream, request) {
+                Ok(WorkerResponse::Error(e)) => return Err(DistributedProverError::WorkerError(e)),
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!("dropping worker: {}", e);
+                    *connection = None;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs `jobs` on the workers and returns their responses in the same order.
+    fn run_jobs(
+        &self,
+        jobs: Vec<WorkerRequest>,
+    ) -> Result<Vec<WorkerResponse>, DistributedProverError> {
+        let num_jobs = jobs.len();
+        let queue = Mutex::new(JobQueue {
+            jobs: jobs.into_iter().enumerate().collect(),
+            in_flight: 0,
+            failed: false,
+        });
+        let queue_changed = Condvar::new();
+        let results = Mutex::new((0..num_jobs).map(|_| None).collect::<Vec<_>>());
+
+        let (queue, queue_changed, results_ref) = (&queue, &queue_changed, &results);
+        thread::scope(|s| {
+            let handles = self
+                .connections
+                .iter()
+                .map(|connection| {
+                    s.spawn(move || self.drive(connection, queue, queue_changed, results_ref))
+                })
+                .collect::<Vec<_>>();
+            handles.into_iter().try_for_each(|handle| handle.join().unwrap())
+        })?;
+
+        let results = results.into_inner().unwrap();
+        let remaining = results.iter().filter(|result| result.is_none()).count();
+        if remaining > 0 {
+            return Err(DistributedProverError::NoWorkersLeft(remaining));
+        }
+        Ok(results.into_iter().map(Option::unwrap).collect())
+    }
+
+    /// Feeds jobs to one worker until the queue is drained or the worker goes away.
+    fn drive(
+        &self,
+        connection: &Mutex<Option<TcpStream>>,
+        queue: &Mutex<JobQueue>,
+        queue_changed: &Condvar,
+        results: &Mutex<Vec<Option<WorkerResponse>>>,
+    ) -> Result<(), DistributedProverError> {
+        let mut connection = connection.lock().unwrap();
+        let Some(stream) = connection.as_mut() else {
+            return Ok(());
+        };
+        loop {
+            // Wait for a job, or until no other worker could hand one back.
+            let (index, job) = {
+                let mut queue = queue.lock().unwrap();
+                loop {
+                    if queue.failed {
+                        return Ok(());
+                    }
+                    if let Some(job) = queue.jobs.pop_front() {
+                        queue.in_flight += 1;
+                        break job;
+                    }
+                    if queue.in_flight == 0 {
+                        return Ok(());
+                    }
+                    queue = queue_changed.wait(queue).unwrap();
+                }
+            };
+
+            let response = Self::call(stream, &job);
+            let mut queue = queue.lock().unwrap();
+            queue.in_flight -= 1;
+            queue_changed.notify_all();
+            match response {
+                Ok(WorkerResponse::Error(e)) => {
+                    queue.failed = true;
+                    return Err(DistributedProverError::WorkerError(e));
+                }
+                Ok(response) => results.lock().unwrap()[index] = Some(response),
+                Err(e) => {
+                    // The worker is gone, so hand its job to the others.
+                    tracing::warn!("dropping worker, requeueing job {}: {}", index, e);
+                    queue.jobs.push_back((index, job));
+                    *connection = None;
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn call(
+        stream: &mut TcpStream,
+        request: &WorkerRequest,
+    ) -> Result<WorkerResponse, DistributedProverError> {
+        write_message(stream, request)?;
+        read_message(stream)
+    }
+}
@@ -0,0 +1,191 @@
+//! Distributed proving across several processes or machines.
+//!
+//! A [`Coordinator`] executes the program in checkpoint mode and hands the checkpoints out to a
+//! set of [`Worker`]s over TCP. Proving a core proof takes two rounds:
+//!
+//! 1. Every checkpoint is traced by a worker, which reports the public values of its shards and
+//!    the events that are deferred to later shards.
+//! 2. The coordinator chains the shard numbers and public values across checkpoints exactly like
+//!    [`zkm_core_machine::utils::prove_with_context`], splits the deferred events into shards and
+//!    sends both back to the workers to be proven.
+//!
+//! Compression then proves each layer of the `REDUCE_BATCH_SIZE` tree in parallel on the workers.
+//!
+//! Messages are bincode-encoded [`WorkerRequest`]s and [`WorkerResponse`]s, each prefixed with its
+//! length as a little-endian `u64`. Messages longer than [`MAX_MESSAGE_LEN`] are rejected.
+
+mod coordinator;
+mod protocol;
+mod worker;
+
+pub use coordinator::*;
+pub use protocol::*;
+pub use worker::*;
+
+use std::io::{self, Read, Write};
+
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+use zkm_core_executor::ExecutionError;
+use zkm_stark::SecurityConfig;
+
+#[derive(Error, Debug)]
+pub enum DistributedProverError {
+    #[error("io error: {0}")]
+    IoError(#[from] io::Error),
+    #[error("serialization error: {0}")]
+    SerializationError(#[from] bincode::Error),
+    #[error("failed to execute program: {0}")]
+    ExecutionError(#[from] ExecutionError),
+    #[error("invalid program: {0}")]
+    InvalidProgram(String),
+    #[error("worker error: {0}")]
+    WorkerError(String),
+    #[error("unexpected response from worker")]
+    UnexpectedResponse,
+    #[error("no workers left to run the remaining {0} jobs")]
+    NoWorkersLeft(usize),
+    #[error("workers only prove with the default security parameters, not {0:?}")]
+    UnsupportedSecurity(SecurityConfig),
+    #[error("message of {0} bytes exceeds the maximum of {MAX_MESSAGE_LEN} bytes")]
+    MessageTooLarge(u64),
+}
+
+/// The maximum length of a message, which bounds the memory a peer can make us allocate.
+pub const MAX_MESSAGE_LEN: u64 = 1 << 32;
+
+/// Writes a length-prefixed, bincode-encoded message.
+pub(crate) fn write_message<T: Serialize>(
+    writer: &mut impl Write,
+    message: &T,
+) -> Result<(), DistributedProverError> {
+    let bytes = bincode::serialize(message)?;
+    if bytes.len() as u64 > MAX_MESSAGE_LEN {
+        return Err(DistributedProverError::MessageTooLarge(bytes.len() as u64));
+    }
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a length-prefixed, bincode-encoded message.
+pub(crate) fn read_message<T: DeserializeOwned>(
+    reader: &mut impl Read,
+) -> Result<T, DistributedProverError> {
+    let mut len = [0u8; 8];
+    reader.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+    if len > MAX_MESSAGE_LEN {
+        return Err(DistributedProverError::MessageTooLarge(len));
+    }
+    // Grow the buffer with the bytes actually received rather than trusting the length.
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(bincode::deserialize(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{TcpListener, TcpStream},
+        thread,
+    };
+
+    use serial_test::serial;
+    use zkm_core_executor::ZKMContext;
+    use zkm_core_machine::{io::ZKMStdin, utils::setup_logger};
+    use zkm_stark::ZKMProverOpts;
+
+    use super::*;
+    use crate::{components::DefaultProverComponents, ZKMProver};
+
+    #[test]
+    fn test_message_round_trip() {
+        let mut bytes = Vec::new();
+        write_message(&mut bytes, &(7u32, "checkpoint".to_string())).unwrap();
+        let message: (u32, String) = read_message(&mut bytes.as_slice()).unwrap();
+        assert_eq!(message, (7, "checkpoint".to_string()));
+
+        // A truncated message fails instead of being padded.
+        let truncated = &bytes[..bytes.len() - 1];
+        assert!(matches!(
+            read_message::<(u32, String)>(&mut &truncated[..]),
+            Err(DistributedProverError::IoError(_))
+        ));
+    }
+
+    #[test]
+    fn test_message_too_large() {
+        let bytes = (MAX_MESSAGE_LEN + 1).to_le_bytes();
+        assert!(matches!(
+            read_message::<Vec<u8>>(&mut bytes.as_slice()),
+            Err(DistributedProverError::MessageTooLarge(len)) if len == MAX_MESSAGE_LEN + 1
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_worker_jobs() -> anyhow::Result<()> {
+        setup_logger();
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        thread::spawn(move || {
+            Worker::new(ZKMProver::<DefaultProverComponents>::new()).serve(listener)
+        });
+        let mut stream = TcpStream::connect(addr)?;
+
+        // Jobs before the setup and malformed checkpoints fail without closing the connection.
+        let trace = WorkerRequest::TraceCheckpoint { checkpoint: vec![1, 2, 3] };
+        write_message(&mut stream, &trace)?;
+        assert!(matches!(read_message::<WorkerResponse>(&mut stream)?, WorkerResponse::Error(_)));
+
+        let elf = test_artifacts::FIBONACCI_ELF.to_vec();
+        write_message(&mut stream, &WorkerRequest::Setup { elf, opts: ZKMProverOpts::default() })?;
+        assert!(matches!(read_message::<WorkerResponse>(&mut stream)?, WorkerResponse::Ready));
+
+        write_message(&mut stream, &trace)?;
+        assert!(matches!(read_message::<WorkerResponse>(&mut stream)?, WorkerResponse::Error(_)));
+
+        // An oversized message closes the connection.
+        stream.write_all(&(MAX_MESSAGE_LEN + 1).to_le_bytes())?;
+        assert!(read_message::<WorkerResponse>(&mut stream).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    #[ignore]
+    fn test_distributed_prove_and_compress() -> anyhow::Result<()> {
+        setup_logger();
+        let elf = test_artifacts::FIBONACCI_ELF;
+
+        // Start two workers on ephemeral ports.
+        let mut addrs = Vec::new();
+        for _ in 0..2 {
+            let listener = TcpListener::bind("127.0.0.1:0")?;
+            addrs.push(listener.local_addr()?);
+            thread::spawn(move || {
+                Worker::new(ZKMProver::<DefaultProverComponents>::new()).serve(listener)
+            });
+        }
+
+        let prover = ZKMProver::<DefaultProverComponents>::new();
+        let (pk, vk) = prover.setup(elf);
+        let opts = ZKMProverOpts::default();
+        let coordinator = Coordinator::connect(&prover, &addrs)?;
+
+        let stdin = ZKMStdin::default();
+        let core_proof = coordinator.prove_core(&pk, &stdin, opts, ZKMContext::default())?;
+        prover.verify(&core_proof.proof, &vk)?;
+
+        let compressed = coordinator.compress(&vk, core_proof, vec![], opts)?;
+        prover.verify_compressed(&compressed, &vk)?;
+
+        Ok(())
+    }
+}
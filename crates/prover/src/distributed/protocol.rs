@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use zkm_core_executor::{ExecutionRecord, ExecutionReport};
+use zkm_core_machine::reduce::ZKMReduceProof;
+use zkm_stark::{air::PublicValues, ShardProof, ZKMCoreOpts, ZKMProverOpts};
+
+use crate::{CoreSC, InnerSC, ZKMCircuitWitness};
+
+/// A job sent from the [`super::Coordinator`] to a [`super::Worker`].
+#[derive(Serialize, Deserialize, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum WorkerRequest {
+    /// Set up the program that the following core jobs on this connection belong to.
+    Setup { elf: Vec<u8>, opts: ZKMProverOpts },
+    /// Trace a serialized `ExecutionState` checkpoint without proving it.
+    TraceCheckpoint { checkpoint: Vec<u8> },
+    /// Trace a serialized `ExecutionState` checkpoint and prove its shards with the given public
+    /// values, one per shard.
+    ProveCheckpoint { checkpoint: Vec<u8>, public_values: Vec<PublicValues<u32, u32>> },
+    /// Prove shards made of deferred events, whose public values are already set.
+    ProveRecords { records: Vec<ExecutionRecord> },
+    /// Prove one node of the compress tree.
+    Reduce { input: ZKMCircuitWitness, opts: ZKMCoreOpts },
+}
+
+/// The result of a [`WorkerRequest`].
+#[derive(Serialize, Deserialize, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum WorkerResponse {
+    /// The program has been set up.
+    Ready,
+    /// The shards of a traced checkpoint.
+    Traced {
+        /// The public values of each shard, as produced by the executor.
+        public_values: Vec<PublicValues<u32, u32>>,
+        /// The events of every shard that are deferred to later shards.
+        deferred: ExecutionRecord,
+        /// The execution report of the checkpoint.
+        report: ExecutionReport,
+    },
+    /// The proofs of the requested shards, in order.
+    ShardProofs(Vec<ShardProof<CoreSC>>),
+    /// The proof of a compress tree node.
+    Reduced(ZKMReduceProof<InnerSC>),
+    /// The job failed on the worker.
+    Error(String),
+}
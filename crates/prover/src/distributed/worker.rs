@@ -0,0 +1,234 @@
+use std::{
+    io,
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+use p3_koala_bear::KoalaBear;
+use zkm_core_executor::{ExecutionRecord, ExecutionReport, ExecutionState, Program};
+use zkm_core_machine::{
+    mips::MipsAir, reduce::ZKMReduceProof, utils::trace_checkpoint_with_precompiles,
+};
+use zkm_recursion_circuit::witness::Witnessable;
+use zkm_recursion_compiler::config::InnerConfig;
+use zkm_recursion_core::Runtime as RecursionRuntime;
+use zkm_stark::{
+    Challenge, MachineProver, MachineProvingKey, ShardProof, StarkGenericConfig, Val, ZKMCoreOpts,
+    ZKMProverOpts,
+};
+
+use super::{read_message, write_message, DistributedProverError, WorkerRequest, WorkerResponse};
+use crate::{components::ZKMProverComponents, CoreSC, InnerSC, ZKMCircuitWitness, ZKMProver};
+
+type CoreProvingKey<C> = <<C as ZKMProverComponents>::CoreProver as MachineProver<
+    CoreSC,
+    MipsAir<KoalaBear, <C as ZKMProverComponents>::Extension>,
+>>::DeviceProvingKey;
+
+/// The program a connection proves core shards for, set by [`WorkerRequest::Setup`].
+struct Session<C: ZKMProverComponents> {
+    program: Program,
+    pk: CoreProvingKey<C>,
+    opts: ZKMProverOpts,
+}
+
+/// Serves proving jobs from a [`super::Coordinator`].
+pub struct Worker<C: ZKMProverComponents> {
+    prover: ZKMProver<C>,
+}
+
+impl<C: ZKMProverComponents> Worker<C> {
+    /// Creates a worker that proves with `prover`.
+    pub fn new(prover: ZKMProver<C>) -> Self {
+        Self { prover }
+    }
+
+    /// Accepts connections on `listener` and serves each on its own thread.
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        thread::scope(|s| {
+            for stream in listener.incoming() {
+                let stream = stream?;
+                s.spawn(move || {
+                    let peer = stream.peer_addr().ok();
+                    if let Err(e) = self.serve_connection(stream) {
+                        tracing::warn!("connection to {:?} failed: {}", peer, e);
+                    }
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Serves jobs from one coordinator connection until it is closed.
+    pub fn serve_connection(&self, mut stream: TcpStream) -> Result<(), DistributedProverError> {
+        let mut session = None;
+        loop {
+            let request = match read_message::<WorkerRequest>(&mut stream) {
+                Ok(request) => request,
+                Err(DistributedProverError::IoError(e))
+                    if e.kind() == io::ErrorKind::UnexpectedEof =>
+                {
+                    return Ok(());
+                }
+                Err(e) => return Err(e),
+            };
+            let response = self
+                .handle(request, &mut session)
+                .unwrap_or_else(|e| WorkerResponse::Error(e.to_string()));
+            write_message(&mut stream, &response)?;
+        }
+    }
+
+    fn handle(
+        &self,
+        request: WorkerRequest,
+        session: &mut Option<Session<C>>,
+    ) -> Result<WorkerResponse, DistributedProverError> {
+        match request {
+            WorkerRequest::Setup { elf, opts } => {
+                let program = self
+                    .prover
+                    .get_program(&elf)
+                    .map_err(|e| DistributedProverError::InvalidProgram(e.to_string()))?;
+                let (pk, _) = self.prover.core_prover.setup(&program);
+                *session = Some(Session { program, pk, opts });
+                Ok(WorkerResponse::Ready)
+            }
+            WorkerRequest::TraceCheckpoint { checkpoint } => {
+                let session = Self::session(session)?;
+                let (mut records, report) = self.trace(session, &checkpoint)?;
+                let public_values = records.iter().map(|record| record.public_values).collect();
+                let mut deferred = ExecutionRecord::new(session.program.clone().into());
+                for record in records.iter_mut() {
+                    deferred.append(&mut record.defer());
+                }
+                Ok(WorkerResponse::Traced { public_values, deferred, report })
+            }
+            WorkerRequest::ProveCheckpoint { checkpoint, public_values } => {
+                let session = Self::session(session)?;
+                let (mut records, _) = self.trace(session, &checkpoint)?;
+                if records.len() != public_values.len() {
+                    return Err(DistributedProverError::WorkerError(format!(
+                        "checkpoint traced to {} shards, expected {}",
+                        records.len(),
+                        public_values.len()
+                    )));
+                }
+                for (record, public_values) in records.iter_mut().zip(public_values) {
+                    // The deferred events were already handed to the coordinator when tracing.
+                    record.defer();
+                    record.public_values = public_values;
+                }
+                self.prove_records(session, records).map(WorkerResponse::ShardProofs)
+            }
+            WorkerRequest::ProveRecords { records } => {
+                let session = Self::session(session)?;
+                self.prove_records(session, records).map(WorkerResponse::ShardProofs)
+            }
+            WorkerRequest::Reduce { input, opts } => {
+                self.prove_reduce(input, opts).map(WorkerResponse::Reduced)
+            }
+        }
+    }
+
+    fn session(session: &Option<Session<C>>) -> Result<&Session<C>, DistributedProverError> {
+        session.as_ref().ok_or_else(|| {
+            DistributedProverError::WorkerError("no program has been set up".to_string())
+        })
+    }
+
+    fn trace(
+        &self,
+        session: &Session<C>,
+        checkpoint: &[u8],
+    ) -> Result<(Vec<ExecutionRecord>, ExecutionReport), DistributedProverError> {
+        let state: ExecutionState = bincode::deserialize(checkpoint)?;
+        Ok(trace_checkpoint_with_precompiles::<CoreSC, C::Extension>(
+            session.program.clone(),
+            state,
+            session.opts.core_opts,
+            self.prover.core_shape_config.as_ref(),
+            &C::precompiles(),
+        ))
+    }
+
+    fn prove_records(
+        &self,
+        session: &Session<C>,
+        mut records: Vec<ExecutionRecord>,
+    ) -> Result<Vec<ShardProof<CoreSC>>, DistributedProverError> {
+        let core_prover = &self.prover.core_prover;
+        core_prover.machine().generate_dependencies(&mut records, &session.opts.core_opts, None);
+
+        let mut challenger = core_prover.config().challenger();
+        session.pk.observe_into(&mut challenger);
+
+        records
+            .into_iter()
+            .map(|mut record| {
+                if let Some(shape_config) = &self.prover.core_shape_config {
+                    shape_config
+                        .fix_shape(&mut record)
+                        .map_err(|e| DistributedProverError::WorkerError(e.to_string()))?;
+                }
+                let traces = core_prover.generate_traces(&record);
+                let data = core_prover.commit(&record, traces);
+                core_prover
+                    .open(&session.pk, data, &mut challenger.clone())
+                    .map_err(|e| DistributedProverError::WorkerError(e.to_string()))
+            })
+            .collect()
+    }
+
+    fn prove_reduce(
+        &self,
+        input: ZKMCircuitWitness,
+        opts: ZKMCoreOpts,
+    ) -> Result<ZKMReduceProof<InnerSC>, DistributedProverError> {
+        let prover = &self.prover;
+        let compress_prover = &prover.compress_prover;
+
+        // Get the program and witness stream.
+        let mut witness_stream = Vec::new();
+        let program = match input {
+            ZKMCircuitWitness::Core(input) => {
+                Witnessable::<InnerConfig>::write(&input, &mut witness_stream);
+                prover.recursion_program(&input)
+            }
+            ZKMCircuitWitness::Deferred(input) => {
+                Witnessable::<InnerConfig>::write(&input, &mut witness_stream);
+                prover.deferred_program(&input)
+            }
+            ZKMCircuitWitness::Compress(input) => {
+                let input_with_merkle = prover.make_merkle_proofs(input);
+                Witnessable::<InnerConfig>::write(&input_with_merkle, &mut witness_stream);
+                prover.compress_program(&input_with_merkle)
+            }
+        };
+
+        // Execute the runtime.
+        let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
+            program.clone(),
+            compress_prover.config().perm.clone(),
+        );
+        runtime.witness_stream = witness_stream.into();
+        runtime.run().map_err(|e| DistributedProverError::WorkerError(e.to_string()))?;
+
+        // Generate the dependencies and traces.
+        let mut records = vec![runtime.record];
+        compress_prover.machine().generate_dependencies(&mut records, &opts, None);
+        let record = records.into_iter().next().unwrap();
+        let traces = compress_prover.generate_traces(&record);
+
+        // Prove the node.
+        let (pk, vk) = compress_prover.setup(&program);
+        let mut challenger = compress_prover.config().challenger();
+        pk.observe_into(&mut challenger);
+        let data = compress_prover.commit(&record, traces);
+        let proof = compress_prover
+            .open(&pk, data, &mut challenger)
+            .map_err(|e| DistributedProverError::WorkerError(e.to_string()))?;
+
+        Ok(ZKMReduceProof { vk, proof })
+    }
+}
@@ -0,0 +1,407 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+use p3_commit::Pcs;
+use p3_field::FieldAlgebra;
+use p3_koala_bear::KoalaBear;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_util::log2_ceil_usize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::instrument;
+use zkm_core_executor::{
+    syscalls::SyscallCode, ExecutionError, ExecutionRecord, Executor, MipsAirId, ZKMContext,
+};
+use zkm_core_machine::{io::ZKMStdin, shape::CoreShapeError};
+use zkm_recursion_core::shape::RecursionShapeConfig;
+use zkm_stark::{
+    air::MachineAir, shape::Shape, MachineProver, SecurityConfig, StarkGenericConfig, Val,
+    ZKMProverOpts, DIGEST_SIZE,
+};
+
+use crate::{components::ZKMProverComponents, CompressAir, ZKMProver, REDUCE_BATCH_SIZE};
+
+/// The log2 height of the trace committed to by the prover benchmark.
+const BENCHMARK_LOG_HEIGHT: usize = 16;
+/// The width of the trace committed to by the prover benchmark.
+const BENCHMARK_WIDTH: usize = 64;
+/// How many times the benchmark is run, keeping the fastest run.
+const BENCHMARK_RUNS: usize = 3;
+
+/// The number of trace commitments of a shard: preprocessed, main, permutation and quotient.
+const NUM_COMMIT_ROUNDS: u64 = 4;
+/// The size of a base field element in a proof.
+const BASE_BYTES: u64 = 4;
+/// The size of an extension field element in a proof.
+const EXT_BYTES: u64 = 4 * BASE_BYTES;
+/// The size of a Merkle tree digest in a proof.
+const DIGEST_BYTES: u64 = DIGEST_SIZE as u64 * BASE_BYTES;
+
+/// The kind of events a shard proves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShardKind {
+    /// Executed instructions.
+    Core,
+    /// The events of a single precompile.
+    Precompile,
+    /// The initialization and finalization of memory.
+    Memory,
+}
+
+/// The shape of a shard predicted by [`ZKMProver::estimate`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardEstimate {
+    /// What the shard proves.
+    pub kind: ShardKind,
+    /// The log2 height of the trace of every chip in the shard.
+    pub log2_heights: BTreeMap<String, usize>,
+    /// The number of cells in the low-degree extensions of the traces of the shard.
+    pub lde_cells: u64,
+}
+
+/// The cost of proving a program, predicted from its execution without generating traces.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZKMEstimate {
+    /// The number of cycles the program ran for.
+    pub cycles: u64,
+    /// The shards of the core proof, in proving order.
+    pub shards: Vec<ShardEstimate>,
+    /// The number of recursion programs proven to compress the shards into a single proof.
+    pub recursion_nodes: usize,
+    /// The number of cells in the low-degree extensions of all the shards.
+    pub core_lde_cells: u64,
+    /// The number of cells in the low-degree extensions of all the recursion programs.
+    pub recursion_lde_cells: u64,
+    /// The approximate size of the core proof, in bytes.
+    pub core_proof_size: u64,
+    /// The approximate size of the compressed proof, in bytes.
+    pub compressed_proof_size: u64,
+    /// The number of cells of low-degree extensions this machine commits to per second.
+    pub commit_throughput: f64,
+    /// The time to commit to every shard at the benchmarked rate.
+    pub core_time: Duration,
+    /// The time to commit to every recursion program at the benchmarked rate.
+    pub compress_time: Duration,
+}
+
+impl ZKMEstimate {
+    /// The largest log2 height of every chip across the shards.
+    pub fn max_log2_heights(&self) -> BTreeMap<String, usize> {
+        let mut max_log2_heights = BTreeMap::new();
+        for shard in self.shards.iter() {
+            for (chip, log2_height) in shard.log2_heights.iter() {
+                let max = max_log2_heights.entry(chip.clone()).or_insert(0);
+                *max = (*max).max(*log2_height);
+            }
+        }
+        max_log2_heights
+    }
+
+    /// The estimated time to generate the compressed proof.
+    pub fn total_time(&self) -> Duration {
+        self.core_time + self.compress_time
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ZKMEstimateError {
+    #[error("invalid program: {0}")]
+    InvalidProgram(String),
+    #[error("failed to execute program: {0}")]
+    ExecutionError(#[from] ExecutionError),
+    #[error("failed to fix shape: {0}")]
+    ShapeError(#[from] CoreShapeError),
+}
+
+impl<C: ZKMProverComponents> ZKMProver<C> {
+    /// Estimates the shards, proof sizes and proving time of a program by executing it, without
+    /// generating any traces.
+    ///
+    /// Shards are predicted from the event counts of the executor, and padded to the shapes the
+    /// prover would fix them to. The times assume that committing to the traces dominates
+    /// proving, and extrapolate a benchmark of the commitment scheme on this machine, so they
+    /// are a lower bound on the actual proving time.
+    #[instrument(name = "estimate", level = "info", skip_all)]
+    pub fn estimate<'a>(
+        &'a self,
+        elf: &[u8],
+        stdin: &ZKMStdin,
+        opts: ZKMProverOpts,
+        mut context: ZKMContext<'a>,
+    ) -> Result<ZKMEstimate, ZKMEstimateError> {
+        context.subproof_verifier = Some(self);
+        context.precompiles.extend(C::precompiles());
+        let program =
+            self.get_program(elf).map_err(|e| ZKMEstimateError::InvalidProgram(e.to_string()))?;
+        let preprocessed_shape = program.preprocessed_shape.clone().unwrap_or_else(|| {
+            [
+                (MipsAirId::Program, log2_ceil_usize(program.instructions.len())),
+                (MipsAirId::Byte, 16),
+            ]
+            .into_iter()
+            .collect()
+        });
+        let program_image_size = program.image.len() as u64;
+
+        // Execute the program, stopping shards where the prover would.
+        let core_opts = opts.core_opts;
+        let mut runtime = Executor::with_context(program, core_opts, context);
+        runtime.maximal_shapes = self.core_shape_config.as_ref().map(|config| {
+            config.maximal_core_shapes(core_opts.shard_size.ilog2() as usize).into_iter().collect()
+        });
+        runtime.shard_event_counts = Some(Vec::new());
+        runtime.write_vecs(&stdin.buffer);
+        runtime.write_files(&stdin.files);
+        for (proof, vk) in stdin.proofs.iter() {
+            runtime.write_proof(proof.clone(), vk.clone());
+        }
+        runtime.run_fast()?;
+
+        let shape_config = self.core_shape_config.as_ref();
+        let mut shapes = Vec::new();
+
+        // The shards of executed instructions.
+        let shard_event_counts = runtime.shard_event_counts.take().unwrap_or_default();
+        for event_counts in shard_event_counts.iter().filter(|counts| counts[MipsAirId::Cpu] > 0) {
+            let heights = MipsAirId::core()
+                .into_iter()
+                .chain([MipsAirId::Lt, MipsAirId::CloClz])
+                .map(|air| (air, event_counts[air] as usize))
+                .collect::<Vec<_>>();
+            let shape = match shape_config {
+                Some(config) => config
+                    .find_core_shape(&heights)
+                    .map(|(_, shape)| shape)
+                    .ok_or_else(|| shape_error(&heights))?,
+                None => shape_from_heights(&heights),
+            };
+            shapes.push((ShardKind::Core, shape));
+        }
+
+        // The shards of deferred precompile events.
+        let mut precompile_counts = BTreeMap::new();
+        for (syscall_code, count) in runtime.report.syscall_counts.iter() {
+            let precompile = if matches!(
+                syscall_code,
+                SyscallCode::SYS_READ | SyscallCode::SYS_GETRANDOM | SyscallCode::SYS_CLOCK_GETTIME
+            ) {
+                SyscallCode::SYS_READ
+            } else if syscall_code.linux_sys() != 0 {
+                SyscallCode::SYS_LINUX
+            } else if syscall_code.should_send() != 0 {
+                syscall_code.count_map()
+            } else {
+                continue;
+            };
+            if *count > 0 {
+                *precompile_counts.entry(precompile).or_insert(0) += *count as usize;
+            }
+        }
+        for (syscall_code, count) in precompile_counts {
+            if syscall_code.is_custom() {
+                tracing::warn!("skipping custom precompile {}", syscall_code);
+                continue;
+            }
+            let threshold =
+                ExecutionRecord::precompile_split_threshold(syscall_code, core_opts.split_opts);
+            for start in (0..count).step_by(threshold) {
+                let num_events = threshold.min(count - start);
+                let heights = [(MipsAirId::SyscallPrecompile, num_events)];
+                let shape = match shape_config {
+                    Some(config) => config
+                        .estimate_precompile_shape(syscall_code, num_events)
+                        .ok_or_else(|| shape_error(&heights))?,
+                    None => shape_from_heights(&heights),
+                };
+                shapes.push((ShardKind::Precompile, shape));
+            }
+        }
+
+        // The shards initializing and finalizing the touched memory.
+        let num_finalized = runtime.report.touched_memory_addresses;
+        let num_initialized = num_finalized.saturating_sub(program_image_size) + 1;
+        let memory_threshold = core_opts.split_opts.memory as u64;
+        for start in (0..num_finalized.max(num_initialized)).step_by(memory_threshold as usize) {
+            let chunk = |total: u64| total.saturating_sub(start).min(memory_threshold) as usize;
+            let heights = [
+                (MipsAirId::MemoryGlobalInit, chunk(num_initialized)),
+                (MipsAirId::MemoryGlobalFinalize, chunk(num_finalized)),
+                (MipsAirId::Global, chunk(num_initialized) + chunk(num_finalized)),
+            ];
+            let shape = match shape_config {
+                Some(config) => {
+                    config.find_memory_shape(&heights).ok_or_else(|| shape_error(&heights))?
+                }
+                None => shape_from_heights(&heights),
+            };
+            shapes.push((ShardKind::Memory, shape));
+        }
+
+        // Measure the costs of the shards, with the costs of the chips of the core machine.
+        let core_costs = self
+            .core_prover
+            .machine()
+            .chips()
+            .iter()
+            .map(|chip| (chip.name(), chip.cost()))
+            .collect::<HashMap<_, _>>();
+        let core_security = opts.security;
+        let mut shards = Vec::new();
+        let mut core_proof_size = 0;
+        for (kind, mut shape) in shapes {
+            shape.extend(preprocessed_shape.clone());
+            let log2_heights = shape
+                .iter()
+                .filter(|(_, log2_height)| **log2_height > 0)
+                .map(|(air, log2_height)| (air.to_string(), *log2_height))
+                .collect::<BTreeMap<_, _>>();
+            let chips = chip_costs(&log2_heights, &core_costs);
+            core_proof_size += estimate_shard_proof_size(&chips, core_security);
+            shards.push(ShardEstimate {
+                kind,
+                lde_cells: estimate_lde_cells(&chips, core_security),
+                log2_heights,
+            });
+        }
+        let core_lde_cells = shards.iter().map(|shard| shard.lde_cells).sum::<u64>();
+
+        // Every shard and deferred proof is verified by a recursion program, which are then
+        // reduced in batches until a single proof is left.
+        let recursion_nodes = num_recursion_nodes(shards.len() + stdin.proofs.len());
+        let compress_costs = self
+            .compress_prover
+            .machine()
+            .chips()
+            .iter()
+            .map(|chip| (chip.name(), chip.cost()))
+            .collect::<HashMap<_, _>>();
+        let compress_shape = match &self.compress_shape_config {
+            Some(config) => config.first().cloned(),
+            None => RecursionShapeConfig::<KoalaBear, CompressAir<KoalaBear>>::default()
+                .first()
+                .cloned(),
+        }
+        .unwrap_or_default();
+        let compress_chips =
+            chip_costs(&compress_shape.into_iter().collect::<BTreeMap<_, _>>(), &compress_costs);
+        let compress_security = self.compress_prover.config().security();
+        let recursion_lde_cells =
+            recursion_nodes as u64 * estimate_lde_cells(&compress_chips, compress_security);
+        let compressed_proof_size = estimate_shard_proof_size(&compress_chips, compress_security);
+
+        let commit_throughput = benchmark_commit_throughput(self.core_prover.config());
+        let time_for = |cells: u64| Duration::from_secs_f64(cells as f64 / commit_throughput);
+
+        Ok(ZKMEstimate {
+            cycles: runtime.state.global_clk,
+            shards,
+            recursion_nodes,
+            core_lde_cells,
+            recursion_lde_cells,
+            core_proof_size,
+            compressed_proof_size,
+            commit_throughput,
+            core_time: time_for(core_lde_cells),
+            compress_time: time_for(recursion_lde_cells),
+        })
+    }
+}
+
+/// Measures how many cells of low-degree extensions the commitment scheme of `config` commits to
+/// per second on this machine.
+fn benchmark_commit_throughput<SC: StarkGenericConfig>(config: &SC) -> f64 {
+    let pcs = config.pcs();
+    let height = 1 << BENCHMARK_LOG_HEIGHT;
+    let values = (0..height * BENCHMARK_WIDTH)
+        .map(|i| Val::<SC>::from_wrapped_u32((i as u32).wrapping_mul(0x9E37_79B9)))
+        .collect::<Vec<_>>();
+    let trace = RowMajorMatrix::new(values, BENCHMARK_WIDTH);
+
+    let elapsed = (0..BENCHMARK_RUNS)
+        .map(|_| {
+            let domain = pcs.natural_domain_for_degree(height);
+            let trace = trace.clone();
+            let start = Instant::now();
+            let _ = pcs.commit(vec![(domain, trace)]);
+            start.elapsed()
+        })
+        .min()
+        .unwrap();
+    let lde_cells = (height * BENCHMARK_WIDTH) << config.security().log_blowup;
+    lde_cells as f64 / elapsed.as_secs_f64()
+}
+
+/// The number of recursion programs proven to reduce `num_leaves` proofs to one.
+fn num_recursion_nodes(num_leaves: usize) -> usize {
+    let mut num_nodes = num_leaves;
+    let mut num_layer_inputs = num_leaves;
+    while num_layer_inputs > 1 {
+        num_layer_inputs = num_layer_inputs.div_ceil(REDUCE_BATCH_SIZE);
+        num_nodes += num_layer_inputs;
+    }
+    num_nodes
+}
+
+/// Pairs the cost of every chip in a shape with its log2 height.
+fn chip_costs(
+    log2_heights: &BTreeMap<String, usize>,
+    costs: &HashMap<String, u64>,
+) -> Vec<(u64, usize)> {
+    log2_heights
+        .iter()
+        .filter_map(|(chip, log2_height)| costs.get(chip).map(|cost| (*cost, *log2_height)))
+        .collect()
+}
+
+/// The number of cells in the low-degree extensions of the traces of a shard.
+fn estimate_lde_cells(chips: &[(u64, usize)], security: SecurityConfig) -> u64 {
+    chips.iter().map(|(cost, log2_height)| cost << (log2_height + security.log_blowup)).sum()
+}
+
+/// Approximates the size of a shard proof from the widths and heights of its traces.
+fn estimate_shard_proof_size(chips: &[(u64, usize)], security: SecurityConfig) -> u64 {
+    let width = chips.iter().map(|(cost, _)| cost).sum::<u64>();
+    let log2_max_height = chips.iter().map(|(_, log2_height)| *log2_height).max().unwrap_or(0);
+    let log2_max_lde_height = (log2_max_height + security.log_blowup) as u64;
+
+    // Every query opens a row of each commitment, with its Merkle path.
+    let trace_bytes = width * BASE_BYTES + NUM_COMMIT_ROUNDS * log2_max_lde_height * DIGEST_BYTES;
+    // And a sibling with its Merkle path in every folding round of FRI.
+    let fri_bytes = (1..=log2_max_height as u64)
+        .map(|round| EXT_BYTES + (log2_max_lde_height - round) * DIGEST_BYTES)
+        .sum::<u64>();
+    // The traces are also opened at two out-of-domain points.
+    let opened_values_bytes = 2 * width * EXT_BYTES;
+
+    security.num_queries() as u64 * (trace_bytes + fri_bytes) + opened_values_bytes
+}
+
+/// A shape fitting `heights` exactly, for when the prover doesn't fix shapes.
+fn shape_from_heights(heights: &[(MipsAirId, usize)]) -> Shape<MipsAirId> {
+    heights
+        .iter()
+        .filter(|(_, height)| *height > 0)
+        .map(|(air, height)| (*air, log2_ceil_usize(*height)))
+        .collect()
+}
+
+fn shape_error(heights: &[(MipsAirId, usize)]) -> ZKMEstimateError {
+    CoreShapeError::ShapeError(
+        heights.iter().map(|(air, height)| (air.to_string(), log2_ceil_usize(*height))).collect(),
+    )
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_num_recursion_nodes() {
+        assert_eq!(num_recursion_nodes(1), 1);
+        assert_eq!(num_recursion_nodes(2), 3);
+        assert_eq!(num_recursion_nodes(5), 5 + 3 + 2 + 1);
+    }
+}
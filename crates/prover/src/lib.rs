@@ -11,9 +11,13 @@
 #![allow(clippy::new_without_default)]
 #![allow(clippy::collapsible_else_if)]
 
+pub mod aggregate;
 pub mod build;
 pub mod components;
+pub mod distributed;
+pub mod estimate;
 pub mod shapes;
+pub mod step;
 pub mod types;
 pub mod utils;
 pub mod verify;
@@ -44,7 +48,7 @@ use zkm_core_machine::{
     mips::MipsAir,
     reduce::ZKMReduceProof,
     shape::CoreShapeConfig,
-    utils::{concurrency::TurnBasedSync, ZKMCoreProverError},
+    utils::{concurrency::TurnBasedSync, ProvingWorkDir, ZKMCoreProverError},
 };
 use zkm_primitives::{hash_deferred_proof, io::ZKMPublicValues};
 use zkm_recursion_circuit::{
@@ -65,8 +69,8 @@ use zkm_recursion_compiler::{
     config::InnerConfig,
     ir::{Builder, Witness},
 };
+pub use zkm_recursion_core::air::RecursionPublicValues;
 use zkm_recursion_core::{
-    air::RecursionPublicValues,
     machine::RecursionAir,
     runtime::ExecutionRecord,
     shape::{RecursionShape, RecursionShapeConfig},
@@ -77,8 +81,8 @@ pub use zkm_recursion_gnark_ffi::proof::{Groth16Bn254Proof, PlonkBn254Proof};
 use zkm_recursion_gnark_ffi::{groth16_bn254::Groth16Bn254Prover, plonk_bn254::PlonkBn254Prover};
 use zkm_stark::{
     air::PublicValues, koala_bear_poseidon2::KoalaBearPoseidon2, Challenge, MachineProver,
-    ShardProof, StarkGenericConfig, StarkVerifyingKey, Val, Word, ZKMCoreOpts, ZKMProverOpts,
-    DIGEST_SIZE,
+    SecurityConfig, ShardProof, StarkGenericConfig, StarkVerifyingKey, Val, Word, ZKMCoreOpts,
+    ZKMProverOpts, DIGEST_SIZE,
 };
 use zkm_stark::{shape::OrderedShape, MachineProvingKey};
 
@@ -132,7 +136,8 @@ pub struct ZKMProver<C: ZKMProverComponents = DefaultProverComponents> {
     pub wrap_prover: C::WrapProver,
 
     /// The cache of compiled recursion programs.
-    pub lift_programs_lru: Mutex<LruCache<ZKMRecursionShape, Arc<RecursionProgram<KoalaBear>>>>,
+    pub lift_programs_lru:
+        Mutex<LruCache<(ZKMRecursionShape, SecurityConfig), Arc<RecursionProgram<KoalaBear>>>>,
 
     /// The number of cache misses for recursion programs.
     pub lift_cache_misses: AtomicUsize,
@@ -153,7 +158,7 @@ pub struct ZKMProver<C: ZKMProverComponents = DefaultProverComponents> {
     pub recursion_vk_tree: MerkleTree<KoalaBear, InnerSC>,
 
     /// The core shape configuration.
-    pub core_shape_config: Option<CoreShapeConfig<KoalaBear>>,
+    pub core_shape_config: Option<CoreShapeConfig<KoalaBear, C::Extension>>,
 
     /// The recursion shape configuration.
     pub compress_shape_config: Option<RecursionShapeConfig<KoalaBear, CompressAir<KoalaBear>>>,
@@ -178,7 +183,7 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
     /// Creates a new [ZKMProver] with lazily initialized components.
     pub fn uninitialized() -> Self {
         // Initialize the provers.
-        let core_machine = MipsAir::machine(CoreSC::default());
+        let core_machine = MipsAir::machine_with_extensions(CoreSC::default(), C::extensions());
         let core_prover = C::CoreProver::new(core_machine);
 
         let compress_machine = CompressAir::compress_machine(InnerSC::default());
@@ -202,7 +207,10 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
         let core_shape_config = env::var("FIX_CORE_SHAPES")
             .map(|v| v.eq_ignore_ascii_case("true"))
             .unwrap_or(true)
-            .then_some(CoreShapeConfig::default());
+            .then(|| {
+                let config = CoreShapeConfig::<KoalaBear, C::Extension>::default();
+                C::extensions().into_iter().fold(config, CoreShapeConfig::with_extension)
+            });
 
         let recursion_shape_config = env::var("FIX_RECURSION_SHAPES")
             .map(|v| v.eq_ignore_ascii_case("true"))
@@ -276,17 +284,45 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
     pub fn initialize(&mut self) {}
 
     /// Creates a proving key and a verifying key for a given MIPS ELF.
-    #[instrument(name = "setup", level = "debug", skip_all)]
     pub fn setup(&self, elf: &[u8]) -> (ZKMProvingKey, ZKMVerifyingKey) {
+        self.setup_with_security(elf, self.core_prover.config().security())
+    }
+
+    /// Creates a proving key and a verifying key for a given MIPS ELF, for core proofs with the
+    /// given security parameters.
+    #[instrument(name = "setup", level = "debug", skip_all)]
+    pub fn setup_with_security(
+        &self,
+        elf: &[u8],
+        security: SecurityConfig,
+    ) -> (ZKMProvingKey, ZKMVerifyingKey) {
         let program = self.get_program(elf).unwrap();
-        let (pk, vk) = self.core_prover.setup(&program);
-        let vk = ZKMVerifyingKey { vk };
-        let pk = ZKMProvingKey {
-            pk: self.core_prover.pk_to_host(&pk),
-            elf: elf.to_vec(),
-            vk: vk.clone(),
-        };
-        (pk, vk)
+        self.with_core_prover(security, |core_prover| {
+            let (pk, vk) = core_prover.setup(&program);
+            let vk = ZKMVerifyingKey { vk };
+            let pk = ZKMProvingKey {
+                pk: core_prover.pk_to_host(&pk),
+                elf: elf.to_vec(),
+                vk: vk.clone(),
+            };
+            (pk, vk)
+        })
+    }
+
+    /// Runs `f` with a core prover for the given security parameters, which is
+    /// [`ZKMProver::core_prover`] unless they differ from its own.
+    pub fn with_core_prover<R>(
+        &self,
+        security: SecurityConfig,
+        f: impl FnOnce(&C::CoreProver) -> R,
+    ) -> R {
+        if self.core_prover.config().security() == security {
+            f(&self.core_prover)
+        } else {
+            let machine =
+                MipsAir::machine_with_extensions(CoreSC::with_security(security), C::extensions());
+            f(&C::CoreProver::new(machine))
+        }
     }
 
     /// Get a program with an allowed preprocessed shape.
@@ -307,10 +343,12 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
         mut context: ZKMContext<'a>,
     ) -> Result<(ZKMPublicValues, ExecutionReport), ExecutionError> {
         context.subproof_verifier = Some(self);
+        context.precompiles.extend(C::precompiles());
         let program = self.get_program(elf).unwrap();
         let opts = ZKMCoreOpts::default();
         let mut runtime = Executor::with_context(program, opts, context);
         runtime.write_vecs(&stdin.buffer);
+        runtime.write_files(&stdin.files);
         for (proof, vkey) in stdin.proofs.iter() {
             runtime.write_proof(proof.clone(), vkey.clone());
         }
@@ -322,25 +360,50 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
     /// the core prover. Uses the provided context.
     #[instrument(name = "prove_core", level = "info", skip_all)]
     pub fn prove_core<'a>(
+        &'a self,
+        pk: &ZKMProvingKey,
+        stdin: &ZKMStdin,
+        opts: ZKMProverOpts,
+        context: ZKMContext<'a>,
+    ) -> Result<ZKMCoreProof, ZKMCoreProverError> {
+        self.prove_core_with_work_dir(pk, stdin, opts, context, None)
+    }
+
+    /// Generate shard proofs like [`ZKMProver::prove_core`], persisting checkpoints and shard
+    /// proofs to `work_dir` and skipping the shards a previous run already proved.
+    #[instrument(name = "prove_core", level = "info", skip_all)]
+    pub fn prove_core_with_work_dir<'a>(
         &'a self,
         pk: &ZKMProvingKey,
         stdin: &ZKMStdin,
         opts: ZKMProverOpts,
         mut context: ZKMContext<'a>,
+        work_dir: Option<&ProvingWorkDir>,
     ) -> Result<ZKMCoreProof, ZKMCoreProverError> {
         context.subproof_verifier = Some(self);
+        context.precompiles.extend(C::precompiles());
         let program = self.get_program(&pk.elf).unwrap();
-        let pk = self.core_prover.pk_to_device(&pk.pk);
         let (proof, public_values_stream, cycles) =
-            zkm_core_machine::utils::prove_with_context::<_, C::CoreProver>(
-                &self.core_prover,
-                &pk,
-                program,
-                stdin,
-                opts.core_opts,
-                context,
-                self.core_shape_config.as_ref(),
-            )?;
+            self.with_core_prover(opts.security, |core_prover| {
+                // The preprocessed traces are committed to with the blowup of the key's parameters,
+                // so a key set up with other parameters has to be derived again.
+                let pk = if pk.vk.vk.security == opts.security {
+                    core_prover.pk_to_device(&pk.pk)
+                } else {
+                    tracing::warn!("proving key was set up with other security parameters");
+                    core_prover.setup(&program).0
+                };
+                zkm_core_machine::utils::prove_with_work_dir::<_, _, C::CoreProver>(
+                    core_prover,
+                    &pk,
+                    program,
+                    stdin,
+                    opts.core_opts,
+                    context,
+                    self.core_shape_config.as_ref(),
+                    work_dir,
+                )
+            })?;
         Self::check_for_high_cycles(cycles);
         let public_values = ZKMPublicValues::from(&public_values_stream);
         Ok(ZKMCoreProof {
@@ -357,15 +420,19 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
     ) -> Arc<RecursionProgram<KoalaBear>> {
         let mut cache = self.lift_programs_lru.lock().unwrap_or_else(|e| e.into_inner());
         cache
-            .get_or_insert(input.shape(), || {
+            .get_or_insert((input.shape(), input.vk.security), || {
                 let misses = self.lift_cache_misses.fetch_add(1, Ordering::Relaxed);
                 tracing::debug!("core cache miss, misses: {}", misses);
                 // Get the operations.
                 let builder_span = tracing::debug_span!("build recursion program").entered();
                 let mut builder = Builder::<InnerConfig>::default();
 
+                // The shards are verified with the parameters they were proven with.
+                let security = input.vk.security;
                 let input = input.read(&mut builder);
-                ZKMRecursiveVerifier::verify(&mut builder, self.core_prover.machine(), input);
+                self.with_core_prover(security, |core_prover| {
+                    ZKMRecursiveVerifier::verify(&mut builder, core_prover.machine(), input)
+                });
                 let operations = builder.into_operations();
                 builder_span.exit();
 
@@ -566,6 +633,8 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
                 end_execution_shard: last_proof_pv.execution_shard,
                 init_addr_bits: last_proof_pv.last_init_addr_bits,
                 finalize_addr_bits: last_proof_pv.last_finalize_addr_bits,
+                random_seed: last_proof_pv.random_seed,
+                epoch: last_proof_pv.epoch,
                 committed_value_digest: last_proof_pv.committed_value_digest,
                 deferred_proofs_digest: last_proof_pv.deferred_proofs_digest,
             });
@@ -606,6 +675,68 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
         deferred_proofs: Vec<ZKMReduceProof<InnerSC>>,
         opts: ZKMProverOpts,
     ) -> Result<ZKMReduceProof<InnerSC>, ZKMRecursionProverError> {
+        self.compress_with_work_dir(vk, proof, deferred_proofs, opts, None)
+    }
+
+    /// Reduce shard proofs like [`ZKMProver::compress`], persisting the proof of every tree node
+    /// to `work_dir` and skipping the nodes a previous run already proved.
+    #[instrument(name = "compress", level = "info", skip_all)]
+    pub fn compress_with_work_dir(
+        &self,
+        vk: &ZKMVerifyingKey,
+        proof: ZKMCoreProof,
+        deferred_proofs: Vec<ZKMReduceProof<InnerSC>>,
+        opts: ZKMProverOpts,
+        work_dir: Option<&ProvingWorkDir>,
+    ) -> Result<ZKMReduceProof<InnerSC>, ZKMRecursionProverError> {
+        self.compress_shards(vk, proof, deferred_proofs, opts, work_dir, true, true)
+    }
+
+    /// Reduce the shard proofs of a part of an execution, see [`ZKMProver::compress_step`].
+    ///
+    /// Unless `is_first_step` is set, the shards don't start the execution. Unless
+    /// `is_last_step` is set, the resulting proof is not complete, since the execution continues.
+    /// Checks that core proofs made with `security` can be compressed.
+    ///
+    /// The allowed recursion vks only include the lift programs for the default parameters, so
+    /// other parameters can only be compressed when the vks are not verified.
+    pub fn check_compress_security(
+        &self,
+        security: SecurityConfig,
+    ) -> Result<(), ZKMRecursionProverError> {
+        if self.vk_verification && security != self.core_prover.config().security() {
+            return Err(ZKMRecursionProverError::UnsupportedSecurity(security));
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn compress_shards(
+        &self,
+        vk: &ZKMVerifyingKey,
+        proof: ZKMCoreProof,
+        deferred_proofs: Vec<ZKMReduceProof<InnerSC>>,
+        opts: ZKMProverOpts,
+        work_dir: Option<&ProvingWorkDir>,
+        is_first_step: bool,
+        is_last_step: bool,
+    ) -> Result<ZKMReduceProof<InnerSC>, ZKMRecursionProverError> {
+        // The lift programs verify the shards with the parameters of the key.
+        let security = vk.vk.security;
+        if let Some(shard_proof) = proof.proof.0.iter().find(|proof| proof.security != security) {
+            return Err(ZKMRecursionProverError::SecurityMismatch(shard_proof.security, security));
+        }
+        self.check_compress_security(security)?;
+
+        // The deferred digest only commits to the vk and committed values of a deferred proof.
+        for deferred_proof in deferred_proofs.iter() {
+            let public_values: &RecursionPublicValues<KoalaBear> =
+                deferred_proof.proof.public_values.as_slice().borrow();
+            if public_values.has_linux_env() {
+                return Err(ZKMRecursionProverError::UncommittedLinuxEnv("defer"));
+            }
+        }
+
         // The batch size for reducing two layers of recursion.
         let batch_size = REDUCE_BATCH_SIZE;
         // The batch size for reducing the first layer of recursion.
@@ -613,8 +744,14 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
 
         let shard_proofs = &proof.proof.0;
 
-        let first_layer_inputs =
+        let mut first_layer_inputs =
             self.get_first_layer_inputs(vk, shard_proofs, &deferred_proofs, first_layer_batch_size);
+        for input in first_layer_inputs.iter_mut() {
+            if let ZKMCircuitWitness::Core(input) = input {
+                input.is_first_shard &= is_first_step;
+                input.is_complete &= is_last_step;
+            }
+        }
 
         // Calculate the expected height of the tree.
         let mut expected_height = if first_layer_inputs.len() == 1 { 0 } else { 1 };
@@ -625,6 +762,11 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
             expected_height += 1;
         }
 
+        // The first error of the work dir. The pipeline proves a node whose proof can't be loaded
+        // and finishes, so that no worker waits on a turn that never comes.
+        let work_dir_error = Mutex::new(None);
+        let work_dir_error = &work_dir_error;
+
         // Generate the proofs.
         let span = tracing::Span::current().clone();
         let (vk, proof) = thread::scope(|s| {
@@ -648,6 +790,15 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
                 });
             }
 
+            // Create the channel for the compress proofs.
+            let proofs_sync = Arc::new(TurnBasedSync::new());
+            let (proofs_tx, proofs_rx) =
+                sync_channel::<(usize, usize, StarkVerifyingKey<InnerSC>, ShardProof<InnerSC>)>(
+                    num_first_layer_inputs * 2,
+                );
+            let proofs_tx = Arc::new(Mutex::new(proofs_tx));
+            let proofs_rx = Arc::new(Mutex::new(proofs_rx));
+
             // Spawn workers who generate the records and traces.
             let record_and_trace_sync = Arc::new(TurnBasedSync::new());
             let (record_and_trace_tx, record_and_trace_rx) =
@@ -665,12 +816,31 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
                 let record_and_trace_sync = Arc::clone(&record_and_trace_sync);
                 let record_and_trace_tx = Arc::clone(&record_and_trace_tx);
                 let input_rx = Arc::clone(&input_rx);
+                let proofs_sync = Arc::clone(&proofs_sync);
+                let proofs_tx = Arc::clone(&proofs_tx);
                 let span = tracing::debug_span!("generate records and traces");
                 s.spawn(move || {
                     let _span = span.enter();
                     loop {
                         let received = { input_rx.lock().unwrap().recv() };
                         if let Ok((index, height, input)) = received {
+                            // If a previous run already proved this node, send its proof directly.
+                            let saved = work_dir.and_then(|dir| {
+                                dir.load_reduce_proof(index).unwrap_or_else(|err| {
+                                    work_dir_error.lock().unwrap().get_or_insert(err);
+                                    None
+                                })
+                            });
+                            if let Some(ZKMReduceProof { vk, proof }) = saved {
+                                record_and_trace_sync.wait_for_turn(index);
+                                record_and_trace_sync.advance_turn();
+
+                                proofs_sync.wait_for_turn(index);
+                                proofs_tx.lock().unwrap().send((index, height, vk, proof)).unwrap();
+                                proofs_sync.advance_turn();
+                                continue;
+                            }
+
                             // Get the program and witness stream.
                             let (program, witness_stream) = tracing::debug_span!(
                                 "get program and witness stream"
@@ -752,13 +922,6 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
             }
 
             // Spawn workers who generate the compress proofs.
-            let proofs_sync = Arc::new(TurnBasedSync::new());
-            let (proofs_tx, proofs_rx) =
-                sync_channel::<(usize, usize, StarkVerifyingKey<InnerSC>, ShardProof<InnerSC>)>(
-                    num_first_layer_inputs * 2,
-                );
-            let proofs_tx = Arc::new(Mutex::new(proofs_tx));
-            let proofs_rx = Arc::new(Mutex::new(proofs_rx));
             let mut prover_handles = Vec::new();
             for _ in 0..opts.recursion_opts.shard_batch_size {
                 let prover_sync = Arc::clone(&proofs_sync);
@@ -810,6 +973,15 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
                                     )
                                     .unwrap();
 
+                                // Persist the proof so a restarted run can skip this node.
+                                if let Some(work_dir) = work_dir {
+                                    let proof =
+                                        ZKMReduceProof { vk: vk.clone(), proof: proof.clone() };
+                                    if let Err(err) = work_dir.save_reduce_proof(index, &proof) {
+                                        work_dir_error.lock().unwrap().get_or_insert(err);
+                                    }
+                                }
+
                                 // Wait for our turn to update the state.
                                 prover_sync.wait_for_turn(index);
 
@@ -867,7 +1039,8 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
 
                             let next_input_height = inputs[0].1 + 1;
 
-                            let is_complete = next_input_height == expected_height;
+                            let is_root = next_input_height == expected_height;
+                            let is_complete = is_root && is_last_step;
 
                             let vks_and_proofs = inputs
                                 .into_iter()
@@ -888,7 +1061,7 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
                             count += 1;
 
                             // If we're at the root of the tree, stop generating inputs.
-                            if is_complete {
+                            if is_root {
                                 break;
                             }
 
@@ -919,6 +1092,10 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
             (vk, proof)
         });
 
+        if let Some(err) = work_dir_error.lock().unwrap().take() {
+            return Err(ZKMRecursionProverError::IoError(err));
+        }
+
         Ok(ZKMReduceProof { vk, proof })
     }
 
@@ -978,6 +1155,14 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
         opts: ZKMProverOpts,
     ) -> Result<ZKMReduceProof<OuterSC>, ZKMRecursionProverError> {
         let ZKMReduceProof { vk: compressed_vk, proof: compressed_proof } = compressed_proof;
+
+        // The SNARK only exposes the vk and committed value digests.
+        let public_values: &RecursionPublicValues<KoalaBear> =
+            compressed_proof.public_values.as_slice().borrow();
+        if public_values.has_linux_env() {
+            return Err(ZKMRecursionProverError::UncommittedLinuxEnv("wrap"));
+        }
+
         let input = ZKMCompressWitnessValues {
             vks_and_proofs: vec![(compressed_vk, compressed_proof)],
             is_complete: true,
@@ -1489,9 +1674,8 @@ pub mod tests {
     /// Tests an end-to-end workflow of proving a program across the entire proof generation
     /// pipeline.
     ///
-    /// Add `FRI_QUERIES`=1 to your environment for faster execution. Should only take a few minutes
-    /// on a Mac M2. Note: This test always re-builds the plonk bn254 artifacts, so setting ZKM_DEV
-    /// is not needed.
+    /// Note: This test always re-builds the plonk bn254 artifacts, so setting ZKM_DEV is not
+    /// needed.
     #[test]
     #[serial]
     #[ignore]
@@ -1515,9 +1699,8 @@ pub mod tests {
     /// Tests an end-to-end workflow of proving a program across the entire proof generation
     /// pipeline.
     ///
-    /// Add `FRI_QUERIES`=1 to your environment for faster execution. Should only take a few minutes
-    /// on a Mac M2. Note: This test always re-builds the plonk bn254 artifacts, so setting ZKM_DEV
-    /// is not needed.
+    /// Note: This test always re-builds the plonk bn254 artifacts, so setting ZKM_DEV is not
+    /// needed.
     #[test]
     #[serial]
     #[ignore]
@@ -1548,4 +1731,23 @@ pub mod tests {
         setup_logger();
         test_e2e_with_deferred_proofs_prover::<DefaultProverComponents>(ZKMProverOpts::default())
     }
+
+    /// Tests the arkworks Groth16 circuit against the constraints and witness of a real wrap
+    /// proof, and that it rejects a tampered witness.
+    #[test]
+    #[serial]
+    #[cfg(feature = "ark-groth16")]
+    fn test_ark_groth16_wrap_constraints() {
+        use p3_bn254_fr::Bn254Fr;
+
+        setup_logger();
+        let (wrap_vk, wrapped_proof) = build::dummy_proof();
+        let (constraints, witness) = build_constraints_and_witness(&wrap_vk, &wrapped_proof);
+        Groth16Bn254Prover::test(constraints.clone(), witness.clone());
+
+        let mut tampered = witness;
+        tampered.committed_values_digest += Bn254Fr::ONE;
+        let result = std::panic::catch_unwind(|| Groth16Bn254Prover::test(constraints, tampered));
+        assert!(result.is_err(), "the circuit accepted a tampered witness");
+    }
 }
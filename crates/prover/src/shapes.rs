@@ -13,10 +13,11 @@ use thiserror::Error;
 use p3_field::FieldAlgebra;
 use p3_koala_bear::KoalaBear;
 use serde::{Deserialize, Serialize};
-use zkm_core_machine::shape::CoreShapeConfig;
+use zkm_core_machine::{mips::MipsExtension, shape::CoreShapeConfig};
 use zkm_recursion_circuit::machine::{
-    ZKMCompressWithVKeyWitnessValues, ZKMCompressWithVkeyShape, ZKMDeferredShape,
-    ZKMDeferredWitnessValues, ZKMRecursionShape, ZKMRecursionWitnessValues,
+    ZKMAggregateShape, ZKMAggregateWitnessValues, ZKMCompressWithVKeyWitnessValues,
+    ZKMCompressWithVkeyShape, ZKMDeferredShape, ZKMDeferredWitnessValues, ZKMRecursionShape,
+    ZKMRecursionWitnessValues,
 };
 use zkm_recursion_core::{
     shape::{RecursionShape, RecursionShapeConfig},
@@ -32,6 +33,7 @@ pub enum ZKMProofShape {
     Compress(Vec<OrderedShape>),
     Deferred(OrderedShape),
     Shrink(OrderedShape),
+    Aggregate(Vec<OrderedShape>),
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -40,6 +42,7 @@ pub enum ZKMCompressProgramShape {
     Compress(ZKMCompressWithVkeyShape),
     Deferred(ZKMDeferredShape),
     Shrink(ZKMCompressWithVkeyShape),
+    Aggregate(ZKMAggregateShape),
 }
 
 impl ZKMCompressProgramShape {
@@ -305,8 +308,8 @@ pub fn build_vk_map_to_file<C: ZKMProverComponents>(
 }
 
 impl ZKMProofShape {
-    pub fn generate<'a>(
-        core_shape_config: &'a CoreShapeConfig<KoalaBear>,
+    pub fn generate<'a, E: MipsExtension<KoalaBear>>(
+        core_shape_config: &'a CoreShapeConfig<KoalaBear, E>,
         recursion_shape_config: &'a RecursionShapeConfig<KoalaBear, CompressAir<KoalaBear>>,
         reduce_batch_size: usize,
     ) -> impl Iterator<Item = Self> + 'a {
@@ -326,6 +329,9 @@ impl ZKMProofShape {
                     .get_all_shape_combinations(1)
                     .map(|mut x| Self::Shrink(x.pop().unwrap())),
             )
+            .chain((1..=reduce_batch_size).flat_map(|batch_size| {
+                recursion_shape_config.get_all_shape_combinations(batch_size).map(Self::Aggregate)
+            }))
     }
 
     pub fn generate_compress_shapes(
@@ -335,8 +341,8 @@ impl ZKMProofShape {
         recursion_shape_config.get_all_shape_combinations(reduce_batch_size)
     }
 
-    pub fn generate_maximal_shapes<'a>(
-        core_shape_config: &'a CoreShapeConfig<KoalaBear>,
+    pub fn generate_maximal_shapes<'a, E: MipsExtension<KoalaBear>>(
+        core_shape_config: &'a CoreShapeConfig<KoalaBear, E>,
         recursion_shape_config: &'a RecursionShapeConfig<KoalaBear, CompressAir<KoalaBear>>,
         reduce_batch_size: usize,
         no_precompiles: bool,
@@ -365,10 +371,13 @@ impl ZKMProofShape {
                     .get_all_shape_combinations(1)
                     .map(|mut x| Self::Shrink(x.pop().unwrap())),
             )
+            .chain((1..=reduce_batch_size).flat_map(|batch_size| {
+                recursion_shape_config.get_all_shape_combinations(batch_size).map(Self::Aggregate)
+            }))
     }
 
-    pub fn dummy_vk_map<'a>(
-        core_shape_config: &'a CoreShapeConfig<KoalaBear>,
+    pub fn dummy_vk_map<'a, E: MipsExtension<KoalaBear>>(
+        core_shape_config: &'a CoreShapeConfig<KoalaBear, E>,
         recursion_shape_config: &'a RecursionShapeConfig<KoalaBear, CompressAir<KoalaBear>>,
         reduce_batch_size: usize,
     ) -> BTreeMap<[KoalaBear; DIGEST_SIZE], usize> {
@@ -394,6 +403,9 @@ impl ZKMCompressProgramShape {
                 compress_shape: vec![proof_shape].into(),
                 merkle_tree_height: height,
             }),
+            ZKMProofShape::Aggregate(proof_shapes) => {
+                Self::Aggregate(ZKMAggregateShape::new(proof_shapes.into(), height))
+            }
         }
     }
 }
@@ -426,6 +438,11 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
                     &input,
                 )
             }
+            ZKMCompressProgramShape::Aggregate(shape) => {
+                let input =
+                    ZKMAggregateWitnessValues::dummy(self.compress_prover.machine(), &shape);
+                self.aggregate_program(&input)
+            }
         }
     }
 }
@@ -437,7 +454,7 @@ mod tests {
     #[test]
     #[ignore]
     fn test_generate_all_shapes() {
-        let core_shape_config = CoreShapeConfig::default();
+        let core_shape_config = CoreShapeConfig::<KoalaBear>::default();
         let recursion_shape_config = RecursionShapeConfig::default();
         let reduce_batch_size = 2;
         let all_shapes =
@@ -0,0 +1,134 @@
+//! Incremental proving of a program whose execution is split into steps, see
+//! [`ZKMProver::prove_core_step`] and [`ZKMProver::compress_step`].
+
+use std::borrow::Borrow;
+
+use p3_field::FieldAlgebra;
+use p3_koala_bear::KoalaBear;
+use tracing::instrument;
+use zkm_core_executor::ZKMContext;
+use zkm_core_machine::{
+    io::ZKMStdin,
+    reduce::ZKMReduceProof,
+    utils::{prove_step_with_context, StepCheckpoint, ZKMCoreProverError},
+};
+use zkm_primitives::io::ZKMPublicValues;
+use zkm_recursion_circuit::{machine::ZKMCompressWitnessValues, witness::Witnessable};
+use zkm_recursion_compiler::config::InnerConfig;
+use zkm_recursion_core::{air::RecursionPublicValues, Runtime as RecursionRuntime};
+use zkm_stark::{Challenge, MachineProver, StarkGenericConfig, Val, ZKMProverOpts};
+
+use crate::{
+    components::ZKMProverComponents, InnerSC, ZKMCoreProof, ZKMCoreProofData, ZKMProver,
+    ZKMProvingKey, ZKMRecursionProverError, ZKMVerifyingKey,
+};
+
+impl<C: ZKMProverComponents> ZKMProver<C> {
+    /// Generate the shard proofs of one step of an incremental computation.
+    ///
+    /// The first step is proven with `resume` set to `None` and starts at the entrypoint. Every
+    /// later step resumes from the [`StepCheckpoint`] returned for the step before it, with
+    /// `stdin` appended to the input. Returns the checkpoint of this step, or `None` if the
+    /// program halted.
+    #[instrument(name = "prove_core_step", level = "info", skip_all)]
+    pub fn prove_core_step<'a>(
+        &'a self,
+        pk: &ZKMProvingKey,
+        stdin: &ZKMStdin,
+        resume: Option<StepCheckpoint>,
+        opts: ZKMProverOpts,
+        mut context: ZKMContext<'a>,
+    ) -> Result<(ZKMCoreProof, Option<StepCheckpoint>), ZKMCoreProverError> {
+        context.subproof_verifier = Some(self);
+        context.precompiles.extend(C::precompiles());
+        let program = self.get_program(&pk.elf).unwrap();
+        let (proof, public_values_stream, cycles, checkpoint) =
+            self.with_core_prover(opts.security, |core_prover| {
+                let pk = if pk.vk.vk.security == opts.security {
+                    core_prover.pk_to_device(&pk.pk)
+                } else {
+                    tracing::warn!("proving key was set up with other security parameters");
+                    core_prover.setup(&program).0
+                };
+                prove_step_with_context::<_, _, C::CoreProver>(
+                    core_prover,
+                    &pk,
+                    program,
+                    stdin,
+                    opts.core_opts,
+                    context,
+                    self.core_shape_config.as_ref(),
+                    resume,
+                )
+            })?;
+        Self::check_for_high_cycles(cycles);
+        let public_values = ZKMPublicValues::from(&public_values_stream);
+        let proof = ZKMCoreProof {
+            proof: ZKMCoreProofData(proof.shard_proofs),
+            stdin: stdin.clone(),
+            public_values,
+            cycles,
+        };
+        Ok((proof, checkpoint))
+    }
+
+    /// Reduce the shard proofs of a step made by [`ZKMProver::prove_core_step`] and fold them into
+    /// `prev`, the compressed proof of the steps before it.
+    ///
+    /// Set `is_last_step` if the program halted in the step. Only then is the proof complete and
+    /// checked by [`ZKMProver::verify_compressed`]; until then, the proof of the steps so far is
+    /// checked by [`ZKMProver::verify_compressed_step`].
+    #[instrument(name = "compress_step", level = "info", skip_all)]
+    pub fn compress_step(
+        &self,
+        vk: &ZKMVerifyingKey,
+        proof: ZKMCoreProof,
+        prev: Option<ZKMReduceProof<InnerSC>>,
+        is_last_step: bool,
+        opts: ZKMProverOpts,
+    ) -> Result<ZKMReduceProof<InnerSC>, ZKMRecursionProverError> {
+        let Some(prev) = prev else {
+            return self.compress_shards(vk, proof, Vec::new(), opts, None, true, is_last_step);
+        };
+        let public_values: &RecursionPublicValues<KoalaBear> =
+            prev.proof.public_values.as_slice().borrow();
+        if public_values.is_complete == KoalaBear::ONE {
+            return Err(ZKMRecursionProverError::ExecutionEnded);
+        }
+
+        // The shards of the step continue the execution proven by `prev`, so the compress program
+        // checks that they are chained to it.
+        let step = self.compress_shards(vk, proof, Vec::new(), opts, None, false, false)?;
+        let input = ZKMCompressWitnessValues {
+            vks_and_proofs: vec![(prev.vk, prev.proof), (step.vk, step.proof)],
+            is_complete: is_last_step,
+        };
+        let input_with_merkle = self.make_merkle_proofs(input);
+        let program = self.compress_program(&input_with_merkle);
+
+        // Run the compress program.
+        let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
+            program.clone(),
+            self.compress_prover.config().perm.clone(),
+        );
+
+        let mut witness_stream = Vec::new();
+        Witnessable::<InnerConfig>::write(&input_with_merkle, &mut witness_stream);
+
+        runtime.witness_stream = witness_stream.into();
+
+        runtime.run().map_err(|e| ZKMRecursionProverError::RuntimeError(e.to_string()))?;
+
+        let (compress_pk, compress_vk) = tracing::debug_span!("setup compress")
+            .in_scope(|| self.compress_prover.setup(&program));
+
+        // Prove the compress program.
+        let mut challenger = self.compress_prover.config().challenger();
+        let mut compress_proof = self
+            .compress_prover
+            .prove(&compress_pk, vec![runtime.record], &mut challenger, opts.recursion_opts)
+            .map_err(|e| ZKMRecursionProverError::RuntimeError(e.to_string()))?;
+
+        Ok(ZKMReduceProof { vk: compress_vk, proof: compress_proof.shard_proofs.pop().unwrap() })
+    }
+}
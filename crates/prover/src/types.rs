@@ -17,7 +17,9 @@ use zkm_recursion_circuit::machine::{
 use zkm_recursion_gnark_ffi::proof::{Groth16Bn254Proof, PlonkBn254Proof};
 
 use thiserror::Error;
-use zkm_stark::{ShardProof, StarkGenericConfig, StarkProvingKey, StarkVerifyingKey, DIGEST_SIZE};
+use zkm_stark::{
+    SecurityConfig, ShardProof, StarkGenericConfig, StarkProvingKey, StarkVerifyingKey, DIGEST_SIZE,
+};
 
 use crate::{
     utils::{koalabears_to_bn254, words_to_bytes_be},
@@ -222,8 +224,25 @@ pub enum ZKMReduceProofWrapper {
 pub enum ZKMRecursionProverError {
     #[error("Runtime error: {0}")]
     RuntimeError(String),
+    #[error("cannot recurse over shards proven with {0:?}, the recursion programs expect {1:?}")]
+    SecurityMismatch(SecurityConfig, SecurityConfig),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("cannot fold a step into the proof of an execution that already ended")]
+    ExecutionEnded,
+    #[error(
+        "cannot compress core proofs made with {0:?} while the recursion verifying keys are \
+         verified, which only allows the default security parameters"
+    )]
+    UnsupportedSecurity(SecurityConfig),
+    #[error(
+        "cannot {0} a proof executed with a nonzero random seed or epoch, since only compressed \
+         proofs commit to them"
+    )]
+    UncommittedLinuxEnv(&'static str),
 }
 
+#[derive(Serialize, Deserialize, Clone)]
 #[allow(clippy::large_enum_variant)]
 pub enum ZKMCircuitWitness {
     Core(ZKMRecursionWitnessValues<CoreSC>),
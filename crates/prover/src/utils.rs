@@ -112,6 +112,7 @@ pub fn get_cycles(elf: &[u8], stdin: &ZKMStdin) -> u64 {
     let program = Program::from(elf).unwrap();
     let mut runtime = Executor::new(program, ZKMCoreOpts::default());
     runtime.write_vecs(&stdin.buffer);
+    runtime.write_files(&stdin.files);
     runtime.run_fast().unwrap();
     runtime.state.global_clk
 }
@@ -2,7 +2,7 @@ use std::{borrow::Borrow, path::Path, str::FromStr};
 
 use anyhow::Result;
 use num_bigint::BigUint;
-use p3_field::{FieldAlgebra, PrimeField};
+use p3_field::{FieldAlgebra, PrimeField, PrimeField32};
 use p3_koala_bear::KoalaBear;
 use zkm_core_executor::{subproof::SubproofVerifier, ZKMReduceProof};
 use zkm_core_machine::cpu::MAX_CPU_LOG_DEGREE;
@@ -17,15 +17,27 @@ use zkm_recursion_gnark_ffi::{
 use zkm_stark::{
     air::{PublicValues, POSEIDON_NUM_WORDS, PV_DIGEST_NUM_WORDS},
     koala_bear_poseidon2::KoalaBearPoseidon2,
-    MachineProof, MachineProver, MachineVerificationError, StarkGenericConfig, Word,
+    MachineProof, MachineProver, MachineVerificationError, SecurityConfig, StarkGenericConfig,
+    Word, DIGEST_SIZE,
 };
 
 use crate::{
+    aggregate::{aggregation_commitment, AggregationClaim},
     components::ZKMProverComponents,
-    utils::{is_recursion_public_values_valid, is_root_public_values_valid},
+    utils::{is_recursion_public_values_valid, is_root_public_values_valid, koalabears_to_bn254},
     CoreSC, HashableKey, OuterSC, ZKMCoreProofData, ZKMProver, ZKMVerifyingKey,
 };
 
+/// The bytes of the committed value digest of a compressed proof.
+fn committed_value_digest(proof: &ZKMReduceProof<KoalaBearPoseidon2>) -> Vec<u8> {
+    let public_values: &RecursionPublicValues<_> = proof.proof.public_values.as_slice().borrow();
+    public_values
+        .committed_value_digest
+        .iter()
+        .flat_map(|word| word.0.iter().map(|byte| byte.as_canonical_u32() as u8))
+        .collect()
+}
+
 #[derive(Error, Debug)]
 pub enum PlonkVerificationError {
     #[error(
@@ -53,11 +65,30 @@ pub enum Groth16VerificationError {
 impl<C: ZKMProverComponents> ZKMProver<C> {
     /// Verify a core proof by verifying the shards, verifying lookup bus, verifying that the
     /// shards are contiguous and complete.
+    ///
+    /// The proof must be at least as secure as the parameters of [`ZKMProver::core_prover`].
     pub fn verify(
         &self,
         proof: &ZKMCoreProofData,
         vk: &ZKMVerifyingKey,
     ) -> Result<(), MachineVerificationError<CoreSC>> {
+        self.verify_with_security(proof, vk, &self.core_prover.config().security())
+    }
+
+    /// Verify a core proof like [`ZKMProver::verify`], accepting any security parameters that
+    /// satisfy `required`.
+    pub fn verify_with_security(
+        &self,
+        proof: &ZKMCoreProofData,
+        vk: &ZKMVerifyingKey,
+        required: &SecurityConfig,
+    ) -> Result<(), MachineVerificationError<CoreSC>> {
+        // The parameters recorded in the verifying key should be secure enough. The machine
+        // verifier checks that every shard was proven with these parameters.
+        if !vk.vk.security.satisfies(required) {
+            return Err(MachineVerificationError::InsufficientSecurity(vk.vk.security, *required));
+        }
+
         // The proof should not be empty.
         if proof.0.is_empty() {
             return Err(MachineVerificationError::EmptyProof);
@@ -179,6 +210,23 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
             }
         }
 
+        // Random seed and epoch constraints.
+        //
+        // - Every shard should have the same random seed and epoch as the first shard.
+        let first_public_values: &PublicValues<Word<_>, _> =
+            first_shard.public_values.as_slice().borrow();
+        for shard_proof in proof.0.iter() {
+            let public_values: &PublicValues<Word<_>, _> =
+                shard_proof.public_values.as_slice().borrow();
+            if public_values.random_seed != first_public_values.random_seed
+                || public_values.epoch != first_public_values.epoch
+            {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "random seed and epoch should be the same for all shards",
+                ));
+            }
+        }
+
         // Memory initialization & finalization constraints.
         //
         // Initialization:
@@ -284,9 +332,11 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
         }
 
         // Verify the shard proof.
-        let mut challenger = self.core_prover.config().challenger();
         let machine_proof = MachineProof { shard_proofs: proof.0.to_vec() };
-        self.core_prover.machine().verify(&vk.vk, &machine_proof, &mut challenger)?;
+        self.with_core_prover(vk.vk.security, |core_prover| {
+            let mut challenger = core_prover.config().challenger();
+            core_prover.machine().verify(&vk.vk, &machine_proof, &mut challenger)
+        })?;
 
         Ok(())
     }
@@ -296,6 +346,82 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
         &self,
         proof: &ZKMReduceProof<KoalaBearPoseidon2>,
         vk: &ZKMVerifyingKey,
+    ) -> Result<(), MachineVerificationError<CoreSC>> {
+        self.verify_compressed_with_vkey_hash(proof, vk.hash_koalabear(), true)
+    }
+
+    /// Verify the compressed proof of the steps of an incremental computation so far, made by
+    /// [`ZKMProver::compress_step`].
+    ///
+    /// Until the program halts, the proof is not complete: it attests that the program of `vk`
+    /// ran from its entrypoint to the end of the last step, but commits to no public values yet.
+    /// Once the program halted, this is the same as [`ZKMProver::verify_compressed`].
+    pub fn verify_compressed_step(
+        &self,
+        proof: &ZKMReduceProof<KoalaBearPoseidon2>,
+        vk: &ZKMVerifyingKey,
+    ) -> Result<(), MachineVerificationError<CoreSC>> {
+        self.verify_compressed_with_vkey_hash(proof, vk.hash_koalabear(), false)?;
+
+        // The lift program of the first shard checks that the execution starts at the entrypoint.
+        let public_values: &RecursionPublicValues<_> =
+            proof.proof.public_values.as_slice().borrow();
+        if public_values.start_shard != KoalaBear::ONE {
+            return Err(MachineVerificationError::InvalidPublicValues(
+                "the proof does not start at the first shard",
+            ));
+        }
+        if public_values.start_reconstruct_deferred_digest.iter().any(|x| *x != KoalaBear::ZERO) {
+            return Err(MachineVerificationError::InvalidPublicValues(
+                "start_reconstruct_deferred_digest is not zero",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Verify that a compressed proof commits to `public_values`.
+    pub fn verify_compressed_public_values(
+        &self,
+        proof: &ZKMReduceProof<KoalaBearPoseidon2>,
+        public_values: &ZKMPublicValues,
+    ) -> Result<(), MachineVerificationError<CoreSC>> {
+        if committed_value_digest(proof) != public_values.hash() {
+            return Err(MachineVerificationError::InvalidPublicValues(
+                "committed value digest mismatch",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Verify a compressed proof made by [`ZKMProver::aggregate`] of proofs with the given
+    /// claims.
+    pub fn verify_aggregated(
+        &self,
+        proof: &ZKMReduceProof<KoalaBearPoseidon2>,
+        claims: &[AggregationClaim],
+    ) -> Result<(), MachineVerificationError<CoreSC>> {
+        if claims.is_empty() {
+            return Err(MachineVerificationError::InvalidPublicValues("no aggregated claims"));
+        }
+
+        // An aggregated proof does not represent any program.
+        self.verify_compressed_with_vkey_hash(proof, [KoalaBear::ZERO; DIGEST_SIZE], true)?;
+
+        if committed_value_digest(proof) != aggregation_commitment(claims) {
+            return Err(MachineVerificationError::InvalidPublicValues(
+                "aggregation commitment mismatch",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn verify_compressed_with_vkey_hash(
+        &self,
+        proof: &ZKMReduceProof<KoalaBearPoseidon2>,
+        vkey_hash: [KoalaBear; DIGEST_SIZE],
+        require_complete: bool,
     ) -> Result<(), MachineVerificationError<CoreSC>> {
         let ZKMReduceProof { vk: compress_vk, proof } = proof;
         let mut challenger = self.compress_prover.config().challenger();
@@ -323,12 +449,11 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
 
         // `is_complete` should be 1. In the reduce program, this ensures that the proof is fully
         // reduced.
-        if public_values.is_complete != KoalaBear::ONE {
+        if require_complete && public_values.is_complete != KoalaBear::ONE {
             return Err(MachineVerificationError::InvalidPublicValues("is_complete is not 1"));
         }
 
         // Verify that the proof is for the Ziren vkey we are expecting.
-        let vkey_hash = vk.hash_koalabear();
         if public_values.zkm_vk_digest != vkey_hash {
             return Err(MachineVerificationError::InvalidPublicValues("Ziren vk hash mismatch"));
         }
@@ -442,6 +567,40 @@ impl<C: ZKMProverComponents> ZKMProver<C> {
 
         Ok(())
     }
+
+    /// Verifies a Groth16 proof of an aggregated proof of proofs with the given claims.
+    pub fn verify_aggregated_groth16_bn254(
+        &self,
+        proof: &Groth16Bn254Proof,
+        claims: &[AggregationClaim],
+        build_dir: &Path,
+    ) -> Result<()> {
+        let prover = Groth16Bn254Prover::new();
+
+        let vkey_hash = BigUint::from_str(&proof.public_inputs[0])?;
+        let committed_values_digest = BigUint::from_str(&proof.public_inputs[1])?;
+
+        // Verify the proof with the corresponding public inputs.
+        prover.verify(proof, &vkey_hash, &committed_values_digest, build_dir)?;
+
+        // An aggregated proof does not represent any program.
+        let zero_vkey_hash = koalabears_to_bn254(&[KoalaBear::ZERO; DIGEST_SIZE]);
+        if vkey_hash != zero_vkey_hash.as_canonical_biguint() {
+            return Err(Groth16VerificationError::InvalidVerificationKey.into());
+        }
+
+        if claims.is_empty() {
+            return Err(Groth16VerificationError::InvalidPublicValues.into());
+        }
+        let mut commitment = aggregation_commitment(claims);
+        // Mask the top 3 bits, as done when the digest is committed to in the wrap program.
+        commitment[0] &= 0b00011111;
+        if committed_values_digest != BigUint::from_bytes_be(&commitment) {
+            return Err(Groth16VerificationError::InvalidPublicValues.into());
+        }
+
+        Ok(())
+    }
 }
 
 /// Verify the vk_hash and public_values_hash in the public inputs of the PlonkBn254Proof match the
@@ -0,0 +1,258 @@
+use std::borrow::{Borrow, BorrowMut};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use p3_air::Air;
+use p3_commit::Mmcs;
+use p3_field::FieldAlgebra;
+use p3_koala_bear::KoalaBear;
+use p3_matrix::dense::RowMajorMatrix;
+
+use zkm_recursion_compiler::{
+    circuit::CircuitV2Builder,
+    ir::{Builder, Felt},
+};
+use zkm_stark::septic_curve::SepticCurve;
+use zkm_stark::septic_digest::SepticDigest;
+use zkm_stark::{
+    air::{MachineAir, POSEIDON_NUM_WORDS},
+    koala_bear_poseidon2::KoalaBearPoseidon2,
+    Dom, ShardProof, StarkMachine, StarkVerifyingKey, Word,
+};
+
+use zkm_recursion_core::{
+    air::{RecursionPublicValues, PV_DIGEST_NUM_WORDS, RECURSIVE_PROOF_NUM_PV_ELTS},
+    DIGEST_SIZE,
+};
+
+use crate::{
+    challenger::{CanObserveVariable, DuplexChallengerVariable},
+    constraints::RecursiveVerifierConstraintFolder,
+    hash::{FieldHasher, FieldHasherVariable},
+    machine::assert_recursion_public_values_valid,
+    stark::{ShardProofVariable, StarkVerifier},
+    CircuitConfig, KoalaBearFriConfig, KoalaBearFriConfigVariable, VerifyingKeyVariable,
+};
+
+use super::{
+    recursion_public_values_digest, ZKMCompressShape, ZKMCompressWitnessValues,
+    ZKMMerkleProofVerifier, ZKMMerkleProofWitnessValues, ZKMMerkleProofWitnessVariable,
+};
+
+/// A program to fold a batch of independent compressed proofs into a single proof.
+pub struct ZKMAggregateVerifier<C, SC, A> {
+    _phantom: std::marker::PhantomData<(C, SC, A)>,
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct ZKMAggregateShape {
+    inner: ZKMCompressShape,
+    height: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "ShardProof<SC>: Serialize, Dom<SC>: Serialize, SC::Digest: Serialize"))]
+#[serde(bound(
+    deserialize = "ShardProof<SC>: Deserialize<'de>, Dom<SC>: DeserializeOwned, SC::Digest: Deserialize<'de>"
+))]
+pub struct ZKMAggregateWitnessValues<SC: KoalaBearFriConfig + FieldHasher<KoalaBear>> {
+    pub vks_and_proofs: Vec<(StarkVerifyingKey<SC>, ShardProof<SC>)>,
+    pub vk_merkle_data: ZKMMerkleProofWitnessValues<SC>,
+}
+
+pub struct ZKMAggregateWitnessVariable<
+    C: CircuitConfig<F = KoalaBear>,
+    SC: FieldHasherVariable<C> + KoalaBearFriConfigVariable<C>,
+> {
+    pub vks_and_proofs: Vec<(VerifyingKeyVariable<C, SC>, ShardProofVariable<C, SC>)>,
+    pub vk_merkle_data: ZKMMerkleProofWitnessVariable<C, SC>,
+}
+
+impl<C, SC, A> ZKMAggregateVerifier<C, SC, A>
+where
+    SC: KoalaBearFriConfigVariable<
+        C,
+        FriChallengerVariable = DuplexChallengerVariable<C>,
+        DigestVariable = [Felt<KoalaBear>; DIGEST_SIZE],
+    >,
+    C: CircuitConfig<F = SC::Val, EF = SC::Challenge, Bit = Felt<KoalaBear>>,
+    <SC::ValMmcs as Mmcs<KoalaBear>>::ProverData<RowMajorMatrix<KoalaBear>>: Clone,
+    A: MachineAir<SC::Val> + for<'a> Air<RecursiveVerifierConstraintFolder<'a, C>>,
+{
+    /// Verify a batch of independent compressed proofs and commit to their claims.
+    ///
+    /// Unlike the deferred verifier, the proofs may come from different programs and are not
+    /// linked to any execution. The verifier:
+    /// - Asserts that each of these proofs is a valid and complete `compress` proof whose
+    ///   verifying key is in the allowed set.
+    /// - Asserts that each of these proofs was executed with a zero random seed and epoch, since
+    ///   the leaves do not commit to them.
+    /// - Hashes every `(zkm_vk_digest, committed_value_digest)` pair into a leaf and computes the
+    ///   Merkle root of the leaves, padded with zero digests to a power of two.
+    /// - Commits the root as the `committed_value_digest` of a complete proof with a zero
+    ///   `zkm_vk_digest`, so that the result can be shrunk and wrapped like any compressed proof.
+    pub fn verify(
+        builder: &mut Builder<C>,
+        machine: &StarkMachine<SC, A>,
+        input: ZKMAggregateWitnessVariable<C, SC>,
+        value_assertions: bool,
+    ) {
+        let ZKMAggregateWitnessVariable { vks_and_proofs, vk_merkle_data } = input;
+
+        // Make sure there is at least one proof.
+        assert!(!vks_and_proofs.is_empty());
+
+        // First, verify the merkle tree proofs.
+        let vk_root = vk_merkle_data.root;
+        let values = vks_and_proofs.iter().map(|(vk, _)| vk.hash(builder)).collect::<Vec<_>>();
+        ZKMMerkleProofVerifier::verify(builder, values, vk_merkle_data, value_assertions);
+
+        let mut leaves = Vec::with_capacity(vks_and_proofs.len());
+        for (vk, shard_proof) in vks_and_proofs {
+            // Initialize a challenger.
+            let mut challenger = machine.config().challenger_variable(builder);
+            // Observe the vk and start pc.
+            challenger.observe(builder, vk.commitment);
+            challenger.observe(builder, vk.pc_start);
+            challenger.observe_slice(builder, vk.initial_global_cumulative_sum.0.x.0);
+            challenger.observe_slice(builder, vk.initial_global_cumulative_sum.0.y.0);
+            // Observe the padding.
+            let zero: Felt<_> = builder.eval(C::F::ZERO);
+            challenger.observe(builder, zero);
+
+            // Observe the public values.
+            challenger.observe_slice(
+                builder,
+                shard_proof.public_values[0..machine.num_pv_elts()].iter().copied(),
+            );
+
+            StarkVerifier::verify_shard(builder, &vk, machine, &mut challenger, &shard_proof);
+
+            // Get the current public values.
+            let current_public_values: &RecursionPublicValues<Felt<C::F>> =
+                shard_proof.public_values.as_slice().borrow();
+            // Assert that the `vk_root` is the same as the witnessed one.
+            for (elem, expected) in current_public_values.vk_root.iter().zip(vk_root.iter()) {
+                builder.assert_felt_eq(*elem, *expected);
+            }
+            // Assert that the public values are valid.
+            assert_recursion_public_values_valid::<C, SC>(builder, current_public_values);
+
+            // Assert that the proof is complete.
+            builder.assert_felt_eq(current_public_values.is_complete, C::F::ONE);
+
+            // Assert that the proof was executed with a zero random seed and epoch.
+            for word in
+                current_public_values.random_seed.iter().chain([&current_public_values.epoch])
+            {
+                for byte in word.0.iter() {
+                    builder.assert_felt_eq(*byte, C::F::ZERO);
+                }
+            }
+
+            // leaf = poseidon2( pv.zkm_vk_digest[..8] || pv.committed_value_digest[..32] )
+            let mut inputs = Vec::with_capacity(DIGEST_SIZE + 32);
+            inputs.extend_from_slice(&current_public_values.zkm_vk_digest);
+            for word in current_public_values.committed_value_digest.iter() {
+                inputs.extend_from_slice(&word.0);
+            }
+            leaves.push(SC::hash(builder, &inputs));
+        }
+
+        // Pad the leaves with zero digests and hash them up to the root.
+        let zero_digest: [Felt<C::F>; DIGEST_SIZE] =
+            std::array::from_fn(|_| builder.eval(C::F::ZERO));
+        leaves.resize(leaves.len().next_power_of_two(), zero_digest);
+        while leaves.len() > 1 {
+            leaves = leaves
+                .chunks_exact(2)
+                .map(|pair| SC::compress(builder, [pair[0], pair[1]]))
+                .collect();
+        }
+        let aggregation_root = leaves[0];
+
+        // Decompose the root into big-endian bytes, four per element.
+        let committed_value_digest: [Word<Felt<C::F>>; PV_DIGEST_NUM_WORDS] =
+            aggregation_root.map(|element| {
+                let bits = builder.num2bits_v2_f(element, 31);
+                let mut bytes = bits
+                    .chunks(8)
+                    .map(|byte_bits| builder.bits2num_v2_f(byte_bits.iter().copied()))
+                    .collect::<Vec<_>>();
+                bytes.reverse();
+                Word(bytes.try_into().unwrap())
+            });
+
+        let mut aggregate_public_values_stream: Vec<Felt<C::F>> =
+            (0..RECURSIVE_PROOF_NUM_PV_ELTS).map(|_| builder.eval(C::F::ZERO)).collect();
+        let aggregate_public_values: &mut RecursionPublicValues<_> =
+            aggregate_public_values_stream.as_mut_slice().borrow_mut();
+
+        // Set the boundary values of a complete proof consisting of a single execution shard.
+        let zero: Felt<_> = builder.eval(C::F::ZERO);
+        let one: Felt<_> = builder.eval(C::F::ONE);
+        let two: Felt<_> = builder.eval(C::F::TWO);
+        aggregate_public_values.start_pc = zero;
+        aggregate_public_values.next_pc = zero;
+        aggregate_public_values.start_shard = one;
+        aggregate_public_values.next_shard = two;
+        aggregate_public_values.start_execution_shard = one;
+        aggregate_public_values.next_execution_shard = two;
+        aggregate_public_values.contains_execution_shard = one;
+        aggregate_public_values.exit_code = zero;
+
+        // There is no program, so the Ziren vk digest is zero.
+        aggregate_public_values.zkm_vk_digest = [zero; DIGEST_SIZE];
+        // Commit to the aggregation root.
+        aggregate_public_values.committed_value_digest = committed_value_digest;
+        // There are no deferred proofs.
+        aggregate_public_values.deferred_proofs_digest = [zero; POSEIDON_NUM_WORDS];
+        aggregate_public_values.start_reconstruct_deferred_digest = [zero; POSEIDON_NUM_WORDS];
+        aggregate_public_values.end_reconstruct_deferred_digest = [zero; POSEIDON_NUM_WORDS];
+        // Set the cumulative sum to zero.
+        aggregate_public_values.global_cumulative_sum =
+            SepticDigest(SepticCurve::convert(SepticDigest::<C::F>::zero().0, |value| {
+                builder.eval(value)
+            }));
+        // Set the is_complete flag.
+        aggregate_public_values.is_complete = one;
+        // Set the vk root from the witness.
+        aggregate_public_values.vk_root = vk_root;
+        // Set the digest according to the previous values.
+        aggregate_public_values.digest =
+            recursion_public_values_digest::<C, SC>(builder, aggregate_public_values);
+
+        SC::commit_recursion_public_values(builder, *aggregate_public_values);
+    }
+}
+
+impl<SC: KoalaBearFriConfig + FieldHasher<KoalaBear>> ZKMAggregateWitnessValues<SC> {
+    pub fn shape(&self) -> ZKMAggregateShape {
+        let proof_shapes = self.vks_and_proofs.iter().map(|(_, proof)| proof.shape()).collect();
+        ZKMAggregateShape {
+            inner: ZKMCompressShape::from(proof_shapes),
+            height: self.vk_merkle_data.vk_merkle_proofs.first().map_or(0, |p| p.path.len()),
+        }
+    }
+}
+
+impl ZKMAggregateWitnessValues<KoalaBearPoseidon2> {
+    pub fn dummy<A: MachineAir<KoalaBear>>(
+        machine: &StarkMachine<KoalaBearPoseidon2, A>,
+        shape: &ZKMAggregateShape,
+    ) -> Self {
+        let inner_witness =
+            ZKMCompressWitnessValues::<KoalaBearPoseidon2>::dummy(machine, &shape.inner);
+        let vks_and_proofs = inner_witness.vks_and_proofs;
+
+        let vk_merkle_data = ZKMMerkleProofWitnessValues::dummy(vks_and_proofs.len(), shape.height);
+
+        Self { vks_and_proofs, vk_merkle_data }
+    }
+}
+
+impl ZKMAggregateShape {
+    pub const fn new(inner: ZKMCompressShape, height: usize) -> Self {
+        Self { inner, height }
+    }
+}
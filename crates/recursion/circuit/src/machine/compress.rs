@@ -122,6 +122,9 @@ where
 
         let mut exit_code: Felt<_> = builder.uninit();
 
+        let mut random_seed: [Word<Felt<_>>; 2] = unsafe { MaybeUninit::zeroed().assume_init() };
+        let mut epoch: Word<Felt<_>> = unsafe { MaybeUninit::zeroed().assume_init() };
+
         let mut execution_shard: Felt<_> = unsafe { MaybeUninit::zeroed().assume_init() };
         let mut committed_value_digest: [Word<Felt<_>>; PV_DIGEST_NUM_WORDS] =
             array::from_fn(|_| {
@@ -249,6 +252,10 @@ where
                 {
                     *digest = *current_digest;
                 }
+
+                // Initialize the random seed and epoch.
+                random_seed = current_public_values.random_seed;
+                epoch = current_public_values.epoch;
             }
 
             // Assert that the current values match the accumulated values.
@@ -268,6 +275,19 @@ where
                 builder.assert_felt_eq(*digest, current);
             }
 
+            // Assert that the random seed and epoch are always the same.
+            for (word, current_word) in
+                random_seed.iter().zip_eq(current_public_values.random_seed.iter())
+            {
+                for (byte, current_byte) in word.0.iter().zip_eq(current_word.0.iter()) {
+                    builder.assert_felt_eq(*byte, *current_byte);
+                }
+            }
+            for (byte, current_byte) in epoch.0.iter().zip_eq(current_public_values.epoch.0.iter())
+            {
+                builder.assert_felt_eq(*byte, *current_byte);
+            }
+
             // Assert that the start pc is equal to the current pc.
             builder.assert_felt_eq(pc, current_public_values.start_pc);
 
@@ -480,6 +500,9 @@ where
         compress_public_values.contains_execution_shard = contains_execution_shard;
         // Set the exit code.
         compress_public_values.exit_code = exit_code;
+        // Set the random seed and epoch.
+        compress_public_values.random_seed = random_seed;
+        compress_public_values.epoch = epoch;
         // Reflect the vk root.
         compress_public_values.vk_root = vk_root;
         // Set the digest according to the previous values.
@@ -6,16 +6,14 @@ use std::{
 };
 
 use itertools::Itertools;
+use p3_air::Air;
 use p3_commit::Mmcs;
 use p3_field::FieldAlgebra;
 use p3_koala_bear::KoalaBear;
 use p3_matrix::dense::RowMajorMatrix;
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use zkm_core_machine::{
-    cpu::MAX_CPU_LOG_DEGREE,
-    mips::{MipsAir, MAX_LOG_NUMBER_OF_SHARDS},
-};
+use zkm_core_machine::{cpu::MAX_CPU_LOG_DEGREE, mips::MAX_LOG_NUMBER_OF_SHARDS};
 
 use zkm_recursion_core::air::PV_DIGEST_NUM_WORDS;
 use zkm_stark::air::LookupScope;
@@ -41,6 +39,7 @@ use zkm_recursion_core::{
 
 use crate::{
     challenger::{CanObserveVariable, DuplexChallengerVariable},
+    constraints::RecursiveVerifierConstraintFolder,
     machine::{assert_complete, recursion_public_values_digest},
     stark::{dummy_vk_and_shard_proof, ShardProofVariable, StarkVerifier},
     CircuitConfig, KoalaBearFriConfig, KoalaBearFriConfigVariable, VerifyingKeyVariable,
@@ -75,12 +74,14 @@ pub struct ZKMRecursionShape {
 }
 
 /// A program for recursively verifying a batch of Ziren proofs.
+///
+/// The machine `A` is a `MipsAir`, possibly with extension chips.
 #[derive(Debug, Clone, Copy)]
-pub struct ZKMRecursiveVerifier<C: Config, SC: KoalaBearFriConfig> {
-    _phantom: PhantomData<(C, SC)>,
+pub struct ZKMRecursiveVerifier<C: Config, SC: KoalaBearFriConfig, A> {
+    _phantom: PhantomData<(C, SC, A)>,
 }
 
-impl<C, SC> ZKMRecursiveVerifier<C, SC>
+impl<C, SC, A> ZKMRecursiveVerifier<C, SC, A>
 where
     SC: KoalaBearFriConfigVariable<
         C,
@@ -89,6 +90,7 @@ where
     >,
     C: CircuitConfig<F = SC::Val, EF = SC::Challenge, Bit = Felt<KoalaBear>>,
     <SC::ValMmcs as Mmcs<KoalaBear>>::ProverData<RowMajorMatrix<KoalaBear>>: Clone,
+    A: MachineAir<SC::Val> + for<'a> Air<RecursiveVerifierConstraintFolder<'a, C>>,
 {
     /// Verify a batch of Ziren shard proofs and aggregate their public values.
     ///
@@ -118,7 +120,7 @@ where
     /// as the one witnessed here.
     pub fn verify(
         builder: &mut Builder<C>,
-        machine: &StarkMachine<SC, MipsAir<SC::Val>>,
+        machine: &StarkMachine<SC, A>,
         input: ZKMRecursionWitnessVariable<C, SC>,
     ) {
         // Read input.
@@ -150,6 +152,10 @@ where
         // Initialize the exit code variable.
         let mut exit_code: Felt<_> = unsafe { MaybeUninit::zeroed().assume_init() };
 
+        // Initialize the random seed and epoch variables.
+        let mut random_seed: [Word<Felt<_>>; 2] = unsafe { MaybeUninit::zeroed().assume_init() };
+        let mut epoch: Word<Felt<_>> = unsafe { MaybeUninit::zeroed().assume_init() };
+
         // Initialize the public values digest.
         let mut committed_value_digest: [Word<Felt<_>>; PV_DIGEST_NUM_WORDS] =
             array::from_fn(|_| Word(array::from_fn(|_| builder.uninit())));
@@ -212,6 +218,10 @@ where
                 // Exit code.
                 exit_code = public_values.exit_code;
 
+                // Random seed and epoch.
+                random_seed = public_values.random_seed;
+                epoch = public_values.epoch;
+
                 // Committed public values digests.
                 for (word, first_word) in committed_value_digest
                     .iter_mut()
@@ -352,6 +362,20 @@ where
                 builder.assert_felt_eq(exit_code, C::F::ZERO);
             }
 
+            // Random seed and epoch constraints.
+            {
+                // Assert that every shard was executed with the same seed and epoch.
+                for (word, pub_word) in random_seed.iter().zip_eq(public_values.random_seed.iter())
+                {
+                    for (byte, pub_byte) in word.0.iter().zip_eq(pub_word.0.iter()) {
+                        builder.assert_felt_eq(*byte, *pub_byte);
+                    }
+                }
+                for (byte, pub_byte) in epoch.0.iter().zip_eq(public_values.epoch.0.iter()) {
+                    builder.assert_felt_eq(*byte, *pub_byte);
+                }
+            }
+
             // Memory initialization & finalization constraints.
             {
                 // Assert that the MemoryInitialize address bits match the current loop variable.
@@ -552,6 +576,8 @@ where
             recursion_public_values.start_reconstruct_deferred_digest = start_deferred_digest;
             recursion_public_values.end_reconstruct_deferred_digest = end_deferred_digest;
             recursion_public_values.exit_code = exit_code;
+            recursion_public_values.random_seed = random_seed;
+            recursion_public_values.epoch = epoch;
             recursion_public_values.is_complete = is_complete;
             // Set the contains an execution shard flag.
             recursion_public_values.contains_execution_shard =
@@ -578,8 +604,8 @@ impl<SC: KoalaBearFriConfig> ZKMRecursionWitnessValues<SC> {
 }
 
 impl ZKMRecursionWitnessValues<KoalaBearPoseidon2> {
-    pub fn dummy(
-        machine: &StarkMachine<KoalaBearPoseidon2, MipsAir<KoalaBear>>,
+    pub fn dummy<A: MachineAir<KoalaBear>>(
+        machine: &StarkMachine<KoalaBearPoseidon2, A>,
         shape: &ZKMRecursionShape,
     ) -> Self {
         let (mut vks, shard_proofs): (Vec<_>, Vec<_>) =
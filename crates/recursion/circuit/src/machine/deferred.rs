@@ -69,6 +69,8 @@ pub struct ZKMDeferredWitnessValues<SC: KoalaBearFriConfig + FieldHasher<KoalaBe
     pub end_execution_shard: SC::Val,
     pub init_addr_bits: [SC::Val; 32],
     pub finalize_addr_bits: [SC::Val; 32],
+    pub random_seed: [Word<SC::Val>; 2],
+    pub epoch: Word<SC::Val>,
     pub is_complete: bool,
 }
 
@@ -87,6 +89,8 @@ pub struct ZKMDeferredWitnessVariable<
     pub end_execution_shard: Felt<C::F>,
     pub init_addr_bits: [Felt<C::F>; 32],
     pub finalize_addr_bits: [Felt<C::F>; 32],
+    pub random_seed: [Word<Felt<C::F>>; 2],
+    pub epoch: Word<Felt<C::F>>,
     pub is_complete: Felt<C::F>,
 }
 
@@ -109,6 +113,8 @@ where
     /// - Asserts that each of these proofs is valid as a `compress` proof.
     /// - Asserts that each of these proofs is complete by checking the `is_complete` flag in the
     ///   proof's public values.
+    /// - Asserts that each of these proofs was executed with a zero random seed and epoch, since
+    ///   the deferred digest does not commit to them.
     /// - Aggregates the proof information into the accumulated deferred digest.
     pub fn verify(
         builder: &mut Builder<C>,
@@ -128,6 +134,8 @@ where
             end_execution_shard,
             init_addr_bits,
             finalize_addr_bits,
+            random_seed,
+            epoch,
             is_complete,
         } = input;
 
@@ -182,6 +190,15 @@ where
             // Assert that the proof is complete.
             builder.assert_felt_eq(current_public_values.is_complete, C::F::ONE);
 
+            // Assert that the proof was executed with a zero random seed and epoch.
+            for word in
+                current_public_values.random_seed.iter().chain([&current_public_values.epoch])
+            {
+                for byte in word.0.iter() {
+                    builder.assert_felt_eq(*byte, C::F::ZERO);
+                }
+            }
+
             // Update deferred proof digest
             // poseidon2( current_digest[..8] || pv.zkm_vk_digest[..8] ||
             // pv.committed_value_digest[..32] )
@@ -225,6 +242,9 @@ where
 
         // Set the exit code to be zero for now.
         deferred_public_values.exit_code = builder.eval(C::F::ZERO);
+        // Set the random seed and epoch to be the hinted values.
+        deferred_public_values.random_seed = random_seed;
+        deferred_public_values.epoch = epoch;
         // Assign the deferred proof digests.
         deferred_public_values.end_reconstruct_deferred_digest = reconstruct_deferred_digest;
         // Set the is_complete flag.
@@ -270,6 +290,8 @@ impl ZKMDeferredWitnessValues<KoalaBearPoseidon2> {
             end_execution_shard: KoalaBear::ZERO,
             init_addr_bits: [KoalaBear::ZERO; 32],
             finalize_addr_bits: [KoalaBear::ZERO; 32],
+            random_seed: [Word::default(); 2],
+            epoch: Word::default(),
         }
     }
 }
@@ -1,3 +1,4 @@
+mod aggregate;
 mod complete;
 mod compress;
 mod core;
@@ -8,6 +9,7 @@ mod vkey_proof;
 mod witness;
 mod wrap;
 
+pub use aggregate::*;
 pub(crate) use complete::*;
 pub use compress::*;
 pub use core::*;
@@ -23,9 +23,10 @@ use crate::{
 };
 
 use super::{
-    ZKMCompressWitnessValues, ZKMCompressWitnessVariable, ZKMDeferredWitnessValues,
-    ZKMDeferredWitnessVariable, ZKMMerkleProofWitnessValues, ZKMMerkleProofWitnessVariable,
-    ZKMRecursionWitnessValues, ZKMRecursionWitnessVariable,
+    ZKMAggregateWitnessValues, ZKMAggregateWitnessVariable, ZKMCompressWitnessValues,
+    ZKMCompressWitnessVariable, ZKMDeferredWitnessValues, ZKMDeferredWitnessVariable,
+    ZKMMerkleProofWitnessValues, ZKMMerkleProofWitnessVariable, ZKMRecursionWitnessValues,
+    ZKMRecursionWitnessVariable,
 };
 
 impl<C: CircuitConfig, T: Witnessable<C>> Witnessable<C> for Word<T> {
@@ -172,6 +173,8 @@ where
         let end_execution_shard = self.end_execution_shard.read(builder);
         let init_addr_bits = self.init_addr_bits.read(builder);
         let finalize_addr_bits = self.finalize_addr_bits.read(builder);
+        let random_seed = self.random_seed.read(builder);
+        let epoch = self.epoch.read(builder);
         let is_complete = InnerVal::from_bool(self.is_complete).read(builder);
 
         ZKMDeferredWitnessVariable {
@@ -186,6 +189,8 @@ where
             end_execution_shard,
             init_addr_bits,
             finalize_addr_bits,
+            random_seed,
+            epoch,
             is_complete,
         }
     }
@@ -202,10 +207,31 @@ where
         self.end_execution_shard.write(witness);
         self.init_addr_bits.write(witness);
         self.finalize_addr_bits.write(witness);
+        self.random_seed.write(witness);
+        self.epoch.write(witness);
         self.is_complete.write(witness);
     }
 }
 
+impl<C> Witnessable<C> for ZKMAggregateWitnessValues<KoalaBearPoseidon2>
+where
+    C: CircuitConfig<F = InnerVal, EF = InnerChallenge, Bit = Felt<InnerVal>>,
+{
+    type WitnessVariable = ZKMAggregateWitnessVariable<C, KoalaBearPoseidon2>;
+
+    fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
+        let vks_and_proofs = self.vks_and_proofs.read(builder);
+        let vk_merkle_data = self.vk_merkle_data.read(builder);
+
+        ZKMAggregateWitnessVariable { vks_and_proofs, vk_merkle_data }
+    }
+
+    fn write(&self, witness: &mut impl WitnessWriter<C>) {
+        self.vks_and_proofs.write(witness);
+        self.vk_merkle_data.write(witness);
+    }
+}
+
 impl<C: CircuitConfig, HV: FieldHasherVariable<C>> Witnessable<C> for MerkleProof<C::F, HV>
 where
     HV::Digest: Witnessable<C, WitnessVariable = HV::DigestVariable>,
@@ -78,6 +78,14 @@ where
         let public_values: &RootPublicValues<Felt<C::F>> = proof.public_values.as_slice().borrow();
         assert_root_public_values_valid::<C, SC>(builder, public_values);
 
+        // Assert that the program was executed with a zero random seed and epoch, since the SNARK
+        // only exposes the vk and committed value digests.
+        for word in public_values.inner.random_seed.iter().chain([&public_values.inner.epoch]) {
+            for byte in word.0.iter() {
+                builder.assert_felt_eq(*byte, C::F::ZERO);
+            }
+        }
+
         // Reflect the public values to the next level.
         SC::commit_recursion_public_values(builder, public_values.inner);
     }
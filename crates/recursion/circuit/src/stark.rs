@@ -161,10 +161,17 @@ pub fn dummy_vk_and_shard_proof<A: MachineAir<KoalaBear>>(
         initial_global_cumulative_sum: SepticDigest::<KoalaBear>::zero(),
         chip_information: preprocessed_chip_information,
         chip_ordering: preprocessed_chip_ordering,
+        security: machine.config().security(),
     };
 
-    let shard_proof =
-        ShardProof { commitment, opened_values, opening_proof, chip_ordering, public_values };
+    let shard_proof = ShardProof {
+        commitment,
+        opened_values,
+        opening_proof,
+        chip_ordering,
+        public_values,
+        security: machine.config().security(),
+    };
 
     (vk, shard_proof)
 }
@@ -2,7 +2,7 @@ use crate::runtime::{DIGEST_SIZE, HASH_RATE, PERMUTATION_WIDTH};
 
 use core::fmt::Debug;
 use p3_challenger::DuplexChallenger;
-use p3_field::PrimeField32;
+use p3_field::{Field, PrimeField32};
 use p3_symmetric::CryptographicPermutation;
 use serde::{Deserialize, Serialize};
 use static_assertions::const_assert_eq;
@@ -140,10 +140,23 @@ pub struct RecursionPublicValues<T> {
     /// since it's value will be individually constrained.
     pub exit_code: T,
 
+    /// The seed of the `getrandom` stream the program was executed with.
+    pub random_seed: [Word<T>; 2],
+
+    /// The epoch the program was executed with.
+    pub epoch: Word<T>,
+
     /// The digest of all the previous public values elements.
     pub digest: [T; DIGEST_SIZE],
 }
 
+impl<F: Field> RecursionPublicValues<F> {
+    /// Whether the proven execution used a nonzero random seed or epoch.
+    pub fn has_linux_env(&self) -> bool {
+        self.random_seed.iter().chain([&self.epoch]).flat_map(|word| word.0).any(|b| !b.is_zero())
+    }
+}
+
 /// Converts the public values to an array of elements.
 impl<F: Copy> RecursionPublicValues<F> {
     pub fn as_array(&self) -> [F; RECURSIVE_PROOF_NUM_PV_ELTS] {
@@ -9,7 +9,7 @@ use p3_merkle_tree::MerkleTreeMmcs;
 use p3_poseidon2::ExternalLayerConstants;
 use p3_symmetric::{Hash, MultiField32PaddingFreeSponge, TruncatedPermutation};
 use serde::{Deserialize, Serialize};
-use zkm_stark::{Com, StarkGenericConfig, ZeroCommitment};
+use zkm_stark::{Com, SecurityConfig, StarkGenericConfig, ZeroCommitment};
 
 use super::{poseidon2::bn254_poseidon2_rc3, zkm_dev_mode};
 
@@ -65,22 +65,20 @@ pub fn outer_perm() -> OuterPerm {
     OuterPerm::new(external_round_constants, internal_round_constants)
 }
 
+/// The security parameters of outer recursion with the given blowup.
+///
+/// This makes `84 / log_blowup` queries, rounded down as the outer circuits and the shrink and
+/// wrap vks were set up with, so it targets 100 bits of security when the blowup divides 84 and
+/// slightly less otherwise. Dev mode makes a single query.
+pub fn outer_security(log_blowup: usize) -> SecurityConfig {
+    let num_queries = if zkm_dev_mode() { 1 } else { 84 / log_blowup };
+    SecurityConfig::conjectured(num_queries * log_blowup + 16, log_blowup, 16)
+}
+
 /// The FRI config for outer recursion.
 /// This targets by default 100 bits of security.
 pub fn outer_fri_config() -> FriConfig<OuterChallengeMmcs> {
-    let perm = outer_perm();
-    let hash = OuterHash::new(perm.clone()).unwrap();
-    let compress = OuterCompress::new(perm.clone());
-    let challenge_mmcs = OuterChallengeMmcs::new(OuterValMmcs::new(hash, compress));
-    let num_queries = if zkm_dev_mode() {
-        1
-    } else {
-        match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 21,
-        }
-    };
-    FriConfig { log_blowup: 4, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+    outer_fri_config_with_blowup(4)
 }
 
 /// The FRI config for outer recursion.
@@ -90,15 +88,7 @@ pub fn outer_fri_config_with_blowup(log_blowup: usize) -> FriConfig<OuterChallen
     let hash = OuterHash::new(perm.clone()).unwrap();
     let compress = OuterCompress::new(perm.clone());
     let challenge_mmcs = OuterChallengeMmcs::new(OuterValMmcs::new(hash, compress));
-    let num_queries = if zkm_dev_mode() {
-        1
-    } else {
-        match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 84 / log_blowup,
-        }
-    };
-    FriConfig { log_blowup, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+    outer_security(log_blowup).fri_config(challenge_mmcs)
 }
 
 #[derive(Deserialize)]
@@ -172,6 +162,10 @@ impl StarkGenericConfig for KoalaBearPoseidon2Outer {
     fn challenger(&self) -> Self::Challenger {
         OuterChallenger::new(self.perm.clone()).unwrap()
     }
+
+    fn security(&self) -> SecurityConfig {
+        SecurityConfig::from_fri_config(self.pcs.fri_config())
+    }
 }
 
 impl ZeroCommitment<KoalaBearPoseidon2Outer> for OuterPcs {
@@ -188,3 +182,17 @@ pub fn test_fri_config() -> FriConfig<OuterChallengeMmcs> {
     let challenge_mmcs = OuterChallengeMmcs::new(OuterValMmcs::new(hash, compress));
     FriConfig { log_blowup: 1, num_queries: 1, proof_of_work_bits: 1, mmcs: challenge_mmcs }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outer_num_queries() {
+        // The query counts the outer circuits were set up with.
+        for log_blowup in 1..=8 {
+            assert_eq!(outer_security(log_blowup).num_queries(), 84 / log_blowup);
+        }
+        assert_eq!(outer_fri_config().num_queries, 21);
+    }
+}
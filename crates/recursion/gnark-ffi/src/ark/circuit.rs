@@ -0,0 +1,303 @@
+//! The recursion circuit as an arkworks constraint synthesizer, mirroring `go/zkm/zkm.go`.
+
+use std::{collections::HashMap, str::FromStr};
+
+use ark_bn254::Fr;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use num_bigint::BigUint;
+use zkm_recursion_compiler::constraints::{opcodes::ConstraintOpcode, Constraint};
+
+use super::{
+    koalabear::{to_binary, Ext, Felt, KoalaBearChip},
+    poseidon2::{Poseidon2Chip, Poseidon2KoalaBearChip},
+};
+use crate::witness::GnarkWitness;
+
+/// Parses a decimal string into a BN254 scalar.
+pub fn parse(value: &str) -> Result<Fr, SynthesisError> {
+    BigUint::from_str(value).map(Fr::from).map_err(|_| SynthesisError::AssignmentMissing)
+}
+
+/// The `j`-th name of the `i`-th argument of a constraint.
+fn arg(args: &[Vec<String>], i: usize, j: usize) -> Result<&str, SynthesisError> {
+    args.get(i).and_then(|arg| arg.get(j)).map(String::as_str).ok_or(SynthesisError::Unsatisfiable)
+}
+
+/// Looks up a variable defined by an earlier constraint.
+fn get<'a, T>(values: &'a HashMap<String, T>, name: &str) -> Result<&'a T, SynthesisError> {
+    values.get(name).ok_or(SynthesisError::Unsatisfiable)
+}
+
+/// Looks up the `i`-th witness.
+fn witness<T: Clone>(values: &[T], index: &str) -> Result<T, SynthesisError> {
+    let i = index.parse::<usize>().map_err(|_| SynthesisError::Unsatisfiable)?;
+    values.get(i).cloned().ok_or(SynthesisError::AssignmentMissing)
+}
+
+/// Builds an array from the results of `f` for each index.
+fn try_array<T, const N: usize>(
+    f: impl FnMut(usize) -> Result<T, SynthesisError>,
+) -> Result<[T; N], SynthesisError> {
+    (0..N)
+        .map(f)
+        .collect::<Result<Vec<_>, _>>()?
+        .try_into()
+        .map_err(|_| SynthesisError::Unsatisfiable)
+}
+
+/// The recursion circuit defined by `constraints`, assigned with `witness`.
+///
+/// The witness only fixes the shape of the circuit during setup, so any witness produced for the
+/// same constraints can be used.
+#[derive(Clone)]
+pub struct ZKMCircuit {
+    pub constraints: Vec<Constraint>,
+    pub witness: GnarkWitness,
+}
+
+impl ZKMCircuit {
+    /// The public inputs of the circuit: the vkey hash and the committed values digest.
+    pub fn public_inputs(&self) -> Result<[Fr; 2], SynthesisError> {
+        Ok([parse(&self.witness.vkey_hash)?, parse(&self.witness.committed_values_digest)?])
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for ZKMCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let vkey_hash = FpVar::new_input(cs.clone(), || parse(&self.witness.vkey_hash))?;
+        let committed_values_digest =
+            FpVar::new_input(cs.clone(), || parse(&self.witness.committed_values_digest))?;
+
+        let witness_vars = self
+            .witness
+            .vars
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || parse(x)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let witness_felts = self
+            .witness
+            .felts
+            .iter()
+            .map(|x| Ok(Felt::from_u32_var(FpVar::new_witness(cs.clone(), || parse(x))?)))
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+        let witness_exts = self
+            .witness
+            .exts
+            .iter()
+            .map(|x| {
+                let mut coefficients = Vec::with_capacity(4);
+                for coefficient in x {
+                    let value = FpVar::new_witness(cs.clone(), || parse(coefficient))?;
+                    coefficients.push(Felt::from_u32_var(value));
+                }
+                // An extension element has 4 coefficients.
+                Ok(Ext(coefficients.try_into().map_err(|_| SynthesisError::AssignmentMissing)?))
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        // Range check the witnesses.
+        for felt in witness_felts.iter() {
+            to_binary(&cs, &felt.value, 31)?;
+        }
+        for ext in witness_exts.iter() {
+            for felt in ext.0.iter() {
+                to_binary(&cs, &felt.value, 31)?;
+            }
+        }
+
+        let field = KoalaBearChip::new(cs.clone());
+        let hash = Poseidon2Chip::new();
+        let hash_koalabear = Poseidon2KoalaBearChip::new(&field);
+        let mut vars: HashMap<String, FpVar<Fr>> = HashMap::new();
+        let mut felts: HashMap<String, Felt> = HashMap::new();
+        let mut exts: HashMap<String, Ext> = HashMap::new();
+
+        // Iterate through the instructions and handle each opcode. Malformed constraints, such as
+        // ones reading a variable that was never defined, are reported as unsatisfiable.
+        for constraint in self.constraints {
+            let args = &constraint.args;
+            let out = arg(args, 0, 0)?.to_string();
+            match constraint.opcode {
+                ConstraintOpcode::ImmV => {
+                    vars.insert(out, FpVar::constant(parse(arg(args, 1, 0)?)?));
+                }
+                ConstraintOpcode::ImmF => {
+                    felts
+                        .insert(out, Felt::from_u32_var(FpVar::constant(parse(arg(args, 1, 0)?)?)));
+                }
+                ConstraintOpcode::ImmE => {
+                    let coefficients = try_array(|i| {
+                        Ok(Felt::from_u32_var(FpVar::constant(parse(arg(args, 1, i)?)?)))
+                    })?;
+                    exts.insert(out, Ext(coefficients));
+                }
+                ConstraintOpcode::AddV => {
+                    let (a, b) = (get(&vars, arg(args, 1, 0)?)?, get(&vars, arg(args, 2, 0)?)?);
+                    vars.insert(out, a + b);
+                }
+                ConstraintOpcode::AddF => {
+                    let (a, b) = (get(&felts, arg(args, 1, 0)?)?, get(&felts, arg(args, 2, 0)?)?);
+                    felts.insert(out, field.add_f(a, b)?);
+                }
+                ConstraintOpcode::AddE => {
+                    let (a, b) = (get(&exts, arg(args, 1, 0)?)?, get(&exts, arg(args, 2, 0)?)?);
+                    exts.insert(out, field.add_e(a, b)?);
+                }
+                ConstraintOpcode::AddEF => {
+                    let (a, b) = (get(&exts, arg(args, 1, 0)?)?, get(&felts, arg(args, 2, 0)?)?);
+                    exts.insert(out, field.add_ef(a, b)?);
+                }
+                ConstraintOpcode::SubV => {
+                    let (a, b) = (get(&vars, arg(args, 1, 0)?)?, get(&vars, arg(args, 2, 0)?)?);
+                    vars.insert(out, a - b);
+                }
+                ConstraintOpcode::SubF => {
+                    let (a, b) = (get(&felts, arg(args, 1, 0)?)?, get(&felts, arg(args, 2, 0)?)?);
+                    felts.insert(out, field.sub_f(a, b)?);
+                }
+                ConstraintOpcode::SubE => {
+                    let (a, b) = (get(&exts, arg(args, 1, 0)?)?, get(&exts, arg(args, 2, 0)?)?);
+                    exts.insert(out, field.sub_e(a, b)?);
+                }
+                ConstraintOpcode::SubEF => {
+                    let (a, b) = (get(&exts, arg(args, 1, 0)?)?, get(&felts, arg(args, 2, 0)?)?);
+                    exts.insert(out, field.sub_ef(a, b)?);
+                }
+                ConstraintOpcode::MulV => {
+                    let (a, b) = (get(&vars, arg(args, 1, 0)?)?, get(&vars, arg(args, 2, 0)?)?);
+                    vars.insert(out, a * b);
+                }
+                ConstraintOpcode::MulF => {
+                    let (a, b) = (get(&felts, arg(args, 1, 0)?)?, get(&felts, arg(args, 2, 0)?)?);
+                    felts.insert(out, field.mul_f(a, b)?);
+                }
+                ConstraintOpcode::MulE => {
+                    let (a, b) = (get(&exts, arg(args, 1, 0)?)?, get(&exts, arg(args, 2, 0)?)?);
+                    exts.insert(out, field.mul_e(a, b)?);
+                }
+                ConstraintOpcode::MulEF => {
+                    let (a, b) = (get(&exts, arg(args, 1, 0)?)?, get(&felts, arg(args, 2, 0)?)?);
+                    exts.insert(out, field.mul_ef(a, b)?);
+                }
+                ConstraintOpcode::DivF => {
+                    let (a, b) = (get(&felts, arg(args, 1, 0)?)?, get(&felts, arg(args, 2, 0)?)?);
+                    felts.insert(out, field.div_f(a, b)?);
+                }
+                ConstraintOpcode::DivE => {
+                    let (a, b) = (get(&exts, arg(args, 1, 0)?)?, get(&exts, arg(args, 2, 0)?)?);
+                    exts.insert(out, field.div_e(a, b)?);
+                }
+                ConstraintOpcode::DivEF => {
+                    let (a, b) = (get(&exts, arg(args, 1, 0)?)?, get(&felts, arg(args, 2, 0)?)?);
+                    exts.insert(out, field.div_ef(a, b)?);
+                }
+                ConstraintOpcode::NegE => {
+                    exts.insert(out, field.neg_e(get(&exts, arg(args, 1, 0)?)?)?);
+                }
+                ConstraintOpcode::InvE => {
+                    exts.insert(out, field.inv_e(get(&exts, arg(args, 1, 0)?)?)?);
+                }
+                ConstraintOpcode::Num2BitsV => {
+                    let num_bits = arg(args, 2, 0)?
+                        .parse::<usize>()
+                        .map_err(|_| SynthesisError::Unsatisfiable)?;
+                    let bits = to_binary(&cs, get(&vars, arg(args, 1, 0)?)?, num_bits)?;
+                    for (name, bit) in args[0].iter().zip(bits) {
+                        vars.insert(name.clone(), bit.into());
+                    }
+                }
+                ConstraintOpcode::Num2BitsF => {
+                    let bits = field.to_binary(get(&felts, arg(args, 1, 0)?)?)?;
+                    for (name, bit) in args[0].iter().zip(bits) {
+                        vars.insert(name.clone(), bit.into());
+                    }
+                }
+                ConstraintOpcode::Permute => {
+                    let mut state: [FpVar<Fr>; 3] =
+                        try_array(|i| Ok(get(&vars, arg(args, i, 0)?)?.clone()))?;
+                    hash.permute_mut(&mut state)?;
+                    for (i, x) in state.into_iter().enumerate() {
+                        vars.insert(arg(args, i, 0)?.to_string(), x);
+                    }
+                }
+                ConstraintOpcode::PermuteKoalaBear => {
+                    let mut state: [Felt; 16] =
+                        try_array(|i| Ok(get(&felts, arg(args, i, 0)?)?.clone()))?;
+                    hash_koalabear.permute_mut(&mut state)?;
+                    for (i, x) in state.into_iter().enumerate() {
+                        felts.insert(arg(args, i, 0)?.to_string(), x);
+                    }
+                }
+                ConstraintOpcode::SelectV => {
+                    let cond = get(&vars, arg(args, 1, 0)?)?;
+                    let (a, b) = (get(&vars, arg(args, 2, 0)?)?, get(&vars, arg(args, 3, 0)?)?);
+                    vars.insert(out, super::koalabear::select(cond, a, b)?);
+                }
+                ConstraintOpcode::SelectF => {
+                    let cond = get(&vars, arg(args, 1, 0)?)?;
+                    let (a, b) = (get(&felts, arg(args, 2, 0)?)?, get(&felts, arg(args, 3, 0)?)?);
+                    felts.insert(out, field.select_f(cond, a, b)?);
+                }
+                ConstraintOpcode::SelectE => {
+                    let cond = get(&vars, arg(args, 1, 0)?)?;
+                    let (a, b) = (get(&exts, arg(args, 2, 0)?)?, get(&exts, arg(args, 3, 0)?)?);
+                    exts.insert(out, field.select_e(cond, a, b)?);
+                }
+                ConstraintOpcode::Ext2Felt => {
+                    let ext = get(&exts, arg(args, 4, 0)?)?.clone();
+                    for (i, felt) in ext.0.into_iter().enumerate() {
+                        felts.insert(arg(args, i, 0)?.to_string(), felt);
+                    }
+                }
+                ConstraintOpcode::AssertEqV => {
+                    get(&vars, arg(args, 0, 0)?)?.enforce_equal(get(&vars, arg(args, 1, 0)?)?)?;
+                }
+                ConstraintOpcode::AssertEqF => {
+                    let (a, b) = (get(&felts, arg(args, 0, 0)?)?, get(&felts, arg(args, 1, 0)?)?);
+                    field.assert_eq_f(a, b)?;
+                }
+                ConstraintOpcode::AssertNeF => {
+                    let (a, b) = (get(&felts, arg(args, 0, 0)?)?, get(&felts, arg(args, 1, 0)?)?);
+                    field.assert_ne_f(a, b)?;
+                }
+                ConstraintOpcode::AssertEqE => {
+                    let (a, b) = (get(&exts, arg(args, 0, 0)?)?, get(&exts, arg(args, 1, 0)?)?);
+                    field.assert_eq_e(a, b)?;
+                }
+                ConstraintOpcode::PrintV | ConstraintOpcode::PrintF | ConstraintOpcode::PrintE => {}
+                ConstraintOpcode::WitnessV => {
+                    vars.insert(out, witness(&witness_vars, arg(args, 1, 0)?)?);
+                }
+                ConstraintOpcode::WitnessF => {
+                    felts.insert(out, witness(&witness_felts, arg(args, 1, 0)?)?);
+                }
+                ConstraintOpcode::WitnessE => {
+                    exts.insert(out, witness(&witness_exts, arg(args, 1, 0)?)?);
+                }
+                ConstraintOpcode::CommitVkeyHash => {
+                    vkey_hash.enforce_equal(get(&vars, &out)?)?;
+                }
+                ConstraintOpcode::CommitCommittedValuesDigest => {
+                    committed_values_digest.enforce_equal(get(&vars, &out)?)?;
+                }
+                ConstraintOpcode::CircuitFelts2Ext => {
+                    let coefficients =
+                        try_array(|i| Ok(get(&felts, arg(args, i + 1, 0)?)?.clone()))?;
+                    exts.insert(out, Ext(coefficients));
+                }
+                ConstraintOpcode::CircuitFelt2Var => {
+                    vars.insert(out, field.reduce_slow(get(&felts, arg(args, 1, 0)?)?)?.value);
+                }
+                ConstraintOpcode::ReduceE => {
+                    let reduced = field.reduce_e(get(&exts, &out)?)?;
+                    exts.insert(out, reduced);
+                }
+                // The circuit doesn't support the opcode, so it can't be satisfied.
+                _ => return Err(SynthesisError::Unsatisfiable),
+            }
+        }
+
+        Ok(())
+    }
+}
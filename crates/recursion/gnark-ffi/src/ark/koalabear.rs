@@ -0,0 +1,349 @@
+//! KoalaBear field arithmetic emulated inside the BN254 circuit.
+//!
+//! This mirrors `go/zkm/koalabear/koalabear.go`: elements are kept unreduced together with an
+//! upper bound on their value, and are only reduced modulo the KoalaBear prime when the bound
+//! gets too large or when the canonical value is needed.
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use num_bigint::BigUint;
+use p3_field::{
+    extension::BinomialExtensionField, Field, FieldAlgebra, FieldExtensionAlgebra, PrimeField32,
+};
+use p3_koala_bear::KoalaBear;
+
+/// The KoalaBear prime.
+pub const MODULUS: u64 = 2130706433;
+
+/// A KoalaBear element which is not necessarily reduced.
+#[derive(Clone)]
+pub struct Felt {
+    pub value: FpVar<Fr>,
+    pub upper_bound: BigUint,
+}
+
+/// An element of the degree 4 extension of KoalaBear, `x^4 - 3`.
+#[derive(Clone)]
+pub struct Ext(pub [Felt; 4]);
+
+impl Felt {
+    /// A constant with its own value as the bound.
+    pub fn constant(value: u64) -> Self {
+        Self { value: FpVar::constant(Fr::from(value)), upper_bound: BigUint::from(value) }
+    }
+
+    /// A variable holding a value which is only known to fit in 32 bits.
+    pub fn from_u32_var(value: FpVar<Fr>) -> Self {
+        Self { value, upper_bound: BigUint::from(1u64 << 32) }
+    }
+}
+
+/// Converts a BN254 scalar to an integer.
+pub fn to_biguint(value: &Fr) -> BigUint {
+    value.into_bigint().into()
+}
+
+/// Decomposes `x` into `num_bits` little-endian bits, constraining `x` to fit in them.
+pub fn to_binary(
+    cs: &ConstraintSystemRef<Fr>,
+    x: &FpVar<Fr>,
+    num_bits: usize,
+) -> Result<Vec<Boolean<Fr>>, SynthesisError> {
+    let bits = (0..num_bits)
+        .map(|i| Boolean::new_witness(cs.clone(), || Ok(x.value()?.into_bigint().get_bit(i))))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut sum = FpVar::zero();
+    let mut coefficient = Fr::from(1u64);
+    for bit in bits.iter() {
+        sum += FpVar::from(bit.clone()) * coefficient;
+        coefficient += coefficient;
+    }
+    sum.enforce_equal(x)?;
+    Ok(bits)
+}
+
+/// Returns `a` if `cond` is one and `b` if it is zero, constraining `cond` to be a bit.
+pub fn select(cond: &FpVar<Fr>, a: &FpVar<Fr>, b: &FpVar<Fr>) -> Result<FpVar<Fr>, SynthesisError> {
+    (cond * (cond - Fr::from(1u64))).enforce_equal(&FpVar::zero())?;
+    Ok(cond * (a - b) + b)
+}
+
+/// The arithmetic on emulated KoalaBear elements.
+pub struct KoalaBearChip {
+    cs: ConstraintSystemRef<Fr>,
+}
+
+impl KoalaBearChip {
+    pub fn new(cs: ConstraintSystemRef<Fr>) -> Self {
+        Self { cs }
+    }
+
+    /// Allocates a value computed out of circuit from the values of existing variables.
+    fn hint(
+        &self,
+        f: impl FnOnce() -> Result<BigUint, SynthesisError>,
+    ) -> Result<FpVar<Fr>, SynthesisError> {
+        FpVar::new_witness(self.cs.clone(), || f().map(Fr::from))
+    }
+
+    pub fn add_f(&self, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        self.reduce_fast(self.add_f_unreduced(a, b))
+    }
+
+    fn add_f_unreduced(&self, a: &Felt, b: &Felt) -> Felt {
+        Felt { value: &a.value + &b.value, upper_bound: &a.upper_bound + &b.upper_bound }
+    }
+
+    pub fn sub_f(&self, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        let neg_b = self.neg_f(b)?;
+        self.add_f(a, &neg_b)
+    }
+
+    pub fn mul_f(&self, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        self.reduce_fast(self.mul_f_unreduced(a, b))
+    }
+
+    fn mul_f_unreduced(&self, a: &Felt, b: &Felt) -> Felt {
+        Felt { value: &a.value * &b.value, upper_bound: &a.upper_bound * &b.upper_bound }
+    }
+
+    pub fn mul_f_const(&self, a: &Felt, b: u64) -> Result<Felt, SynthesisError> {
+        self.reduce_fast(Felt { value: &a.value * Fr::from(b), upper_bound: &a.upper_bound * b })
+    }
+
+    pub fn neg_f(&self, a: &Felt) -> Result<Felt, SynthesisError> {
+        let lifted_modulus = (&a.upper_bound / MODULUS + 1u64) * MODULUS;
+        self.reduce_fast(Felt {
+            value: FpVar::constant(Fr::from(lifted_modulus.clone())) - &a.value,
+            upper_bound: lifted_modulus,
+        })
+    }
+
+    fn inv_f(&self, a: &Felt) -> Result<Felt, SynthesisError> {
+        let value = &a.value;
+        let inverse = self.hint(|| {
+            let a = (to_biguint(&value.value()?) % MODULUS).to_u32_digits();
+            let a = KoalaBear::from_canonical_u32(a.first().copied().unwrap_or(0));
+            Ok(BigUint::from(a.try_inverse().unwrap_or(KoalaBear::ZERO).as_canonical_u32()))
+        })?;
+        to_binary(&self.cs, &inverse, 31)?;
+        let inverse = Felt { value: inverse, upper_bound: BigUint::from(1u64 << 31) };
+        let product = self.mul_f(a, &inverse)?;
+        self.assert_eq_f(&product, &Felt::constant(1))?;
+        Ok(inverse)
+    }
+
+    pub fn div_f(&self, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        let b_inv = self.inv_f(b)?;
+        self.mul_f(a, &b_inv)
+    }
+
+    pub fn assert_eq_f(&self, a: &Felt, b: &Felt) -> Result<(), SynthesisError> {
+        let a = self.reduce_slow(a)?;
+        let b = self.reduce_slow(b)?;
+        a.value.enforce_equal(&b.value)
+    }
+
+    pub fn assert_ne_f(&self, a: &Felt, b: &Felt) -> Result<(), SynthesisError> {
+        let a = self.reduce_slow(a)?;
+        let b = self.reduce_slow(b)?;
+        a.value.enforce_not_equal(&b.value)
+    }
+
+    pub fn select_f(&self, cond: &FpVar<Fr>, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        Ok(Felt {
+            value: select(cond, &a.value, &b.value)?,
+            upper_bound: a.upper_bound.clone().max(b.upper_bound.clone()),
+        })
+    }
+
+    pub fn add_e(&self, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        self.map2_e(a, b, |a, b| self.add_f(a, b))
+    }
+
+    pub fn sub_e(&self, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        self.map2_e(a, b, |a, b| self.sub_f(a, b))
+    }
+
+    pub fn add_ef(&self, a: &Ext, b: &Felt) -> Result<Ext, SynthesisError> {
+        let mut out = a.clone();
+        out.0[0] = self.add_f(&a.0[0], b)?;
+        Ok(out)
+    }
+
+    pub fn sub_ef(&self, a: &Ext, b: &Felt) -> Result<Ext, SynthesisError> {
+        let mut out = a.clone();
+        out.0[0] = self.sub_f(&a.0[0], b)?;
+        Ok(out)
+    }
+
+    pub fn mul_e(&self, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        let mut out: [Felt; 4] = std::array::from_fn(|_| Felt::constant(0));
+        for i in 0..4 {
+            for j in 0..4 {
+                let product = self.mul_f_unreduced(&a.0[i], &b.0[j]);
+                if i + j >= 4 {
+                    // x^4 = 3.
+                    let product = Felt {
+                        value: &product.value * Fr::from(3u64),
+                        upper_bound: &product.upper_bound * 3u64,
+                    };
+                    out[i + j - 4] = self.add_f_unreduced(&out[i + j - 4], &product);
+                } else {
+                    out[i + j] = self.add_f_unreduced(&out[i + j], &product);
+                }
+            }
+        }
+        let [x, y, z, w] = out;
+        Ok(Ext([
+            self.reduce_fast(x)?,
+            self.reduce_fast(y)?,
+            self.reduce_fast(z)?,
+            self.reduce_fast(w)?,
+        ]))
+    }
+
+    pub fn mul_ef(&self, a: &Ext, b: &Felt) -> Result<Ext, SynthesisError> {
+        let [x, y, z, w] = &a.0;
+        Ok(Ext([self.mul_f(x, b)?, self.mul_f(y, b)?, self.mul_f(z, b)?, self.mul_f(w, b)?]))
+    }
+
+    pub fn inv_e(&self, a: &Ext) -> Result<Ext, SynthesisError> {
+        let inverse = |i: usize| {
+            self.hint(|| {
+                let coefficients =
+                    a.0.iter()
+                        .map(|x| {
+                            let x = (to_biguint(&x.value.value()?) % MODULUS).to_u32_digits();
+                            Ok(KoalaBear::from_canonical_u32(x.first().copied().unwrap_or(0)))
+                        })
+                        .collect::<Result<Vec<_>, SynthesisError>>()?;
+                let a = BinomialExtensionField::<KoalaBear, 4>::from_base_slice(&coefficients);
+                let a_inv = a.try_inverse().unwrap_or(BinomialExtensionField::ZERO);
+                Ok(BigUint::from(a_inv.as_base_slice()[i].as_canonical_u32()))
+            })
+        };
+        let mut out = Vec::with_capacity(4);
+        for i in 0..4 {
+            let value = inverse(i)?;
+            to_binary(&self.cs, &value, 31)?;
+            out.push(Felt { value, upper_bound: BigUint::from(1u64 << 31) });
+        }
+        let out = Ext(out.try_into().unwrap_or_else(|_| unreachable!()));
+
+        let product = self.mul_e(a, &out)?;
+        let one = Ext([Felt::constant(1), Felt::constant(0), Felt::constant(0), Felt::constant(0)]);
+        self.assert_eq_e(&product, &one)?;
+        Ok(out)
+    }
+
+    pub fn div_e(&self, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        let b_inv = self.inv_e(b)?;
+        self.mul_e(a, &b_inv)
+    }
+
+    pub fn div_ef(&self, a: &Ext, b: &Felt) -> Result<Ext, SynthesisError> {
+        let b_inv = self.inv_f(b)?;
+        self.mul_ef(a, &b_inv)
+    }
+
+    pub fn neg_e(&self, a: &Ext) -> Result<Ext, SynthesisError> {
+        let [x, y, z, w] = &a.0;
+        Ok(Ext([self.neg_f(x)?, self.neg_f(y)?, self.neg_f(z)?, self.neg_f(w)?]))
+    }
+
+    pub fn select_e(&self, cond: &FpVar<Fr>, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        self.map2_e(a, b, |a, b| self.select_f(cond, a, b))
+    }
+
+    pub fn assert_eq_e(&self, a: &Ext, b: &Ext) -> Result<(), SynthesisError> {
+        for (a, b) in a.0.iter().zip(b.0.iter()) {
+            self.assert_eq_f(a, b)?;
+        }
+        Ok(())
+    }
+
+    pub fn reduce_e(&self, a: &Ext) -> Result<Ext, SynthesisError> {
+        let [x, y, z, w] = &a.0;
+        Ok(Ext([
+            self.reduce_slow(x)?,
+            self.reduce_slow(y)?,
+            self.reduce_slow(z)?,
+            self.reduce_slow(w)?,
+        ]))
+    }
+
+    /// The 31 bits of the canonical value of `a`.
+    pub fn to_binary(&self, a: &Felt) -> Result<Vec<Boolean<Fr>>, SynthesisError> {
+        let a = self.reduce_slow(a)?;
+        to_binary(&self.cs, &a.value, 31)
+    }
+
+    fn map2_e(
+        &self,
+        a: &Ext,
+        b: &Ext,
+        f: impl Fn(&Felt, &Felt) -> Result<Felt, SynthesisError>,
+    ) -> Result<Ext, SynthesisError> {
+        Ok(Ext([
+            f(&a.0[0], &b.0[0])?,
+            f(&a.0[1], &b.0[1])?,
+            f(&a.0[2], &b.0[2])?,
+            f(&a.0[3], &b.0[3])?,
+        ]))
+    }
+
+    /// Reduces `a` if its bound no longer leaves room for a multiplication.
+    pub fn reduce_fast(&self, a: Felt) -> Result<Felt, SynthesisError> {
+        if a.upper_bound.bits() >= 120 {
+            let bits = a.upper_bound.bits();
+            return Ok(Felt {
+                value: self.reduce_with_max_bits(&a.value, bits)?,
+                upper_bound: BigUint::from(MODULUS - 1),
+            });
+        }
+        Ok(a)
+    }
+
+    /// Reduces `a` to its canonical value.
+    pub fn reduce_slow(&self, a: &Felt) -> Result<Felt, SynthesisError> {
+        if a.upper_bound < BigUint::from(MODULUS) {
+            return Ok(a.clone());
+        }
+        Ok(Felt {
+            value: self.reduce_with_max_bits(&a.value, a.upper_bound.bits())?,
+            upper_bound: BigUint::from(MODULUS - 1),
+        })
+    }
+
+    fn reduce_with_max_bits(
+        &self,
+        x: &FpVar<Fr>,
+        max_bits: u64,
+    ) -> Result<FpVar<Fr>, SynthesisError> {
+        if max_bits <= 30 {
+            return Ok(x.clone());
+        }
+        let quotient = self.hint(|| Ok(to_biguint(&x.value()?) / MODULUS))?;
+        let remainder = self.hint(|| Ok(to_biguint(&x.value()?) % MODULUS))?;
+        to_binary(&self.cs, &quotient, (max_bits - 30) as usize)?;
+
+        // Check that the remainder is less than the KoalaBear modulus, by decomposing it into a
+        // 24 bit limb and a 7 bit limb.
+        let low_limb = self.hint(|| Ok(to_biguint(&remainder.value()?) % (1u64 << 24)))?;
+        let high_limb = self.hint(|| Ok(to_biguint(&remainder.value()?) >> 24))?;
+        (&high_limb * Fr::from(1u64 << 24) + &low_limb).enforce_equal(&remainder)?;
+        to_binary(&self.cs, &high_limb, 7)?;
+        to_binary(&self.cs, &low_limb, 24)?;
+
+        // If the most significant bits are all 1, then the least significant bits must all be
+        // zero for the element to be less than the KoalaBear modulus.
+        let should_check = (&high_limb - Fr::from(127u64)).is_zero()?;
+        (FpVar::from(should_check) * &low_limb).enforce_equal(&FpVar::zero())?;
+
+        (&quotient * Fr::from(MODULUS) + &remainder).enforce_equal(x)?;
+        Ok(remainder)
+    }
+}
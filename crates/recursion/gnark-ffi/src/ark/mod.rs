@@ -0,0 +1,341 @@
+//! A pure-Rust Groth16 backend built on arkworks.
+//!
+//! These functions are drop-in replacements for the Groth16 functions of the Go bindings, and
+//! read and write the same files in the build directory, except for the proving and verifying
+//! keys which are stored in the arkworks format.
+//!
+//! The backend compiles the circuit to its own constraint system, so it can't use the keys of
+//! the released gnark artifacts, and its proofs don't verify against their verifying key or the
+//! deployed verifier contracts. [`build_groth16_bn254`] instead runs a circuit-specific setup
+//! locally, whose randomness is only known to the machine that ran it. The keys are thus meant
+//! for development and self-hosted deployments: proofs are checked with the verifying key and
+//! the contracts written next to the keys, and the `groth16_vkey_hash` of every proof identifies
+//! the keys it was made with.
+
+mod circuit;
+mod koalabear;
+mod poseidon2;
+
+use std::{fs::File, io::BufReader, path::Path};
+
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use num_bigint::BigUint;
+use rand::rngs::OsRng;
+use zkm_recursion_compiler::constraints::Constraint;
+
+use crate::{witness::GnarkWitness, Groth16Bn254Proof};
+use circuit::ZKMCircuit;
+
+const CONSTRAINTS_PATH: &str = "constraints.json";
+const WITNESS_PATH: &str = "groth16_witness.json";
+const PK_PATH: &str = "groth16_ark_pk.bin";
+const VK_PATH: &str = "groth16_ark_vk.bin";
+const GNARK_VK_PATH: &str = "groth16_vk.bin";
+const VERIFIER_CONTRACT_PATH: &str = "Groth16Verifier.sol";
+
+const GNARK_COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+const GNARK_COMPRESSED_LARGEST: u8 = 0b11 << 6;
+const GNARK_COMPRESSED_INFINITY: u8 = 0b01 << 6;
+const ARK_MASK: u8 = 0b11 << 6;
+const ARK_COMPRESSED_NEGATIVE: u8 = 0b10 << 6;
+const ARK_COMPRESSED_INFINITY: u8 = 0b01 << 6;
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> T {
+    let file = File::open(path).unwrap_or_else(|e| panic!("failed to open {path:?}: {e}"));
+    serde_json::from_reader(BufReader::new(file))
+        .unwrap_or_else(|e| panic!("failed to deserialize {path:?}: {e}"))
+}
+
+fn load_circuit(constraints_path: &Path, witness_path: &Path) -> ZKMCircuit {
+    let constraints: Vec<Constraint> = read_json(constraints_path);
+    let witness: GnarkWitness = read_json(witness_path);
+    ZKMCircuit { constraints, witness }
+}
+
+/// Builds the circuit in `data_dir`, writing the keys and the Solidity verifier.
+pub fn build_groth16_bn254(data_dir: &str) {
+    let data_dir = Path::new(data_dir);
+    let circuit = load_circuit(&data_dir.join(CONSTRAINTS_PATH), &data_dir.join(WITNESS_PATH));
+    let public_inputs = circuit.public_inputs().expect("invalid public inputs in the witness");
+
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), &mut OsRng)
+        .expect("failed to run the Groth16 setup");
+
+    // Sanity check the keys with the witness used to build the circuit.
+    let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut OsRng).expect("failed to prove");
+    assert!(
+        Groth16::<Bn254>::verify(&vk, &public_inputs, &proof).expect("failed to verify"),
+        "the proof of the build witness does not verify"
+    );
+
+    std::fs::create_dir_all(data_dir).unwrap();
+    std::fs::write(data_dir.join(VERIFIER_CONTRACT_PATH), groth16_verifier_contract(&vk)).unwrap();
+    std::fs::write(data_dir.join(GNARK_VK_PATH), gnark_verifying_key(&pk)).unwrap();
+
+    let mut vk_bytes = Vec::new();
+    vk.serialize_uncompressed(&mut vk_bytes).unwrap();
+    std::fs::write(data_dir.join(VK_PATH), vk_bytes).unwrap();
+
+    let mut pk_file = File::create(data_dir.join(PK_PATH)).unwrap();
+    pk.serialize_uncompressed(&mut pk_file).unwrap();
+}
+
+/// Proves the circuit built in `data_dir` with the witness at `witness_path`.
+///
+/// The vkey hash of the returned proof is left empty, to be set by the caller.
+pub fn prove_groth16_bn254(data_dir: &str, witness_path: &str) -> Groth16Bn254Proof {
+    let data_dir = Path::new(data_dir);
+    let circuit = load_circuit(&data_dir.join(CONSTRAINTS_PATH), Path::new(witness_path));
+    let public_inputs =
+        [circuit.witness.vkey_hash.clone(), circuit.witness.committed_values_digest.clone()];
+
+    let pk_file = File::open(data_dir.join(PK_PATH)).unwrap_or_else(|e| {
+        panic!(
+            "failed to open the arkworks proving key in {data_dir:?}: {e}; the circuit must be \
+             built with the arkworks backend first"
+        )
+    });
+    let pk = ProvingKey::<Bn254>::deserialize_uncompressed_unchecked(BufReader::new(pk_file))
+        .expect("failed to deserialize the proving key");
+    let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut OsRng).expect("failed to prove");
+
+    let proof = hex::encode(encode_proof(&proof));
+    Groth16Bn254Proof {
+        public_inputs,
+        encoded_proof: proof.clone(),
+        raw_proof: proof,
+        groth16_vkey_hash: [0; 32],
+    }
+}
+
+/// Verifies a proof against the circuit built in `data_dir` and the given public inputs.
+pub fn verify_groth16_bn254(
+    data_dir: &str,
+    proof: &str,
+    vkey_hash: &str,
+    committed_values_digest: &str,
+) -> Result<(), String> {
+    let vk_bytes = std::fs::read(Path::new(data_dir).join(VK_PATH)).map_err(|e| e.to_string())?;
+    let vk = VerifyingKey::<Bn254>::deserialize_uncompressed(vk_bytes.as_slice())
+        .map_err(|e| e.to_string())?;
+    let proof = decode_proof(&hex::decode(proof).map_err(|e| e.to_string())?)?;
+    let parse =
+        |input: &str| circuit::parse(input).map_err(|_| format!("invalid public input {input}"));
+    let public_inputs = [parse(vkey_hash)?, parse(committed_values_digest)?];
+    match Groth16::<Bn254>::verify(&vk, &public_inputs, &proof) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("invalid proof".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Whether `data_dir` holds the keys written by [`build_groth16_bn254`].
+pub fn groth16_bn254_ark_artifacts_exist(data_dir: &Path) -> bool {
+    [PK_PATH, VK_PATH, GNARK_VK_PATH, VERIFIER_CONTRACT_PATH]
+        .iter()
+        .all(|path| data_dir.join(path).exists())
+}
+
+/// Checks that the witness satisfies the constraints, panicking otherwise.
+pub fn test_groth16_bn254(witness_json: &str, constraints_json: &str) {
+    let circuit = load_circuit(Path::new(constraints_json), Path::new(witness_json));
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    if let Err(e) = circuit.generate_constraints(cs.clone()) {
+        panic!("failed to generate the constraints: {e}");
+    }
+    if !cs.is_satisfied().expect("failed to check the constraints") {
+        panic!(
+            "constraint {} is not satisfied",
+            cs.which_is_unsatisfied().ok().flatten().unwrap_or_default()
+        );
+    }
+}
+
+fn to_hex(x: &Fq) -> String {
+    format!("0x{}", hex::encode(x.into_bigint().to_bytes_be()))
+}
+
+fn fq_from_be_bytes(bytes: &[u8]) -> Result<Fq, String> {
+    let x = BigUint::from_bytes_be(bytes);
+    if x >= BigUint::from(Fq::MODULUS) {
+        return Err("coordinate is not in the base field".to_string());
+    }
+    Ok(Fq::from(x))
+}
+
+/// Encodes a proof as the eight words taken by `Groth16Verifier.Verify`.
+fn encode_proof(proof: &Proof<Bn254>) -> Vec<u8> {
+    let (a_x, a_y) = proof.a.xy().unwrap_or_default();
+    let (b_x, b_y) = proof.b.xy().unwrap_or_default();
+    let (c_x, c_y) = proof.c.xy().unwrap_or_default();
+    [a_x, a_y, b_x.c1, b_x.c0, b_y.c1, b_y.c0, c_x, c_y]
+        .iter()
+        .flat_map(|x| x.into_bigint().to_bytes_be())
+        .collect()
+}
+
+/// Decodes a proof encoded by [`encode_proof`], checking that the points are valid.
+fn decode_proof(bytes: &[u8]) -> Result<Proof<Bn254>, String> {
+    if bytes.len() != 256 {
+        return Err(format!("expected a 256 byte proof, got {} bytes", bytes.len()));
+    }
+    let words = bytes.chunks_exact(32).map(fq_from_be_bytes).collect::<Result<Vec<_>, String>>()?;
+
+    let g1 = |x: Fq, y: Fq| {
+        if x.is_zero() && y.is_zero() {
+            return Ok(G1Affine::identity());
+        }
+        let point = G1Affine::new_unchecked(x, y);
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err("invalid G1 point".to_string());
+        }
+        Ok(point)
+    };
+    let b_x = ark_bn254::Fq2::new(words[3], words[2]);
+    let b_y = ark_bn254::Fq2::new(words[5], words[4]);
+    let b = if b_x.is_zero() && b_y.is_zero() {
+        G2Affine::identity()
+    } else {
+        let point = G2Affine::new_unchecked(b_x, b_y);
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err("invalid G2 point".to_string());
+        }
+        point
+    };
+    Ok(Proof { a: g1(words[0], words[1])?, b, c: g1(words[6], words[7])? })
+}
+
+/// Compresses a point in the gnark format: the big endian x coordinate, with the sign of y in
+/// the two most significant bits.
+fn gnark_compressed(point: impl CanonicalSerialize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    point.serialize_compressed(&mut bytes).unwrap();
+    bytes.reverse();
+    let flag = match bytes[0] & ARK_MASK {
+        ARK_COMPRESSED_INFINITY => GNARK_COMPRESSED_INFINITY,
+        ARK_COMPRESSED_NEGATIVE => GNARK_COMPRESSED_LARGEST,
+        _ => GNARK_COMPRESSED_SMALLEST,
+    };
+    bytes[0] = bytes[0] & !ARK_MASK | flag;
+    bytes
+}
+
+/// Serializes the verifying key in the gnark format, which is read by the Groth16 verifier of
+/// `zkm-verifier` and whose hash identifies the circuit.
+fn gnark_verifying_key(pk: &ProvingKey<Bn254>) -> Vec<u8> {
+    let vk = &pk.vk;
+    let mut bytes = Vec::new();
+    bytes.extend(gnark_compressed(vk.alpha_g1));
+    bytes.extend(gnark_compressed(pk.beta_g1));
+    bytes.extend(gnark_compressed(vk.beta_g2));
+    bytes.extend(gnark_compressed(vk.gamma_g2));
+    bytes.extend(gnark_compressed(pk.delta_g1));
+    bytes.extend(gnark_compressed(vk.delta_g2));
+    bytes.extend((vk.gamma_abc_g1.len() as u32).to_be_bytes());
+    for point in vk.gamma_abc_g1.iter() {
+        bytes.extend(gnark_compressed(*point));
+    }
+    // No public and commitment committed wires, and no commitment keys.
+    bytes.extend(0u32.to_be_bytes());
+    bytes.extend(0u32.to_be_bytes());
+    bytes
+}
+
+/// Renders the Solidity verifier for the verifying key.
+fn groth16_verifier_contract(vk: &VerifyingKey<Bn254>) -> String {
+    let g1 = |point: G1Affine| point.xy().map(|(x, y)| (to_hex(&x), to_hex(&y))).unwrap();
+    let g2_neg = |point: G2Affine| {
+        let (x, y) = (-point).xy().unwrap();
+        [to_hex(&x.c0), to_hex(&x.c1), to_hex(&y.c0), to_hex(&y.c1)]
+    };
+
+    let mut contract = include_str!("../../assets/Groth16Verifier.txt").to_string();
+    let (alpha_x, alpha_y) = g1(vk.alpha_g1);
+    contract = contract.replace("{ALPHA_X}", &alpha_x).replace("{ALPHA_Y}", &alpha_y);
+    for (name, point) in [("BETA", vk.beta_g2), ("GAMMA", vk.gamma_g2), ("DELTA", vk.delta_g2)] {
+        let [x_0, x_1, y_0, y_1] = g2_neg(point);
+        contract = contract
+            .replace(&format!("{{{name}_NEG_X_0}}"), &x_0)
+            .replace(&format!("{{{name}_NEG_X_1}}"), &x_1)
+            .replace(&format!("{{{name}_NEG_Y_0}}"), &y_0)
+            .replace(&format!("{{{name}_NEG_Y_1}}"), &y_1);
+    }
+    for (name, point) in ["CONSTANT", "PUB_0", "PUB_1"].iter().zip(vk.gamma_abc_g1.iter()) {
+        let (x, y) = g1(*point);
+        contract =
+            contract.replace(&format!("{{{name}_X}}"), &x).replace(&format!("{{{name}_Y}}"), &y);
+    }
+    contract
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::CurveGroup;
+    use ark_ff::UniformRand;
+    use ark_relations::r1cs::SynthesisError;
+    use zkm_recursion_compiler::constraints::opcodes::ConstraintOpcode;
+
+    use super::*;
+
+    #[test]
+    fn test_proof_encoding() {
+        let mut rng = OsRng;
+        let proof = Proof::<Bn254> {
+            a: (G1Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+            b: (G2Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+            c: (G1Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+        };
+        let encoded = encode_proof(&proof);
+        assert_eq!(encoded.len(), 256);
+        assert_eq!(decode_proof(&encoded).unwrap(), proof);
+
+        let mut corrupted = encoded.clone();
+        corrupted[31] ^= 1;
+        assert!(decode_proof(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_malformed_constraints() {
+        let witness = GnarkWitness {
+            vkey_hash: "1".to_string(),
+            committed_values_digest: "2".to_string(),
+            ..Default::default()
+        };
+        let synthesize = |constraints: Vec<Constraint>| {
+            let circuit = ZKMCircuit { constraints, witness: witness.clone() };
+            circuit.generate_constraints(ConstraintSystem::<Fr>::new_ref())
+        };
+        let constraint = |opcode, args: &[&[&str]]| Constraint {
+            opcode,
+            args: args.iter().map(|arg| arg.iter().map(|x| x.to_string()).collect()).collect(),
+        };
+
+        // Reading a variable that was never defined.
+        let undefined = constraint(ConstraintOpcode::AddV, &[&["c"], &["a"], &["b"]]);
+        assert!(matches!(synthesize(vec![undefined]), Err(SynthesisError::Unsatisfiable)));
+
+        // Reading a witness that was not given.
+        let missing = constraint(ConstraintOpcode::WitnessV, &[&["a"], &["0"]]);
+        assert!(matches!(synthesize(vec![missing]), Err(SynthesisError::AssignmentMissing)));
+
+        // An immediate that is not a number.
+        let invalid = constraint(ConstraintOpcode::ImmV, &[&["a"], &["x"]]);
+        assert!(matches!(synthesize(vec![invalid]), Err(SynthesisError::AssignmentMissing)));
+
+        // Missing arguments.
+        let truncated = constraint(ConstraintOpcode::MulV, &[&["c"]]);
+        assert!(matches!(synthesize(vec![truncated]), Err(SynthesisError::Unsatisfiable)));
+
+        let valid = vec![
+            constraint(ConstraintOpcode::ImmV, &[&["a"], &["1"]]),
+            constraint(ConstraintOpcode::CommitVkeyHash, &[&["a"]]),
+        ];
+        assert!(synthesize(valid).is_ok());
+    }
+}
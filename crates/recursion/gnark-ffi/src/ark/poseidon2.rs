@@ -0,0 +1,209 @@
+//! The Poseidon2 permutations over BN254 and KoalaBear, mirroring `go/zkm/poseidon2`.
+
+use ark_bn254::Fr;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::SynthesisError;
+use num_bigint::BigUint;
+use p3_field::PrimeField;
+use zkm_primitives::RC_16_30_U32;
+use zkm_recursion_core::stark::bn254_poseidon2_rc3;
+
+use super::koalabear::{Felt, KoalaBearChip, MODULUS};
+
+const WIDTH: usize = 3;
+const NUM_EXTERNAL_ROUNDS: usize = 8;
+const NUM_INTERNAL_ROUNDS: usize = 56;
+
+const KOALABEAR_WIDTH: usize = 16;
+const KOALABEAR_NUM_EXTERNAL_ROUNDS: usize = 8;
+const KOALABEAR_NUM_INTERNAL_ROUNDS: usize = 13;
+
+/// The diagonal of the internal matrix minus one, see `koala-bear/src/poseidon2.rs` in Plonky3.
+const KOALABEAR_MAT_INTERNAL_DIAG_M1: [u64; KOALABEAR_WIDTH] = [
+    2130706431, 1, 2, 1065353217, 3, 4, 1065353216, 2130706430, 2130706429, 2122383361, 1864368129,
+    2130706306, 8323072, 266338304, 133169152, 127,
+];
+
+/// The Poseidon2 permutation over a state of three BN254 elements.
+pub struct Poseidon2Chip {
+    round_constants: Vec<[Fr; WIDTH]>,
+}
+
+impl Poseidon2Chip {
+    pub fn new() -> Self {
+        let round_constants = bn254_poseidon2_rc3()
+            .into_iter()
+            .map(|round| round.map(|x| Fr::from(x.as_canonical_biguint())))
+            .collect();
+        Self { round_constants }
+    }
+
+    pub fn permute_mut(&self, state: &mut [FpVar<Fr>; WIDTH]) -> Result<(), SynthesisError> {
+        // The initial linear layer.
+        Self::matrix_permute_mut(state);
+
+        // The first half of the external rounds.
+        let rounds = NUM_EXTERNAL_ROUNDS + NUM_INTERNAL_ROUNDS;
+        let rounds_f_beginning = NUM_EXTERNAL_ROUNDS / 2;
+        for r in 0..rounds_f_beginning {
+            self.external_round(state, r)?;
+        }
+
+        // The internal rounds.
+        let p_end = rounds_f_beginning + NUM_INTERNAL_ROUNDS;
+        for r in rounds_f_beginning..p_end {
+            state[0] += self.round_constants[r][0];
+            state[0] = Self::sbox_p(&state[0])?;
+            Self::diffusion_permute_mut(state);
+        }
+
+        // The second half of the external rounds.
+        for r in p_end..rounds {
+            self.external_round(state, r)?;
+        }
+        Ok(())
+    }
+
+    fn external_round(
+        &self,
+        state: &mut [FpVar<Fr>; WIDTH],
+        round: usize,
+    ) -> Result<(), SynthesisError> {
+        for (x, rc) in state.iter_mut().zip(self.round_constants[round]) {
+            *x = Self::sbox_p(&(&*x + rc))?;
+        }
+        Self::matrix_permute_mut(state);
+        Ok(())
+    }
+
+    fn sbox_p(input: &FpVar<Fr>) -> Result<FpVar<Fr>, SynthesisError> {
+        let squared = input.square()?;
+        let input_4 = squared.square()?;
+        Ok(input_4 * input)
+    }
+
+    fn diffusion_permute_mut(state: &mut [FpVar<Fr>; WIDTH]) {
+        let sum = &state[0] + &state[1] + &state[2];
+        state[2] = &state[2] * Fr::from(2u64);
+        for x in state.iter_mut() {
+            *x += &sum;
+        }
+    }
+
+    fn matrix_permute_mut(state: &mut [FpVar<Fr>; WIDTH]) {
+        let sum = &state[0] + &state[1] + &state[2];
+        for x in state.iter_mut() {
+            *x += &sum;
+        }
+    }
+}
+
+impl Default for Poseidon2Chip {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The Poseidon2 permutation over a state of sixteen emulated KoalaBear elements.
+pub struct Poseidon2KoalaBearChip<'a> {
+    field: &'a KoalaBearChip,
+}
+
+impl<'a> Poseidon2KoalaBearChip<'a> {
+    pub fn new(field: &'a KoalaBearChip) -> Self {
+        Self { field }
+    }
+
+    pub fn permute_mut(&self, state: &mut [Felt; KOALABEAR_WIDTH]) -> Result<(), SynthesisError> {
+        // The initial linear layer.
+        self.external_linear_layer(state)?;
+
+        // The first half of the external rounds.
+        let rounds = KOALABEAR_NUM_EXTERNAL_ROUNDS + KOALABEAR_NUM_INTERNAL_ROUNDS;
+        let rounds_f_beginning = KOALABEAR_NUM_EXTERNAL_ROUNDS / 2;
+        for r in 0..rounds_f_beginning {
+            self.external_round(state, r)?;
+        }
+
+        // The internal rounds.
+        let p_end = rounds_f_beginning + KOALABEAR_NUM_INTERNAL_ROUNDS;
+        for r in rounds_f_beginning..p_end {
+            state[0] = self.field.add_f(&state[0], &Felt::constant(RC_16_30_U32[r][0] as u64))?;
+            state[0] = self.sbox_p(&state[0])?;
+            self.matmul_internal(state)?;
+        }
+
+        // The second half of the external rounds.
+        for r in p_end..rounds {
+            self.external_round(state, r)?;
+        }
+        Ok(())
+    }
+
+    fn external_round(
+        &self,
+        state: &mut [Felt; KOALABEAR_WIDTH],
+        round: usize,
+    ) -> Result<(), SynthesisError> {
+        for (x, rc) in state.iter_mut().zip(RC_16_30_U32[round]) {
+            *x = self.field.add_f(x, &Felt::constant(rc as u64))?;
+            *x = self.sbox_p(x)?;
+        }
+        self.external_linear_layer(state)
+    }
+
+    fn sbox_p(&self, input: &Felt) -> Result<Felt, SynthesisError> {
+        let input = self.field.add_f(input, &Felt::constant(0))?;
+        let input = self.field.reduce_slow(&input)?;
+        let cubed = &input.value * &input.value * &input.value;
+        self.field.reduce_slow(&Felt { value: cubed, upper_bound: BigUint::from(MODULUS).pow(3) })
+    }
+
+    fn mds_light_permutation_4x4(&self, state: &mut [Felt]) -> Result<(), SynthesisError> {
+        let f = self.field;
+        let t01 = f.add_f(&state[0], &state[1])?;
+        let t23 = f.add_f(&state[2], &state[3])?;
+        let t0123 = f.add_f(&t01, &t23)?;
+        let t01123 = f.add_f(&t0123, &state[1])?;
+        let t01233 = f.add_f(&t0123, &state[3])?;
+        state[3] = f.add_f(&t01233, &f.mul_f_const(&state[0], 2)?)?;
+        state[1] = f.add_f(&t01123, &f.mul_f_const(&state[2], 2)?)?;
+        state[0] = f.add_f(&t01123, &t01)?;
+        state[2] = f.add_f(&t01233, &t23)?;
+        Ok(())
+    }
+
+    fn external_linear_layer(
+        &self,
+        state: &mut [Felt; KOALABEAR_WIDTH],
+    ) -> Result<(), SynthesisError> {
+        for chunk in state.chunks_mut(4) {
+            self.mds_light_permutation_4x4(chunk)?;
+        }
+
+        let mut sums = [state[0].clone(), state[1].clone(), state[2].clone(), state[3].clone()];
+        for i in (4..KOALABEAR_WIDTH).step_by(4) {
+            for (j, sum) in sums.iter_mut().enumerate() {
+                *sum = self.field.add_f(sum, &state[i + j])?;
+            }
+        }
+
+        for (i, x) in state.iter_mut().enumerate() {
+            *x = self.field.add_f(x, &sums[i % 4])?;
+        }
+        Ok(())
+    }
+
+    fn matmul_internal(&self, state: &mut [Felt; KOALABEAR_WIDTH]) -> Result<(), SynthesisError> {
+        let mut sum = Felt::constant(0);
+        for x in state.iter() {
+            sum = self.field.add_f(&sum, x)?;
+        }
+
+        for (x, diag) in state.iter_mut().zip(KOALABEAR_MAT_INTERNAL_DIAG_M1) {
+            *x = self.field.mul_f(x, &Felt::constant(diag))?;
+            *x = self.field.add_f(x, &sum)?;
+        }
+        Ok(())
+    }
+}
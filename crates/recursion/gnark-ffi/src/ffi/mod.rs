@@ -1,2 +1,15 @@
+#[cfg(feature = "native")]
 mod native;
+#[cfg(feature = "native")]
 pub use native::*;
+
+// The arkworks backend replaces the Groth16 functions of the Go bindings.
+#[cfg(feature = "ark")]
+pub use crate::ark::{
+    build_groth16_bn254, prove_groth16_bn254, test_groth16_bn254, verify_groth16_bn254,
+};
+
+#[cfg(not(feature = "native"))]
+mod unavailable;
+#[cfg(not(feature = "native"))]
+pub use unavailable::*;
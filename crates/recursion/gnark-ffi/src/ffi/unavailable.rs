@@ -0,0 +1,57 @@
+//! Stand-ins for the Go bindings when the `native` feature is disabled.
+
+#[cfg(not(feature = "ark"))]
+use crate::Groth16Bn254Proof;
+use crate::PlonkBn254Proof;
+
+const NATIVE_REQUIRED: &str =
+    "PLONK proving requires the `native` feature of zkm-recursion-gnark-ffi";
+#[cfg(not(feature = "ark"))]
+const BACKEND_REQUIRED: &str =
+    "Groth16 proving requires the `native` or `ark` feature of zkm-recursion-gnark-ffi";
+
+pub fn build_plonk_bn254(_data_dir: &str) {
+    panic!("{NATIVE_REQUIRED}")
+}
+
+pub fn prove_plonk_bn254(_data_dir: &str, _witness_path: &str) -> PlonkBn254Proof {
+    panic!("{NATIVE_REQUIRED}")
+}
+
+pub fn verify_plonk_bn254(
+    _data_dir: &str,
+    _proof: &str,
+    _vkey_hash: &str,
+    _committed_values_digest: &str,
+) -> Result<(), String> {
+    Err(NATIVE_REQUIRED.to_string())
+}
+
+pub fn test_plonk_bn254(_witness_json: &str, _constraints_json: &str) {
+    panic!("{NATIVE_REQUIRED}")
+}
+
+#[cfg(not(feature = "ark"))]
+pub fn build_groth16_bn254(_data_dir: &str) {
+    panic!("{BACKEND_REQUIRED}")
+}
+
+#[cfg(not(feature = "ark"))]
+pub fn prove_groth16_bn254(_data_dir: &str, _witness_path: &str) -> Groth16Bn254Proof {
+    panic!("{BACKEND_REQUIRED}")
+}
+
+#[cfg(not(feature = "ark"))]
+pub fn verify_groth16_bn254(
+    _data_dir: &str,
+    _proof: &str,
+    _vkey_hash: &str,
+    _committed_values_digest: &str,
+) -> Result<(), String> {
+    Err(BACKEND_REQUIRED.to_string())
+}
+
+#[cfg(not(feature = "ark"))]
+pub fn test_groth16_bn254(_witness_json: &str, _constraints_json: &str) {
+    panic!("{BACKEND_REQUIRED}")
+}
@@ -36,6 +36,38 @@ impl Groth16Bn254Prover {
         Sha256::digest(vk_bin_bytes).into()
     }
 
+    /// Renders the ZKMVerifier contract for the circuit with the given verifying key hash.
+    pub fn zkm_verifier_contract(vkey_hash: [u8; 32]) -> String {
+        include_str!("../assets/ZKMVerifierGroth16.txt")
+            .replace("{ZKM_CIRCUIT_VERSION}", ZKM_CIRCUIT_VERSION)
+            .replace("{VERIFIER_HASH}", format!("0x{}", hex::encode(vkey_hash)).as_str())
+            .replace("{PROOF_SYSTEM}", "Groth16")
+    }
+
+    /// Writes the verifier contracts of the circuit built in `build_dir` to `out_dir`.
+    ///
+    /// The `IZKMVerifier` interface is written to `out_dir`, and the verifier contracts to a
+    /// subdirectory named after the circuit version, matching their imports.
+    pub fn export_contracts(build_dir: &Path, out_dir: &Path) -> std::io::Result<()> {
+        let version_dir = out_dir.join(ZKM_CIRCUIT_VERSION);
+        std::fs::create_dir_all(&version_dir)?;
+        std::fs::write(
+            out_dir.join("IZKMVerifier.sol"),
+            include_str!("../assets/IZKMVerifier.sol"),
+        )?;
+        let vkey_hash: [u8; 32] =
+            Sha256::digest(std::fs::read(build_dir.join("groth16_vk.bin"))?).into();
+        std::fs::write(
+            version_dir.join("ZKMVerifierGroth16.sol"),
+            Self::zkm_verifier_contract(vkey_hash),
+        )?;
+        std::fs::copy(
+            build_dir.join("Groth16Verifier.sol"),
+            version_dir.join("Groth16Verifier.sol"),
+        )?;
+        Ok(())
+    }
+
     /// Executes the prover in testing mode with a circuit definition and witness.
     pub fn test<C: Config>(constraints: Vec<Constraint>, witness: Witness<C>) {
         let serialized = serde_json::to_string(&constraints).unwrap();
@@ -59,11 +91,7 @@ impl Groth16Bn254Prover {
     pub fn build_contracts(build_dir: PathBuf) {
         // Write the corresponding asset files to the build dir.
         let zkm_verifier_path = build_dir.join("ZKMVerifierGroth16.sol");
-        let vkey_hash = Self::get_vkey_hash(&build_dir);
-        let zkm_verifier_str = include_str!("../assets/ZKMVerifierGroth16.txt")
-            .replace("{ZKM_CIRCUIT_VERSION}", ZKM_CIRCUIT_VERSION)
-            .replace("{VERIFIER_HASH}", format!("0x{}", hex::encode(vkey_hash)).as_str())
-            .replace("{PROOF_SYSTEM}", "Groth16");
+        let zkm_verifier_str = Self::zkm_verifier_contract(Self::get_vkey_hash(&build_dir));
         let mut zkm_verifier_file = File::create(zkm_verifier_path).unwrap();
         zkm_verifier_file.write_all(zkm_verifier_str.as_bytes()).unwrap();
 
@@ -1,3 +1,5 @@
+#[cfg(feature = "ark")]
+mod ark;
 mod koalabear;
 
 pub mod ffi;
@@ -10,3 +12,6 @@ pub use groth16_bn254::*;
 pub use plonk_bn254::*;
 pub use proof::*;
 pub use witness::*;
+
+#[cfg(feature = "ark")]
+pub use ark::groth16_bn254_ark_artifacts_exist;
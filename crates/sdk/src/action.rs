@@ -1,13 +1,17 @@
 use zkm_core_executor::{ExecutionReport, HookEnv, ZKMContextBuilder};
 use zkm_core_machine::io::ZKMStdin;
 use zkm_primitives::io::ZKMPublicValues;
-use zkm_prover::{components::DefaultProverComponents, ZKMProvingKey};
+use zkm_prover::{
+    aggregate::AggregationClaim, components::DefaultProverComponents, estimate::ZKMEstimate,
+    ZKMProvingKey,
+};
 
-use anyhow::{Ok, Result};
+use anyhow::{bail, Ok, Result};
 use std::time::Duration;
-use zkm_stark::{ZKMCoreOpts, ZKMProverOpts};
+use zkm_stark::{SecurityConfig, ZKMCoreOpts, ZKMProverOpts};
 
-use crate::{provers::ProofOpts, Prover, ZKMProofKind, ZKMProofWithPublicValues};
+use crate::install::try_build_groth16_circuit_artifacts;
+use crate::{provers::ProofOpts, Prover, ZKMProof, ZKMProofKind, ZKMProofWithPublicValues};
 
 /// Builder to prepare and configure execution of a program on an input.
 /// May be run with [Self::run].
@@ -70,6 +74,27 @@ impl<'a> Execute<'a> {
         self
     }
 
+    /// Set the wall-clock time at the start of execution, see
+    /// [`ZKMContextBuilder::epoch`](zkm_core_executor::ZKMContextBuilder::epoch).
+    pub fn epoch(mut self, epoch: u32) -> Self {
+        self.context_builder.epoch(epoch);
+        self
+    }
+
+    /// Set the seed of the stream returned by `getrandom`, see
+    /// [`ZKMContextBuilder::random_seed`](zkm_core_executor::ZKMContextBuilder::random_seed).
+    pub fn random_seed(mut self, seed: u64) -> Self {
+        self.context_builder.random_seed(seed);
+        self
+    }
+
+    /// Fail on unrecognized Linux syscalls instead of treating them as no-ops, see
+    /// [`ZKMContextBuilder::strict_syscalls`](zkm_core_executor::ZKMContextBuilder::strict_syscalls).
+    pub fn strict_syscalls(mut self, strict: bool) -> Self {
+        self.context_builder.strict_syscalls(strict);
+        self
+    }
+
     /// Skip deferred proof verification.
     pub fn set_skip_deferred_proof_verification(mut self, value: bool) -> Self {
         self.context_builder.set_skip_deferred_proof_verification(value);
@@ -77,6 +102,80 @@ impl<'a> Execute<'a> {
     }
 }
 
+/// Builder to prepare and configure the estimation of the cost of proving a program on an input.
+/// May be run with [Self::run].
+pub struct Estimate<'a> {
+    prover: &'a dyn Prover<DefaultProverComponents>,
+    context_builder: ZKMContextBuilder<'a>,
+    elf: &'a [u8],
+    stdin: ZKMStdin,
+    core_opts: ZKMCoreOpts,
+    security: SecurityConfig,
+}
+
+impl<'a> Estimate<'a> {
+    /// Prepare to estimate the cost of proving the given program on the given input.
+    ///
+    /// Prefer using [ProverClient::estimate](super::ProverClient::estimate).
+    /// See there for more documentation.
+    pub fn new(
+        prover: &'a dyn Prover<DefaultProverComponents>,
+        elf: &'a [u8],
+        stdin: ZKMStdin,
+    ) -> Self {
+        Self {
+            prover,
+            elf,
+            stdin,
+            context_builder: Default::default(),
+            core_opts: ZKMCoreOpts::default(),
+            security: SecurityConfig::default(),
+        }
+    }
+
+    /// Execute the program on the input and estimate the cost of proving it, consuming the built
+    /// action `self`.
+    pub fn run(self) -> Result<ZKMEstimate> {
+        let Self { prover, elf, stdin, mut context_builder, core_opts, security } = self;
+        let opts = ZKMProverOpts { core_opts, recursion_opts: ZKMCoreOpts::recursion(), security };
+        let context = context_builder.build();
+        Ok(prover.zkm_prover().estimate(elf, &stdin, opts, context)?)
+    }
+
+    /// Add a runtime [Hook](super::Hook) into the context.
+    ///
+    /// See [Execute::with_hook].
+    pub fn with_hook(
+        mut self,
+        fd: u32,
+        f: impl FnMut(HookEnv, &[u8]) -> Vec<Vec<u8>> + Send + Sync + 'a,
+    ) -> Self {
+        self.context_builder.hook(fd, f);
+        self
+    }
+
+    /// Set the shard size the proof would be made with.
+    pub fn shard_size(mut self, value: usize) -> Self {
+        self.core_opts.shard_size = value;
+        self
+    }
+
+    /// Set the security parameters the core proof would be made with.
+    pub fn security(mut self, value: SecurityConfig) -> Self {
+        self.security = value;
+        self
+    }
+
+    /// Set the maximum number of cpu cycles to use for execution.
+    ///
+    /// If the cycle limit is exceeded, execution will return
+    /// [`zkm_core_executor::ExecutionError::ExceededCycleLimit`].
+    pub fn max_cycles(mut self, max_cycles: u64) -> Self {
+        self.context_builder.max_cycles(max_cycles);
+        self
+    }
+}
+
 /// Builder to prepare and configure proving execution of a program on an input.
 /// May be run with [Self::run].
 pub struct Prove<'a> {
@@ -87,6 +186,7 @@ pub struct Prove<'a> {
     stdin: ZKMStdin,
     core_opts: ZKMCoreOpts,
     recursion_opts: ZKMCoreOpts,
+    security: SecurityConfig,
     timeout: Option<Duration>,
 }
 
@@ -108,6 +208,7 @@ impl<'a> Prove<'a> {
             context_builder: Default::default(),
             core_opts: ZKMCoreOpts::default(),
             recursion_opts: ZKMCoreOpts::recursion(),
+            security: pk.vk.vk.security,
             timeout: None,
         }
     }
@@ -122,9 +223,14 @@ impl<'a> Prove<'a> {
             mut context_builder,
             core_opts,
             recursion_opts,
+            security,
             timeout,
         } = self;
-        let opts = ZKMProverOpts { core_opts, recursion_opts };
+        // Fail before proving if the core proof could not be compressed.
+        if kind != ZKMProofKind::Core {
+            prover.zkm_prover().check_compress_security(security)?;
+        }
+        let opts = ZKMProverOpts { core_opts, recursion_opts, security };
         let proof_opts = ProofOpts { zkm_prover_opts: opts, timeout };
         let context = context_builder.build();
 
@@ -199,6 +305,18 @@ impl<'a> Prove<'a> {
         self
     }
 
+    /// Set the security parameters of the core proof, which default to the ones the proving key
+    /// was set up with.
+    ///
+    /// The proof can only be verified with a verifying key set up with the same parameters, see
+    /// [ProverClient::setup_with_security](super::ProverClient::setup_with_security). Compressed,
+    /// Plonk and Groth16 proofs need the default parameters when the recursion verifying keys are
+    /// verified, and [Self::run] fails otherwise.
+    pub fn security(mut self, value: SecurityConfig) -> Self {
+        self.security = value;
+        self
+    }
+
     /// Set whether we should reconstruct commitments while proving.
     pub fn reconstruct_commitments(mut self, value: bool) -> Self {
         self.core_opts.reconstruct_commitments = value;
@@ -214,6 +332,27 @@ impl<'a> Prove<'a> {
         self
     }
 
+    /// Set the wall-clock time at the start of execution, see
+    /// [`ZKMContextBuilder::epoch`](zkm_core_executor::ZKMContextBuilder::epoch).
+    pub fn epoch(mut self, epoch: u32) -> Self {
+        self.context_builder.epoch(epoch);
+        self
+    }
+
+    /// Set the seed of the stream returned by `getrandom`, see
+    /// [`ZKMContextBuilder::random_seed`](zkm_core_executor::ZKMContextBuilder::random_seed).
+    pub fn random_seed(mut self, seed: u64) -> Self {
+        self.context_builder.random_seed(seed);
+        self
+    }
+
+    /// Fail on unrecognized Linux syscalls instead of treating them as no-ops, see
+    /// [`ZKMContextBuilder::strict_syscalls`](zkm_core_executor::ZKMContextBuilder::strict_syscalls).
+    pub fn strict_syscalls(mut self, strict: bool) -> Self {
+        self.context_builder.strict_syscalls(strict);
+        self
+    }
+
     /// Set the timeout for the proof's generation.
     ///
     /// This parameter is only used when the prover is run in network mode.
@@ -228,3 +367,94 @@ impl<'a> Prove<'a> {
         self
     }
 }
+
+/// Builder to prepare and configure the aggregation of compressed proofs into one proof.
+/// May be run with [Self::run].
+pub struct Aggregate<'a> {
+    prover: &'a dyn Prover<DefaultProverComponents>,
+    kind: ZKMProofKind,
+    proofs: Vec<ZKMProofWithPublicValues>,
+    recursion_opts: ZKMCoreOpts,
+}
+
+impl<'a> Aggregate<'a> {
+    /// Prepare to aggregate the given compressed proofs.
+    ///
+    /// Prefer using [ProverClient::aggregate](super::ProverClient::aggregate).
+    /// See there for more documentation.
+    pub fn new(
+        prover: &'a dyn Prover<DefaultProverComponents>,
+        proofs: Vec<ZKMProofWithPublicValues>,
+    ) -> Self {
+        Self {
+            prover,
+            kind: ZKMProofKind::Compressed,
+            proofs,
+            recursion_opts: ZKMCoreOpts::recursion(),
+        }
+    }
+
+    /// Aggregate the proofs, consuming the built action `self`.
+    ///
+    /// The public values of the returned proof are the [AggregationClaim] of every input proof,
+    /// in order, encoded with [AggregationClaim::to_bytes].
+    pub fn run(self) -> Result<ZKMProofWithPublicValues> {
+        let Self { prover, kind, proofs, recursion_opts } = self;
+        let opts = ZKMProverOpts { recursion_opts, ..Default::default() };
+
+        let mut reduce_proofs = Vec::with_capacity(proofs.len());
+        let mut public_values = ZKMPublicValues::new();
+        for (i, bundle) in proofs.into_iter().enumerate() {
+            let ZKMProof::Compressed(proof) = bundle.proof else {
+                bail!("proof {i} is not a compressed proof");
+            };
+            let claim = AggregationClaim::from_proof(&proof);
+            if claim.public_values_digest[..] != bundle.public_values.hash()[..] {
+                bail!("the public values of proof {i} do not match the proof");
+            }
+            public_values.write_slice(&claim.to_bytes());
+            reduce_proofs.push(*proof);
+        }
+
+        let aggregated = prover.zkm_prover().aggregate(reduce_proofs, opts)?;
+        let proof = match kind {
+            ZKMProofKind::Compressed => ZKMProof::Compressed(Box::new(aggregated)),
+            ZKMProofKind::Groth16 => {
+                let shrink_proof = prover.zkm_prover().shrink(aggregated, opts)?;
+                let outer_proof = prover.zkm_prover().wrap_bn254(shrink_proof, opts)?;
+
+                let groth16_bn254_artifacts =
+                    try_build_groth16_circuit_artifacts(&outer_proof.vk, &outer_proof.proof);
+                ZKMProof::Groth16(
+                    prover.zkm_prover().wrap_groth16_bn254(outer_proof, &groth16_bn254_artifacts),
+                )
+            }
+            _ => unreachable!(),
+        };
+
+        Ok(ZKMProofWithPublicValues {
+            proof,
+            stdin: ZKMStdin::new(),
+            public_values,
+            zkm_version: prover.version().to_string(),
+        })
+    }
+
+    /// Set the proof kind to the compressed mode. This is the default.
+    pub fn compressed(mut self) -> Self {
+        self.kind = ZKMProofKind::Compressed;
+        self
+    }
+
+    /// Set the proof mode to the groth16 bn254 mode.
+    pub fn groth16(mut self) -> Self {
+        self.kind = ZKMProofKind::Groth16;
+        self
+    }
+
+    /// Set the recursion shard size and shard batch size for the aggregation.
+    pub fn recursion_opts(mut self, value: ZKMCoreOpts) -> Self {
+        self.recursion_opts = value;
+        self
+    }
+}
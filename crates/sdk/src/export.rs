@@ -0,0 +1,149 @@
+//! # Ziren Export
+//!
+//! Fixtures, calldata and verifier contracts for verifying Plonk and Groth16 proofs onchain.
+
+use std::path::Path;
+
+use ethers::{
+    abi::{encode, Token},
+    utils::keccak256,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zkm_prover::HashableKey;
+
+use crate::{
+    install::try_install_circuit_artifacts, ZKMProof, ZKMProofKind, ZKMProofWithPublicValues,
+    ZKMVerifyingKey,
+};
+
+/// The signature of `IZKMVerifier.verifyProof`.
+pub const VERIFY_PROOF_SIGNATURE: &str = "verifyProof(bytes32,bytes,bytes)";
+
+/// A JSON fixture holding everything needed to verify a proof with the `ZKMVerifier` contract.
+///
+/// All byte strings are `0x`-prefixed hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZKMProofFixture {
+    /// The proof system, either `groth16` or `plonk`.
+    pub proof_system: String,
+    /// The hash of the circuit's verifying key, as returned by `VERIFIER_HASH()`.
+    pub verifier_hash: String,
+    /// The program verifying key, as returned by [`HashableKey::bytes32`].
+    pub vkey: String,
+    /// The public values of the program.
+    pub public_values: String,
+    /// The proof, as returned by [`ZKMProofWithPublicValues::bytes`].
+    pub proof: String,
+}
+
+#[derive(Error, Debug)]
+pub enum ZKMExportError {
+    #[error("only Plonk and Groth16 proofs can be verified onchain")]
+    UnsupportedProofKind,
+    #[error("failed to write the verifier contracts: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl ZKMProofWithPublicValues {
+    /// Returns the JSON fixture for verifying the proof onchain against `vk`.
+    pub fn fixture(&self, vk: &ZKMVerifyingKey) -> Result<ZKMProofFixture, ZKMExportError> {
+        let (proof_system, verifier_hash) = match &self.proof {
+            ZKMProof::Plonk(proof) => ("plonk", proof.plonk_vkey_hash),
+            ZKMProof::Groth16(proof) => ("groth16", proof.groth16_vkey_hash),
+            _ => return Err(ZKMExportError::UnsupportedProofKind),
+        };
+        Ok(ZKMProofFixture {
+            proof_system: proof_system.to_string(),
+            verifier_hash: format!("0x{}", hex::encode(verifier_hash)),
+            vkey: vk.bytes32(),
+            public_values: format!("0x{}", hex::encode(self.public_values.as_slice())),
+            proof: format!("0x{}", hex::encode(self.bytes())),
+        })
+    }
+
+    /// Returns the ABI-encoded calldata of `IZKMVerifier.verifyProof` for the proof and `vk`.
+    pub fn calldata(&self, vk: &ZKMVerifyingKey) -> Result<Vec<u8>, ZKMExportError> {
+        if !matches!(self.proof, ZKMProof::Plonk(_) | ZKMProof::Groth16(_)) {
+            return Err(ZKMExportError::UnsupportedProofKind);
+        }
+        let program_vkey = hex::decode(&vk.bytes32()[2..]).unwrap();
+        let arguments = encode(&[
+            Token::FixedBytes(program_vkey),
+            Token::Bytes(self.public_values.to_vec()),
+            Token::Bytes(self.bytes()),
+        ]);
+        Ok([&keccak256(VERIFY_PROOF_SIGNATURE)[..4], &arguments].concat())
+    }
+}
+
+/// Writes the Solidity verifier contracts for the Plonk or Groth16 circuit to `out_dir`.
+///
+/// The contracts are rendered with the verifying key hash of the circuit artifacts used by
+/// [`crate::ProverClient`], which are installed first if needed.
+pub fn export_verifier_contracts(
+    kind: ZKMProofKind,
+    out_dir: impl AsRef<Path>,
+) -> Result<(), ZKMExportError> {
+    let dev_mode = zkm_prover::build::zkm_dev_mode();
+    match kind {
+        ZKMProofKind::Plonk => zkm_prover::build::export_plonk_bn254_contracts(
+            if dev_mode {
+                zkm_prover::build::plonk_bn254_artifacts_dev_dir()
+            } else {
+                try_install_circuit_artifacts("plonk")
+            },
+            out_dir,
+        )?,
+        ZKMProofKind::Groth16 => zkm_prover::build::export_groth16_bn254_contracts(
+            if dev_mode {
+                zkm_prover::build::groth16_bn254_artifacts_dev_dir()
+            } else {
+                try_install_circuit_artifacts("groth16")
+            },
+            out_dir,
+        )?,
+        _ => return Err(ZKMExportError::UnsupportedProofKind),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use zkm_core_machine::io::ZKMStdin;
+    use zkm_primitives::io::ZKMPublicValues;
+    use zkm_prover::Groth16Bn254Proof;
+
+    use super::*;
+
+    #[test]
+    fn test_verify_proof_calldata() {
+        let (_, vk) = crate::ProverClient::mock().setup(test_artifacts::FIBONACCI_ELF);
+        let mut public_values = ZKMPublicValues::new();
+        public_values.write(&7u32);
+        let proof = ZKMProofWithPublicValues {
+            proof: ZKMProof::Groth16(Groth16Bn254Proof {
+                encoded_proof: "ab".to_string(),
+                groth16_vkey_hash: [1; 32],
+                public_inputs: ["".to_string(), "".to_string()],
+                raw_proof: "".to_string(),
+            }),
+            stdin: ZKMStdin::new(),
+            public_values,
+            zkm_version: "".to_string(),
+        };
+
+        let fixture = proof.fixture(&vk).unwrap();
+        assert_eq!(fixture.proof_system, "groth16");
+        assert_eq!(fixture.proof, "0x01010101ab");
+        assert_eq!(fixture.public_values, "0x07000000");
+
+        let calldata = proof.calldata(&vk).unwrap();
+        // The selector of `verifyProof(bytes32,bytes,bytes)`.
+        assert_eq!(calldata[..4], keccak256(VERIFY_PROOF_SIGNATURE)[..4]);
+        assert_eq!(hex::encode(&calldata[4..36]), fixture.vkey[2..]);
+        // Three head words, then the public values and the proof as length-prefixed words.
+        assert_eq!(calldata.len(), 4 + 3 * 32 + 2 * 32 + 2 * 32);
+    }
+}
@@ -14,6 +14,9 @@ use {
     std::{cmp::min, process::Command},
 };
 
+use zkm_prover::OuterSC;
+use zkm_stark::{ShardProof, StarkVerifyingKey};
+
 use crate::ZKM_CIRCUIT_VERSION;
 
 /// The base URL for the S3 bucket containing the circuit artifacts.
@@ -34,6 +37,13 @@ pub fn plonk_circuit_artifacts_dir() -> PathBuf {
 /// Tries to install the groth16 circuit artifacts if they are not already installed.
 #[must_use]
 pub fn try_install_circuit_artifacts(artifacts_type: &str) -> PathBuf {
+    // The arkworks backend can't use the released gnark keys and builds its own, see
+    // `try_build_groth16_circuit_artifacts`.
+    #[cfg(feature = "ark-groth16")]
+    if artifacts_type == "groth16" {
+        return zkm_prover::build::groth16_bn254_ark_artifacts_dir();
+    }
+
     let build_dir = if artifacts_type == "groth16" {
         groth16_circuit_artifacts_dir()
     } else if artifacts_type == "plonk" {
@@ -64,6 +74,37 @@ pub fn try_install_circuit_artifacts(artifacts_type: &str) -> PathBuf {
     build_dir
 }
 
+/// Gets the groth16 circuit artifacts to wrap the given proof with.
+///
+/// The artifacts are built in development mode and by the arkworks backend, which builds them
+/// once per circuit version, and installed otherwise.
+pub fn try_build_groth16_circuit_artifacts(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+) -> PathBuf {
+    if zkm_prover::build::zkm_dev_mode() {
+        zkm_prover::build::try_build_groth16_bn254_artifacts_dev(template_vk, template_proof)
+    } else {
+        try_build_release_groth16_circuit_artifacts(template_vk, template_proof)
+    }
+}
+
+#[cfg(feature = "ark-groth16")]
+fn try_build_release_groth16_circuit_artifacts(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+) -> PathBuf {
+    zkm_prover::build::try_build_groth16_bn254_ark_artifacts(template_vk, template_proof)
+}
+
+#[cfg(not(feature = "ark-groth16"))]
+fn try_build_release_groth16_circuit_artifacts(
+    _template_vk: &StarkVerifyingKey<OuterSC>,
+    _template_proof: &ShardProof<OuterSC>,
+) -> PathBuf {
+    try_install_circuit_artifacts("groth16")
+}
+
 /// Install the latest circuit artifacts.
 ///
 /// This function will download the latest circuit artifacts from the S3 bucket and extract them
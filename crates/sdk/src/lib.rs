@@ -3,11 +3,13 @@
 //! A library for interacting with the Ziren zkVM.
 
 pub mod action;
+pub mod export;
 // pub mod artifacts;
 pub mod install;
 
 #[cfg(feature = "network")]
 pub use crate::network::prover::NetworkProver;
+use anyhow::Result;
 use cfg_if::cfg_if;
 use std::env;
 // #[cfg(feature = "cuda")]
@@ -18,6 +20,7 @@ pub mod proof;
 pub mod provers;
 pub mod utils;
 
+pub use export::{export_verifier_contracts, ZKMExportError, ZKMProofFixture};
 pub use proof::*;
 pub use provers::ZKMVerificationError;
 use zkm_prover::components::DefaultProverComponents;
@@ -27,12 +30,15 @@ pub use provers::{CpuProver, MockProver, Prover};
 
 pub use zkm_build::include_elf;
 pub use zkm_core_executor::{ExecutionReport, HookEnv, ZKMContext, ZKMContextBuilder};
-pub use zkm_core_machine::{io::ZKMStdin, ZKM_CIRCUIT_VERSION};
+pub use zkm_core_machine::{io::ZKMStdin, utils::StepCheckpoint, ZKM_CIRCUIT_VERSION};
 pub use zkm_primitives::io::ZKMPublicValues;
 pub use zkm_prover::{
+    aggregate::{aggregation_commitment, AggregationClaim},
+    estimate::{ShardEstimate, ShardKind, ZKMEstimate},
     CoreSC, HashableKey, InnerSC, OuterSC, PlonkBn254Proof, ProverMode, ZKMProver, ZKMProvingKey,
     ZKMVerifyingKey,
 };
+pub use zkm_stark::{SecurityConfig, SecurityModel};
 
 // Re-export the utilities.
 use crate::utils::block_on;
@@ -202,6 +208,28 @@ impl ProverClient {
         action::Execute::new(self.prover.as_ref(), elf, stdin)
     }
 
+    /// Prepare to estimate how long proving the given program on the given input takes and how
+    /// big the proof is, without proving it. The returned [action::Estimate] may be configured
+    /// via its methods before running.
+    ///
+    /// To estimate, call [action::Estimate::run], which executes the program and returns the
+    /// predicted shards, recursion tree and proving time.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use zkm_sdk::{ProverClient, ZKMStdin};
+    ///
+    /// let elf = test_artifacts::FIBONACCI_ELF;
+    /// let client = ProverClient::new();
+    /// let mut stdin = ZKMStdin::new();
+    /// stdin.write(&10usize);
+    /// let estimate = client.estimate(elf, stdin).run().unwrap();
+    /// println!("{} shards, ~{:?}", estimate.shards.len(), estimate.total_time());
+    /// ```
+    pub fn estimate<'a>(&'a self, elf: &'a [u8], stdin: ZKMStdin) -> action::Estimate<'a> {
+        action::Estimate::new(self.prover.as_ref(), elf, stdin)
+    }
+
     /// Prepare to prove the execution of the given program with the given input in the default
     /// mode. The returned [action::Prove] may be configured via its methods before running.
     /// For example, calling [action::Prove::compressed] sets the mode to compressed mode.
@@ -235,6 +263,55 @@ impl ProverClient {
         action::Prove::new(self.prover.as_ref(), pk, stdin)
     }
 
+    /// Proves the next step of an incremental computation.
+    ///
+    /// The program ends each step but the last with `zkm_zkvm::lib::ivc::end_step`. The first
+    /// step is proven with `prev` set to `None` and starts at the entrypoint; every later step
+    /// is given the proof of the steps before it and resumes from where they stopped, with
+    /// `input` appended to the program's input. The returned proof folds the new step into
+    /// `prev`, so it attests to the execution so far; check it with [Self::verify_step]. The
+    /// public values are committed once the program halts.
+    ///
+    /// Steps are proven locally and can't verify proofs.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use zkm_sdk::{ProverClient, ZKMStdin};
+    ///
+    /// let elf = test_artifacts::RUNNING_SUM_ELF;
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let mut proof = None;
+    /// for batch in [vec![1u64, 2], vec![3], vec![]] {
+    ///     let mut input = ZKMStdin::new();
+    ///     input.write(&batch);
+    ///     proof = Some(client.prove_step(&pk, proof.as_ref(), input).unwrap());
+    /// }
+    /// let mut proof = proof.unwrap();
+    /// client.verify_step(&proof, &vk).unwrap();
+    /// assert_eq!(proof.proof.public_values.read::<u64>(), 6);
+    /// ```
+    pub fn prove_step(
+        &self,
+        pk: &ZKMProvingKey,
+        prev: Option<&ZKMStepProof>,
+        input: ZKMStdin,
+    ) -> Result<ZKMStepProof> {
+        self.prover.prove_step(pk, prev, input)
+    }
+
+    /// Verifies the proof of the steps made by [Self::prove_step] so far.
+    ///
+    /// Until the program halts, this only checks that the steps ran the program of `vk` from its
+    /// entrypoint.
+    pub fn verify_step(
+        &self,
+        proof: &ZKMStepProof,
+        vk: &ZKMVerifyingKey,
+    ) -> Result<(), ZKMVerificationError> {
+        self.prover.verify_step(proof, vk)
+    }
+
     /// Verifies that the given proof is valid and matches the given verification key produced by
     /// [Self::setup].
     ///
@@ -258,6 +335,50 @@ impl ProverClient {
         self.prover.verify(proof, vk)
     }
 
+    /// Prepare to fold complete compressed proofs, possibly of different programs, into one
+    /// proof. The returned [action::Aggregate] may be configured via its methods before running.
+    ///
+    /// To aggregate, call [action::Aggregate::run]. By default the aggregated proof is a
+    /// compressed proof; use [action::Aggregate::groth16] to wrap it for onchain verification.
+    /// The aggregated proof commits to [aggregation_commitment] of the [AggregationClaim] of every
+    /// input proof, and its public values are those claims. Check it with
+    /// [Self::verify_aggregation].
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use zkm_sdk::{AggregationClaim, ProverClient, ZKMStdin};
+    ///
+    /// let elf = test_artifacts::FIBONACCI_ELF;
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proofs = (0..3usize)
+    ///     .map(|n| {
+    ///         let mut stdin = ZKMStdin::new();
+    ///         stdin.write(&n);
+    ///         client.prove(&pk, stdin).compressed().run().unwrap()
+    ///     })
+    ///     .collect();
+    /// let aggregated = client.aggregate(proofs).run().unwrap();
+    /// client.verify_aggregation(&aggregated).unwrap();
+    /// let claims = AggregationClaim::decode_all(aggregated.public_values.as_slice()).unwrap();
+    /// assert_eq!(claims.len(), 3);
+    /// ```
+    pub fn aggregate(&self, proofs: Vec<ZKMProofWithPublicValues>) -> action::Aggregate<'_> {
+        action::Aggregate::new(self.prover.as_ref(), proofs)
+    }
+
+    /// Verifies that the given proof was made by [Self::aggregate] and commits to the claims in
+    /// its public values.
+    ///
+    /// The verifying key and public values digest of every aggregated proof are in the claims;
+    /// compare them to the expected ones to check what was aggregated.
+    pub fn verify_aggregation(
+        &self,
+        proof: &ZKMProofWithPublicValues,
+    ) -> Result<(), ZKMVerificationError> {
+        self.prover.verify_aggregation(proof)
+    }
+
     /// Gets the current version of the Ziren zkVM.
     ///
     /// Note: This is not the same as the version of the Ziren SDK.
@@ -284,6 +405,30 @@ impl ProverClient {
     pub fn setup(&self, elf: &[u8]) -> (ZKMProvingKey, ZKMVerifyingKey) {
         self.prover.setup(elf)
     }
+
+    /// Setup a program like [Self::setup], for core proofs with the given security parameters.
+    ///
+    /// Proofs made with these keys are rejected by [Self::verify] if the parameters are weaker
+    /// than the default ones.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use zkm_sdk::{ProverClient, SecurityConfig, ZKMStdin};
+    ///
+    /// let elf = test_artifacts::FIBONACCI_ELF;
+    /// let client = ProverClient::new();
+    /// let security = SecurityConfig::conjectured(128, 2, 20);
+    /// let (pk, vk) = client.setup_with_security(elf, security);
+    /// let proof = client.prove(&pk, ZKMStdin::new()).core().run().unwrap();
+    /// client.verify(&proof, &vk).unwrap();
+    /// ```
+    pub fn setup_with_security(
+        &self,
+        elf: &[u8],
+        security: SecurityConfig,
+    ) -> (ZKMProvingKey, ZKMVerifyingKey) {
+        self.prover.setup_with_security(elf, security)
+    }
 }
 
 impl Default for ProverClient {
@@ -585,4 +730,69 @@ mod tests {
         let proof = client.prove(&pk, stdin).compress_to_groth16().run().unwrap();
         client.verify(&proof, &vk).unwrap();
     }
+
+    fn step_input(batch: &[u64]) -> ZKMStdin {
+        let mut input = ZKMStdin::new();
+        input.write(&batch.to_vec());
+        input
+    }
+
+    #[test]
+    fn test_e2e_prove_step() {
+        utils::setup_logger();
+        let client = ProverClient::cpu();
+        let elf = test_artifacts::RUNNING_SUM_ELF;
+        let (pk, vk) = client.setup(elf);
+
+        let mut proof = None;
+        for batch in [&[1u64, 2][..], &[3], &[4, 5, 6]] {
+            let step = client.prove_step(&pk, proof.as_ref(), step_input(batch)).unwrap();
+            assert!(!step.halted());
+            client.verify_step(&step, &vk).unwrap();
+            proof = Some(step);
+        }
+
+        let mut proof = client.prove_step(&pk, proof.as_ref(), step_input(&[])).unwrap();
+        assert!(proof.halted());
+        client.verify_step(&proof, &vk).unwrap();
+        client.verify(&proof.proof, &vk).unwrap();
+        assert_eq!(proof.proof.public_values.read::<u64>(), 21);
+    }
+
+    #[test]
+    fn test_prove_step_rejects_invalid_steps() {
+        utils::setup_logger();
+        let client = ProverClient::cpu();
+        let elf = test_artifacts::RUNNING_SUM_ELF;
+        let (pk, vk) = client.setup(elf);
+
+        // A step can't verify proofs.
+        let proof = client.prove(&pk, step_input(&[])).compressed().run().unwrap();
+        let ZKMProof::Compressed(reduce_proof) = proof.proof.clone() else { panic!() };
+        let mut input = step_input(&[1]);
+        input.write_proof(*reduce_proof, vk.vk.clone());
+        assert!(client.prove_step(&pk, None, input).is_err());
+
+        // No step can follow the one the program halted in.
+        let halted = client.prove_step(&pk, None, step_input(&[])).unwrap();
+        assert!(halted.halted());
+        assert!(client.prove_step(&pk, Some(&halted), step_input(&[1])).is_err());
+
+        // An unfinished proof doesn't pass as a complete one.
+        let step = client.prove_step(&pk, None, step_input(&[1])).unwrap();
+        let mut forged = step.clone();
+        forged.checkpoint = None;
+        assert!(client.verify_step(&forged, &vk).is_err());
+    }
+
+    #[test]
+    fn test_verify_step_rejects_other_program() {
+        utils::setup_logger();
+        let client = ProverClient::cpu();
+        let (pk, _) = client.setup(test_artifacts::RUNNING_SUM_ELF);
+        let (_, other_vk) = client.setup(test_artifacts::FIBONACCI_ELF);
+
+        let step = client.prove_step(&pk, None, step_input(&[1, 2])).unwrap();
+        assert!(client.verify_step(&step, &other_vk).is_err());
+    }
 }
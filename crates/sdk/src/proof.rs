@@ -1,14 +1,23 @@
-use std::{fmt::Debug, fs::File, path::Path};
+use std::{borrow::Borrow, fmt::Debug, fs::File, io::BufReader, path::Path};
 
-use anyhow::Result;
+use num_bigint::BigUint;
+use p3_field::PrimeField;
+use p3_koala_bear::KoalaBear;
 use serde::{Deserialize, Serialize};
 use strum_macros::{EnumDiscriminants, EnumTryAs};
-use zkm_core_executor::ZKMReduceProof;
-use zkm_core_machine::io::ZKMStdin;
+use thiserror::Error;
+use zkm_core_executor::{LegacyReduceProof, ZKMReduceProof};
+use zkm_core_machine::{
+    io::{LegacyZKMStdin, ZKMStdin},
+    utils::StepCheckpoint,
+};
 use zkm_primitives::io::ZKMPublicValues;
 
-use zkm_prover::{CoreSC, Groth16Bn254Proof, InnerSC, PlonkBn254Proof};
-use zkm_stark::{MachineVerificationError, ShardProof};
+use zkm_prover::{
+    utils::koalabears_to_bn254, CoreSC, Groth16Bn254Proof, InnerSC, PlonkBn254Proof,
+    RecursionPublicValues,
+};
+use zkm_stark::{LegacyShardProof, MachineVerificationError, SecurityConfig, ShardProof};
 
 /// A proof generated with Ziren of a particular proof mode.
 /// Consistent with the definition in file crates/verifier/src/stark/mod.rs
@@ -42,17 +51,371 @@ pub struct ZKMProofWithPublicValues {
     pub zkm_version: String,
 }
 
+/// A [`ZKMProof`] saved before shard proofs and verifying keys recorded their security
+/// parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LegacyZKMProof {
+    Core(Vec<LegacyShardProof<CoreSC>>),
+    Compressed(Box<LegacyReduceProof<InnerSC>>),
+    Plonk(PlonkBn254Proof),
+    Groth16(Groth16Bn254Proof),
+    CompressToGroth16,
+}
+
+/// A proof saved by an SDK that wrote the raw bincode encoding of [`ZKMProofWithPublicValues`].
+///
+/// Such proofs don't record the security parameters of their STARK proofs and verifying keys,
+/// so [`Self::with_security`] makes the caller state them before the proof can be used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyZKMProofWithPublicValues {
+    pub proof: LegacyZKMProof,
+    pub stdin: LegacyZKMStdin,
+    pub public_values: ZKMPublicValues,
+    pub zkm_version: String,
+}
+
+impl LegacyZKMProofWithPublicValues {
+    /// Loads a proof saved by an SDK that wrote the raw bincode encoding of the proof.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ZKMProofFormatError> {
+        let file = BufReader::new(File::open(path)?);
+        bincode::deserialize_from(file).map_err(Into::into)
+    }
+
+    /// Decodes a proof encoded by an SDK that wrote the raw bincode encoding of the proof.
+    pub fn decode(bytes: &[u8]) -> Result<Self, ZKMProofFormatError> {
+        bincode::deserialize(bytes).map_err(Into::into)
+    }
+
+    /// The proof, assuming its STARK proofs and verifying keys, including those of the proofs in
+    /// its stdin, were proven with `security`. Its stdin has no files.
+    ///
+    /// The SDKs that saved such proofs proved core and compressed proofs with
+    /// [`SecurityConfig::default`] unless they were configured otherwise. Verification fails if
+    /// `security` is not the one the proof was proven with.
+    pub fn with_security(self, security: SecurityConfig) -> ZKMProofWithPublicValues {
+        let proof = match self.proof {
+            LegacyZKMProof::Core(shard_proofs) => ZKMProof::Core(
+                shard_proofs.into_iter().map(|proof| proof.with_security(security)).collect(),
+            ),
+            LegacyZKMProof::Compressed(proof) => {
+                ZKMProof::Compressed(Box::new(proof.with_security(security)))
+            }
+            LegacyZKMProof::Plonk(proof) => ZKMProof::Plonk(proof),
+            LegacyZKMProof::Groth16(proof) => ZKMProof::Groth16(proof),
+            LegacyZKMProof::CompressToGroth16 => ZKMProof::CompressToGroth16,
+        };
+        ZKMProofWithPublicValues {
+            proof,
+            stdin: self.stdin.with_security(security),
+            public_values: self.public_values,
+            zkm_version: self.zkm_version,
+        }
+    }
+}
+
+/// The proof of the steps of an incremental computation made so far, see
+/// [`crate::ProverClient::prove_step`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZKMStepProof {
+    /// The compressed proof of all the steps so far. Its public values are empty until the
+    /// program halts.
+    pub proof: ZKMProofWithPublicValues,
+    /// The state the next step resumes from, or `None` if the program halted.
+    pub checkpoint: Option<StepCheckpoint>,
+}
+
+impl ZKMStepProof {
+    /// Whether the program halted, so that no more steps can follow.
+    pub fn halted(&self) -> bool {
+        self.checkpoint.is_none()
+    }
+}
+
+/// The magic bytes every saved proof starts with.
+pub const PROOF_MAGIC: [u8; 4] = *b"ZKMP";
+
+/// The version of the proof file format written by [`ZKMProofWithPublicValues::save`].
+pub const PROOF_FORMAT_VERSION: u16 = 1;
+
+/// The metadata at the start of a saved proof, which can be read without decoding the proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZKMProofHeader {
+    /// The version of the file format.
+    pub format_version: u16,
+    /// The kind of the proof.
+    pub kind: ZKMProofKind,
+    /// The Bn254 hash of the program's verifying key as 32 big-endian bytes, as in
+    /// `HashableKey::bytes32`. It is zero for core proofs, which do not record it.
+    pub vk_hash: [u8; 32],
+    /// The version of the Ziren SDK that saved the proof.
+    pub ziren_version: String,
+    /// The circuit version the proof was generated with.
+    pub circuit_version: String,
+}
+
+#[derive(Error, Debug)]
+pub enum ZKMProofFormatError {
+    #[error("failed to access the proof file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(
+        "not a versioned proof file; proofs saved by an older SDK must be loaded with \
+         `LegacyZKMProofWithPublicValues::load`, given the security parameters they were proven \
+         with and saved again"
+    )]
+    Legacy,
+    #[error("proof format version {found} is not supported by this SDK (current: {current})")]
+    UnsupportedVersion { found: u16, current: u16 },
+    #[error("unknown proof kind {0}")]
+    UnknownKind(u8),
+    #[error("the proof is truncated")]
+    Truncated,
+    #[error("the proof has {0} trailing bytes")]
+    TrailingBytes(usize),
+    #[error("the {0} is not valid UTF-8")]
+    InvalidString(&'static str),
+    #[error("the encoded proof is not valid hex: {0}")]
+    InvalidEncodedProof(#[from] hex::FromHexError),
+    #[error("failed to (de)serialize the proof: {0}")]
+    Bincode(#[from] bincode::Error),
+}
+
+/// Reads the fields of a saved proof in order.
+struct ProofReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ProofReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ZKMProofFormatError> {
+        if self.bytes.len() < len {
+            return Err(ZKMProofFormatError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ZKMProofFormatError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], ZKMProofFormatError> {
+        let len = u32::from_le_bytes(self.array()?);
+        self.take(len as usize)
+    }
+
+    fn string(&mut self, field: &'static str) -> Result<String, ZKMProofFormatError> {
+        let bytes = self.bytes()?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| ZKMProofFormatError::InvalidString(field))
+    }
+}
+
+fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    buf.extend_from_slice(bytes);
+}
+
+fn kind_to_u8(kind: ZKMProofKind) -> u8 {
+    match kind {
+        ZKMProofKind::Core => 0,
+        ZKMProofKind::Compressed => 1,
+        ZKMProofKind::Plonk => 2,
+        ZKMProofKind::Groth16 => 3,
+        ZKMProofKind::CompressToGroth16 => 4,
+    }
+}
+
+fn kind_from_u8(kind: u8) -> Result<ZKMProofKind, ZKMProofFormatError> {
+    match kind {
+        0 => Ok(ZKMProofKind::Core),
+        1 => Ok(ZKMProofKind::Compressed),
+        2 => Ok(ZKMProofKind::Plonk),
+        3 => Ok(ZKMProofKind::Groth16),
+        4 => Ok(ZKMProofKind::CompressToGroth16),
+        kind => Err(ZKMProofFormatError::UnknownKind(kind)),
+    }
+}
+
+/// Encodes a decimal field element as 32 big-endian bytes, or zero if it is not one.
+fn decimal_to_bytes32(decimal: &str) -> [u8; 32] {
+    let bytes = decimal.parse::<BigUint>().unwrap_or_default().to_bytes_be();
+    let mut bytes32 = [0u8; 32];
+    if bytes.len() <= 32 {
+        bytes32[32 - bytes.len()..].copy_from_slice(&bytes);
+    }
+    bytes32
+}
+
 impl ZKMProofWithPublicValues {
-    /// Saves the proof to a path.
-    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
-        bincode::serialize_into(File::create(path).expect("failed to open file"), self)
-            .map_err(Into::into)
+    /// Saves the proof to a path in the versioned format described in [`Self::encode`].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ZKMProofFormatError> {
+        std::fs::write(path, self.encode()?).map_err(Into::into)
     }
 
-    /// Loads a proof from a path.
-    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
-        bincode::deserialize_from(File::open(path).expect("failed to open file"))
-            .map_err(Into::into)
+    /// Loads a proof saved with [`Self::save`].
+    ///
+    /// Returns [`ZKMProofFormatError::Legacy`] for proofs saved before the format was versioned;
+    /// those can still be read with [`LegacyZKMProofWithPublicValues::load`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ZKMProofFormatError> {
+        Self::decode(&std::fs::read(path)?)
+    }
+
+    /// The header [`Self::encode`] writes for this proof.
+    pub fn header(&self) -> ZKMProofHeader {
+        let vk_hash = match &self.proof {
+            ZKMProof::Compressed(proof) => {
+                let public_values: &RecursionPublicValues<KoalaBear> =
+                    proof.proof.public_values.as_slice().borrow();
+                let bytes = koalabears_to_bn254(&public_values.zkm_vk_digest)
+                    .as_canonical_biguint()
+                    .to_bytes_be();
+                let mut vk_hash = [0u8; 32];
+                vk_hash[32 - bytes.len()..].copy_from_slice(&bytes);
+                vk_hash
+            }
+            ZKMProof::Plonk(proof) => decimal_to_bytes32(&proof.public_inputs[0]),
+            ZKMProof::Groth16(proof) => decimal_to_bytes32(&proof.public_inputs[0]),
+            ZKMProof::Core(_) | ZKMProof::CompressToGroth16 => [0u8; 32],
+        };
+        ZKMProofHeader {
+            format_version: PROOF_FORMAT_VERSION,
+            kind: ZKMProofKind::from(&self.proof),
+            vk_hash,
+            ziren_version: env!("CARGO_PKG_VERSION").to_string(),
+            circuit_version: self.zkm_version.clone(),
+        }
+    }
+
+    /// Encodes the proof in the versioned proof format.
+    ///
+    /// The format is a language-neutral envelope around the proof. Integers are little-endian. A `bytes` field is a `u32` length followed by the bytes, and a
+    /// `string` field is a `bytes` field holding UTF-8. The fields are, in order:
+    ///
+    /// | field             | encoding                                                                |
+    /// |-------------------|-------------------------------------------------------------------------|
+    /// | magic             | the 4 bytes `ZKMP`                                                      |
+    /// | format version    | `u16`                                                                   |
+    /// | proof kind        | `u8`: core 0, compressed 1, Plonk 2, Groth16 3, compressed-to-Groth16 4 |
+    /// | vk hash           | 32 bytes, see [`ZKMProofHeader::vk_hash`]                               |
+    /// | Ziren version     | `string`                                                                |
+    /// | circuit version   | `string`                                                                |
+    /// | public values     | `bytes`                                                                 |
+    /// | proof             | `bytes`, depending on the proof kind                                    |
+    /// | stdin             | `bytes`, the bincode encoding of the [`ZKMStdin`]                       |
+    ///
+    /// Plonk and Groth16 proofs are encoded as the 32-byte hash of the circuit's verifying key,
+    /// the two public inputs as decimal `string`s, the encoded proof as `bytes` and the raw proof
+    /// as a `string`. Compressed-to-Groth16 proofs are empty.
+    ///
+    /// Only the envelope and the Plonk and Groth16 proofs are a stable, language-neutral
+    /// encoding. Core and compressed proofs, like the stdin, are the bincode encoding of the Rust
+    /// types, which is not specified and can change between SDK versions. Services outside Rust
+    /// can read their header and public values and skip them by their length, but need the SDK
+    /// that saved them to decode or verify them.
+    pub fn encode(&self) -> Result<Vec<u8>, ZKMProofFormatError> {
+        let header = self.header();
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&PROOF_MAGIC);
+        buf.extend_from_slice(&header.format_version.to_le_bytes());
+        buf.push(kind_to_u8(header.kind));
+        buf.extend_from_slice(&header.vk_hash);
+        write_bytes(&mut buf, header.ziren_version.as_bytes());
+        write_bytes(&mut buf, header.circuit_version.as_bytes());
+        write_bytes(&mut buf, self.public_values.as_slice());
+
+        let proof = match &self.proof {
+            ZKMProof::Core(proof) => bincode::serialize(proof)?,
+            ZKMProof::Compressed(proof) => bincode::serialize(proof)?,
+            ZKMProof::Plonk(PlonkBn254Proof {
+                public_inputs,
+                encoded_proof,
+                raw_proof,
+                plonk_vkey_hash: vkey_hash,
+            })
+            | ZKMProof::Groth16(Groth16Bn254Proof {
+                public_inputs,
+                encoded_proof,
+                raw_proof,
+                groth16_vkey_hash: vkey_hash,
+            }) => {
+                let mut proof = vkey_hash.to_vec();
+                write_bytes(&mut proof, public_inputs[0].as_bytes());
+                write_bytes(&mut proof, public_inputs[1].as_bytes());
+                write_bytes(&mut proof, &hex::decode(encoded_proof)?);
+                write_bytes(&mut proof, raw_proof.as_bytes());
+                proof
+            }
+            ZKMProof::CompressToGroth16 => Vec::new(),
+        };
+        write_bytes(&mut buf, &proof);
+        write_bytes(&mut buf, &bincode::serialize(&self.stdin)?);
+        Ok(buf)
+    }
+
+    /// Decodes a proof encoded with [`Self::encode`].
+    pub fn decode(bytes: &[u8]) -> Result<Self, ZKMProofFormatError> {
+        let mut reader = ProofReader { bytes };
+        let header = Self::read_header(&mut reader)?;
+        let public_values = ZKMPublicValues::from(reader.bytes()?);
+
+        let mut proof_reader = ProofReader { bytes: reader.bytes()? };
+        let proof = match header.kind {
+            ZKMProofKind::Core => ZKMProof::Core(bincode::deserialize(proof_reader.bytes)?),
+            ZKMProofKind::Compressed => {
+                ZKMProof::Compressed(bincode::deserialize(proof_reader.bytes)?)
+            }
+            ZKMProofKind::Plonk | ZKMProofKind::Groth16 => {
+                let vkey_hash = proof_reader.array::<32>()?;
+                let public_inputs =
+                    [proof_reader.string("public input")?, proof_reader.string("public input")?];
+                let encoded_proof = hex::encode(proof_reader.bytes()?);
+                let raw_proof = proof_reader.string("raw proof")?;
+                if header.kind == ZKMProofKind::Plonk {
+                    ZKMProof::Plonk(PlonkBn254Proof {
+                        public_inputs,
+                        encoded_proof,
+                        raw_proof,
+                        plonk_vkey_hash: vkey_hash,
+                    })
+                } else {
+                    ZKMProof::Groth16(Groth16Bn254Proof {
+                        public_inputs,
+                        encoded_proof,
+                        raw_proof,
+                        groth16_vkey_hash: vkey_hash,
+                    })
+                }
+            }
+            ZKMProofKind::CompressToGroth16 => ZKMProof::CompressToGroth16,
+        };
+        let stdin = bincode::deserialize(reader.bytes()?)?;
+        if !reader.bytes.is_empty() {
+            return Err(ZKMProofFormatError::TrailingBytes(reader.bytes.len()));
+        }
+
+        Ok(Self { proof, stdin, public_values, zkm_version: header.circuit_version })
+    }
+
+    /// Reads the header of a proof encoded with [`Self::encode`] without decoding the proof.
+    pub fn decode_header(bytes: &[u8]) -> Result<ZKMProofHeader, ZKMProofFormatError> {
+        Self::read_header(&mut ProofReader { bytes })
+    }
+
+    fn read_header(reader: &mut ProofReader<'_>) -> Result<ZKMProofHeader, ZKMProofFormatError> {
+        if reader.bytes.len() < PROOF_MAGIC.len() || reader.array::<4>()? != PROOF_MAGIC {
+            return Err(ZKMProofFormatError::Legacy);
+        }
+        let format_version = u16::from_le_bytes(reader.array()?);
+        if format_version != PROOF_FORMAT_VERSION {
+            return Err(ZKMProofFormatError::UnsupportedVersion {
+                found: format_version,
+                current: PROOF_FORMAT_VERSION,
+            });
+        }
+        let kind = kind_from_u8(reader.array::<1>()?[0])?;
+        let vk_hash = reader.array()?;
+        let ziren_version = reader.string("Ziren version")?;
+        let circuit_version = reader.string("circuit version")?;
+        Ok(ZKMProofHeader { format_version, kind, vk_hash, ziren_version, circuit_version })
     }
 
     /// Returns the raw proof as a string.
@@ -173,6 +536,120 @@ mod tests {
         assert_eq!(mock_groth16_proof.bytes(), Vec::<u8>::new());
     }
 
+    #[test]
+    fn test_groth16_proof_encoding_roundtrip() {
+        let mut public_values = ZKMPublicValues::new();
+        public_values.write(&42u32);
+        let proof = ZKMProofWithPublicValues {
+            proof: ZKMProof::Groth16(Groth16Bn254Proof {
+                encoded_proof: "abcd".to_string(),
+                groth16_vkey_hash: [7; 32],
+                public_inputs: ["258".to_string(), "3".to_string()],
+                raw_proof: "raw".to_string(),
+            }),
+            stdin: ZKMStdin::new(),
+            public_values,
+            zkm_version: "v1.0.0".to_string(),
+        };
+
+        let bytes = proof.encode().unwrap();
+        let header = ZKMProofWithPublicValues::decode_header(&bytes).unwrap();
+        assert_eq!(header.format_version, PROOF_FORMAT_VERSION);
+        assert_eq!(header.kind, ZKMProofKind::Groth16);
+        assert_eq!(header.vk_hash[30..], [1, 2]);
+        assert_eq!(header.circuit_version, "v1.0.0");
+
+        let decoded = ZKMProofWithPublicValues::decode(&bytes).unwrap();
+        assert_eq!(decoded.public_values.as_slice(), proof.public_values.as_slice());
+        assert_eq!(decoded.zkm_version, proof.zkm_version);
+        let ZKMProof::Groth16(groth16) = decoded.proof else { panic!("wrong proof kind") };
+        assert_eq!(groth16.encoded_proof, "abcd");
+        assert_eq!(groth16.groth16_vkey_hash, [7; 32]);
+        assert_eq!(groth16.public_inputs, ["258".to_string(), "3".to_string()]);
+        assert_eq!(groth16.raw_proof, "raw");
+    }
+
+    #[test]
+    fn test_proof_decoding_errors() {
+        let proof = ZKMProofWithPublicValues {
+            proof: ZKMProof::Core(vec![]),
+            stdin: ZKMStdin::new(),
+            public_values: ZKMPublicValues::new(),
+            zkm_version: "".to_string(),
+        };
+        let bytes = proof.encode().unwrap();
+        assert!(ZKMProofWithPublicValues::decode(&bytes).is_ok());
+
+        // Proofs saved by older SDKs are raw bincode.
+        let legacy = bincode::serialize(&proof).unwrap();
+        assert!(matches!(
+            ZKMProofWithPublicValues::decode(&legacy),
+            Err(ZKMProofFormatError::Legacy)
+        ));
+
+        for version in [0, PROOF_FORMAT_VERSION + 1] {
+            let mut other = bytes.clone();
+            other[4..6].copy_from_slice(&version.to_le_bytes());
+            assert!(matches!(
+                ZKMProofWithPublicValues::decode(&other),
+                Err(ZKMProofFormatError::UnsupportedVersion { found, .. }) if found == version
+            ));
+        }
+
+        assert!(matches!(
+            ZKMProofWithPublicValues::decode(&bytes[..bytes.len() - 1]),
+            Err(ZKMProofFormatError::Truncated)
+        ));
+        assert!(matches!(
+            ZKMProofWithPublicValues::load("/nonexistent/proof.bin"),
+            Err(ZKMProofFormatError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_legacy_proof() {
+        use hashbrown::HashMap;
+        use p3_field::FieldAlgebra;
+        use p3_fri::FriProof;
+        use zkm_stark::{ShardCommitment, ShardOpenedValues};
+
+        let shard_proof = LegacyShardProof {
+            commitment: ShardCommitment {
+                main_commit: [KoalaBear::ZERO; 8].into(),
+                permutation_commit: [KoalaBear::ZERO; 8].into(),
+                quotient_commit: [KoalaBear::ZERO; 8].into(),
+            },
+            opened_values: ShardOpenedValues { chips: vec![] },
+            opening_proof: FriProof {
+                commit_phase_commits: vec![],
+                query_proofs: vec![],
+                final_poly: Default::default(),
+                pow_witness: KoalaBear::ZERO,
+            },
+            chip_ordering: HashMap::new(),
+            public_values: vec![],
+        };
+        let legacy = LegacyZKMProofWithPublicValues {
+            proof: LegacyZKMProof::Core(vec![shard_proof]),
+            stdin: LegacyZKMStdin { buffer: vec![vec![1]], ptr: 0, proofs: vec![] },
+            public_values: ZKMPublicValues::new(),
+            zkm_version: "v1.0.0".to_string(),
+        };
+        let bytes = bincode::serialize(&legacy).unwrap();
+
+        let security = SecurityConfig::conjectured(128, 2, 20);
+        let proof = LegacyZKMProofWithPublicValues::decode(&bytes).unwrap().with_security(security);
+        let ZKMProof::Core(shard_proofs) = &proof.proof else { panic!("wrong proof kind") };
+        assert_eq!(shard_proofs[0].security, security);
+        assert_eq!(proof.stdin.buffer, vec![vec![1]]);
+        assert_eq!(proof.zkm_version, "v1.0.0");
+
+        // Once given its security, the proof is saved in the versioned format.
+        let decoded = ZKMProofWithPublicValues::decode(&proof.encode().unwrap()).unwrap();
+        let ZKMProof::Core(shard_proofs) = decoded.proof else { panic!("wrong proof kind") };
+        assert_eq!(shard_proofs[0].security, security);
+    }
+
     #[test]
     #[should_panic(expected = "only Stark, Plonk and Groth16 proofs are verifiable onchain")]
     fn test_core_proof_bytes_unimplemented() {
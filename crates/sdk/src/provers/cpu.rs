@@ -1,9 +1,12 @@
 use anyhow::Result;
 use zkm_core_executor::ZKMContext;
 use zkm_core_machine::io::ZKMStdin;
-use zkm_prover::{components::DefaultProverComponents, ZKMProver};
+use zkm_prover::{
+    components::{DefaultProverComponents, ZKMProverComponents},
+    ZKMProver,
+};
 
-use crate::install::try_install_circuit_artifacts;
+use crate::install::{try_build_groth16_circuit_artifacts, try_install_circuit_artifacts};
 use crate::{
     provers::ProofOpts, Prover, ZKMProof, ZKMProofKind, ZKMProofWithPublicValues, ZKMProvingKey,
     ZKMVerifyingKey,
@@ -12,8 +15,11 @@ use crate::{
 use super::ProverType;
 
 /// An implementation of [crate::ProverClient] that can generate end-to-end proofs locally.
-pub struct CpuProver {
-    prover: ZKMProver<DefaultProverComponents>,
+///
+/// To prove programs using precompiles from outside of Ziren, create it with
+/// [`CpuProver::from_prover`] from a [ZKMProver] whose components provide the extension chips.
+pub struct CpuProver<C: ZKMProverComponents = DefaultProverComponents> {
+    prover: ZKMProver<C>,
 }
 
 impl CpuProver {
@@ -22,9 +28,11 @@ impl CpuProver {
         let prover = ZKMProver::new();
         Self { prover }
     }
+}
 
+impl<C: ZKMProverComponents> CpuProver<C> {
     /// Creates a new [LocalProver] from an existing [ZKMProver].
-    pub fn from_prover(prover: ZKMProver<DefaultProverComponents>) -> Self {
+    pub fn from_prover(prover: ZKMProver<C>) -> Self {
         Self { prover }
     }
 
@@ -45,14 +53,8 @@ impl CpuProver {
         // Genenerate the wrap proof.
         let outer_proof = self.prover.wrap_bn254(shrink_proof, opts.zkm_prover_opts)?;
 
-        let groth16_bn254_artifacts = if zkm_prover::build::zkm_dev_mode() {
-            zkm_prover::build::try_build_groth16_bn254_artifacts_dev(
-                &outer_proof.vk,
-                &outer_proof.proof,
-            )
-        } else {
-            try_install_circuit_artifacts("groth16")
-        };
+        let groth16_bn254_artifacts =
+            try_build_groth16_circuit_artifacts(&outer_proof.vk, &outer_proof.proof);
 
         let proof = self.prover.wrap_groth16_bn254(outer_proof, &groth16_bn254_artifacts);
         Ok(ZKMProofWithPublicValues {
@@ -64,7 +66,7 @@ impl CpuProver {
     }
 }
 
-impl Prover<DefaultProverComponents> for CpuProver {
+impl<C: ZKMProverComponents> Prover<C> for CpuProver<C> {
     fn id(&self) -> ProverType {
         ProverType::Cpu
     }
@@ -73,7 +75,7 @@ impl Prover<DefaultProverComponents> for CpuProver {
         self.prover.setup(elf)
     }
 
-    fn zkm_prover(&self) -> &ZKMProver<DefaultProverComponents> {
+    fn zkm_prover(&self) -> &ZKMProver<C> {
         &self.prover
     }
 
@@ -152,14 +154,8 @@ impl Prover<DefaultProverComponents> for CpuProver {
                 cycles,
             ));
         } else if kind == ZKMProofKind::Groth16 {
-            let groth16_bn254_artifacts = if zkm_prover::build::zkm_dev_mode() {
-                zkm_prover::build::try_build_groth16_bn254_artifacts_dev(
-                    &outer_proof.vk,
-                    &outer_proof.proof,
-                )
-            } else {
-                try_install_circuit_artifacts("groth16")
-            };
+            let groth16_bn254_artifacts =
+                try_build_groth16_circuit_artifacts(&outer_proof.vk, &outer_proof.proof);
 
             let proof = self.prover.wrap_groth16_bn254(outer_proof, &groth16_bn254_artifacts);
             return Ok((
@@ -2,7 +2,9 @@
 use hashbrown::HashMap;
 use zkm_core_executor::{ZKMContext, ZKMReduceProof};
 use zkm_core_machine::io::ZKMStdin;
-use zkm_stark::{ShardCommitment, ShardOpenedValues, ShardProof, StarkVerifyingKey};
+use zkm_stark::{
+    SecurityConfig, ShardCommitment, ShardOpenedValues, ShardProof, StarkVerifyingKey,
+};
 
 use crate::{
     Prover, ZKMProof, ZKMProofKind, ZKMProofWithPublicValues, ZKMProvingKey, ZKMVerificationError,
@@ -87,6 +89,7 @@ impl Prover<DefaultProverComponents> for MockProver {
                     },
                     chip_ordering: HashMap::new(),
                     public_values: vec![],
+                    security: SecurityConfig::default(),
                 };
 
                 let reduce_vk = StarkVerifyingKey {
@@ -95,6 +98,7 @@ impl Prover<DefaultProverComponents> for MockProver {
                     chip_information: vec![],
                     chip_ordering: HashMap::new(),
                     initial_global_cumulative_sum: SepticDigest::zero(),
+                    security: SecurityConfig::default(),
                 };
 
                 let proof = ZKMProof::Compressed(Box::new(ZKMReduceProof {
@@ -13,7 +13,7 @@ use p3_field::PrimeField32;
 use std::borrow::Borrow;
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use strum_macros::EnumString;
 use thiserror::Error;
 use zkm_core_executor::ExecutionReport;
@@ -21,14 +21,15 @@ use zkm_core_executor::ZKMContext;
 use zkm_core_machine::{io::ZKMStdin, ZKM_CIRCUIT_VERSION};
 use zkm_primitives::io::ZKMPublicValues;
 use zkm_prover::{
+    aggregate::AggregationClaim,
     components::{DefaultProverComponents, ZKMProverComponents},
     CoreSC, InnerSC, ZKMCoreProofData, ZKMProver, ZKMProvingKey, ZKMVerifyingKey,
 };
-use zkm_stark::{air::PublicValues, MachineVerificationError, Word, ZKMProverOpts};
+use zkm_stark::{air::PublicValues, MachineVerificationError, SecurityConfig, Word, ZKMProverOpts};
 
 use crate::install::try_install_circuit_artifacts;
 use crate::ProverClient;
-use crate::{ZKMProof, ZKMProofKind, ZKMProofWithPublicValues};
+use crate::{ZKMProof, ZKMProofKind, ZKMProofWithPublicValues, ZKMStepProof};
 
 /// The type of prover.
 #[derive(Debug, PartialEq, EnumString)]
@@ -62,6 +63,8 @@ pub enum ZKMVerificationError {
     Plonk(anyhow::Error),
     #[error("Groth16 verification error: {0}")]
     Groth16(anyhow::Error),
+    #[error("Unsupported proof kind")]
+    UnsupportedProofKind,
 }
 
 /// An implementation of [crate::ProverClient].
@@ -82,6 +85,16 @@ pub trait Prover<C: ZKMProverComponents>: Send + Sync {
     /// Generate the proving and verifying keys for the given program.
     fn setup(&self, elf: &[u8]) -> (ZKMProvingKey, ZKMVerifyingKey);
 
+    /// Generate the proving and verifying keys for the given program, for core proofs with the
+    /// given security parameters.
+    fn setup_with_security(
+        &self,
+        elf: &[u8],
+        security: SecurityConfig,
+    ) -> (ZKMProvingKey, ZKMVerifyingKey) {
+        self.zkm_prover().setup_with_security(elf, security)
+    }
+
     /// Prove the execution of a MIPS ELF with the given inputs, according to the given proof mode.
     fn prove(
         &self,
@@ -216,6 +229,111 @@ pub trait Prover<C: ZKMProverComponents>: Send + Sync {
             ZKMProof::CompressToGroth16 => unreachable!(),
         }
     }
+
+    /// Prove the next step of an incremental computation, see [`crate::ProverClient::prove_step`].
+    ///
+    /// Steps are always proven locally.
+    fn prove_step(
+        &self,
+        pk: &ZKMProvingKey,
+        prev: Option<&ZKMStepProof>,
+        input: ZKMStdin,
+    ) -> Result<ZKMStepProof> {
+        if !input.proofs.is_empty() {
+            bail!("the steps of an incremental computation cannot verify proofs");
+        }
+        let (prev_proof, resume) = match prev {
+            None => (None, None),
+            Some(ZKMStepProof { checkpoint: None, .. }) => {
+                bail!("the program halted in the previous step")
+            }
+            Some(ZKMStepProof { proof, checkpoint: Some(checkpoint) }) => {
+                let ZKMProof::Compressed(reduce_proof) = &proof.proof else {
+                    bail!("the proof of the previous steps must be compressed");
+                };
+                (Some(*reduce_proof.clone()), Some(checkpoint.clone()))
+            }
+        };
+
+        let security = pk.vk.vk.security;
+        self.zkm_prover().check_compress_security(security)?;
+        let opts = ZKMProverOpts { security, ..Default::default() };
+        let (proof, checkpoint) =
+            self.zkm_prover().prove_core_step(pk, &input, resume, opts, ZKMContext::default())?;
+        let public_values = proof.public_values.clone();
+        let reduce_proof = self.zkm_prover().compress_step(
+            &pk.vk,
+            proof,
+            prev_proof,
+            checkpoint.is_none(),
+            opts,
+        )?;
+        Ok(ZKMStepProof {
+            proof: ZKMProofWithPublicValues {
+                proof: ZKMProof::Compressed(Box::new(reduce_proof)),
+                stdin: input,
+                public_values,
+                zkm_version: self.version().to_string(),
+            },
+            checkpoint,
+        })
+    }
+
+    /// Verify the proof of the steps of an incremental computation made so far.
+    ///
+    /// Once the program halted, this is the same as [`Prover::verify`]. Before that, the proof
+    /// only attests that the steps so far ran the program of `vkey` from its entrypoint.
+    fn verify_step(
+        &self,
+        proof: &ZKMStepProof,
+        vkey: &ZKMVerifyingKey,
+    ) -> Result<(), ZKMVerificationError> {
+        if proof.halted() {
+            return self.verify(&proof.proof, vkey);
+        }
+        if proof.proof.zkm_version != self.version() {
+            return Err(ZKMVerificationError::VersionMismatch(proof.proof.zkm_version.clone()));
+        }
+        match &proof.proof.proof {
+            ZKMProof::Compressed(reduce_proof) => self
+                .zkm_prover()
+                .verify_compressed_step(reduce_proof, vkey)
+                .map_err(ZKMVerificationError::Recursion),
+            _ => Err(ZKMVerificationError::UnsupportedProofKind),
+        }
+    }
+
+    /// Verify that the given proof was made by [`crate::action::Aggregate`] and commits to the
+    /// claims encoded in its public values.
+    fn verify_aggregation(
+        &self,
+        bundle: &ZKMProofWithPublicValues,
+    ) -> Result<(), ZKMVerificationError> {
+        if bundle.zkm_version != self.version() {
+            return Err(ZKMVerificationError::VersionMismatch(bundle.zkm_version.clone()));
+        }
+        let claims = AggregationClaim::decode_all(bundle.public_values.as_slice())
+            .ok_or(ZKMVerificationError::InvalidPublicValues)?;
+        match &bundle.proof {
+            ZKMProof::Compressed(proof) => self
+                .zkm_prover()
+                .verify_aggregated(proof, &claims)
+                .map_err(ZKMVerificationError::Recursion),
+            ZKMProof::Groth16(proof) => self
+                .zkm_prover()
+                .verify_aggregated_groth16_bn254(
+                    proof,
+                    &claims,
+                    &if zkm_prover::build::zkm_dev_mode() {
+                        zkm_prover::build::groth16_bn254_artifacts_dev_dir()
+                    } else {
+                        try_install_circuit_artifacts("groth16")
+                    },
+                )
+                .map_err(ZKMVerificationError::Groth16),
+            _ => Err(ZKMVerificationError::UnsupportedProofKind),
+        }
+    }
 }
 
 impl Prover<DefaultProverComponents> for ProverClient {
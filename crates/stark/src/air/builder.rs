@@ -206,6 +206,7 @@ pub trait InstructionAirBuilder: BaseAirBuilder {
         is_rw_a: impl Into<Self::Expr>,
         is_write_hi: impl Into<Self::Expr>,
         is_halt: impl Into<Self::Expr>,
+        is_trap: impl Into<Self::Expr>,
         is_sequential: impl Into<Self::Expr>,
         multiplicity: impl Into<Self::Expr>,
     ) {
@@ -225,6 +226,7 @@ pub trait InstructionAirBuilder: BaseAirBuilder {
             .chain(once(is_rw_a.into()))
             .chain(once(is_write_hi.into()))
             .chain(once(is_halt.into()))
+            .chain(once(is_trap.into()))
             .chain(once(is_sequential.into()))
             .collect();
 
@@ -254,6 +256,7 @@ pub trait InstructionAirBuilder: BaseAirBuilder {
         is_rw_a: impl Into<Self::Expr>,
         is_write_hi: impl Into<Self::Expr>,
         is_halt: impl Into<Self::Expr>,
+        is_trap: impl Into<Self::Expr>,
         is_sequential: impl Into<Self::Expr>,
         multiplicity: impl Into<Self::Expr>,
     ) {
@@ -273,6 +276,7 @@ pub trait InstructionAirBuilder: BaseAirBuilder {
             .chain(once(is_rw_a.into()))
             .chain(once(is_write_hi.into()))
             .chain(once(is_halt.into()))
+            .chain(once(is_trap.into()))
             .chain(once(is_sequential.into()))
             .collect();
 
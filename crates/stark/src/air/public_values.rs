@@ -37,6 +37,12 @@ pub struct PublicValues<W, T> {
     /// The exit code of the program.  Only valid if halt has been executed.
     pub exit_code: T,
 
+    /// The seed of the `getrandom` stream, as its low and high words.
+    pub random_seed: [W; 2],
+
+    /// The wall-clock time at the start of execution, in seconds since the Unix epoch.
+    pub epoch: W,
+
     /// The shard number.
     pub shard: T,
 
@@ -56,7 +62,7 @@ pub struct PublicValues<W, T> {
     pub last_finalize_addr_bits: [T; 32],
 
     /// This field is here to ensure that the size of the public values struct is a multiple of 8.
-    pub empty: [T; 3],
+    pub empty: [T; 7],
 }
 
 impl PublicValues<u32, u32> {
@@ -96,6 +102,17 @@ impl<F: PrimeField32> PublicValues<Word<F>, F> {
             .flat_map(|w| w.into_iter().map(|f| f.as_canonical_u32() as u8))
             .collect_vec()
     }
+
+    /// Returns the `getrandom` seed the execution was committed to.
+    pub fn random_seed(&self) -> u64 {
+        let [lo, hi] = self.random_seed.map(|w| w.to_u32());
+        u64::from(lo) | (u64::from(hi) << 32)
+    }
+
+    /// Returns the epoch the execution was committed to.
+    pub fn epoch(&self) -> u32 {
+        self.epoch.to_u32()
+    }
 }
 
 impl<T: Clone> Borrow<PublicValues<Word<T>, T>> for [T] {
@@ -130,6 +147,8 @@ impl<F: FieldAlgebra> From<PublicValues<u32, u32>> for PublicValues<Word<F>, F>
             start_pc,
             next_pc,
             exit_code,
+            random_seed,
+            epoch,
             shard,
             execution_shard,
             previous_init_addr_bits,
@@ -148,6 +167,8 @@ impl<F: FieldAlgebra> From<PublicValues<u32, u32>> for PublicValues<Word<F>, F>
         let start_pc = F::from_canonical_u32(start_pc);
         let next_pc = F::from_canonical_u32(next_pc);
         let exit_code = F::from_canonical_u32(exit_code);
+        let random_seed = random_seed.map(Word::from);
+        let epoch = Word::from(epoch);
         let shard = F::from_canonical_u32(shard);
         let execution_shard = F::from_canonical_u32(execution_shard);
         let previous_init_addr_bits = previous_init_addr_bits.map(F::from_canonical_u32);
@@ -161,13 +182,15 @@ impl<F: FieldAlgebra> From<PublicValues<u32, u32>> for PublicValues<Word<F>, F>
             start_pc,
             next_pc,
             exit_code,
+            random_seed,
+            epoch,
             shard,
             execution_shard,
             previous_init_addr_bits,
             last_init_addr_bits,
             previous_finalize_addr_bits,
             last_finalize_addr_bits,
-            empty: [F::ZERO, F::ZERO, F::ZERO],
+            empty: [F::ZERO; 7],
         }
     }
 }
@@ -3,6 +3,8 @@ use p3_commit::{Pcs, PolynomialSpace};
 use p3_field::{ExtensionField, Field, PrimeField};
 use serde::{de::DeserializeOwned, Serialize};
 
+use crate::SecurityConfig;
+
 pub type PcsError<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
     <SC as StarkGenericConfig>::Challenger,
@@ -72,6 +74,9 @@ pub trait StarkGenericConfig: 'static + Send + Sync + Serialize + DeserializeOwn
 
     /// Initialize a new challenger.
     fn challenger(&self) -> Self::Challenger;
+
+    /// The security parameters the PCS was configured with.
+    fn security(&self) -> SecurityConfig;
 }
 
 pub trait ZeroCommitment<SC: StarkGenericConfig> {
@@ -1,6 +1,6 @@
 #![allow(missing_docs)]
 
-use crate::{Com, StarkGenericConfig, ZeroCommitment};
+use crate::{Com, SecurityConfig, StarkGenericConfig, ZeroCommitment};
 use p3_challenger::DuplexChallenger;
 use p3_commit::ExtensionMmcs;
 use p3_dft::Radix2DitParallel;
@@ -49,33 +49,21 @@ pub fn inner_perm() -> InnerPerm {
     poseidon2_init()
 }
 
-/// The FRI config for Ziren proofs.
+/// The FRI config for Ziren proofs with the given security parameters.
 #[must_use]
-pub fn zkm_fri_config() -> FriConfig<InnerChallengeMmcs> {
+pub fn zkm_fri_config(security: SecurityConfig) -> FriConfig<InnerChallengeMmcs> {
     let perm = inner_perm();
     let hash = InnerHash::new(perm.clone());
     let compress = InnerCompress::new(perm.clone());
     let challenge_mmcs = InnerChallengeMmcs::new(InnerValMmcs::new(hash, compress));
-    let num_queries = match std::env::var("FRI_QUERIES") {
-        Ok(value) => value.parse().unwrap(),
-        Err(_) => 84,
-    };
-    FriConfig { log_blowup: 1, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+    security.fri_config(challenge_mmcs)
 }
 
 /// The FRI config for inner recursion.
-/// This targets by default 100 bits of security.
+/// This targets the default 100 bits of security.
 #[must_use]
 pub fn inner_fri_config() -> FriConfig<InnerChallengeMmcs> {
-    let perm = inner_perm();
-    let hash = InnerHash::new(perm.clone());
-    let compress = InnerCompress::new(perm.clone());
-    let challenge_mmcs = InnerChallengeMmcs::new(InnerValMmcs::new(hash, compress));
-    let num_queries = match std::env::var("FRI_QUERIES") {
-        Ok(value) => value.parse().unwrap(),
-        Err(_) => 84,
-    };
-    FriConfig { log_blowup: 1, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+    zkm_fri_config(SecurityConfig::default())
 }
 
 /// The recursion config used for recursive reduce circuit.
@@ -142,6 +130,10 @@ impl StarkGenericConfig for KoalaBearPoseidon2Inner {
     fn challenger(&self) -> Self::Challenger {
         InnerChallenger::new(self.perm.clone())
     }
+
+    fn security(&self) -> SecurityConfig {
+        SecurityConfig::from_fri_config(self.pcs.fri_config())
+    }
 }
 
 impl ZeroCommitment<KoalaBearPoseidon2Inner> for InnerPcs {
@@ -164,7 +156,7 @@ pub mod koala_bear_poseidon2 {
     use serde::{Deserialize, Serialize};
     use zkm_primitives::RC_16_30;
 
-    use crate::{Com, StarkGenericConfig, ZeroCommitment, DIGEST_SIZE};
+    use crate::{Com, SecurityConfig, StarkGenericConfig, ZeroCommitment, DIGEST_SIZE};
 
     pub type Val = KoalaBear;
     pub type Challenge = BinomialExtensionField<Val, 4>;
@@ -198,51 +190,32 @@ pub mod koala_bear_poseidon2 {
         Perm::new(external_round_constants, internal_round_constants)
     }
 
+    /// The FRI config for the given security parameters.
     #[must_use]
-    /// This targets by default 100 bits of security.
-    pub fn default_fri_config() -> FriConfig<ChallengeMmcs> {
+    pub fn fri_config(security: SecurityConfig) -> FriConfig<ChallengeMmcs> {
         let perm = my_perm();
         let hash = MyHash::new(perm.clone());
         let compress = MyCompress::new(perm.clone());
         let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-        let num_queries = match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 84,
-        };
-        FriConfig { log_blowup: 1, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+        security.fri_config(challenge_mmcs)
     }
 
     #[must_use]
     /// This targets by default 100 bits of security.
-    pub fn compressed_fri_config() -> FriConfig<ChallengeMmcs> {
-        let perm = my_perm();
-        let hash = MyHash::new(perm.clone());
-        let compress = MyCompress::new(perm.clone());
-        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-        let num_queries = match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 42,
-        };
-        FriConfig { log_blowup: 2, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+    pub fn default_fri_config() -> FriConfig<ChallengeMmcs> {
+        fri_config(SecurityConfig::default())
     }
 
     #[must_use]
     /// This targets by default 100 bits of security.
-    pub fn ultra_compressed_fri_config() -> FriConfig<ChallengeMmcs> {
-        let perm = my_perm();
-        let hash = MyHash::new(perm.clone());
-        let compress = MyCompress::new(perm.clone());
-        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-        let num_queries = match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 28,
-        };
-        FriConfig { log_blowup: 3, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+    pub fn compressed_fri_config() -> FriConfig<ChallengeMmcs> {
+        fri_config(SecurityConfig::compressed())
     }
 
-    enum KoalaBearPoseidon2Type {
-        Default,
-        Compressed,
+    #[must_use]
+    /// This targets by default 100 bits of security.
+    pub fn ultra_compressed_fri_config() -> FriConfig<ChallengeMmcs> {
+        fri_config(SecurityConfig::ultra_compressed())
     }
 
     #[derive(Deserialize)]
@@ -250,53 +223,41 @@ pub mod koala_bear_poseidon2 {
     pub struct KoalaBearPoseidon2 {
         pub perm: Perm,
         pcs: Pcs,
-        config_type: KoalaBearPoseidon2Type,
+        security: SecurityConfig,
     }
 
     impl KoalaBearPoseidon2 {
         #[must_use]
         pub fn new() -> Self {
-            let perm = my_perm();
-            let hash = MyHash::new(perm.clone());
-            let compress = MyCompress::new(perm.clone());
-            let val_mmcs = ValMmcs::new(hash, compress);
-            let dft = Dft::default();
-            let fri_config = default_fri_config();
-            let pcs = Pcs::new(dft, val_mmcs, fri_config);
-            Self { pcs, perm, config_type: KoalaBearPoseidon2Type::Default }
+            Self::with_security(SecurityConfig::default())
         }
 
         #[must_use]
         pub fn compressed() -> Self {
-            let perm = my_perm();
-            let hash = MyHash::new(perm.clone());
-            let compress = MyCompress::new(perm.clone());
-            let val_mmcs = ValMmcs::new(hash, compress);
-            let dft = Dft::default();
-            let fri_config = compressed_fri_config();
-            let pcs = Pcs::new(dft, val_mmcs, fri_config);
-            Self { pcs, perm, config_type: KoalaBearPoseidon2Type::Compressed }
+            Self::with_security(SecurityConfig::compressed())
         }
 
         #[must_use]
         pub fn ultra_compressed() -> Self {
+            Self::with_security(SecurityConfig::ultra_compressed())
+        }
+
+        /// Creates a config whose FRI parameters are derived from `security`.
+        #[must_use]
+        pub fn with_security(security: SecurityConfig) -> Self {
             let perm = my_perm();
             let hash = MyHash::new(perm.clone());
             let compress = MyCompress::new(perm.clone());
             let val_mmcs = ValMmcs::new(hash, compress);
             let dft = Dft::default();
-            let fri_config = ultra_compressed_fri_config();
-            let pcs = Pcs::new(dft, val_mmcs, fri_config);
-            Self { pcs, perm, config_type: KoalaBearPoseidon2Type::Compressed }
+            let pcs = Pcs::new(dft, val_mmcs, fri_config(security));
+            Self { pcs, perm, security }
         }
     }
 
     impl Clone for KoalaBearPoseidon2 {
         fn clone(&self) -> Self {
-            match self.config_type {
-                KoalaBearPoseidon2Type::Default => Self::new(),
-                KoalaBearPoseidon2Type::Compressed => Self::compressed(),
-            }
+            Self::with_security(self.security)
         }
     }
 
@@ -336,6 +297,10 @@ pub mod koala_bear_poseidon2 {
         fn challenger(&self) -> Self::Challenger {
             Challenger::new(self.perm.clone())
         }
+
+        fn security(&self) -> SecurityConfig {
+            self.security
+        }
     }
 
     impl ZeroCommitment<KoalaBearPoseidon2> for Pcs {
@@ -0,0 +1,123 @@
+//! Shard proofs and verifying keys encoded before they recorded their security parameters.
+//!
+//! Such encodings decode into [`LegacyShardProof`] and [`LegacyStarkVerifyingKey`], whose
+//! security is unknown. Converting them with `with_security` makes the caller state the
+//! parameters they were proven with, which verification then checks like any other.
+
+use std::fmt::Debug;
+
+use hashbrown::HashMap;
+use p3_matrix::Dimensions;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    septic_digest::SepticDigest, Challenge, Com, Dom, OpeningProof, SecurityConfig,
+    ShardCommitment, ShardOpenedValues, ShardProof, StarkGenericConfig, StarkVerifyingKey, Val,
+};
+
+/// A [`ShardProof`] encoded without its security parameters.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(bound = "")]
+pub struct LegacyShardProof<SC: StarkGenericConfig> {
+    pub commitment: ShardCommitment<Com<SC>>,
+    pub opened_values: ShardOpenedValues<Val<SC>, Challenge<SC>>,
+    pub opening_proof: OpeningProof<SC>,
+    pub chip_ordering: HashMap<String, usize>,
+    pub public_values: Vec<Val<SC>>,
+}
+
+impl<SC: StarkGenericConfig> LegacyShardProof<SC> {
+    /// The shard proof, assuming it was proven with `security`.
+    pub fn with_security(self, security: SecurityConfig) -> ShardProof<SC> {
+        ShardProof {
+            commitment: self.commitment,
+            opened_values: self.opened_values,
+            opening_proof: self.opening_proof,
+            chip_ordering: self.chip_ordering,
+            public_values: self.public_values,
+            security,
+        }
+    }
+}
+
+impl<SC: StarkGenericConfig> Debug for LegacyShardProof<SC> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LegacyShardProof").finish()
+    }
+}
+
+/// A [`StarkVerifyingKey`] encoded without its security parameters.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "Dom<SC>: Serialize"))]
+#[serde(bound(deserialize = "Dom<SC>: DeserializeOwned"))]
+pub struct LegacyStarkVerifyingKey<SC: StarkGenericConfig> {
+    pub commit: Com<SC>,
+    pub pc_start: Val<SC>,
+    pub initial_global_cumulative_sum: SepticDigest<Val<SC>>,
+    pub chip_information: Vec<(String, Dom<SC>, Dimensions)>,
+    pub chip_ordering: HashMap<String, usize>,
+}
+
+impl<SC: StarkGenericConfig> LegacyStarkVerifyingKey<SC> {
+    /// The verifying key, assuming the program was set up with `security`.
+    pub fn with_security(self, security: SecurityConfig) -> StarkVerifyingKey<SC> {
+        StarkVerifyingKey {
+            commit: self.commit,
+            pc_start: self.pc_start,
+            initial_global_cumulative_sum: self.initial_global_cumulative_sum,
+            chip_information: self.chip_information,
+            chip_ordering: self.chip_ordering,
+            security,
+        }
+    }
+}
+
+impl<SC: StarkGenericConfig> Debug for LegacyStarkVerifyingKey<SC> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LegacyVerifyingKey").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::FieldAlgebra;
+    use p3_fri::FriProof;
+    use p3_koala_bear::KoalaBear;
+
+    use super::*;
+    use crate::koala_bear_poseidon2::KoalaBearPoseidon2;
+
+    #[test]
+    fn test_legacy_shard_proof() {
+        let legacy = LegacyShardProof::<KoalaBearPoseidon2> {
+            commitment: ShardCommitment {
+                main_commit: [KoalaBear::ZERO; 8].into(),
+                permutation_commit: [KoalaBear::ZERO; 8].into(),
+                quotient_commit: [KoalaBear::ZERO; 8].into(),
+            },
+            opened_values: ShardOpenedValues { chips: vec![] },
+            opening_proof: FriProof {
+                commit_phase_commits: vec![],
+                query_proofs: vec![],
+                final_poly: Default::default(),
+                pow_witness: KoalaBear::ZERO,
+            },
+            chip_ordering: HashMap::new(),
+            public_values: vec![KoalaBear::ONE],
+        };
+
+        // Like bincode, postcard can't tell that the security is missing.
+        let bytes = postcard::to_allocvec(&legacy).unwrap();
+        assert!(postcard::from_bytes::<ShardProof<KoalaBearPoseidon2>>(&bytes).is_err());
+
+        let legacy: LegacyShardProof<KoalaBearPoseidon2> = postcard::from_bytes(&bytes).unwrap();
+        let security = SecurityConfig::compressed();
+        let proof = legacy.with_security(security);
+        assert_eq!(proof.security, security);
+        assert_eq!(proof.public_values, vec![KoalaBear::ONE]);
+
+        let bytes = postcard::to_allocvec(&proof).unwrap();
+        let decoded: ShardProof<KoalaBearPoseidon2> = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.security, security);
+    }
+}
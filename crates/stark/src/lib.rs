@@ -10,6 +10,7 @@ mod config;
 mod debug;
 mod folder;
 mod kb31_poseidon2;
+mod legacy;
 mod lookup;
 mod machine;
 mod opts;
@@ -18,6 +19,7 @@ mod proof;
 mod prover;
 mod quotient;
 mod record;
+mod security;
 pub mod septic_curve;
 pub mod septic_digest;
 pub mod septic_extension;
@@ -35,6 +37,7 @@ pub use config::*;
 pub use debug::*;
 pub use folder::*;
 pub use kb31_poseidon2::*;
+pub use legacy::*;
 pub use lookup::*;
 pub use machine::*;
 pub use opts::*;
@@ -43,6 +46,7 @@ pub use proof::*;
 pub use prover::*;
 pub use quotient::*;
 pub use record::*;
+pub use security::*;
 pub use types::*;
 pub use verifier::*;
 pub use word::*;
@@ -27,7 +27,8 @@ use crate::{
 };
 
 use super::{
-    Chip, Com, MachineProof, PcsProverData, StarkGenericConfig, Val, VerificationError, Verifier,
+    Chip, Com, MachineProof, PcsProverData, SecurityConfig, StarkGenericConfig, Val,
+    VerificationError, Verifier,
 };
 
 /// A chip in a machine.
@@ -101,6 +102,8 @@ pub struct StarkVerifyingKey<SC: StarkGenericConfig> {
     pub chip_information: Vec<(String, Dom<SC>, Dimensions)>,
     /// The chip ordering.
     pub chip_ordering: HashMap<String, usize>,
+    /// The security parameters the program was set up with.
+    pub security: SecurityConfig,
 }
 
 impl<SC: StarkGenericConfig> StarkVerifyingKey<SC> {
@@ -434,6 +437,7 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>> + Air<SymbolicAirBuilder<Val
                 initial_global_cumulative_sum,
                 chip_information,
                 chip_ordering,
+                security: self.config.security(),
             },
         )
     }
@@ -482,6 +486,17 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>> + Air<SymbolicAirBuilder<Val
         SC::Challenger: Clone,
         A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
     {
+        // The key and every shard must have been produced with the parameters of this machine.
+        let security = self.config.security();
+        if vk.security != security {
+            return Err(MachineVerificationError::SecurityMismatch(vk.security, security));
+        }
+        if let Some(shard_proof) =
+            proof.shard_proofs.iter().find(|shard_proof| shard_proof.security != security)
+        {
+            return Err(MachineVerificationError::SecurityMismatch(shard_proof.security, security));
+        }
+
         // Observe the preprocessed commitment.
         vk.observe_into(challenger);
 
@@ -556,6 +571,10 @@ pub enum MachineVerificationError<SC: StarkGenericConfig> {
     CpuLogDegreeTooLarge(usize),
     /// The verification key is not allowed.
     InvalidVerificationKey,
+    /// The proof or verifying key was produced with other security parameters than expected.
+    SecurityMismatch(SecurityConfig, SecurityConfig),
+    /// The proof was produced with weaker security parameters than required.
+    InsufficientSecurity(SecurityConfig, SecurityConfig),
 }
 
 impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
@@ -598,6 +617,12 @@ impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
             MachineVerificationError::InvalidVerificationKey => {
                 write!(f, "Invalid verification key")
             }
+            MachineVerificationError::SecurityMismatch(found, expected) => {
+                write!(f, "Security mismatch: found {:?}, expected {:?}", found, expected)
+            }
+            MachineVerificationError::InsufficientSecurity(found, required) => {
+                write!(f, "Insufficient security: found {:?}, required {:?}", found, required)
+            }
         }
     }
 }
@@ -3,6 +3,8 @@ use std::env;
 use serde::{Deserialize, Serialize};
 use sysinfo::System;
 
+use crate::SecurityConfig;
+
 const MAX_SHARD_SIZE: usize = 1 << 21;
 const RECURSION_MAX_SHARD_SIZE: usize = 1 << 22;
 const MAX_SHARD_BATCH_SIZE: usize = 8;
@@ -20,11 +22,17 @@ pub struct ZKMProverOpts {
     pub core_opts: ZKMCoreOpts,
     /// Options for the recursion prover.
     pub recursion_opts: ZKMCoreOpts,
+    /// The security parameters of the core proof.
+    pub security: SecurityConfig,
 }
 
 impl Default for ZKMProverOpts {
     fn default() -> Self {
-        Self { core_opts: ZKMCoreOpts::default(), recursion_opts: ZKMCoreOpts::recursion() }
+        Self {
+            core_opts: ZKMCoreOpts::default(),
+            recursion_opts: ZKMCoreOpts::recursion(),
+            security: SecurityConfig::default(),
+        }
     }
 }
 
@@ -68,6 +76,8 @@ impl ZKMProverOpts {
         opts.core_opts.split_opts.keccak /= divisor;
         opts.core_opts.split_opts.sha_extend /= divisor;
         opts.core_opts.split_opts.sha_compress /= divisor;
+        opts.core_opts.split_opts.blake3_compress /= divisor;
+        opts.core_opts.split_opts.blake2b_compress /= divisor;
         opts.core_opts.split_opts.memory /= divisor;
 
         opts.recursion_opts.shard_batch_size = 2;
@@ -95,6 +105,10 @@ pub struct ZKMCoreOpts {
     pub checkpoints_channel_capacity: usize,
     /// The capacity of the channel for records and traces.
     pub records_and_traces_channel_capacity: usize,
+    /// Whether to fail on Linux syscalls the executor does not recognize instead of treating them
+    /// as no-ops.
+    #[serde(default)]
+    pub strict_syscalls: bool,
 }
 
 impl Default for ZKMCoreOpts {
@@ -127,6 +141,7 @@ impl Default for ZKMCoreOpts {
                     |s| s.parse::<usize>().unwrap_or(DEFAULT_RECORDS_AND_TRACES_CHANNEL_CAPACITY),
                 ),
             reconstruct_commitments: true,
+            strict_syscalls: false,
         };
 
         tracing::info!(
@@ -140,6 +155,8 @@ impl Default for ZKMCoreOpts {
         opts.split_opts.keccak /= divisor;
         opts.split_opts.sha_extend /= divisor;
         opts.split_opts.sha_compress /= divisor;
+        opts.split_opts.blake3_compress /= divisor;
+        opts.split_opts.blake2b_compress /= divisor;
         opts.split_opts.memory /= divisor;
 
         opts
@@ -189,6 +206,7 @@ impl ZKMCoreOpts {
                     |s| s.parse::<usize>().unwrap_or(DEFAULT_RECORDS_AND_TRACES_CHANNEL_CAPACITY),
                 ),
             reconstruct_commitments: true,
+            strict_syscalls: false,
         }
     }
 }
@@ -204,6 +222,10 @@ pub struct SplitOpts {
     pub sha_extend: usize,
     /// The threshold for sha compress events.
     pub sha_compress: usize,
+    /// The threshold for blake3 compress events.
+    pub blake3_compress: usize,
+    /// The threshold for blake2b compress events.
+    pub blake2b_compress: usize,
     /// The threshold for memory events.
     pub memory: usize,
 }
@@ -217,6 +239,8 @@ impl SplitOpts {
             keccak: 8 * deferred_split_threshold / 24,
             sha_extend: 32 * deferred_split_threshold / 48,
             sha_compress: 32 * deferred_split_threshold / 80,
+            blake3_compress: 32 * deferred_split_threshold / 88,
+            blake2b_compress: 32 * deferred_split_threshold / 120,
             memory: 64 * deferred_split_threshold,
         }
     }
@@ -616,6 +616,7 @@ where
             opening_proof,
             chip_ordering: data.chip_ordering,
             public_values: data.public_values,
+            security: config.security(),
         })
     }
 
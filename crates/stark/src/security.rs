@@ -0,0 +1,147 @@
+use p3_fri::FriConfig;
+use serde::{Deserialize, Serialize};
+
+/// How the soundness of a FRI query is estimated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SecurityModel {
+    /// Assumes that each query contributes `log_blowup` bits of security, as conjectured for
+    /// Reed-Solomon codes up to capacity.
+    Conjectured,
+    /// Only counts the security provable in the unique decoding regime, where each query
+    /// contributes `log2(2 / (1 + rate))` bits.
+    Proven,
+}
+
+/// The security parameters of a STARK, from which the FRI parameters are derived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SecurityConfig {
+    /// How the security of the queries is estimated.
+    pub model: SecurityModel,
+    /// The number of bits of security to target, including the grinding bits.
+    pub security_bits: usize,
+    /// The log of the blowup factor of the Reed-Solomon code.
+    pub log_blowup: usize,
+    /// The number of bits of proof of work required before sampling the queries.
+    pub proof_of_work_bits: usize,
+}
+
+impl SecurityConfig {
+    /// Targets `security_bits` of conjectured security with the given blowup and grinding.
+    #[must_use]
+    pub const fn conjectured(
+        security_bits: usize,
+        log_blowup: usize,
+        proof_of_work_bits: usize,
+    ) -> Self {
+        Self { model: SecurityModel::Conjectured, security_bits, log_blowup, proof_of_work_bits }
+    }
+
+    /// Targets `security_bits` of proven security with the given blowup and grinding.
+    #[must_use]
+    pub const fn proven(
+        security_bits: usize,
+        log_blowup: usize,
+        proof_of_work_bits: usize,
+    ) -> Self {
+        Self { model: SecurityModel::Proven, security_bits, log_blowup, proof_of_work_bits }
+    }
+
+    /// The parameters of compressed proofs, which trade prover time for fewer queries.
+    #[must_use]
+    pub const fn compressed() -> Self {
+        Self::conjectured(100, 2, 16)
+    }
+
+    /// The parameters of ultra-compressed proofs.
+    #[must_use]
+    pub const fn ultra_compressed() -> Self {
+        Self::conjectured(100, 3, 16)
+    }
+
+    /// Describes an existing FRI config as the conjectured security it achieves.
+    #[must_use]
+    pub fn from_fri_config<M>(config: &FriConfig<M>) -> Self {
+        Self::conjectured(
+            config.log_blowup * config.num_queries + config.proof_of_work_bits,
+            config.log_blowup,
+            config.proof_of_work_bits,
+        )
+    }
+
+    /// The number of FRI queries needed to reach the targeted security.
+    #[must_use]
+    pub fn num_queries(&self) -> usize {
+        assert!(self.log_blowup > 0, "log_blowup must be positive");
+        let query_bits = self.security_bits.saturating_sub(self.proof_of_work_bits) as f64;
+        (query_bits / self.bits_per_query(self.model)).ceil() as usize
+    }
+
+    /// The number of bits of security these parameters achieve under `model`.
+    #[must_use]
+    pub fn achieved_bits(&self, model: SecurityModel) -> usize {
+        let query_bits = self.num_queries() as f64 * self.bits_per_query(model);
+        query_bits.floor() as usize + self.proof_of_work_bits
+    }
+
+    /// Whether these parameters are at least as secure as `required`, under its model.
+    #[must_use]
+    pub fn satisfies(&self, required: &SecurityConfig) -> bool {
+        self.achieved_bits(required.model) >= required.security_bits
+    }
+
+    /// Builds the FRI config for these parameters.
+    #[must_use]
+    pub fn fri_config<M>(&self, mmcs: M) -> FriConfig<M> {
+        FriConfig {
+            log_blowup: self.log_blowup,
+            num_queries: self.num_queries(),
+            proof_of_work_bits: self.proof_of_work_bits,
+            mmcs,
+        }
+    }
+
+    fn bits_per_query(&self, model: SecurityModel) -> f64 {
+        match model {
+            SecurityModel::Conjectured => self.log_blowup as f64,
+            SecurityModel::Proven => {
+                let rate = (-(self.log_blowup as f64)).exp2();
+                (2.0 / (1.0 + rate)).log2()
+            }
+        }
+    }
+}
+
+impl Default for SecurityConfig {
+    /// 100 bits of conjectured security with a blowup of 2.
+    fn default() -> Self {
+        Self::conjectured(100, 1, 16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_num_queries() {
+        assert_eq!(SecurityConfig::default().num_queries(), 84);
+        assert_eq!(SecurityConfig::compressed().num_queries(), 42);
+        assert_eq!(SecurityConfig::ultra_compressed().num_queries(), 28);
+        // The parameters of outer recursion.
+        assert_eq!(SecurityConfig::conjectured(100, 4, 16).num_queries(), 21);
+    }
+
+    #[test]
+    fn test_security_comparison() {
+        let default = SecurityConfig::default();
+        let weaker = SecurityConfig::conjectured(80, 1, 16);
+        assert!(default.satisfies(&weaker));
+        assert!(!weaker.satisfies(&default));
+
+        // The same queries prove far fewer bits than are conjectured.
+        let proven = SecurityConfig::proven(100, 1, 16);
+        assert!(proven.num_queries() > default.num_queries());
+        assert!(!default.satisfies(&proven));
+        assert!(proven.satisfies(&default));
+    }
+}
@@ -7,7 +7,7 @@ use itertools::Itertools;
 use p3_matrix::{dense::RowMajorMatrixView, stack::VerticalPair};
 use serde::{Deserialize, Serialize};
 
-use super::{Challenge, Com, OpeningProof, StarkGenericConfig, Val};
+use super::{Challenge, Com, OpeningProof, SecurityConfig, StarkGenericConfig, Val};
 use crate::septic_digest::SepticDigest;
 use crate::shape::OrderedShape;
 
@@ -70,7 +70,7 @@ pub struct ShardOpenedValues<F, EF> {
 /// The maximum number of elements that can be stored in the public values vec.  Both Ziren and
 /// recursive proofs need to pad their public values vec to this length.  This is required since the
 /// recursion verification program expects the public values vec to be fixed length.
-pub const PROOF_MAX_NUM_PVS: usize = 231;
+pub const PROOF_MAX_NUM_PVS: usize = 243;
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(bound = "")]
@@ -80,6 +80,8 @@ pub struct ShardProof<SC: StarkGenericConfig> {
     pub opening_proof: OpeningProof<SC>,
     pub chip_ordering: HashMap<String, usize>,
     pub public_values: Vec<Val<SC>>,
+    /// The security parameters the shard was proven with.
+    pub security: SecurityConfig,
 }
 
 impl<SC: StarkGenericConfig> Debug for ShardProof<SC> {
@@ -0,0 +1,27 @@
+#![no_std]
+#![no_main]
+zkm_zkvm::entrypoint!(main);
+
+use zkm_zkvm::lib::blake3::blake3;
+
+/// The digest of "abc".
+const ABC_DIGEST: [u8; 32] = [
+    0x64, 0x37, 0xb3, 0xac, 0x38, 0x46, 0x51, 0x33, 0xff, 0xb6, 0x3b, 0x75, 0x27, 0x3a, 0x8d, 0xb5,
+    0x48, 0xc5, 0x58, 0x46, 0x5d, 0x79, 0xdb, 0x03, 0xfd, 0x35, 0x9c, 0x6c, 0xd5, 0xbd, 0x9d, 0x85,
+];
+
+/// The digest of the 1025 byte input of the official test vectors, which spans two chunks.
+const TWO_CHUNKS_DIGEST: [u8; 32] = [
+    0xd0, 0x02, 0x78, 0xae, 0x47, 0xeb, 0x27, 0xb3, 0x4f, 0xae, 0xcf, 0x67, 0xb4, 0xfe, 0x26, 0x3f,
+    0x82, 0xd5, 0x41, 0x29, 0x16, 0xc1, 0xff, 0xd9, 0x7c, 0x8c, 0xb7, 0xfb, 0x81, 0x4b, 0x84, 0x44,
+];
+
+pub fn main() {
+    assert_eq!(blake3(b"abc"), ABC_DIGEST);
+
+    let mut input = [0u8; 1025];
+    for (i, byte) in input.iter_mut().enumerate() {
+        *byte = (i % 251) as u8;
+    }
+    assert_eq!(blake3(&input), TWO_CHUNKS_DIGEST);
+}
@@ -0,0 +1,17 @@
+#![no_main]
+zkm_zkvm::entrypoint!(main);
+
+use zkm_zkvm::lib::ivc;
+
+pub fn main() {
+    let mut sum = 0u64;
+    loop {
+        let batch = zkm_zkvm::io::read::<Vec<u64>>();
+        if batch.is_empty() {
+            break;
+        }
+        sum += batch.iter().sum::<u64>();
+        ivc::end_step();
+    }
+    zkm_zkvm::io::commit(&sum);
+}
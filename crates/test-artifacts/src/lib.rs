@@ -12,6 +12,9 @@ pub const SHA2_ELF: &[u8] = include_elf!("sha2-test");
 pub const SHA_EXTEND_ELF: &[u8] = include_elf!("sha-extend-test");
 pub const SHA_COMPRESS_ELF: &[u8] = include_elf!("sha-compress-test");
 
+pub const BLAKE3_COMPRESS_ELF: &[u8] = include_elf!("blake3-compress-test");
+pub const BLAKE2B_COMPRESS_ELF: &[u8] = include_elf!("blake2b-compress-test");
+
 pub const SHA3_CHAIN_ELF: &[u8] = include_elf!("sha3-chain");
 pub const KECCAK_SPONGE_ELF: &[u8] = include_elf!("keccak-sponge-test");
 pub const PANIC_ELF: &[u8] = include_elf!("panic-test");
@@ -75,3 +78,5 @@ pub const U256XU2048_MUL_ELF: &[u8] = include_elf!("u256x2048-mul");
 pub const UNCONSTRAINED_ELF: &[u8] = include_elf!("unconstrained");
 
 pub const MAX_MEMORY_ELF: &[u8] = include_elf!("max_memory");
+
+pub const RUNNING_SUM_ELF: &[u8] = include_elf!("running-sum");
@@ -0,0 +1,70 @@
+//! Trap handler for floating-point instructions.
+//!
+//! Ziren has no AIR for floating-point instructions. Instead, the executor decodes each of them
+//! to a trap into `__zkm_fpu_trap` (see `fpu_trap.s`), which emulates the instruction in software
+//! with [`zkm_primitives::fpu`], so that it is proven like the rest of the program. This lets
+//! guests link C libraries compiled for hard-float, as long as they are compiled with
+//! `-fno-delayed-branch`: a trap in a branch delay slot fails to execute.
+//!
+//! The handler itself must not use floating-point instructions, which holds as long as this crate
+//! is compiled for the soft-float zkVM target.
+
+use zkm_primitives::fpu::{FpuBus, FpuState};
+
+/// The general-purpose registers saved by the trap handler, followed by LO and HI.
+#[no_mangle]
+#[allow(non_upper_case_globals)]
+static mut __zkm_fpu_regs: [u32; 34] = [0; 34];
+
+/// The stack of the trap handler, so that it does not write below the stack pointer of the
+/// trapped code.
+#[no_mangle]
+#[allow(non_upper_case_globals)]
+static mut __zkm_fpu_stack: [u64; 2048] = [0; 2048];
+
+/// The state of the emulated FPU.
+static mut FPU: FpuState = FpuState::new();
+
+/// The saved registers and the memory of the guest.
+struct Trap<'a> {
+    regs: &'a mut [u32; 34],
+}
+
+impl FpuBus for Trap<'_> {
+    fn read_register(&mut self, reg: u32) -> u32 {
+        self.regs[reg as usize]
+    }
+
+    fn write_register(&mut self, reg: u32, value: u32) {
+        if reg != 0 {
+            self.regs[reg as usize] = value;
+        }
+    }
+
+    fn load(&mut self, addr: u32) -> u32 {
+        // SAFETY: The address is aligned, and all memory of the guest is accessible.
+        unsafe { core::ptr::read(addr as *const u32) }
+    }
+
+    fn store(&mut self, addr: u32, value: u32) {
+        // SAFETY: The address is aligned, and all memory of the guest is accessible.
+        unsafe { core::ptr::write(addr as *mut u32, value) }
+    }
+}
+
+/// Emulate the trapped instruction `word` on the saved registers `regs`.
+///
+/// # Safety
+///
+/// Must only be called by `__zkm_fpu_trap`, with `regs` pointing to `__zkm_fpu_regs`.
+#[no_mangle]
+unsafe extern "C" fn zkm_fpu_emulate(regs: *mut [u32; 34], word: u32) {
+    // SAFETY: The VM is single threaded, and the trap handler is not reentrant.
+    let (regs, fpu) = unsafe { (&mut *regs, &mut *core::ptr::addr_of_mut!(FPU)) };
+    // The FPU branches are not trapped, so the delay slot address is never used.
+    if let Err(err) = fpu.execute(word, 0, &mut Trap { regs: &mut *regs }) {
+        panic!("cannot emulate floating-point instruction {word:#010x}: {err:?}");
+    }
+    // BC1F and BC1T on condition code 0 are decoded to branches on $k1.
+    regs[27] = u32::from(fpu.condition(0));
+}
@@ -0,0 +1,97 @@
+// Trap handler for floating-point instructions, see `fpu.rs`.
+//
+// Ziren decodes every FPU instruction of a program that defines `__zkm_fpu_trap` to a jump to it
+// without a delay slot, which links the address of the next instruction in $k0. The handler saves
+// the registers in `__zkm_fpu_regs`, emulates the instruction at $k0 - 4 on its own stack, restores
+// the registers and returns to $k0 with $k1 set by `zkm_fpu_emulate`.
+
+	.section .text.main
+	.globl	__zkm_fpu_trap
+	.type	__zkm_fpu_trap,@function
+	.set	noreorder
+	.set	nomacro
+	.set	noat
+__zkm_fpu_trap:
+	lui	$k1, %hi(__zkm_fpu_regs)
+	addiu	$k1, $k1, %lo(__zkm_fpu_regs)
+	sw	$1, 4($k1)
+	sw	$2, 8($k1)
+	sw	$3, 12($k1)
+	sw	$4, 16($k1)
+	sw	$5, 20($k1)
+	sw	$6, 24($k1)
+	sw	$7, 28($k1)
+	sw	$8, 32($k1)
+	sw	$9, 36($k1)
+	sw	$10, 40($k1)
+	sw	$11, 44($k1)
+	sw	$12, 48($k1)
+	sw	$13, 52($k1)
+	sw	$14, 56($k1)
+	sw	$15, 60($k1)
+	sw	$16, 64($k1)
+	sw	$17, 68($k1)
+	sw	$18, 72($k1)
+	sw	$19, 76($k1)
+	sw	$20, 80($k1)
+	sw	$21, 84($k1)
+	sw	$22, 88($k1)
+	sw	$23, 92($k1)
+	sw	$24, 96($k1)
+	sw	$25, 100($k1)
+	sw	$26, 104($k1)
+	sw	$28, 112($k1)
+	sw	$29, 116($k1)
+	sw	$30, 120($k1)
+	sw	$31, 124($k1)
+	mflo	$t0
+	sw	$t0, 128($k1)
+	mfhi	$t0
+	sw	$t0, 132($k1)
+	move	$s0, $k1
+	move	$a0, $k1
+	lw	$a1, -4($k0)
+	lui	$sp, %hi(__zkm_fpu_stack + 16384)
+	jal	zkm_fpu_emulate
+	addiu	$sp, $sp, %lo(__zkm_fpu_stack + 16384)
+	move	$k1, $s0
+	lw	$t0, 128($k1)
+	mtlo	$t0
+	lw	$t0, 132($k1)
+	mthi	$t0
+	lw	$1, 4($k1)
+	lw	$2, 8($k1)
+	lw	$3, 12($k1)
+	lw	$4, 16($k1)
+	lw	$5, 20($k1)
+	lw	$6, 24($k1)
+	lw	$7, 28($k1)
+	lw	$8, 32($k1)
+	lw	$9, 36($k1)
+	lw	$10, 40($k1)
+	lw	$11, 44($k1)
+	lw	$12, 48($k1)
+	lw	$13, 52($k1)
+	lw	$14, 56($k1)
+	lw	$15, 60($k1)
+	lw	$16, 64($k1)
+	lw	$17, 68($k1)
+	lw	$18, 72($k1)
+	lw	$19, 76($k1)
+	lw	$20, 80($k1)
+	lw	$21, 84($k1)
+	lw	$22, 88($k1)
+	lw	$23, 92($k1)
+	lw	$24, 96($k1)
+	lw	$25, 100($k1)
+	lw	$28, 112($k1)
+	lw	$29, 116($k1)
+	lw	$30, 120($k1)
+	lw	$31, 124($k1)
+	lw	$k0, 104($k1)
+	jr	$k0
+	lw	$k1, 108($k1)
+	.set	at
+	.set	macro
+	.set	reorder
+	.size	__zkm_fpu_trap, .-__zkm_fpu_trap
@@ -139,6 +139,9 @@ macro_rules! entrypoint {
 #[cfg(all(target_os = "zkvm", feature = "libm"))]
 mod libm;
 
+#[cfg(target_os = "zkvm")]
+mod fpu;
+
 /// The number of 32 bit words that the public values digest is composed of.
 pub const PV_DIGEST_NUM_WORDS: usize = 8;
 pub const POSEIDON_NUM_WORDS: usize = 8;
@@ -184,6 +187,7 @@ mod zkvm {
 
     core::arch::global_asm!(include_str!("memset.s"));
     core::arch::global_asm!(include_str!("memcpy.s"));
+    core::arch::global_asm!(include_str!("fpu_trap.s"));
 
     core::arch::global_asm!(
         r#"
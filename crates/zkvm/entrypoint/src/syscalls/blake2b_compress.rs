@@ -0,0 +1,24 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Executes the BLAKE2b compression function on the given working vector and message block.
+///
+/// The first 8 lanes of the working vector are overwritten with the new chaining value.
+///
+/// ### Safety
+///
+/// The caller must ensure that `state` and `msg` are valid pointers to data that is aligned along
+/// a four byte boundary, and that they do not overlap.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_blake2b_compress(state: *mut [u64; 16], msg: *const [u64; 16]) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "syscall",
+            in("$2") crate::syscalls::BLAKE2B_COMPRESS,
+            in("$4") state,
+            in("$5") msg,
+        );
+    }
+}
@@ -0,0 +1,24 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Executes the BLAKE3 compression function on the given initial state and message block.
+///
+/// The state is overwritten with the 16 word output of the compression function.
+///
+/// ### Safety
+///
+/// The caller must ensure that `state` and `msg` are valid pointers to data that is aligned along
+/// a four byte boundary, and that they do not overlap.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_blake3_compress(state: *mut [u32; 16], msg: *const [u32; 16]) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "syscall",
+            in("$2") crate::syscalls::BLAKE3_COMPRESS,
+            in("$4") state,
+            in("$5") msg,
+        );
+    }
+}
@@ -0,0 +1,23 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Executes a precompile registered from outside of Ziren.
+///
+/// `code` must be one of [`crate::syscalls::CUSTOM_0`] to [`crate::syscalls::CUSTOM_7`]. By
+/// convention `arg1` and `arg2` point to the input and output of the precompile.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_custom(code: u32, arg1: u32, arg2: u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "syscall",
+            in("$2") code,
+            in("$4") arg1,
+            in("$5") arg2,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -0,0 +1,68 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Invokes a Linux syscall, returning its result or the negated errno if it failed.
+#[cfg(target_os = "zkvm")]
+unsafe fn linux_syscall(code: u32, a0: u32, a1: u32, a2: u32) -> isize {
+    let ret: u32;
+    let errno: u32;
+    asm!(
+        "syscall",
+        inlateout("$2") code => ret,
+        in("$4") a0,
+        in("$5") a1,
+        in("$6") a2,
+        lateout("$7") errno,
+    );
+    if errno != 0 {
+        -(errno as isize)
+    } else {
+        ret as isize
+    }
+}
+
+/// Opens the file of the virtual filesystem at the NUL-terminated `path` for reading, returning
+/// its file descriptor or the negated errno.
+///
+/// The virtual filesystem is a hint that the proof doesn't check, so the results of the file
+/// syscalls are chosen by the prover.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_open(path: *const u8) -> isize {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        linux_syscall(crate::syscalls::SYS_OPEN, path as u32, 0, 0)
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Reads up to `nbytes` from the file descriptor `fd` into `read_buf`, returning the number of
+/// bytes read or the negated errno.
+///
+/// A single call reads at most 4096 bytes, and fewer if `read_buf` is not word-aligned.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_read(fd: u32, read_buf: *mut u8, nbytes: usize) -> isize {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        linux_syscall(crate::syscalls::SYS_READ, fd, read_buf as u32, nbytes as u32)
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Closes the file descriptor `fd`, returning zero or the negated errno.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_close(fd: u32) -> isize {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        linux_syscall(crate::syscalls::SYS_CLOSE, fd, 0, 0)
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -1,8 +1,12 @@
 mod bigint;
+mod blake2b_compress;
+mod blake3_compress;
 mod bls12381;
 mod bn254;
+mod custom;
 mod ed25519;
 mod fptower;
+mod fs;
 mod halt;
 mod io;
 mod keccak_sponge;
@@ -12,6 +16,7 @@ mod secp256k1;
 mod secp256r1;
 mod sha_compress;
 mod sha_extend;
+mod step;
 mod sys;
 mod u256x2048_mul;
 mod uint256_mul;
@@ -20,10 +25,14 @@ mod unconstrained;
 mod verify;
 
 pub use bigint::*;
+pub use blake2b_compress::*;
+pub use blake3_compress::*;
 pub use bls12381::*;
 pub use bn254::*;
+pub use custom::*;
 pub use ed25519::*;
 pub use fptower::*;
+pub use fs::*;
 pub use halt::*;
 pub use io::*;
 pub use keccak_sponge::*;
@@ -33,6 +42,7 @@ pub use secp256k1::*;
 pub use secp256r1::*;
 pub use sha_compress::*;
 pub use sha_extend::*;
+pub use step::*;
 pub use sys::*;
 pub use u256x2048_mul::*;
 pub use uint256_mul::*;
@@ -100,6 +110,9 @@ pub const BN254_DOUBLE: u32 = 0x00_01_00_0F;
 /// Executes the `COMMIT` precompile.
 pub const COMMIT: u32 = 0x00_00_00_10;
 
+/// Ends the current step of an incremental computation.
+pub const END_STEP: u32 = 0x00_00_00_11;
+
 /// Executes the `COMMIT_DEFERRED_PROOFS` precompile.
 pub const COMMIT_DEFERRED_PROOFS: u32 = 0x00_00_00_1A;
 
@@ -162,3 +175,42 @@ pub const BN254_FP2_MUL: u32 = 0x01_01_00_2B;
 
 /// Executes the `POSEIDON2_PERMUTE` precompile.
 pub const POSEIDON2_PERMUTE: u32 = 0x00_01_00_30;
+
+/// Executes the `BLAKE3_COMPRESS` precompile.
+pub const BLAKE3_COMPRESS: u32 = 0x01_01_00_31;
+
+/// Executes the `BLAKE2B_COMPRESS` precompile.
+pub const BLAKE2B_COMPRESS: u32 = 0x01_01_00_32;
+
+/// Executes the precompile registered as `CUSTOM_0` in the executor.
+pub const CUSTOM_0: u32 = 0x01_01_00_40;
+
+/// Executes the precompile registered as `CUSTOM_1` in the executor.
+pub const CUSTOM_1: u32 = 0x01_01_00_41;
+
+/// Executes the precompile registered as `CUSTOM_2` in the executor.
+pub const CUSTOM_2: u32 = 0x01_01_00_42;
+
+/// Executes the precompile registered as `CUSTOM_3` in the executor.
+pub const CUSTOM_3: u32 = 0x01_01_00_43;
+
+/// Executes the precompile registered as `CUSTOM_4` in the executor.
+pub const CUSTOM_4: u32 = 0x01_01_00_44;
+
+/// Executes the precompile registered as `CUSTOM_5` in the executor.
+pub const CUSTOM_5: u32 = 0x01_01_00_45;
+
+/// Executes the precompile registered as `CUSTOM_6` in the executor.
+pub const CUSTOM_6: u32 = 0x01_01_00_46;
+
+/// Executes the precompile registered as `CUSTOM_7` in the executor.
+pub const CUSTOM_7: u32 = 0x01_01_00_47;
+
+/// Reads from a file descriptor, with the MIPS o32 Linux syscall number.
+pub const SYS_READ: u32 = 4003;
+
+/// Opens a file of the virtual filesystem, with the MIPS o32 Linux syscall number.
+pub const SYS_OPEN: u32 = 4005;
+
+/// Closes a file descriptor, with the MIPS o32 Linux syscall number.
+pub const SYS_CLOSE: u32 = 4006;
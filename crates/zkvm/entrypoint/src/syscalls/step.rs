@@ -0,0 +1,17 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Ends the current step of an incremental computation.
+///
+/// The host proves the execution so far and resumes the program from here with the input of the
+/// next step.
+#[no_mangle]
+pub extern "C" fn syscall_end_step() {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!("syscall", in("$2") crate::syscalls::END_STEP);
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
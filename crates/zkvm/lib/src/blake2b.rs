@@ -0,0 +1,62 @@
+use crate::syscall_blake2b_compress;
+
+/// The BLAKE2b initialization vector.
+pub const BLAKE2B_IV: [u64; 8] = [
+    0x6A09E667F3BCC908,
+    0xBB67AE8584CAA73B,
+    0x3C6EF372FE94F82B,
+    0xA54FF53A5F1D36F1,
+    0x510E527FADE682D1,
+    0x9B05688C2B3E6C1F,
+    0x1F83D9ABFB41BD6B,
+    0x5BE0CD19137E2179,
+];
+
+const BLAKE2B_BLOCK_LEN: usize = 128;
+
+/// Executes the BLAKE2b compression function `F` of RFC 7693 with 12 rounds.
+///
+/// `t` is the number of bytes hashed so far, including this block, and `f` marks the last block.
+pub fn blake2b_compress(h: &mut [u64; 8], m: &[u64; 16], t: u128, f: bool) {
+    let mut state = [0u64; 16];
+    state[..8].copy_from_slice(h);
+    state[8..].copy_from_slice(&BLAKE2B_IV);
+    state[12] ^= t as u64;
+    state[13] ^= (t >> 64) as u64;
+    if f {
+        state[14] = !state[14];
+    }
+    unsafe {
+        syscall_blake2b_compress(&mut state, m);
+    }
+    h.copy_from_slice(&state[..8]);
+}
+
+/// Hashes the input with unkeyed BLAKE2b and returns a digest of `out_len` bytes.
+///
+/// # Panics
+///
+/// Panics if `out_len` is not between 1 and 64.
+pub fn blake2b(input: &[u8], out_len: usize) -> Vec<u8> {
+    assert!((1..=64).contains(&out_len), "BLAKE2b digests are between 1 and 64 bytes");
+
+    let mut h = BLAKE2B_IV;
+    h[0] ^= 0x0101_0000 ^ out_len as u64;
+
+    let num_blocks = input.len().div_ceil(BLAKE2B_BLOCK_LEN).max(1);
+    for (i, block) in input.chunks(BLAKE2B_BLOCK_LEN).take(num_blocks - 1).enumerate() {
+        let t = ((i + 1) * BLAKE2B_BLOCK_LEN) as u128;
+        blake2b_compress(&mut h, &block_lanes(block), t, false);
+    }
+    let last_block = &input[(num_blocks - 1) * BLAKE2B_BLOCK_LEN..];
+    blake2b_compress(&mut h, &block_lanes(last_block), input.len() as u128, true);
+
+    h.iter().flat_map(|lane| lane.to_le_bytes()).take(out_len).collect()
+}
+
+/// Reads a block of at most 128 bytes as little-endian lanes, padded with zeros.
+fn block_lanes(bytes: &[u8]) -> [u64; 16] {
+    let mut block = [0u8; BLAKE2B_BLOCK_LEN];
+    block[..bytes.len()].copy_from_slice(bytes);
+    core::array::from_fn(|i| u64::from_le_bytes(block[8 * i..8 * i + 8].try_into().unwrap()))
+}
@@ -0,0 +1,132 @@
+use crate::syscall_blake3_compress;
+
+/// The BLAKE3 initialization vector, which is also the key of the default hash mode.
+pub const BLAKE3_IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+
+/// Executes the BLAKE3 compression function and returns its full 16 word output.
+///
+/// The first 8 words of the output are the new chaining value.
+pub fn blake3_compress(
+    cv: &[u32; 8],
+    block: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let mut state = [
+        cv[0],
+        cv[1],
+        cv[2],
+        cv[3],
+        cv[4],
+        cv[5],
+        cv[6],
+        cv[7],
+        BLAKE3_IV[0],
+        BLAKE3_IV[1],
+        BLAKE3_IV[2],
+        BLAKE3_IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        block_len,
+        flags,
+    ];
+    unsafe {
+        syscall_blake3_compress(&mut state, block);
+    }
+    state
+}
+
+/// Hashes the input with BLAKE3 in its default mode and returns the 32 byte digest.
+pub fn blake3(input: &[u8]) -> [u8; 32] {
+    let num_chunks = input.len().div_ceil(CHUNK_LEN).max(1);
+
+    // The chaining values of the complete subtrees, merged as soon as a subtree is complete.
+    let mut cv_stack: Vec<[u32; 8]> = Vec::new();
+    for (chunk_counter, chunk) in input.chunks(CHUNK_LEN).take(num_chunks - 1).enumerate() {
+        let mut cv = chunk_output(chunk, chunk_counter as u64).chaining_value();
+        let mut total_chunks = chunk_counter + 1;
+        while total_chunks & 1 == 0 {
+            cv = parent_output(&cv_stack.pop().unwrap(), &cv).chaining_value();
+            total_chunks >>= 1;
+        }
+        cv_stack.push(cv);
+    }
+
+    let last_chunk = &input[(num_chunks - 1) * CHUNK_LEN..];
+    let mut output = chunk_output(last_chunk, (num_chunks - 1) as u64);
+    while let Some(left) = cv_stack.pop() {
+        output = parent_output(&left, &output.chaining_value());
+    }
+    output.root_hash()
+}
+
+/// The inputs of the last compression of a chunk or a parent node.
+struct Output {
+    cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        let words =
+            blake3_compress(&self.cv, &self.block, self.counter, self.block_len, self.flags);
+        words[..8].try_into().unwrap()
+    }
+
+    fn root_hash(&self) -> [u8; 32] {
+        let words = blake3_compress(&self.cv, &self.block, 0, self.block_len, self.flags | ROOT);
+        let mut hash = [0u8; 32];
+        for (bytes, word) in hash.chunks_exact_mut(4).zip(words) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        hash
+    }
+}
+
+fn chunk_output(chunk: &[u8], counter: u64) -> Output {
+    let num_blocks = chunk.len().div_ceil(BLOCK_LEN).max(1);
+    let mut cv = BLAKE3_IV;
+    let mut flags = CHUNK_START;
+    for block in chunk.chunks(BLOCK_LEN).take(num_blocks - 1) {
+        let words = blake3_compress(&cv, &block_words(block), counter, BLOCK_LEN as u32, flags);
+        cv = words[..8].try_into().unwrap();
+        flags = 0;
+    }
+
+    let last_block = &chunk[(num_blocks - 1) * BLOCK_LEN..];
+    Output {
+        cv,
+        block: block_words(last_block),
+        counter,
+        block_len: last_block.len() as u32,
+        flags: flags | CHUNK_END,
+    }
+}
+
+fn parent_output(left: &[u32; 8], right: &[u32; 8]) -> Output {
+    let mut block = [0u32; 16];
+    block[..8].copy_from_slice(left);
+    block[8..].copy_from_slice(right);
+    Output { cv: BLAKE3_IV, block, counter: 0, block_len: BLOCK_LEN as u32, flags: PARENT }
+}
+
+/// Reads a block of at most 64 bytes as little-endian words, padded with zeros.
+fn block_words(bytes: &[u8]) -> [u32; 16] {
+    let mut block = [0u8; BLOCK_LEN];
+    block[..bytes.len()].copy_from_slice(bytes);
+    core::array::from_fn(|i| u32::from_le_bytes(block[4 * i..4 * i + 4].try_into().unwrap()))
+}
@@ -1,7 +1,13 @@
 #![allow(unused_unsafe)]
-use crate::{read_vec_raw, syscall_write, ReadVecResult};
+use crate::{
+    read_vec_raw, syscall_close, syscall_open, syscall_read, syscall_write, ReadVecResult,
+};
 use serde::{de::DeserializeOwned, Serialize};
-use std::io::{Result, Write};
+use sha2::{Digest, Sha256};
+use std::{
+    ffi::CString,
+    io::{Error, Result, Write},
+};
 pub use zkm_primitives::consts::fd::*;
 
 /// A writer that writes to a file descriptor inside the zkVM.
@@ -143,3 +149,59 @@ pub fn hint_slice(buf: &[u8]) {
 pub fn write(fd: u32, buf: &[u8]) {
     SyscallWriter { fd }.write_all(buf).unwrap();
 }
+
+/// Read a file of the virtual filesystem.
+///
+/// The files are written on the host with `ZKMStdin::write_file`. Like [`read`], they are a hint:
+/// the proof doesn't check the file syscalls against what the host wrote, so a malicious prover
+/// can make this return any contents or fail. Check the contents or commit to them with
+/// [`read_file_and_commit`].
+///
+/// ### Examples
+/// ```ignore
+/// let data = zkm_zkvm::io::read_file("data.bin").unwrap();
+/// ```
+pub fn read_file(path: &str) -> Result<Vec<u8>> {
+    let c_path = CString::new(path)?;
+    let fd = unsafe { syscall_open(c_path.as_ptr() as *const u8) };
+    if fd < 0 {
+        return Err(Error::from_raw_os_error(-fd as i32));
+    }
+    let fd = fd as u32;
+
+    // A read fills at most 1024 words, so use a word-aligned buffer of that size to get the most
+    // out of each of them.
+    let mut buf = vec![0u32; 1024];
+    let mut contents = Vec::new();
+    loop {
+        let n =
+            unsafe { syscall_read(fd, buf.as_mut_ptr() as *mut u8, size_of_val(buf.as_slice())) };
+        if n < 0 {
+            unsafe { syscall_close(fd) };
+            return Err(Error::from_raw_os_error(-n as i32));
+        }
+        if n == 0 {
+            break;
+        }
+        let bytes = unsafe { core::slice::from_raw_parts(buf.as_ptr() as *const u8, n as usize) };
+        contents.extend_from_slice(bytes);
+    }
+    unsafe { syscall_close(fd) };
+    Ok(contents)
+}
+
+/// Read a file of the virtual filesystem with [`read_file`], and commit its path and SHA-256
+/// digest to the public values stream so that the verifier knows which contents were read.
+///
+/// The commitment can be read back as a `(String, [u8; 32])`.
+///
+/// ### Examples
+/// ```ignore
+/// let data = zkm_zkvm::io::read_file_and_commit("data.bin").unwrap();
+/// ```
+pub fn read_file_and_commit(path: &str) -> Result<Vec<u8>> {
+    let contents = read_file(path)?;
+    let digest: [u8; 32] = Sha256::digest(&contents).into();
+    commit(&(path, digest));
+    Ok(contents)
+}
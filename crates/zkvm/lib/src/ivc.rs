@@ -0,0 +1,17 @@
+//! Incremental proving of a program over a stream of inputs.
+//!
+//! The program runs once over all the steps of the computation and calls [`end_step`] at the end
+//! of every step but the last. The host proves each step with `ProverClient::prove_step`, which
+//! resumes the program from the execution state the previous step ended in, with the input of
+//! the next step. The proofs of the steps are folded into one compressed proof of the whole
+//! execution, which commits to the public values of all the steps once the program halts.
+
+use crate::syscall_end_step;
+
+/// End the current step of the computation.
+///
+/// The program continues with the memory and registers it had here once the host proves the
+/// next step, whose input is appended to the input read so far.
+pub fn end_step() {
+    unsafe { syscall_end_step() }
+}
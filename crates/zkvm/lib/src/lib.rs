@@ -3,6 +3,8 @@
 //! Documentation for these syscalls can be found in the zkVM entrypoint
 //! `zkm_zkvm::syscalls` module.
 
+pub mod blake2b;
+pub mod blake3;
 pub mod bls12381;
 pub mod bn254;
 #[cfg(feature = "ecdsa")]
@@ -10,6 +12,7 @@ pub mod ecdsa;
 
 pub mod ed25519;
 pub mod io;
+pub mod ivc;
 pub mod keccak256;
 pub mod poseidon2;
 pub mod secp256k1;
@@ -27,8 +30,15 @@ extern "C" {
     /// Writes the bytes in the given buffer to the given file descriptor.
     pub fn syscall_write(fd: u32, write_buf: *const u8, nbytes: usize);
 
-    /// Reads the bytes from the given file descriptor into the given buffer.
-    pub fn syscall_read(fd: u32, read_buf: *mut u8, nbytes: usize);
+    /// Reads the bytes from the given file descriptor into the given buffer, returning the number
+    /// of bytes read or the negated errno.
+    pub fn syscall_read(fd: u32, read_buf: *mut u8, nbytes: usize) -> isize;
+
+    /// Opens a file of the virtual filesystem, returning its file descriptor or the negated errno.
+    pub fn syscall_open(path: *const u8) -> isize;
+
+    /// Closes the given file descriptor, returning zero or the negated errno.
+    pub fn syscall_close(fd: u32) -> isize;
 
     /// Executes the SHA-256 extend operation on the given word array.
     pub fn syscall_sha256_extend(w: *mut [u32; 64]);
@@ -78,6 +88,12 @@ extern "C" {
     /// Executes the Poseidon2 permutation
     pub fn syscall_poseidon2_permute(state: *mut [u32; 16]);
 
+    /// Executes the BLAKE3 compression function on the given initial state and message block.
+    pub fn syscall_blake3_compress(state: *mut [u32; 16], msg: *const [u32; 16]);
+
+    /// Executes the BLAKE2b compression function on the given working vector and message block.
+    pub fn syscall_blake2b_compress(state: *mut [u64; 16], msg: *const [u64; 16]);
+
     /// Executes an uint256 multiplication on the given inputs.
     pub fn syscall_uint256_mulmod(x: *mut [u32; 8], y: *const [u32; 8]);
 
@@ -94,6 +110,9 @@ extern "C" {
     /// Exits unconstrained mode.
     pub fn syscall_exit_unconstrained();
 
+    /// Ends the current step of an incremental computation.
+    pub fn syscall_end_step();
+
     /// Defers the verification of a valid Ziren zkVM proof.
     pub fn syscall_verify_zkm_proof(vk_digest: &[u32; 8], pv_digest: &[u8; 32]);
 
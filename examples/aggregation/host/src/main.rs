@@ -1,8 +1,8 @@
 //! A simple example showing how to aggregate proofs of multiple programs with ZKM.
 
 use zkm_sdk::{
-    include_elf, HashableKey, ProverClient, ZKMProof, ZKMProofWithPublicValues, ZKMStdin,
-    ZKMVerifyingKey,
+    include_elf, AggregationClaim, HashableKey, ProverClient, ZKMProof, ZKMProofWithPublicValues,
+    ZKMStdin, ZKMVerifyingKey,
 };
 
 /// A program that aggregates the proofs of the simple program.
@@ -53,7 +53,21 @@ fn main() {
     let input_3 = AggregationInput { proof: proof_3, vk: fibonacci_vk.clone() };
     let inputs = vec![input_1, input_2, input_3];
 
-    // Aggregate the proofs.
+    // Aggregate the proofs with the built-in aggregation circuit, which needs no guest program.
+    tracing::info_span!("aggregate the proofs with the built-in circuit").in_scope(|| {
+        let proofs = inputs.iter().map(|input| input.proof.clone()).collect();
+        let aggregated = client.aggregate(proofs).run().expect("aggregation failed");
+        client.verify_aggregation(&aggregated).expect("verification failed");
+
+        // The public values are the verifying key and public values digest of every proof.
+        let claims = AggregationClaim::decode_all(aggregated.public_values.as_slice()).unwrap();
+        for (claim, input) in claims.iter().zip(inputs.iter()) {
+            assert_eq!(claim.vk_digest, input.vk.hash_u32());
+            assert_eq!(claim.public_values_digest[..], input.proof.public_values.hash()[..]);
+        }
+    });
+
+    // Aggregate the proofs with the aggregation program.
     tracing::info_span!("aggregate the proofs").in_scope(|| {
         let mut stdin = ZKMStdin::new();
 
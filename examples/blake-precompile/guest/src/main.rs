@@ -0,0 +1,23 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use blake2::{Blake2b512, Digest};
+use zkm_zkvm::lib::{blake2b::blake2b, blake3::blake3};
+zkm_zkvm::entrypoint!(main);
+
+pub fn main() {
+    let use_precompile: bool = zkm_zkvm::io::read();
+    let input: Vec<u8> = zkm_zkvm::io::read();
+
+    let (blake3_digest, blake2b_digest) = if use_precompile {
+        (blake3(&input), blake2b(&input, 64))
+    } else {
+        (*blake3::hash(&input).as_bytes(), Blake2b512::digest(&input).to_vec())
+    };
+
+    zkm_zkvm::io::commit::<[u8; 32]>(&blake3_digest);
+    zkm_zkvm::io::commit::<Vec<u8>>(&blake2b_digest);
+}
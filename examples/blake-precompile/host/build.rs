@@ -0,0 +1,3 @@
+fn main() {
+    zkm_build::build_program("../guest");
+}
@@ -0,0 +1,48 @@
+use std::env;
+use zkm_sdk::{include_elf, utils, ProverClient, ZKMStdin};
+
+/// The ELF we want to execute inside the zkVM.
+const ELF: &[u8] = include_elf!("blake-precompile");
+
+fn stdin(use_precompile: bool, input: &[u8]) -> ZKMStdin {
+    let mut stdin = ZKMStdin::new();
+    stdin.write(&use_precompile);
+    stdin.write(&input.to_vec());
+    stdin
+}
+
+fn main() {
+    utils::setup_logger();
+
+    // The input to hash, 64 KiB by default.
+    let len = env::var("INPUT_LEN").map(|len| len.parse().unwrap()).unwrap_or(1 << 16);
+    let input = (0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+    // Create a `ProverClient` method.
+    let client = ProverClient::new();
+
+    // Hash the input in software first, then with the BLAKE3 and BLAKE2b precompiles.
+    let mut digests = Vec::new();
+    for use_precompile in [false, true] {
+        let (mut public_values, report) =
+            client.execute(ELF, stdin(use_precompile, &input)).run().unwrap();
+        let blake3_digest = public_values.read::<[u8; 32]>();
+        let blake2b_digest = public_values.read::<Vec<u8>>();
+        println!(
+            "hashed {len} bytes {} precompiles in {} cycles",
+            if use_precompile { "with" } else { "without" },
+            report.total_instruction_count()
+        );
+        log::info!("blake3: {}", hex::encode(blake3_digest));
+        log::info!("blake2b: {}", hex::encode(&blake2b_digest));
+        digests.push((blake3_digest, blake2b_digest));
+    }
+    assert_eq!(digests[0], digests[1], "precompile digests differ from software digests");
+
+    // Generate and verify a proof of the precompile run.
+    let (pk, vk) = client.setup(ELF);
+    let proof = client.prove(&pk, stdin(true, &input)).run().unwrap();
+    client.verify(&proof, &vk).expect("verification failed");
+
+    println!("successfully generated and verified proof for the program!")
+}
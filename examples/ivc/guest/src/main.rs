@@ -0,0 +1,23 @@
+//! A program that keeps a running sum over a stream of batches, one batch per step.
+
+#![no_main]
+zkm_zkvm::entrypoint!(main);
+
+use zkm_zkvm::lib::ivc;
+
+pub fn main() {
+    let mut sum = 0u64;
+    loop {
+        // An empty batch ends the stream.
+        let batch = zkm_zkvm::io::read::<Vec<u64>>();
+        if batch.is_empty() {
+            break;
+        }
+        sum += batch.iter().sum::<u64>();
+
+        // Wait for the next batch, which the host appends to the input of the next step.
+        ivc::end_step();
+    }
+
+    zkm_zkvm::io::commit(&sum);
+}
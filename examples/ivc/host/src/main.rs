@@ -0,0 +1,32 @@
+use zkm_sdk::{include_elf, utils, ProverClient, ZKMStdin};
+
+/// The ELF of the running sum program.
+const IVC_ELF: &[u8] = include_elf!("ivc");
+
+fn main() {
+    // Setup the logger.
+    utils::setup_logger();
+
+    let client = ProverClient::new();
+    let (pk, vk) = client.setup(IVC_ELF);
+
+    // Prove one step per batch, each resuming where the step before it stopped. The empty batch
+    // at the end makes the program halt.
+    let batches = [vec![1u64, 2, 3], vec![4, 5], vec![6, 7, 8, 9], vec![]];
+    let mut proof = None;
+    for (i, batch) in batches.iter().enumerate() {
+        let mut input = ZKMStdin::new();
+        input.write(batch);
+        let step = client.prove_step(&pk, proof.as_ref(), input).expect("proving failed");
+        client.verify_step(&step, &vk).expect("verification failed");
+        tracing::info!("proved step {i}");
+        proof = Some(step);
+    }
+
+    // The last proof attests to every step.
+    let mut proof = proof.unwrap();
+    assert!(proof.halted());
+    let sum = proof.proof.public_values.read::<u64>();
+    assert_eq!(sum, batches.iter().flatten().sum::<u64>());
+    println!("proved a running sum of {sum} over {} steps", batches.len());
+}